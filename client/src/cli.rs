@@ -2,25 +2,42 @@
 
 use {
     data::{col_data::ColData, login_info::LoginInfo, protocol_result::ProtocolResult},
-    parser::{main_parser::make_parse, statements::statement::Statement},
+    parser::{
+        main_parser::make_parse,
+        statements::{dml_statement::dml_statement_parser::DmlStatement, statement::Statement},
+    },
     protocol::{
         aliases::{
             results::Result,
-            types::{Byte, Double, Int, Long, ShortInt, Uint},
+            types::{Byte, Double, Int, Long, ShortInt, Uint, Uuid},
         },
         errors::error::Error,
-        headers::{flags::Flag, length::Length, opcode::Opcode, stream::Stream, version::Version},
-        messages::responses::{
-            result::{col_type::ColType, rows_flags::RowsFlag},
-            result_kinds::ResultKind,
+        headers::{
+            compression::{is_compressed, Compression},
+            flags::Flag,
+            length::Length,
+            opcode::Opcode,
+            stream::Stream,
+            version::Version,
+        },
+        messages::{
+            requests::batch_types::BatchType,
+            responses::{
+                events::event_types::EventType,
+                result::{col_type::ColType, rows_flags::RowsFlag},
+                result_kinds::ResultKind,
+            },
         },
         notations::consistency::Consistency,
-        traits::Byteable,
-        utils::{encode_string_map_to_bytes, encode_string_to_bytes, parse_bytes_to_string},
+        traits::{Byteable, Maskable},
+        utils::{
+            encode_string_map_to_bytes, encode_string_to_bytes, encode_uuid_to_string,
+            parse_bytes_to_string,
+        },
     },
     rustls::{ClientConfig, ClientConnection, RootCertStore, StreamOwned as LsStream},
     server::{
-        cql_frame::frame::Frame,
+        cql_frame::{batch_body::BatchQuery, frame::Frame, query_body::QueryBody},
         nodes::{
             actions::opcode::SvAction, addr::loader::AddrLoader, port_type::PortType,
             table_metadata::column_data_type::ColumnDataType,
@@ -28,12 +45,12 @@ use {
         utils::handle_pem_file_iter,
     },
     std::{
-        collections::HashSet,
-        io::{stdin, BufRead, BufReader, Read, Write},
+        collections::{HashMap, HashSet},
+        io::{stdin, BufRead, BufReader, ErrorKind, Read, Write},
         net::{SocketAddr, TcpStream},
         str::FromStr,
         sync::Arc,
-        time::{Duration, Instant},
+        time::{Duration, Instant, SystemTime, UNIX_EPOCH},
     },
     tokenizer::tok::tokenize_query,
 };
@@ -45,6 +62,10 @@ pub type TlsStream = LsStream<ClientConnection, TcpStream>;
 const MAX_RETRIES: Uint = 2;
 /// La cantidad (en bytes) del _header_ de un mensaje.
 const HEADER_SIZE: usize = 9;
+/// La cantidad máxima de filas que se piden por página en un SELECT.
+const DEFAULT_PAGE_SIZE: Int = 5000;
+/// La cantidad (en bytes) del ID de una sesión de _tracing_.
+const TRACING_ID_SIZE: usize = 16;
 
 /// Estructura principal de un cliente.
 #[derive(Clone)]
@@ -55,23 +76,57 @@ pub struct Client {
     /// Un contador interno para llevar la cuenta de IDs de conexiones.
     requests_stream: HashSet<ShortInt>,
 
+    /// Los bytes recibidos del servidor que todavía no completan un _frame_.
+    read_buffer: Vec<Byte>,
+
+    /// Las respuestas recibidas que todavía no fueron reclamadas, según el ID de stream de la
+    /// request a la que responden.
+    responses: HashMap<ShortInt, ProtocolResult>,
+
     /// El _Consistency Level_ de las queries.
     consistency_level: Consistency,
 
     /// Información de logueo, a usar en caso de necesitar reconectarse.
     login_info: LoginInfo,
+
+    /// Los eventos recibidos del servidor que todavía no fueron consumidos.
+    pending_events: Vec<EventType>,
+
+    /// El _paging state_ de la última página de filas recibida para cada request, según su ID
+    /// de stream, si le quedan más páginas.
+    paging_states: HashMap<ShortInt, Vec<Byte>>,
+
+    /// El algoritmo de compresión a pedir en el STARTUP, si se quiere comprimir.
+    preferred_compression: Option<Compression>,
+
+    /// El algoritmo de compresión que aceptó el servidor en el último STARTUP, si hay.
+    compression: Option<Compression>,
+
+    /// Si se pide _tracing_ para las queries.
+    tracing: bool,
+
+    /// El ID de la última sesión de _tracing_ recibida del servidor, si hay.
+    last_trace_id: Option<Uuid>,
 }
 
 impl Client {
     /// Crea una nueva instancia de cliente.
     ///
-    /// El _Consistency Level_ será `Quorum` por defecto.
+    /// El _Consistency Level_ será `Quorum` por defecto, y se pedirá comprimir los mensajes con LZ4.
     pub fn new(addr_loader: AddrLoader, requests_stream: HashSet<ShortInt>) -> Self {
         Self {
             addr_loader,
             requests_stream,
+            read_buffer: Vec::new(),
+            responses: HashMap::new(),
             consistency_level: Consistency::Quorum,
             login_info: LoginInfo::default(),
+            pending_events: Vec::new(),
+            paging_states: HashMap::new(),
+            preferred_compression: Some(Compression::Lz4),
+            compression: None,
+            tracing: false,
+            last_trace_id: None,
         }
     }
 
@@ -125,6 +180,22 @@ impl Client {
         }
     }
 
+    /// Activa o desactiva el _tracing_ de las queries, batches y queries preparadas.
+    pub fn set_tracing(&mut self, tracing: bool) {
+        self.tracing = tracing;
+    }
+
+    /// Devuelve el ID de la última sesión de _tracing_ recibida del servidor, si hay.
+    pub fn take_trace_id(&mut self) -> Option<Uuid> {
+        self.last_trace_id.take()
+    }
+
+    /// Modifica el algoritmo de compresión a pedir en el próximo STARTUP, o `None` para no
+    /// comprimir los mensajes.
+    pub fn set_compression(&mut self, compression: Option<Compression>) {
+        self.preferred_compression = compression;
+    }
+
     /// Crea una conexion tls
     pub fn create_tls_connection(
         &self,
@@ -153,6 +224,13 @@ impl Client {
             self.login_info = login_info;
         }
 
+        // Primero se inicia la conexión, negociando la compresión de los mensajes
+        let (_, startup_tls) = self.send_startup(tls_stream)?;
+        if startup_tls.is_some() {
+            // Hubo que reconectarse, y la nueva conexión ya quedó logueada
+            return Ok(startup_tls);
+        }
+
         let (protocol_result, tls_opt) = self.send_query(
             format!(
                 "User: {} Password: {}",
@@ -211,6 +289,14 @@ impl Client {
                         self.send_shutdown()?;
                         return Ok(());
                     }
+                    if let Some(tracing) = parse_tracing_command(&input) {
+                        self.set_tracing(tracing);
+                        println!(
+                            "Tracing {}",
+                            if tracing { "activado" } else { "desactivado" }
+                        );
+                        continue;
+                    }
                     match self.send_query(&input, &mut tls_stream) {
                         Ok(res) => {
                             if let ProtocolResult::QueryError(err) = res.0 {
@@ -221,6 +307,11 @@ impl Client {
                             eprintln!("Error al enviar la query: {e}");
                         }
                     }
+                    if self.tracing {
+                        if let Err(e) = self.print_last_trace(&mut tls_stream) {
+                            eprintln!("Error al consultar el tracing: {e}");
+                        }
+                    }
                 }
                 Err(e) => {
                     eprintln!("Error leyendo la entrada: {e}");
@@ -231,45 +322,387 @@ impl Client {
         Ok(())
     }
 
+    /// Muestra los eventos de la última sesión de _tracing_ recibida del servidor, consultándolos
+    /// sin _tracing_ en el keyspace `system_traces`.
+    fn print_last_trace(&mut self, tls_stream: &mut TlsStream) -> Result<()> {
+        let Some(trace_id) = self.take_trace_id() else {
+            return Ok(());
+        };
+        let session_id = encode_uuid_to_string(trace_id);
+        let query = format!(
+            "SELECT activity, source, source_elapsed, thread FROM system_traces.events WHERE session_id = '{session_id}'"
+        );
+        self.tracing = false;
+        let result = self.send_query(&query, tls_stream);
+        self.tracing = true;
+        match result?.0 {
+            ProtocolResult::Rows(rows) => {
+                println!("Tracing de la sesión {session_id}:");
+                for row in rows {
+                    let values: Vec<String> = row.iter().map(col_data_as_string).collect();
+                    println!("  {}", values.join(" | "));
+                }
+            }
+            ProtocolResult::QueryError(err) => println!("{err}"),
+            _ => {}
+        }
+        Ok(())
+    }
+
     /// Envía una query al servidor y devuelve la respuesta del mismo.
     ///
-    /// La query será enviada con el _Consistency Level_ actual.
+    /// La query será enviada con el _Consistency Level_ actual. Los SELECT se piden de a
+    /// páginas, y se devuelven las filas de todas juntas.
     pub fn send_query(
         &mut self,
         query: &str,
         tls_stream: &mut TlsStream,
     ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let stream_id = self.reserve_stream_id();
+        let result = match make_parse(&mut tokenize_query(query)) {
+            Ok(Statement::DmlStatement(DmlStatement::SelectStatement(_))) => {
+                self.send_paged_select(query, stream_id, tls_stream)
+            }
+            Ok(Statement::Startup) => self.send_startup(tls_stream),
+            Ok(statement) => match self.prepare_request_to_send(statement, stream_id, query) {
+                Ok(frame) => self.send_frame_with_retries(&frame, tls_stream),
+                Err(err) => Err(err),
+            },
+            Err(err) => Err(Error::ServerError(err.to_string())),
+        };
+        self.requests_stream.remove(&stream_id);
+        result
+    }
+
+    /// Envía una query con marcadores `?` o `:nombre`, vinculándolos con los valores dados, en orden.
+    ///
+    /// Como los valores viajan aparte de la query, los strings pueden tener cualquier caracter,
+    /// comillas incluidas. La query será enviada con el _Consistency Level_ actual.
+    pub fn send_query_with_values(
+        &mut self,
+        query: &str,
+        values: &[ColData],
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let stream_id = self.reserve_stream_id();
+        let mut query_body = QueryBody::new(query.to_string(), self.consistency_level);
+        query_body.set_values(values.iter().map(col_data_as_bytes).collect());
+        let result = match current_timestamp_micros() {
+            Ok(timestamp) => {
+                query_body.set_timestamp(timestamp);
+                let frame = Frame::new_query(stream_id, &query_body).as_bytes();
+                self.send_frame_with_retries(&frame, tls_stream)
+            }
+            Err(err) => Err(err),
+        };
+        self.requests_stream.remove(&stream_id);
+        result
+    }
+
+    /// Envía un STARTUP pidiendo el algoritmo de compresión elegido.
+    ///
+    /// El STARTUP y su respuesta nunca van comprimidos. Si el servidor lo acepta, los mensajes
+    /// siguientes de la conexión van comprimidos con ese algoritmo.
+    fn send_startup(
+        &mut self,
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        self.compression = None;
+        let frame = Client::prepare_startup_message(self.preferred_compression)?;
+        let (result, tls_opt) = self.send_frame_with_retries(&frame, tls_stream)?;
+        if !matches!(result, ProtocolResult::QueryError(_)) {
+            self.compression = self.preferred_compression;
+        }
+        Ok((result, tls_opt))
+    }
+
+    /// Envía un SELECT de a páginas, pidiendo cada una desde el _paging state_ de la anterior
+    /// hasta que no queden más, y junta las filas de todas.
+    fn send_paged_select(
+        &mut self,
+        query: &str,
+        stream_id: ShortInt,
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let mut rows = Vec::new();
+        let mut paging_state = None;
+        let mut new_tls: Option<TlsStream> = None;
+        loop {
+            let mut query_body = QueryBody::new(query.to_string(), self.consistency_level);
+            query_body.set_paging(DEFAULT_PAGE_SIZE, paging_state);
+            let frame = Frame::new_query(stream_id, &query_body).as_bytes();
+            let current_tls = match new_tls.as_mut() {
+                Some(reconnected) => reconnected,
+                None => &mut *tls_stream,
+            };
+            let (result, reconnected) = self.send_frame_with_retries(&frame, current_tls)?;
+            if reconnected.is_some() {
+                new_tls = reconnected;
+            }
+            match result {
+                ProtocolResult::Rows(page_rows) => rows.extend(page_rows),
+                other => return Ok((other, new_tls)),
+            }
+            paging_state = self.paging_states.remove(&stream_id);
+            if paging_state.is_none() {
+                return Ok((ProtocolResult::Rows(rows), new_tls));
+            }
+        }
+    }
+
+    /// Prepara una query en el servidor, devolviendo su ID dentro de un [ProtocolResult::Prepared].
+    ///
+    /// La query puede tener marcadores `?`, a vincular luego con [execute](Client::execute).
+    pub fn prepare(
+        &mut self,
+        query: &str,
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let stream_id = self.reserve_stream_id();
+        let frame = Frame::new_prepare(stream_id, query).as_bytes();
+        let result = self.send_frame_with_retries(&frame, tls_stream);
+        self.requests_stream.remove(&stream_id);
+        result
+    }
+
+    /// Ejecuta una query previamente [preparada](Client::prepare), vinculando sus marcadores
+    /// con los valores dados, en orden.
+    ///
+    /// La query será ejecutada con el _Consistency Level_ actual.
+    pub fn execute(
+        &mut self,
+        id: &[Byte],
+        values: &[ColData],
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let stream_id = self.reserve_stream_id();
+        let values = values.iter().map(col_data_as_bytes).collect();
+        let frame = Frame::new_execute(stream_id, id, self.consistency_level, values).as_bytes();
+        let result = self.send_frame_with_retries(&frame, tls_stream);
+        self.requests_stream.remove(&stream_id);
+        result
+    }
+
+    /// Envía varias queries como un único BATCH del tipo dado.
+    ///
+    /// Las queries serán ejecutadas con el _Consistency Level_ actual.
+    pub fn send_batch(
+        &mut self,
+        batch_type: BatchType,
+        queries: &[&str],
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let stream_id = self.reserve_stream_id();
+        let batch_queries = queries
+            .iter()
+            .map(|query| BatchQuery::Query {
+                query: query.to_string(),
+                values: Vec::new(),
+            })
+            .collect();
+        let frame = Frame::new_batch(stream_id, batch_type, batch_queries, self.consistency_level)
+            .as_bytes();
+        let result = self.send_frame_with_retries(&frame, tls_stream);
+        self.requests_stream.remove(&stream_id);
+        result
+    }
+
+    /// Registra al cliente para recibir los eventos de los tipos dados.
+    ///
+    /// Los tipos válidos son `TOPOLOGY_CHANGE`, `STATUS_CHANGE` y `SCHEMA_CHANGE`. Los eventos
+    /// llegan por la misma conexión, y se pueden consumir con [poll_events](Client::poll_events).
+    pub fn register(
+        &mut self,
+        event_types: &[&str],
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let stream_id = self.reserve_stream_id();
+        let frame = Frame::new_register(stream_id, event_types).as_bytes();
+        let result = self.send_frame_with_retries(&frame, tls_stream);
+        self.requests_stream.remove(&stream_id);
+        result
+    }
+
+    /// Envía una query sin esperar su respuesta, devolviendo el ID de stream con el que se la
+    /// reclama luego con [try_take_response](Client::try_take_response).
+    ///
+    /// Así se pueden tener varias requests en curso sobre una misma conexión. A diferencia de
+    /// [send_query](Client::send_query), los SELECT no se piden de a páginas.
+    pub fn submit_query(&mut self, query: &str, tls_stream: &mut TlsStream) -> Result<ShortInt> {
+        let statement = match make_parse(&mut tokenize_query(query)) {
+            Ok(Statement::Startup) => {
+                return Err(Error::ServerError(
+                    "El STARTUP se tiene que mandar esperando su respuesta".to_string(),
+                ))
+            }
+            Ok(statement) => statement,
+            Err(err) => return Err(Error::ServerError(err.to_string())),
+        };
+        let stream_id = self.reserve_stream_id();
+        let sent = match self.prepare_request_to_send(statement, stream_id, query) {
+            Ok(frame) => self.write_frame(&frame, tls_stream),
+            Err(err) => Err(err),
+        };
+        match sent {
+            Ok(_) => Ok(stream_id),
+            Err(err) => {
+                self.requests_stream.remove(&stream_id);
+                Err(err)
+            }
+        }
+    }
+
+    /// Envía varias queries como un único BATCH del tipo dado sin esperar su respuesta,
+    /// devolviendo el ID de stream con el que se la reclama luego con
+    /// [try_take_response](Client::try_take_response).
+    pub fn submit_batch(
+        &mut self,
+        batch_type: BatchType,
+        queries: &[&str],
+        tls_stream: &mut TlsStream,
+    ) -> Result<ShortInt> {
+        let stream_id = self.reserve_stream_id();
+        let batch_queries = queries
+            .iter()
+            .map(|query| BatchQuery::Query {
+                query: query.to_string(),
+                values: Vec::new(),
+            })
+            .collect();
+        let frame = Frame::new_batch(stream_id, batch_type, batch_queries, self.consistency_level)
+            .as_bytes();
+        match self.write_frame(&frame, tls_stream) {
+            Ok(_) => Ok(stream_id),
+            Err(err) => {
+                self.requests_stream.remove(&stream_id);
+                Err(err)
+            }
+        }
+    }
+
+    /// Lee sin bloquearse lo que haya llegado del servidor, y devuelve la respuesta a la
+    /// request con el ID de stream dado si ya está, liberando el ID.
+    ///
+    /// Las respuestas a otras requests quedan guardadas hasta que se las reclame, y los eventos
+    /// se encolan para [take_events](Client::take_events).
+    pub fn try_take_response(
+        &mut self,
+        stream_id: ShortInt,
+        tls_stream: &mut TlsStream,
+    ) -> Result<Option<ProtocolResult>> {
+        if !self.responses.contains_key(&stream_id) {
+            self.read_available(tls_stream)?;
+            self.dispatch_frames()?;
+        }
+        let response = self.responses.remove(&stream_id);
+        if response.is_some() {
+            self.requests_stream.remove(&stream_id);
+        }
+        Ok(response)
+    }
+
+    /// Espera hasta el tiempo dado por eventos del servidor, y devuelve todos los recibidos
+    /// hasta el momento, incluyendo los que llegaron mientras se esperaban otras respuestas.
+    pub fn poll_events(
+        &mut self,
+        tls_stream: &mut TlsStream,
+        timeout: Duration,
+    ) -> Result<Vec<EventType>> {
+        let deadline = Instant::now() + timeout;
+        let mut buffer = vec![0; 8192];
+        while Instant::now() < deadline {
+            match tls_stream.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(n) => self.read_buffer.extend_from_slice(&buffer[..n]),
+                Err(ref e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    std::thread::sleep(Duration::from_millis(50));
+                }
+                Err(e) => return Err(Error::ServerError(format!("Error de lectura: {e}"))),
+            }
+            self.dispatch_frames()?;
+        }
+        Ok(self.take_events())
+    }
+
+    /// Devuelve los eventos recibidos que todavía no fueron consumidos, vaciando la cola.
+    pub fn take_events(&mut self) -> Vec<EventType> {
+        std::mem::take(&mut self.pending_events)
+    }
+
+    /// Reserva el primer ID de stream que no esté en uso.
+    fn reserve_stream_id(&mut self) -> ShortInt {
         let mut stream_id: ShortInt = 0;
         while self.requests_stream.contains(&stream_id) {
             stream_id += 1;
         }
         self.requests_stream.insert(stream_id);
+        // Una respuesta que llegó tarde a un uso anterior del ID no es de esta request
+        self.responses.remove(&stream_id);
+        self.paging_states.remove(&stream_id);
+        stream_id
+    }
+
+    /// Envía un _frame_ al servidor, reconectándose si falla hasta [MAX_RETRIES] veces.
+    fn send_frame_with_retries(
+        &mut self,
+        frame: &[Byte],
+        tls_stream: &mut TlsStream,
+    ) -> Result<(ProtocolResult, Option<TlsStream>)> {
         let mut tls_opt: Option<TlsStream> = None;
+        let mut last_error = None;
+        let frame = self.add_tracing_flag(frame);
+        for _ in 0..=MAX_RETRIES {
+            let frame = match self.compression {
+                Some(compression) => compression.compress_frame(&frame)?,
+                None => frame.clone(),
+            };
+            match self.write_to_server(tls_opt, &frame, tls_stream) {
+                Ok(value) => return Ok(value),
+                Err(e) => last_error = Some(e),
+            };
+            // A este punto sabemos que el TLS Stream algo tiene, hay que cambiarlo
+            // Lo que quedó a medio leer de la conexión anterior ya no se va a completar
+            self.read_buffer.clear();
+            let mut new_tls =
+                self.create_tls_connection(get_client_connection()?, self.connect()?)?;
+            self.login(self.login_info.to_owned(), &mut new_tls)?;
+            tls_opt = Some(new_tls);
+        }
+        match last_error {
+            Some(e) => Err(e),
+            None => Err(Error::ServerError("Error desconocido".into())),
+        }
+    }
 
-        let result = match make_parse(&mut tokenize_query(query)) {
-            Ok(statement) => {
-                let frame = self.prepare_request_to_send(statement, stream_id, query)?;
-                let mut last_error = None;
-                for _ in 0..=MAX_RETRIES {
-                    match self.write_to_server(tls_opt, &frame, tls_stream) {
-                        Ok(value) => return Ok(value),
-                        Err(e) => last_error = Some(e),
-                    };
-                    // A este punto sabemos que el TLS Stream algo tiene, hay que cambiarlo
-                    let mut new_tls =
-                        self.create_tls_connection(get_client_connection()?, self.connect()?)?;
-                    self.login(self.login_info.to_owned(), &mut new_tls)?;
-                    tls_opt = Some(new_tls);
-                }
-                match last_error {
-                    Some(e) => Err(e),
-                    None => Err(Error::ServerError("Error desconocido".into())),
-                }
-            }
-            Err(err) => Err(Error::ServerError(err.to_string())),
+    /// Marca el _frame_ con la flag [Tracing](Flag::Tracing) si se pidió _tracing_ y es una
+    /// request que lo admite.
+    fn add_tracing_flag(&self, frame: &[Byte]) -> Vec<Byte> {
+        let mut frame = frame.to_vec();
+        let traceable = frame.get(4).is_some_and(|opcode| {
+            matches!(
+                Opcode::try_from(*opcode),
+                Ok(Opcode::Query | Opcode::Execute | Opcode::Batch)
+            )
+        });
+        if self.tracing && traceable {
+            frame[1] |= Flag::Tracing.collapse();
+        }
+        frame
+    }
+
+    /// Escribe un _frame_ en el stream sin esperar su respuesta, comprimiéndolo si corresponde.
+    fn write_frame(&self, frame: &[Byte], tls_stream: &mut TlsStream) -> Result<()> {
+        let frame = self.add_tracing_flag(frame);
+        let frame = match self.compression {
+            Some(compression) => compression.compress_frame(&frame)?,
+            None => frame,
         };
-        self.requests_stream.remove(&stream_id);
-        result
+        tls_stream
+            .write_all(&frame)
+            .map_err(|e| Error::ServerError(format!("Error al escribir: {e}")))?;
+        tls_stream
+            .flush()
+            .map_err(|e| Error::ServerError(format!("Error al flush: {e}")))
     }
 
     fn prepare_request_to_send(
@@ -279,13 +712,20 @@ impl Client {
         query: &str,
     ) -> Result<Vec<Byte>> {
         let frame = match statement {
-            Statement::DmlStatement(_) | Statement::DdlStatement(_) => {
+            Statement::DmlStatement(_) => {
+                // Las escrituras llevan el timestamp del cliente, así se ordenan según cuándo
+                // las hizo y no según a qué coordinador le llegaron
+                let mut query_body = QueryBody::new(query.to_string(), self.consistency_level);
+                query_body.set_timestamp(current_timestamp_micros()?);
+                Frame::new_query(stream_id, &query_body).as_bytes()
+            }
+            Statement::DdlStatement(_) => {
                 Frame::new(stream_id, query, self.consistency_level).as_bytes()
             }
             Statement::LoginUser(user) => {
                 Client::prepare_auth_response_message(&user.user, &user.password)?
             }
-            Statement::Startup => Client::prepare_startup_message()?,
+            Statement::Startup => Client::prepare_startup_message(self.preferred_compression)?,
         };
         Ok(frame)
     }
@@ -296,10 +736,11 @@ impl Client {
         frame: &[Byte],
         tls_stream: &mut TlsStream,
     ) -> Result<(ProtocolResult, Option<TlsStream>)> {
+        let stream_id = ShortInt::from_be_bytes([frame[2], frame[3]]);
         if let Some(cur_tls) = tls_opt.as_mut() {
             match cur_tls.write_all(frame) {
                 Ok(_) => match cur_tls.flush() {
-                    Ok(_) => match self.read_complete_response(cur_tls) {
+                    Ok(_) => match self.read_complete_response(stream_id, cur_tls) {
                         Ok(response) => Ok((response, tls_opt)),
                         Err(e) => Err(e),
                     },
@@ -310,7 +751,7 @@ impl Client {
         } else {
             match tls_stream.write_all(frame) {
                 Ok(_) => match tls_stream.flush() {
-                    Ok(_) => match self.read_complete_response(tls_stream) {
+                    Ok(_) => match self.read_complete_response(stream_id, tls_stream) {
                         Ok(response) => Ok((response, None)),
                         Err(e) => Err(e),
                     },
@@ -362,64 +803,91 @@ impl Client {
         Ok(bytes)
     }
 
-    fn read_complete_response(&mut self, tls_stream: &mut TlsStream) -> Result<ProtocolResult> {
-        let mut response = Vec::new();
+    /// Lee del stream hasta recibir la respuesta a la request con el ID de stream dado.
+    ///
+    /// Las respuestas a otras requests que lleguen antes quedan guardadas hasta que se las
+    /// reclame, y los eventos se encolan.
+    fn read_complete_response(
+        &mut self,
+        stream_id: ShortInt,
+        tls_stream: &mut TlsStream,
+    ) -> Result<ProtocolResult> {
         let mut buffer = vec![0; 8192];
 
         // Establecer un deadline absoluto
         let deadline = Instant::now() + Duration::from_secs(5);
-        // Primero leer el header completo
-        while response.len() < HEADER_SIZE {
+        loop {
+            self.dispatch_frames()?;
+            if let Some(response) = self.responses.remove(&stream_id) {
+                return Ok(response);
+            }
             if Instant::now() > deadline {
-                return Err(Error::ServerError("Timeout al leer header".into()));
+                return Err(Error::ServerError("Timeout al leer la respuesta".into()));
             }
             match tls_stream.read(&mut buffer) {
                 Ok(0) => {
-                    if response.is_empty() {
-                        return Err(Error::ServerError(
-                            "Conexión cerrada por el servidor".into(),
-                        ));
-                    }
-                    break;
+                    return Err(Error::ServerError(
+                        "Conexión cerrada por el servidor".into(),
+                    ));
                 }
                 Ok(n) => {
-                    response.extend_from_slice(&buffer[..n]);
+                    self.read_buffer.extend_from_slice(&buffer[..n]);
                 }
-                Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
                     std::thread::sleep(Duration::from_millis(50));
                     continue;
                 }
                 Err(e) => return Err(Error::ServerError(format!("Error de lectura: {e}"))),
             }
         }
+    }
 
-        // Leer el cuerpo si hay header
-        if response.len() >= HEADER_SIZE {
-            let body_length = self.get_body_length(&response)?;
-            let total_expected_length = HEADER_SIZE + body_length;
-
-            while response.len() < total_expected_length {
-                if std::time::Instant::now() > deadline {
-                    return Err(Error::ServerError("Timeout al leer cuerpo".into()));
+    /// Lee del stream todos los bytes que ya hayan llegado, sin bloquearse esperando más.
+    fn read_available(&mut self, tls_stream: &mut TlsStream) -> Result<()> {
+        let mut buffer = vec![0; 8192];
+        tls_stream
+            .sock
+            .set_nonblocking(true)
+            .map_err(|e| Error::ServerError(format!("Error al configurar non-blocking: {e}")))?;
+        let read = loop {
+            match tls_stream.read(&mut buffer) {
+                Ok(0) => {
+                    break Err(Error::ServerError(
+                        "Conexión cerrada por el servidor".into(),
+                    ))
                 }
+                Ok(n) => self.read_buffer.extend_from_slice(&buffer[..n]),
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => break Ok(()),
+                Err(e) => break Err(Error::ServerError(format!("Error de lectura: {e}"))),
+            }
+        };
+        tls_stream
+            .sock
+            .set_nonblocking(false)
+            .map_err(|e| Error::ServerError(format!("Error al configurar non-blocking: {e}")))?;
+        read
+    }
 
-                match tls_stream.read(&mut buffer) {
-                    Ok(0) => break,
-                    Ok(n) => {
-                        response.extend_from_slice(&buffer[..n]);
-                    }
-                    Err(ref e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                        std::thread::sleep(Duration::from_millis(50));
-                        continue;
-                    }
-                    Err(e) => return Err(Error::ServerError(format!("Error de lectura: {e}"))),
+    /// Procesa los _frames_ completos que haya entre los bytes recibidos, guardando cada
+    /// respuesta según el ID de stream de su request y encolando los eventos.
+    ///
+    /// Los bytes de un _frame_ que todavía no llegó entero quedan para la próxima vez.
+    fn dispatch_frames(&mut self) -> Result<()> {
+        while self.read_buffer.len() >= HEADER_SIZE {
+            let total_length = HEADER_SIZE + self.get_body_length(&self.read_buffer)?;
+            if self.read_buffer.len() < total_length {
+                break;
+            }
+            let frame: Vec<Byte> = self.read_buffer.drain(..total_length).collect();
+            let stream_id = ShortInt::from_be_bytes([frame[2], frame[3]]);
+            match self.handle_response(&frame)? {
+                ProtocolResult::Event(event) => self.pending_events.push(event),
+                response => {
+                    self.responses.insert(stream_id, response);
                 }
             }
-
-            return self.handle_response(&response[..total_expected_length]);
         }
-
-        Err(Error::ServerError("Respuesta incompleta".into()))
+        Ok(())
     }
 
     fn get_body_length(&self, response: &[Byte]) -> Result<usize> {
@@ -437,6 +905,9 @@ impl Client {
                 "No se cumple el protocolo del header".to_string(),
             ));
         };
+        let decompressed = self.decompress_response(request)?;
+        let untraced = self.take_tracing_id(decompressed)?;
+        let request = &untraced[..];
         let _version = Version::try_from(request[0])?;
         let _flags = Flag::try_from(request[1])?;
         let _stream = Stream::try_from(request[2..4].to_vec())?;
@@ -449,7 +920,7 @@ impl Client {
             Opcode::Authenticate => self.handle_authenticate(),
             Opcode::Supported => self.handle_supported(),
             Opcode::Result => self.handle_result(lenght, request),
-            Opcode::Event => self.handle_event(),
+            Opcode::Event => self.handle_event(request),
             Opcode::AuthChallenge => self.handle_auth_challenge(),
             Opcode::AuthSuccess => self.handle_auth_success(),
             _ => {
@@ -461,6 +932,44 @@ impl Client {
         result
     }
 
+    /// Descomprime el body de una respuesta, si llegó comprimido.
+    fn decompress_response(&self, response: &[Byte]) -> Result<Vec<Byte>> {
+        if !is_compressed(response) {
+            return Ok(response.to_vec());
+        }
+        match self.compression {
+            Some(compression) => compression.decompress_frame(response),
+            None => Err(Error::ProtocolError(
+                "Se recibió una respuesta comprimida sin haber negociado la compresión".to_string(),
+            )),
+        }
+    }
+
+    /// Quita el ID de la sesión de _tracing_ del principio del body de una respuesta, si lo
+    /// tiene, guardándolo para consultar luego sus eventos.
+    fn take_tracing_id(&mut self, mut response: Vec<Byte>) -> Result<Vec<Byte>> {
+        if !Flag::has_mask(&response[1], &Flag::Tracing) {
+            return Ok(response);
+        }
+        let body_length = self.get_body_length(&response)?;
+        if body_length < TRACING_ID_SIZE || response.len() < HEADER_SIZE + TRACING_ID_SIZE {
+            return Err(Error::ProtocolError(
+                "La respuesta no contiene el ID de la sesión de tracing".to_string(),
+            ));
+        }
+        let mut trace_id = [0; TRACING_ID_SIZE];
+        trace_id.copy_from_slice(&response[HEADER_SIZE..HEADER_SIZE + TRACING_ID_SIZE]);
+        self.last_trace_id = Some(Uuid::from_be_bytes(trace_id));
+
+        response.drain(HEADER_SIZE..HEADER_SIZE + TRACING_ID_SIZE);
+        response[1] &= !Flag::Tracing.collapse();
+        response.splice(
+            5..HEADER_SIZE,
+            ((body_length - TRACING_ID_SIZE) as Uint).to_be_bytes(),
+        );
+        Ok(response)
+    }
+
     fn handle_request_error(&self, _lenght: Length, request: &[Byte]) -> Result<ProtocolResult> {
         match Error::try_from(request[HEADER_SIZE..].to_vec()) {
             Ok(error) => Ok(ProtocolResult::QueryError(error)),
@@ -469,9 +978,7 @@ impl Client {
     }
 
     fn handle_ready(&self) -> Result<ProtocolResult> {
-        Err(Error::Invalid(
-            "La funcionalidad Ready no está soportada.".to_string(),
-        ))
+        Ok(ProtocolResult::Void)
     }
 
     fn handle_authenticate(&self) -> Result<ProtocolResult> {
@@ -484,24 +991,23 @@ impl Client {
         ))
     }
 
-    fn handle_result(&self, lenght: Length, request: &[Byte]) -> Result<ProtocolResult> {
+    fn handle_result(&mut self, lenght: Length, request: &[Byte]) -> Result<ProtocolResult> {
         match ResultKind::try_from(request[HEADER_SIZE..HEADER_SIZE + 4].to_vec())? {
             ResultKind::Void => Ok(ProtocolResult::Void),
-            ResultKind::Rows => self.deserialize_rows(lenght, &request[13..]),
+            ResultKind::Rows => {
+                let stream_id = ShortInt::from_be_bytes([request[2], request[3]]);
+                self.deserialize_rows(lenght, stream_id, &request[13..])
+            }
             ResultKind::SetKeyspace => self.set_keyspace(lenght, &request[13..]),
-            ResultKind::Prepared => Err(Error::Invalid(
-                "No se soporta la respuesta de tipo Prepared.".to_string(),
-            )),
-            ResultKind::SchemaChange => Err(Error::Invalid(
-                "No se soporta la respuesta de tipo SchemaChange.".to_string(),
-            )),
+            ResultKind::Prepared => self.deserialize_prepared(&request[13..]),
+            ResultKind::SchemaChange => Ok(ProtocolResult::SchemaChange),
         }
     }
 
-    fn handle_event(&self) -> Result<ProtocolResult> {
-        Err(Error::Invalid(
-            "La funcionalidad Event no está soportada.".to_string(),
-        ))
+    fn handle_event(&self, request: &[Byte]) -> Result<ProtocolResult> {
+        Ok(ProtocolResult::Event(EventType::try_from(
+            &request[HEADER_SIZE..],
+        )?))
     }
 
     fn handle_auth_challenge(&self) -> Result<ProtocolResult> {
@@ -514,10 +1020,31 @@ impl Client {
         Ok(ProtocolResult::AuthSuccess)
     }
 
-    fn deserialize_rows(&self, _lenght: Length, request: &[Byte]) -> Result<ProtocolResult> {
-        let _flags = RowsFlag::try_from(request[..4].to_vec())?;
+    fn deserialize_rows(
+        &mut self,
+        _lenght: Length,
+        stream_id: ShortInt,
+        request: &[Byte],
+    ) -> Result<ProtocolResult> {
+        let flags = self.read_bytes_to_int(request, 0)?;
         let columns_count = Uint::from_be_bytes([request[4], request[5], request[6], request[7]]);
         let mut actual_position: usize = 8;
+        self.paging_states.remove(&stream_id);
+        if RowsFlag::has_mask(&flags, &RowsFlag::HasMorePages) {
+            let state_len = self.read_bytes_to_int(request, actual_position)?.max(0) as usize;
+            actual_position += 4;
+            match request.get(actual_position..actual_position + state_len) {
+                Some(state) => {
+                    self.paging_states.insert(stream_id, state.to_vec());
+                }
+                None => {
+                    return Err(Error::Invalid(
+                        "No se recibio el paging state completo".to_string(),
+                    ))
+                }
+            }
+            actual_position += state_len;
+        }
         let mut col_names: Vec<String> = Vec::new(); // usar col_names
         let mut col_types: Vec<ColType> = Vec::new(); // usar col_types que deberia ser ademas ColumnDataType
         for _ in 0..columns_count {
@@ -543,6 +1070,21 @@ impl Client {
         Ok(ProtocolResult::Rows(rows))
     }
 
+    fn deserialize_prepared(&self, request: &[Byte]) -> Result<ProtocolResult> {
+        if request.len() < 2 {
+            return Err(Error::Invalid(
+                "No se recibio el ID de la query preparada".to_string(),
+            ));
+        }
+        let id_len = ShortInt::from_be_bytes([request[0], request[1]]) as usize;
+        match request.get(2..2 + id_len) {
+            Some(id) => Ok(ProtocolResult::Prepared(id.to_vec())),
+            None => Err(Error::Invalid(
+                "No se recibio una query con el largo esperado".to_string(),
+            )),
+        }
+    }
+
     fn match_col_type(
         &self,
         col_types: &[ColType],
@@ -550,26 +1092,31 @@ impl Client {
         request: &[Byte],
         actual_position: &mut usize,
     ) -> Result<ColData> {
-        let col_data = match ColumnDataType::from(col_types[i as usize].clone()) {
-            ColumnDataType::String => {
-                let value = self.parse_string(request, actual_position)?;
-                *actual_position += value.len();
-                ColData::String(value)
-            }
-            ColumnDataType::Timestamp => {
-                let value = self.parse_column_value::<Long>(request, actual_position)?;
-                *actual_position += value.to_string().len();
-                ColData::Timestamp(value)
-            }
-            ColumnDataType::Double => {
-                let value = self.parse_column_value::<Double>(request, actual_position)?;
-                *actual_position += value.to_string().len();
-                ColData::Double(value)
-            }
-            ColumnDataType::Int => {
-                let value = self.parse_column_value::<Int>(request, actual_position)?;
-                *actual_position += value.to_string().len();
-                ColData::Int(value)
+        let col_type = col_types[i as usize].clone();
+        // Los valores nulos llegan vacíos, y sólo tienen sentido como tales si no son strings
+        let is_string = matches!(
+            ColumnDataType::from(col_type.clone()),
+            ColumnDataType::String
+        );
+        if !is_string && self.read_bytes_to_int(request, *actual_position)? <= 0 {
+            *actual_position += 4;
+            return Ok(ColData::Null);
+        }
+        let col_data = match (&col_type, ColumnDataType::from(col_type.clone())) {
+            (ColType::Bigint, _) | (_, ColumnDataType::Counter) => {
+                ColData::Bigint(self.parse_column_value::<Long>(request, actual_position)?)
+            }
+            (_, ColumnDataType::String) => {
+                ColData::String(self.parse_string(request, actual_position)?)
+            }
+            (_, ColumnDataType::Timestamp) => {
+                ColData::Timestamp(self.parse_column_value::<Long>(request, actual_position)?)
+            }
+            (_, ColumnDataType::Double) => {
+                ColData::Double(self.parse_column_value::<Double>(request, actual_position)?)
+            }
+            (_, ColumnDataType::Int) => {
+                ColData::Int(self.parse_column_value::<Int>(request, actual_position)?)
             }
         };
         Ok(col_data)
@@ -609,6 +1156,7 @@ impl Client {
         let right_position = *actual_position + value_len as usize;
         let str_value = std::str::from_utf8(&request[*actual_position..right_position])
             .map_err(|_| Error::TruncateError("Error al transformar bytes a utf8".to_string()))?;
+        *actual_position = right_position;
 
         str_value.parse::<T>().map_err(|e| {
             Error::TruncateError(format!("Error al parsear string '{str_value}': {e}"))
@@ -620,8 +1168,10 @@ impl Client {
         *actual_position += 4;
         let right_position = *actual_position + string_len as usize;
 
-        String::from_utf8(request[*actual_position..right_position].to_vec())
-            .map_err(|_| Error::TruncateError("Error al transformar bytes a utf8".to_string()))
+        let value = String::from_utf8(request[*actual_position..right_position].to_vec())
+            .map_err(|_| Error::TruncateError("Error al transformar bytes a utf8".to_string()));
+        *actual_position = right_position;
+        value
     }
 
     /// Manda un mensaje aislado a una cierta dirección.
@@ -664,16 +1214,20 @@ impl Client {
 
         Ok(())
     }
-    /// Crea una request Startup para ser mandada
-    pub fn prepare_startup_message() -> Result<Vec<Byte>> {
+    /// Crea una request Startup para ser mandada, pidiendo comprimir los mensajes con el
+    /// algoritmo dado si hay.
+    pub fn prepare_startup_message(compression: Option<Compression>) -> Result<Vec<Byte>> {
         let mut response = Vec::new();
         response.append(&mut Version::RequestV5.as_bytes());
         response.append(&mut Flag::Default.as_bytes());
         response.append(&mut Stream::new(0).as_bytes());
         response.append(&mut Opcode::Startup.as_bytes());
         response.append(&mut Length::new(0).as_bytes());
-        let cql_version = vec![("CQL_VERSION".to_string(), "5.0.0".to_string())];
-        let mut string_map_as_bytes = encode_string_map_to_bytes(cql_version);
+        let mut options = vec![("CQL_VERSION".to_string(), "5.0.0".to_string())];
+        if let Some(compression) = compression {
+            options.push(("COMPRESSION".to_string(), compression.name().to_string()));
+        }
+        let mut string_map_as_bytes = encode_string_map_to_bytes(options);
         let length: Uint = string_map_as_bytes.len() as Uint;
         response.append(&mut string_map_as_bytes);
         response.splice(5..9, length.to_be_bytes());
@@ -699,6 +1253,53 @@ impl Client {
     }
 }
 
+/// Devuelve la hora actual en microsegundos desde la época UNIX.
+fn current_timestamp_micros() -> Result<Long> {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_micros() as Long)
+        .map_err(|e| Error::ServerError(format!("No se pudo obtener la hora actual: {e}")))
+}
+
+/// Serializa el valor de una columna para vincularlo a un marcador de una query.
+fn col_data_as_bytes(col_data: &ColData) -> Vec<Byte> {
+    match col_data {
+        ColData::String(value) => value.as_bytes().to_vec(),
+        ColData::Timestamp(value) => value.to_be_bytes().to_vec(),
+        ColData::Double(value) => value.to_be_bytes().to_vec(),
+        ColData::Int(value) => value.to_be_bytes().to_vec(),
+        ColData::Bigint(value) => value.to_be_bytes().to_vec(),
+        ColData::Null => Vec::new(),
+    }
+}
+
+/// Reconoce los comandos `TRACING ON` y `TRACING OFF`, devolviendo si se pidió activar el
+/// _tracing_.
+fn parse_tracing_command(input: &str) -> Option<bool> {
+    let command = input
+        .trim_end_matches(';')
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+        .to_uppercase();
+    match command.as_str() {
+        "TRACING ON" => Some(true),
+        "TRACING OFF" => Some(false),
+        _ => None,
+    }
+}
+
+fn col_data_as_string(col_data: &ColData) -> String {
+    match col_data {
+        ColData::String(value) => value.to_string(),
+        ColData::Timestamp(value) => value.to_string(),
+        ColData::Double(value) => value.to_string(),
+        ColData::Int(value) => value.to_string(),
+        ColData::Bigint(value) => value.to_string(),
+        ColData::Null => "null".to_string(),
+    }
+}
+
 fn print_initial_message() {
     println!(
         "ECHO MODE:\n \
@@ -707,6 +1308,7 @@ fn print_initial_message() {
             ----------\n \
             'q' o línea vacía para salir\n \
             'shutdown' para mandar un mensaje de apagado al servidor (y salir)\n \
+            'TRACING ON' / 'TRACING OFF' para mostrar o no el tracing de cada query\n \
             ----------\n"
     );
 }
@@ -11,4 +11,8 @@ pub enum ColData {
     Double(Double),
     /// Representa un dato de tipo Int.
     Int(Int),
+    /// Representa un dato de tipo Bigint, como el resultado de `COUNT`.
+    Bigint(Long),
+    /// Representa la ausencia de un valor.
+    Null,
 }
@@ -1,5 +1,7 @@
 use crate::col_data::ColData;
-use protocol::errors::error::Error;
+use protocol::{
+    aliases::types::Byte, errors::error::Error, messages::responses::events::event_types::EventType,
+};
 
 /// Resultado de una operación en el protocolo de Cassandra.
 #[derive(Clone, Debug)]
@@ -13,8 +15,8 @@ pub enum ProtocolResult {
     /// El resultado de una _query_ `use`.
     SetKeyspace(String),
 
-    /// El resultado de una _query_ de tipo PREPARE.
-    Prepared,
+    /// El resultado de una _query_ de tipo PREPARE, con el ID de la _query_ preparada.
+    Prepared(Vec<Byte>),
 
     /// El resultado de una _query_ que altera un _schema_.
     SchemaChange,
@@ -22,6 +24,9 @@ pub enum ProtocolResult {
     /// Indica que el cliente fue aceptado por el servidor.
     AuthSuccess,
 
+    /// Un evento enviado por el servidor a un cliente registrado al mismo.
+    Event(EventType),
+
     /// El resultado de una _query_ que indica un error en la consulta.
     QueryError(Error),
 }
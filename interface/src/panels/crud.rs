@@ -1,9 +1,12 @@
 //! Módulo para operaciones CRUD en los paneles.
 
 use {
-    crate::{utils::distances::distance_eta, utils::util::send_client_query},
+    crate::utils::{
+        distances::distance_eta,
+        util::{send_client_query, send_client_query_with_values},
+    },
     client::conn_holder::ConnectionHolder,
-    data::{airports::airp::Airport, flights::states::FlightState},
+    data::{airports::airp::Airport, col_data::ColData, flights::states::FlightState},
     protocol::aliases::{
         results::Result,
         types::{Int, Long, Ulong},
@@ -40,31 +43,34 @@ pub fn insert_flight(
         _ => ("N/A".to_string(), "N/A".to_string()),
     };
 
-    let inc_fl_query = format!(
-        "INSERT INTO vuelos_entrantes (id, orig, dest, llegada, estado) VALUES ({}, '{}', '{}', {}, '{}');",
-        flight_id as Int, cur_iata_code, ex_iata_code, eta, FlightState::Preparing
-    );
-
-    let dep_fl_query = format!(
-        "INSERT INTO vuelos_salientes (id, orig, dest, salida, estado) VALUES ({}, '{}', '{}', {}, '{}');",
-        flight_id as Int, cur_iata_code, ex_iata_code, timestamp, FlightState::Preparing
-    );
+    // La vista de vuelos entrantes se actualiza sola a partir de la tabla de salientes
+    let query = "INSERT INTO vuelos_salientes (id, orig, dest, salida, llegada, estado) VALUES (?, ?, ?, ?, ?, ?);";
+    let values = vec![
+        ColData::Int(flight_id as Int),
+        ColData::String(cur_iata_code.to_string()),
+        ColData::String(ex_iata_code.to_string()),
+        ColData::Timestamp(timestamp),
+        ColData::Timestamp(eta),
+        ColData::String(FlightState::Preparing.to_string()),
+    ];
 
-    send_client_query(con_info, inc_fl_query.as_str())?;
-    send_client_query(con_info, dep_fl_query.as_str())?;
+    send_client_query_with_values(con_info, query, &values)?;
 
     Ok(())
 }
 
-/// Manda una _query_ para borrar el vuelo por su ID.
+/// Manda una _query_ para borrar el vuelo por su aeropuerto de origen y su ID.
 ///
 /// Se asume que en la conexión, uno ya se encuentra logueado.
-pub fn delete_flight_by_id(con_info: &mut ConnectionHolder, flight_id: Int) -> Result<()> {
-    let inc_delete = format!("DELETE FROM vuelos_entrantes WHERE id = {flight_id};");
-    let dep_delete = format!("DELETE FROM vuelos_salientes WHERE id = {flight_id};");
+pub fn delete_flight_by_id(
+    con_info: &mut ConnectionHolder,
+    orig: &str,
+    flight_id: Int,
+) -> Result<()> {
+    let delete =
+        format!("DELETE FROM vuelos_salientes WHERE orig = '{orig}' AND id = {flight_id};");
 
-    send_client_query(con_info, inc_delete.as_str())?;
-    send_client_query(con_info, dep_delete.as_str())?;
+    send_client_query(con_info, delete.as_str())?;
 
     Ok(())
 }
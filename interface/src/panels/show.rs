@@ -23,8 +23,8 @@ use {
     },
 };
 
-/// IDs de aeropuertos a borrar después.
-pub type DeleteQueue = HashSet<Int>;
+/// Vuelos a borrar después, según su aeropuerto de origen y su ID.
+pub type DeleteQueue = HashSet<(String, Int)>;
 
 /// Muestra por un panel lateral los detalles del aeropuerto actualmente seleccionado.
 pub fn cur_airport_info(
@@ -102,8 +102,8 @@ pub fn cur_airport_info(
     });
 
     if !delete_queue.is_empty() {
-        for flight_id in delete_queue {
-            let _ = delete_flight_by_id(con_info, flight_id);
+        for (orig, flight_id) in delete_queue {
+            let _ = delete_flight_by_id(con_info, &orig, flight_id);
         }
     }
 
@@ -309,7 +309,7 @@ fn show_flights(
                             widget_details.flight_editor = Some(FlightEditorWindow::from(flight));
                         }
                         if hor_ui.button(RichText::new("Delete").heading()).clicked() {
-                            delete_queue.insert(flight.id);
+                            delete_queue.insert((flight.orig.clone(), flight.id));
                         }
                     });
                     scroll_ui.add_space(interspace);
@@ -391,7 +391,7 @@ impl FlightsLoader {
                 timestamp - (DAY_IN_SECONDS / 2),
             ),
             FlightType::Departing => format!(
-                "SELECT * FROM vuelos_salientes WHERE orig = '{}' AND salida < {} AND salida > {};",
+                "SELECT id, orig, dest, salida, estado FROM vuelos_salientes WHERE orig = '{}' AND salida < {} AND salida > {};",
                 iata_code,
                 timestamp + (DAY_IN_SECONDS / 2),
                 timestamp - (DAY_IN_SECONDS / 2),
@@ -2,7 +2,7 @@
 
 use {
     client::conn_holder::ConnectionHolder,
-    data::protocol_result::ProtocolResult,
+    data::{col_data::ColData, protocol_result::ProtocolResult},
     protocol::{aliases::results::Result, errors::error::Error},
 };
 
@@ -34,3 +34,37 @@ pub fn send_client_query(con_info: &mut ConnectionHolder, query: &str) -> Result
 
     Ok(())
 }
+
+/// Manda una _query_ con marcadores, vinculándolos con los valores dados, en orden.
+///
+/// Se asume que en la conexión, uno ya se encuentra logueado.
+/// Si se reconecta, devuelve el nuevo _TLS Stream_.
+pub fn send_client_query_with_values(
+    con_info: &mut ConnectionHolder,
+    query: &str,
+    values: &[ColData],
+) -> Result<()> {
+    let client_lock = con_info.get_cli();
+
+    let mut client = match client_lock.lock() {
+        Ok(cli) => cli,
+        Err(poison_err) => {
+            client_lock.clear_poison();
+            return Err(Error::ServerError(format!(
+                "Error de lock envenenado tratando de leer un cliente:\n\n{poison_err}"
+            )));
+        }
+    };
+
+    let (protocol_result, mut new_tls_opt) =
+        client.send_query_with_values(query, values, &mut con_info.tls_stream)?;
+    if let Some(new_tls) = new_tls_opt.take() {
+        con_info.tls_stream = new_tls;
+    }
+
+    if let ProtocolResult::QueryError(err) = protocol_result {
+        println!("{err}");
+    }
+
+    Ok(())
+}
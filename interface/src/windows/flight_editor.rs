@@ -10,7 +10,6 @@ use {
         traits::PrettyShow,
     },
     eframe::egui::{Align2, Color32, ComboBox, Frame, Key, RichText, Ui, Window},
-    protocol::aliases::results::Result,
 };
 
 /// Editor para modificar detalles de un vuelo en curso y sus datos en vivo.
@@ -49,17 +48,6 @@ impl FlightEditorWindow {
         }
     }
 
-    /// Manda queries tanto a los vuelos entrantes como los salientes.
-    fn inc_dep_queries(
-        con_info: &mut ConnectionHolder,
-        inc_query: &str,
-        dep_query: &str,
-    ) -> Result<()> {
-        send_client_query(con_info, inc_query)?;
-        send_client_query(con_info, dep_query)?;
-        Ok(())
-    }
-
     /// Muestra la ventana del editor.
     pub fn show(
         &mut self,
@@ -104,16 +92,11 @@ impl FlightEditorWindow {
                         if hor_ui.text_edit_singleline(&mut self.orig).lost_focus()
                             && hor_ui.input(|i| i.key_pressed(Key::Enter))
                         {
-                            if let Err(err) = Self::inc_dep_queries(
+                            if let Err(err) = send_client_query(
                                 con_info,
                                 format!(
-                                    "UPDATE vuelos_entrantes SET orig = '{}' WHERE id = {};",
-                                    self.orig, flight.id,
-                                )
-                                .as_str(),
-                                format!(
-                                    "UPDATE vuelos_salientes SET orig = '{}' WHERE id = {};",
-                                    self.orig, flight.id,
+                                    "UPDATE vuelos_salientes SET orig = '{}' WHERE orig = '{}' AND id = {};",
+                                    self.orig, flight.orig, flight.id,
                                 )
                                 .as_str(),
                             ) {
@@ -132,16 +115,11 @@ impl FlightEditorWindow {
                         if hor_ui.text_edit_singleline(&mut self.dest).lost_focus()
                             && hor_ui.input(|i| i.key_pressed(Key::Enter))
                         {
-                            if let Err(err) = Self::inc_dep_queries(
+                            if let Err(err) = send_client_query(
                                 con_info,
                                 format!(
-                                    "UPDATE vuelos_entrantes SET dest = '{}' WHERE id = {};",
-                                    self.dest, flight.id,
-                                )
-                                .as_str(),
-                                format!(
-                                    "UPDATE vuelos_salientes SET dest = '{}' WHERE id = {};",
-                                    self.dest, flight.id,
+                                    "UPDATE vuelos_salientes SET dest = '{}' WHERE orig = '{}' AND id = {};",
+                                    self.dest, flight.orig, flight.id,
                                 )
                                 .as_str(),
                             ) {
@@ -174,19 +152,13 @@ impl FlightEditorWindow {
                                 FlightType::Incoming => "llegada",
                                 FlightType::Departing => "salida",
                             };
-                            if let Err(err) = Self::inc_dep_queries(
+                            if let Err(err) = send_client_query(
                                 con_info,
                                 format!(
-                                    "UPDATE vuelos_entrantes SET {} = {} WHERE id = {};",
-                                    timestamp_col,
-                                    local_date.timestamp(),
-                                    flight.id,
-                                )
-                                .as_str(),
-                                format!(
-                                    "UPDATE vuelos_salientes SET {} = {} WHERE id = {};",
+                                    "UPDATE vuelos_salientes SET {} = {} WHERE orig = '{}' AND id = {};",
                                     timestamp_col,
                                     local_date.timestamp(),
+                                    flight.orig,
                                     flight.id,
                                 )
                                 .as_str(),
@@ -234,16 +206,11 @@ impl FlightEditorWindow {
                                 );
                             });
                         if self.state != flight.state {
-                            if let Err(err) = Self::inc_dep_queries(
+                            if let Err(err) = send_client_query(
                                 con_info,
                                 format!(
-                                    "UPDATE vuelos_entrantes SET estado = '{}' WHERE id = {};",
-                                    self.state, flight.id,
-                                )
-                                .as_str(),
-                                format!(
-                                    "UPDATE vuelos_salientes SET estado = '{}' WHERE id = {};",
-                                    self.state, flight.id,
+                                    "UPDATE vuelos_salientes SET estado = '{}' WHERE orig = '{}' AND id = {};",
+                                    self.state, flight.orig, flight.id,
                                 )
                                 .as_str(),
                             ) {
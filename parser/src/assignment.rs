@@ -1,6 +1,8 @@
 use crate::{
     data_types::{
-        identifier::identifier_mod::Identifier, literal::list_literal::ListLiteral, term::Term,
+        identifier::identifier_mod::Identifier,
+        literal::list_literal::ListLiteral,
+        term::{BindFn, Term},
     },
     statements::ddl_statement::ddl_statement_parser::check_words,
 };
@@ -9,16 +11,25 @@ use protocol::{aliases::results::Result, errors::error::Error};
 /// assignment: simple_selection'=' term
 /// `| column_name'=' column_name ( '+' | '-' ) term
 /// | column_name'=' list_literal'+' column_name
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Assignment {
     /// simple_selection'=' term
     ColumnNameTerm(Identifier, Term),
     /// `| column_name'=' column_name ( '+' | '-' ) term
-    ColumnNameColTerm(Identifier, Identifier, Term),
+    ColumnNameColTerm(Identifier, Identifier, ArithmeticOperator, Term),
     /// | column_name'=' list_literal'+' column_name
     ColumnNameListCol(Identifier, ListLiteral, Identifier),
 }
 
+/// El operador de un assignment `column_name '=' column_name ( '+' | '-' ) term`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArithmeticOperator {
+    /// '+'
+    Plus,
+    /// '-'
+    Minus,
+}
+
 impl Assignment {
     /// Revisa que tipo de Assignment tiene el proximo valor de la lista, si el primer tipo de valor no es el esperado entonces devuelve None.
     /// Una vez comprobado que el primer parametro es correcto, entonces en cualquier caso donde se encuentre un error o
@@ -39,6 +50,7 @@ impl Assignment {
                 column_name,
                 values.0,
                 values.1,
+                values.2,
             )));
         }
         if let Some(values) = Assignment::check_column_name_list_col(lista)? {
@@ -53,6 +65,17 @@ impl Assignment {
         ))
     }
 
+    /// Vincula el término asignado, si es un marcador, con un valor para la columna asignada.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<()> {
+        match self {
+            Assignment::ColumnNameTerm(column_name, term)
+            | Assignment::ColumnNameColTerm(column_name, _, _, term) => {
+                term.bind(column_name.get_name(), bind)
+            }
+            Assignment::ColumnNameListCol(..) => Ok(()),
+        }
+    }
+
     fn check_column_name_term(lista: &mut Vec<String>) -> Result<Option<Term>> {
         let term = match Term::is_term(lista)? {
             Some(value) => value,
@@ -61,19 +84,25 @@ impl Assignment {
         Ok(Some(term))
     }
 
-    fn check_column_name_col_term(lista: &mut Vec<String>) -> Result<Option<(Identifier, Term)>> {
+    fn check_column_name_col_term(
+        lista: &mut Vec<String>,
+    ) -> Result<Option<(Identifier, ArithmeticOperator, Term)>> {
         let column_name = match Identifier::check_identifier(lista)? {
             Some(value) => value,
             None => return Ok(None),
         };
-        if !check_words(lista, "+") && !check_words(lista, "-") {
+        let operator = if check_words(lista, "+") {
+            ArithmeticOperator::Plus
+        } else if check_words(lista, "-") {
+            ArithmeticOperator::Minus
+        } else {
             return Err(Error::SyntaxError("Falto un '+' o '-'".to_string()));
-        }
+        };
         let term = match Term::is_term(lista)? {
             Some(value) => value,
             None => return Err(Error::SyntaxError("Tipo de dato incorrecto".to_string())),
         };
-        Ok(Some((column_name, term)))
+        Ok(Some((column_name, operator, term)))
     }
 
     fn check_column_name_list_col(
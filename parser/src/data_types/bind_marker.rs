@@ -0,0 +1,54 @@
+/// Marcador de una variable, cuyo valor se vincula al momento de ejecutar la _query_.
+///
+/// bind_marker::= '?' | ':' identifier
+#[derive(Debug, Clone, PartialEq)]
+pub enum BindMarker {
+    /// Marcador posicional, `?`.
+    Positional,
+
+    /// Marcador con nombre, `:nombre`.
+    Named(String),
+}
+
+impl BindMarker {
+    /// Verifica si el token es un marcador. Si lo es, lo retorna.
+    pub fn from_token(token: &str) -> Option<Self> {
+        if token == "?" {
+            return Some(BindMarker::Positional);
+        }
+        let name = token.strip_prefix(':')?;
+        let mut chars = name.chars();
+        match chars.next() {
+            Some(first)
+                if first.is_ascii_alphabetic()
+                    && chars.all(|c| c.is_ascii_alphanumeric() || c == '_') =>
+            {
+                Some(BindMarker::Named(name.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    /// Verifica si la lista de tokens empieza con un marcador. Si es así, lo consume y lo retorna.
+    pub fn is_bind_marker(lista: &mut Vec<String>) -> Option<Self> {
+        let marker = BindMarker::from_token(lista.first()?)?;
+        lista.remove(0);
+        Some(marker)
+    }
+
+    /// Devuelve el nombre del marcador, si es que tiene uno.
+    pub fn get_name(&self) -> Option<&str> {
+        match self {
+            BindMarker::Positional => None,
+            BindMarker::Named(name) => Some(name),
+        }
+    }
+
+    /// Devuelve el marcador tal cual se escribe en la _query_.
+    pub fn get_value_as_string(&self) -> String {
+        match self {
+            BindMarker::Positional => "?".to_string(),
+            BindMarker::Named(name) => format!(":{name}"),
+        }
+    }
+}
@@ -63,6 +63,41 @@ impl Constant {
         }
     }
 
+    /// Crea la constante de un número con el mismo tipo que le daría el parser al leerlo: entero
+    /// si no tiene decimales y entra en un [Int], o si no [Double](Constant::Double).
+    pub fn from_number(number: Double) -> Self {
+        if number.fract() == 0.0 && number >= Int::MIN as Double && number <= Int::MAX as Double {
+            Constant::Integer(number as Int)
+        } else {
+            Constant::Double(number)
+        }
+    }
+
+    /// Devuelve la constante escrita como un literal de CQL, tal que el parser la vuelva a leer
+    /// con el mismo valor.
+    pub fn as_cql(&self) -> String {
+        match self {
+            // Las comillas del valor se duplican para que el tokenizador no corte el string
+            Constant::String(value) => format!("'{}'", value.replace('\'', "''")),
+            Constant::Integer(value) => value.to_string(),
+            Constant::Double(value) => value.to_string(),
+            Constant::Boolean(value) => value.to_string().to_uppercase(),
+            Constant::Uuid(value) => {
+                let hex = format!("{value:032x}");
+                format!(
+                    "{}-{}-{}-{}-{}",
+                    &hex[0..8],
+                    &hex[8..12],
+                    &hex[12..16],
+                    &hex[16..20],
+                    &hex[20..32]
+                )
+            }
+            Constant::Blob(value) => format!("0x{value:x}"),
+            Constant::NULL => "NULL".to_string(),
+        }
+    }
+
     /// Recibe un vector de tokens y verifica si es una constante, si lo es, la retorna.
     /// Si no es una constante, retorna None, o Error en caso de no poder parsearlo.
     pub fn is_constant(lista: &mut Vec<String>) -> Result<Option<Constant>> {
@@ -33,6 +33,14 @@ impl Identifier {
         }
     }
 
+    /// Devuelve el identificador escrito como en una _query_, entre comillas dobles si las tenía.
+    pub fn as_cql(&self) -> String {
+        match self {
+            Identifier::QuotedIdentifier(id) => format!("\"{}\"", id.get_name()),
+            Identifier::UnquotedIdentifier(id) => id.get_name().to_string(),
+        }
+    }
+
     /// Verifica si la lista de tokens es un identificador. Si lo es, lo retorna.
     /// Si no lo es, retorna None.
     pub fn check_identifier(lista: &mut Vec<String>) -> Result<Option<Identifier>> {
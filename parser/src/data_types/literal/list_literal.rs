@@ -2,7 +2,7 @@ use crate::{data_types::term::Term, statements::ddl_statement::ddl_statement_par
 use protocol::{aliases::results::Result, errors::error::Error};
 
 /// Literal de tipo lista.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct ListLiteral {
     /// Valores de la lista, términos.
     values: Vec<Term>,
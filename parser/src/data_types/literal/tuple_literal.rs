@@ -4,7 +4,7 @@ use protocol::{aliases::results::Result, errors::error::Error};
 /// Literal de tipo tupla.
 ///
 /// tuple_literal::= '(' term( ',' term )* ')'
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TupleLiteral {
     /// Elementos de la tupla, términos.
     pub items: Vec<Term>,
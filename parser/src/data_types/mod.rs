@@ -1,5 +1,7 @@
 //! Paquete de módulos que contienen los tipos de datos que se pueden utilizar en la gramática de CQL.
 
+/// Módulo que contiene los marcadores de variables.
+pub mod bind_marker;
 /// Módulo que contiene el tipo de dato `Constant`.
 pub mod constant;
 /// Módulo que contiene los tipos de _CQL_.
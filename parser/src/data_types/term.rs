@@ -1,18 +1,32 @@
 use {
-    crate::data_types::constant::Constant, protocol::aliases::results::Result, std::cmp::Ordering,
+    crate::data_types::{bind_marker::BindMarker, constant::Constant},
+    protocol::{
+        aliases::{results::Result, types::Long},
+        errors::error::Error,
+    },
+    std::cmp::Ordering,
 };
 
+/// Función que devuelve el valor a vincular con un marcador, dado el nombre de la columna o de
+/// la cláusula a la que corresponde.
+pub type BindFn<'a> = dyn FnMut(&BindMarker, &str) -> Result<Constant> + 'a;
+
 /// Término de una expresión.
 #[derive(Debug, Clone)]
 pub enum Term {
     /// Constante.
     Constant(Constant),
+
+    /// Marcador de una variable, a vincular antes de ejecutar la _query_.
+    BindMarker(BindMarker),
 }
 
 impl PartialEq for Term {
     fn eq(&self, other: &Self) -> bool {
         match (self, other) {
             (Term::Constant(c1), Term::Constant(c2)) => c1 == c2,
+            (Term::BindMarker(m1), Term::BindMarker(m2)) => m1 == m2,
+            _ => false,
         }
     }
 }
@@ -22,6 +36,7 @@ impl Term {
     pub fn get_value(&self) -> String {
         match self {
             Term::Constant(c) => c.get_value(),
+            Term::BindMarker(marker) => marker.get_value_as_string(),
         }
     }
 
@@ -31,13 +46,54 @@ impl Term {
         if let Some(constant) = Constant::is_constant(lista)? {
             return Ok(Some(Term::Constant(constant)));
         }
+        if let Some(marker) = BindMarker::is_bind_marker(lista) {
+            return Ok(Some(Term::BindMarker(marker)));
+        }
         Ok(None)
     }
 
+    /// Obtiene el número entero del término, para las cláusulas que reciben un número en vez del
+    /// valor de una columna, como `LIMIT` o `USING TTL`.
+    ///
+    /// Los números que no entran en un [Int](protocol::aliases::types::Int) se parsean como
+    /// [Double](Constant::Double), así que también se aceptan si no tienen decimales.
+    pub fn get_integer_value(&self, clause: &str) -> Result<Long> {
+        match self {
+            Term::Constant(Constant::Integer(value)) => Ok(*value as Long),
+            Term::Constant(Constant::Double(value)) if value.fract() == 0.0 => Ok(*value as Long),
+            Term::BindMarker(marker) => Err(Error::Invalid(format!(
+                "El marcador {} del {clause} no tiene un valor vinculado",
+                marker.get_value_as_string()
+            ))),
+            Term::Constant(_) => Err(Error::SyntaxError(format!(
+                "El valor brindado al {clause} no es un numero"
+            ))),
+        }
+    }
+
+    /// Si el término es un marcador, lo reemplaza por la constante que devuelve `bind` para él.
+    ///
+    /// `slot` es el nombre de la columna o de la cláusula a la que corresponde el término.
+    pub fn bind(&mut self, slot: &str, bind: &mut BindFn) -> Result<()> {
+        if let Term::BindMarker(marker) = self {
+            *self = Term::Constant(bind(marker, slot)?);
+        }
+        Ok(())
+    }
+
+    /// Devuelve el término escrito como en una _query_.
+    pub fn as_cql(&self) -> String {
+        match self {
+            Term::Constant(constant) => constant.as_cql(),
+            Term::BindMarker(marker) => marker.get_value_as_string(),
+        }
+    }
+
     /// Devuelve el valor del término como un String.
     pub fn get_value_as_string(&self) -> String {
         match self {
             Term::Constant(constant) => constant.get_value_as_string(),
+            Term::BindMarker(marker) => marker.get_value_as_string(),
         }
     }
 }
@@ -46,6 +102,8 @@ impl PartialOrd for Term {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
             (Term::Constant(c1), Term::Constant(c2)) => c1.partial_cmp(c2),
+            // Un marcador sin vincular no tiene valor con el cual comparar
+            _ => None,
         }
     }
 }
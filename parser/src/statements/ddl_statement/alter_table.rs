@@ -10,8 +10,8 @@ pub enum AlterTableInstruction {
     AddColumns(bool, Vec<ColumnDefinition>),
     /// Eliminar columnas de la tabla.
     DropColumns(bool, Vec<String>),
-    /// Modificar opciones de la tabla.
-    WithOptions(bool, Vec<Options>),
+    /// Modificar opciones de la tabla, cada una junto a su nombre.
+    WithOptions(bool, Vec<(String, Options)>),
     /// Renombrar columnas en la tabla.
     RenameColumns(bool, Vec<(String, String)>),
 }
@@ -0,0 +1,43 @@
+use crate::{data_types::identifier::identifier_mod::Identifier, table_name::TableName};
+
+/// Representa una sentencia CQL `CREATE INDEX`.
+#[derive(Debug)]
+pub struct CreateIndex {
+    /// Indica si la declaración contiene la cláusula `IF NOT EXISTS`.
+    pub if_not_exists: bool,
+    /// Nombre del índice, si se indicó uno.
+    pub name: Option<Identifier>,
+    /// Tabla sobre la que se crea el índice.
+    pub table_name: TableName,
+    /// Columna indexada.
+    pub column: Identifier,
+}
+
+impl CreateIndex {
+    /// Crea una nueva instancia de `CreateIndex`.
+    pub fn new(
+        if_not_exists: bool,
+        name: Option<Identifier>,
+        table_name: TableName,
+        column: Identifier,
+    ) -> Self {
+        CreateIndex {
+            if_not_exists,
+            name,
+            table_name,
+            column,
+        }
+    }
+
+    /// Devuelve el nombre del índice. Si no se indicó uno, se usa `<tabla>_<columna>_idx`.
+    pub fn get_index_name(&self) -> String {
+        match &self.name {
+            Some(name) => name.get_name().to_string(),
+            None => format!(
+                "{}_{}_idx",
+                self.table_name.get_name(),
+                self.column.get_name()
+            ),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use crate::{primary_key::PrimaryKey, table_name::TableName};
+
+/// Representa una sentencia CQL `CREATE MATERIALIZED VIEW`.
+#[derive(Debug)]
+pub struct CreateMaterializedView {
+    /// Indica si la declaración contiene la cláusula `IF NOT EXISTS`.
+    pub if_not_exists: bool,
+    /// Nombre de la vista a crear.
+    pub name: TableName,
+    /// Columnas de la tabla base que guarda la vista. Si está vacío, se seleccionaron todas con `*`.
+    pub columns: Vec<String>,
+    /// Tabla base de la que se derivan las filas de la vista.
+    pub base_table: TableName,
+    /// Columnas que el `WHERE` pide que no sean nulas.
+    pub not_null_columns: Vec<String>,
+    /// Clave primaria de la vista.
+    pub primary_key: PrimaryKey,
+    /// Orden de agrupamiento de las columnas.
+    pub clustering_order: Option<Vec<(String, String)>>,
+}
+
+impl CreateMaterializedView {
+    /// Crea una nueva instancia de `CreateMaterializedView`.
+    pub fn new(
+        if_not_exists: bool,
+        name: TableName,
+        columns: Vec<String>,
+        base_table: TableName,
+        not_null_columns: Vec<String>,
+        primary_key: PrimaryKey,
+        clustering_order: Option<Vec<(String, String)>>,
+    ) -> Self {
+        CreateMaterializedView {
+            if_not_exists,
+            name,
+            columns,
+            base_table,
+            not_null_columns,
+            primary_key,
+            clustering_order,
+        }
+    }
+
+    /// Obtiene el nombre de la vista.
+    pub fn get_name(&self) -> String {
+        self.name.get_name()
+    }
+
+    /// Obtiene el nombre del keyspace al que pertenece la vista.
+    pub fn get_keyspace(&self) -> Option<String> {
+        self.name.get_keyspace()
+    }
+}
@@ -5,9 +5,13 @@ use crate::{
         alter_keyspace::AlterKeyspace,
         alter_table::{AlterTable, AlterTableInstruction},
         column_definition::ColumnDefinition,
+        create_index::CreateIndex,
         create_keyspace::CreateKeyspace,
+        create_materialized_view::CreateMaterializedView,
         create_table::CreateTable,
+        drop_index::DropIndex,
         drop_keyspace::DropKeyspace,
+        drop_materialized_view::DropMaterializedView,
         drop_table::DropTable,
         option::Options,
         truncate::Truncate,
@@ -24,6 +28,10 @@ use protocol::{aliases::results::Result, errors::error::Error};
 ///         | alter_table_statement
 ///         | drop_table_statement
 ///         | truncate_statement
+///         | create_index_statement
+///         | drop_index_statement
+///         | create_materialized_view_statement
+///         | drop_materialized_view_statement
 #[derive(Debug)]
 pub enum DdlStatement {
     /// use_statement::= USE keyspace_name
@@ -54,6 +62,23 @@ pub enum DdlStatement {
 
     /// truncate_statement::= TRUNCATE [ TABLE ] table_name
     TruncateStatement(Truncate),
+
+    /// create_index_statement::= CREATE INDEX [ IF NOT EXISTS ] [ index_name ]
+    /// ON table_name '(' column_name ')'
+    CreateIndexStatement(CreateIndex),
+
+    /// drop_index_statement::= DROP INDEX [ IF EXISTS ] [ keyspace_name '.' ] index_name
+    DropIndexStatement(DropIndex),
+
+    /// create_materialized_view_statement::= CREATE MATERIALIZED VIEW [ IF NOT EXISTS ] view_name
+    /// AS SELECT ( '*' | column_name ( ',' column_name )* ) FROM table_name
+    /// [ WHERE column_name IS NOT NULL ( AND column_name IS NOT NULL )* ]
+    /// PRIMARY KEY '(' primary_key ')'
+    /// [ WITH CLUSTERING ORDER BY '(' column_name ( ASC | DESC ) ( ',' column_name ( ASC | DESC ) )* ')' ]
+    CreateMaterializedViewStatement(CreateMaterializedView),
+
+    /// drop_materialized_view_statement::= DROP MATERIALIZED VIEW [ IF EXISTS ] view_name
+    DropMaterializedViewStatement(DropMaterializedView),
 }
 
 /// Crea el enum `DdlStatement` con el tipo de struct de acuerdo a la sintaxis dada, si la entrada proporcionada no satisface
@@ -75,6 +100,18 @@ pub fn ddl_statement(list: &mut Vec<String>) -> Result<Option<DdlStatement>> {
         return Ok(Some(DdlStatement::DropTableStatement(parsed_value)));
     } else if let Some(parsed_value) = truncate_statement(list)? {
         return Ok(Some(DdlStatement::TruncateStatement(parsed_value)));
+    } else if let Some(parsed_value) = create_index_statement(list)? {
+        return Ok(Some(DdlStatement::CreateIndexStatement(parsed_value)));
+    } else if let Some(parsed_value) = drop_index_statement(list)? {
+        return Ok(Some(DdlStatement::DropIndexStatement(parsed_value)));
+    } else if let Some(parsed_value) = create_materialized_view_statement(list)? {
+        return Ok(Some(DdlStatement::CreateMaterializedViewStatement(
+            parsed_value,
+        )));
+    } else if let Some(parsed_value) = drop_materialized_view_statement(list)? {
+        return Ok(Some(DdlStatement::DropMaterializedViewStatement(
+            parsed_value,
+        )));
     }
     Ok(None)
 }
@@ -267,6 +304,238 @@ fn truncate_statement(list: &mut Vec<String>) -> Result<Option<Truncate>> {
     Ok(None)
 }
 
+fn create_index_statement(list: &mut Vec<String>) -> Result<Option<CreateIndex>> {
+    if check_words(list, "CREATE INDEX") {
+        let if_not_exists = check_words(list, "IF NOT EXISTS");
+
+        let name = if list.first().is_some_and(|word| word != "ON") {
+            match Identifier::check_identifier(list)? {
+                Some(name) => Some(name),
+                None => {
+                    return Err(Error::SyntaxError(
+                        "El nombre del índice no es válido".to_string(),
+                    ))
+                }
+            }
+        } else {
+            None
+        };
+
+        if !check_words(list, "ON") {
+            return Err(Error::SyntaxError(
+                "Se esperaba la palabra clave 'ON'".to_string(),
+            ));
+        }
+
+        let table_name = match TableName::check_kind_of_name(list)? {
+            Some(value) => value,
+            None => {
+                return Err(Error::SyntaxError(
+                    "Falta el nombre de la tabla".to_string(),
+                ))
+            }
+        };
+
+        if !check_words(list, "(") {
+            return Err(Error::SyntaxError(
+                "Falta el paréntesis de apertura".to_string(),
+            ));
+        }
+        let column = match list.first() {
+            Some(word) if word != ")" => Identifier::new(list.remove(0)),
+            _ => {
+                return Err(Error::SyntaxError(
+                    "Se esperaba la columna a indexar".to_string(),
+                ))
+            }
+        };
+        if !check_words(list, ")") {
+            return Err(Error::SyntaxError(
+                "Falta el paréntesis de cierre".to_string(),
+            ));
+        }
+
+        return Ok(Some(CreateIndex::new(
+            if_not_exists,
+            name,
+            table_name,
+            column,
+        )));
+    }
+    Ok(None)
+}
+
+fn drop_index_statement(list: &mut Vec<String>) -> Result<Option<DropIndex>> {
+    if check_words(list, "DROP INDEX") {
+        let if_exists = check_words(list, "IF EXISTS");
+
+        if list.is_empty() {
+            return Err(Error::SyntaxError("Falta el nombre del índice".to_string()));
+        }
+        // Igual que con las tablas, el tokenizador deja `keyspace.indice` en una misma palabra
+        let mut keyspace = None;
+        if let Some((keyspace_name, index_name)) = list[0].clone().split_once('.') {
+            keyspace = Some(keyspace_name.to_string());
+            list[0] = index_name.to_string();
+        }
+        let name = Identifier::new(list.remove(0));
+
+        return Ok(Some(DropIndex::new(if_exists, keyspace, name)));
+    }
+    Ok(None)
+}
+
+fn create_materialized_view_statement(
+    list: &mut Vec<String>,
+) -> Result<Option<CreateMaterializedView>> {
+    if check_words(list, "CREATE MATERIALIZED VIEW") {
+        let if_not_exists = check_words(list, "IF NOT EXISTS");
+
+        let name = match TableName::check_kind_of_name(list)? {
+            Some(name) => name,
+            None => {
+                return Err(Error::SyntaxError(
+                    "No se indicó el nombre de la vista".to_string(),
+                ))
+            }
+        };
+
+        if !check_words(list, "AS SELECT") {
+            return Err(Error::SyntaxError(
+                "Se esperaba 'AS SELECT' luego del nombre de la vista".to_string(),
+            ));
+        }
+        let columns = parse_view_columns(list)?;
+
+        if !check_words(list, "FROM") {
+            return Err(Error::SyntaxError(
+                "Se esperaba la palabra clave 'FROM'".to_string(),
+            ));
+        }
+        let base_table = match TableName::check_kind_of_name(list)? {
+            Some(value) => value,
+            None => {
+                return Err(Error::SyntaxError(
+                    "Falta el nombre de la tabla base".to_string(),
+                ))
+            }
+        };
+
+        let mut not_null_columns = Vec::new();
+        if check_words(list, "WHERE") {
+            loop {
+                if list.is_empty() {
+                    return Err(Error::SyntaxError(
+                        "Falta la columna en el WHERE de la vista".to_string(),
+                    ));
+                }
+                not_null_columns.push(list.remove(0));
+                if !check_words(list, "IS NOT NULL") {
+                    return Err(Error::SyntaxError(
+                        "El WHERE de una vista solo admite condiciones 'IS NOT NULL'".to_string(),
+                    ));
+                }
+                if !check_words(list, "AND") {
+                    break;
+                }
+            }
+        }
+
+        let primary_key = match parse_primary_key(list)? {
+            Some(primary_key) => primary_key,
+            None => {
+                return Err(Error::SyntaxError(
+                    "La vista necesita una PRIMARY KEY".to_string(),
+                ))
+            }
+        };
+        // Con una sola columna en la clave, `PrimaryKey::parse` no consume el paréntesis de cierre
+        check_words(list, ")");
+
+        let mut clustering_order = None;
+        if check_words(list, "WITH CLUSTERING ORDER BY") {
+            clustering_order = Some(parse_view_clustering_order(list)?);
+        }
+
+        return Ok(Some(CreateMaterializedView::new(
+            if_not_exists,
+            name,
+            columns,
+            base_table,
+            not_null_columns,
+            primary_key,
+            clustering_order,
+        )));
+    }
+    Ok(None)
+}
+
+fn drop_materialized_view_statement(
+    list: &mut Vec<String>,
+) -> Result<Option<DropMaterializedView>> {
+    if check_words(list, "DROP MATERIALIZED VIEW") {
+        let view_name = match TableName::check_kind_of_name(list)? {
+            Some(value) => value,
+            None => {
+                return Err(Error::SyntaxError(
+                    "Falta el nombre de la vista".to_string(),
+                ))
+            }
+        };
+        return Ok(Some(DropMaterializedView::new(view_name)));
+    }
+    Ok(None)
+}
+
+/// Lee las columnas del `SELECT` de una vista. Devuelve una lista vacía si se usó `*`.
+fn parse_view_columns(list: &mut Vec<String>) -> Result<Vec<String>> {
+    if check_words(list, "*") {
+        return Ok(Vec::new());
+    }
+    let mut columns = Vec::new();
+    loop {
+        match list.first() {
+            Some(word) if word != "FROM" && word != "," => columns.push(list.remove(0)),
+            _ => {
+                return Err(Error::SyntaxError(
+                    "Se esperaba el nombre de una columna en el SELECT de la vista".to_string(),
+                ))
+            }
+        }
+        if !check_words(list, ",") {
+            break;
+        }
+    }
+    Ok(columns)
+}
+
+fn parse_view_clustering_order(list: &mut Vec<String>) -> Result<Vec<(String, String)>> {
+    if !check_words(list, "(") {
+        return Err(Error::SyntaxError(
+            "Falta el paréntesis de apertura en CLUSTERING ORDER BY".to_string(),
+        ));
+    }
+    let mut order = Vec::new();
+    loop {
+        if list.len() < 2 {
+            return Err(Error::SyntaxError(
+                "Se esperaba una columna y su orden (ASC o DESC)".to_string(),
+            ));
+        }
+        let column = list.remove(0);
+        order.push((column, list.remove(0)));
+        if !check_words(list, ",") {
+            break;
+        }
+    }
+    if !check_words(list, ")") {
+        return Err(Error::SyntaxError(
+            "Falta el paréntesis de cierre en CLUSTERING ORDER BY".to_string(),
+        ));
+    }
+    Ok(order)
+}
+
 fn options(list: &mut Vec<String>) -> Result<Vec<Options>> {
     let mut options: Vec<Options> = Vec::new();
     match is_an_option(list)? {
@@ -404,7 +673,7 @@ fn parse_alter_table_instruction(list: &mut Vec<String>) -> Result<AlterTableIns
         Ok(AlterTableInstruction::DropColumns(if_exists, columns))
     } else if check_words(list, "WITH") {
         let if_exists = check_words(list, "IF EXISTS");
-        let options = options(list)?;
+        let options = named_table_options(list)?;
         Ok(AlterTableInstruction::WithOptions(if_exists, options))
     } else if check_words(list, "RENAME") {
         let if_exists = check_words(list, "IF EXISTS");
@@ -417,6 +686,36 @@ fn parse_alter_table_instruction(list: &mut Vec<String>) -> Result<AlterTableIns
     }
 }
 
+/// table_options::= identifier '=' option ( AND identifier '=' option )*
+fn named_table_options(list: &mut Vec<String>) -> Result<Vec<(String, Options)>> {
+    let mut options = Vec::new();
+    loop {
+        if list.len() < 3 {
+            return Err(Error::SyntaxError(
+                "Se esperaba una opción de la forma `nombre = valor`".to_string(),
+            ));
+        }
+        let name = match Identifier::check_identifier(list)? {
+            Some(identifier) => identifier.get_name().to_string(),
+            None => {
+                return Err(Error::SyntaxError(
+                    "Se esperaba el nombre de la opción".to_string(),
+                ))
+            }
+        };
+        if !check_words(list, "=") {
+            return Err(Error::SyntaxError(format!(
+                "Falto el '=' de la opción {name}"
+            )));
+        }
+        options.push((name, Options::check_options(list)?));
+        if !check_words(list, "AND") {
+            break;
+        }
+    }
+    Ok(options)
+}
+
 fn parse_column_renames(list: &mut Vec<String>) -> Result<Vec<(String, String)>> {
     let mut renames = Vec::new();
 
@@ -1093,6 +1392,28 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_07_alter_table_with_named_options() -> Result<()> {
+        let query =
+            "ALTER TABLE users WITH comment = 'vuelos en vivo' AND default_time_to_live = 3600";
+        let mut tokens = tokenize_query(query);
+
+        let result = alter_table_statement(&mut tokens)?;
+        let alter_table = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        let AlterTableInstruction::WithOptions(_, options) = alter_table.instruction else {
+            return Err(Error::SyntaxError(
+                "Se esperaba una instrucción WITH".into(),
+            ));
+        };
+        assert_eq!(options.len(), 2);
+        assert_eq!(options[0].0, "comment");
+        assert_eq!(options[0].1.get_value_as_string(), "vuelos en vivo");
+        assert_eq!(options[1].0, "default_time_to_live");
+        assert_eq!(options[1].1.get_value_as_string(), "3600");
+        Ok(())
+    }
+
     // DROP TABLE TESTS:
     #[test]
     fn test_01_basic_drop_table_statement() -> Result<()> {
@@ -1209,6 +1530,146 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_01_basic_create_index_statement() -> Result<()> {
+        let query = "CREATE INDEX ON vuelos (estado)";
+        let mut tokens = tokenize_query(query);
+
+        let result = create_index_statement(&mut tokens)?;
+        let index = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert!(!index.if_not_exists);
+        assert!(index.name.is_none());
+        assert_eq!(index.table_name.get_name(), "vuelos");
+        assert_eq!(index.column.get_name(), "estado");
+        assert_eq!(index.get_index_name(), "vuelos_estado_idx");
+        Ok(())
+    }
+
+    #[test]
+    fn test_02_create_index_with_name_and_keyspace() -> Result<()> {
+        let query = "CREATE INDEX IF NOT EXISTS idx_estado ON aviones.vuelos ( estado )";
+        let mut tokens = tokenize_query(query);
+
+        let result = create_index_statement(&mut tokens)?;
+        let index = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert!(index.if_not_exists);
+        assert_eq!(index.get_index_name(), "idx_estado");
+        assert_eq!(index.table_name.get_keyspace(), Some("aviones".to_string()));
+        assert_eq!(index.table_name.get_name(), "vuelos");
+        assert_eq!(index.column.get_name(), "estado");
+        assert!(tokens.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_03_invalid_create_index_statement() -> Result<()> {
+        let mut without_on = tokenize_query("CREATE INDEX idx vuelos (estado)");
+        assert!(create_index_statement(&mut without_on).is_err());
+
+        let mut without_column = tokenize_query("CREATE INDEX ON vuelos ()");
+        assert!(create_index_statement(&mut without_column).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_01_basic_drop_index_statement() -> Result<()> {
+        let query = "DROP INDEX IF EXISTS aviones.vuelos_estado_idx";
+        let mut tokens = tokenize_query(query);
+
+        let result = drop_index_statement(&mut tokens)?;
+        let index = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert!(index.if_exists);
+        assert_eq!(index.keyspace, Some("aviones".to_string()));
+        assert_eq!(index.name.get_name(), "vuelos_estado_idx");
+        Ok(())
+    }
+
+    #[test]
+    fn test_02_invalid_drop_index_statement() -> Result<()> {
+        let mut tokens = tokenize_query("DROP INDEX");
+        assert!(drop_index_statement(&mut tokens).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_01_basic_create_materialized_view_statement() -> Result<()> {
+        let query = "CREATE MATERIALIZED VIEW vuelos_entrantes AS SELECT * FROM vuelos_salientes WHERE dest IS NOT NULL AND id IS NOT NULL PRIMARY KEY ((dest), id)";
+        let mut tokens = tokenize_query(query);
+
+        let result = create_materialized_view_statement(&mut tokens)?;
+        let view = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert!(!view.if_not_exists);
+        assert_eq!(view.get_name(), "vuelos_entrantes");
+        assert!(view.columns.is_empty());
+        assert_eq!(view.base_table.get_name(), "vuelos_salientes");
+        assert_eq!(view.not_null_columns, vec!["dest", "id"]);
+        assert_eq!(view.primary_key.partition_key, vec!["dest"]);
+        assert_eq!(view.primary_key.clustering_columns, vec!["id"]);
+        assert!(view.clustering_order.is_none());
+        assert!(tokens.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_02_create_materialized_view_with_columns_and_clustering_order() -> Result<()> {
+        let query = "CREATE MATERIALIZED VIEW IF NOT EXISTS aviones.por_destino AS SELECT id, orig, dest FROM aviones.vuelos PRIMARY KEY (dest, id) WITH CLUSTERING ORDER BY (id DESC)";
+        let mut tokens = tokenize_query(query);
+
+        let result = create_materialized_view_statement(&mut tokens)?;
+        let view = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert!(view.if_not_exists);
+        assert_eq!(view.get_keyspace(), Some("aviones".to_string()));
+        assert_eq!(view.get_name(), "por_destino");
+        assert_eq!(view.columns, vec!["id", "orig", "dest"]);
+        assert_eq!(view.base_table.get_keyspace(), Some("aviones".to_string()));
+        assert_eq!(view.base_table.get_name(), "vuelos");
+        assert!(view.not_null_columns.is_empty());
+        assert_eq!(view.primary_key.partition_key, vec!["dest"]);
+        assert_eq!(view.primary_key.clustering_columns, vec!["id"]);
+        assert_eq!(
+            view.clustering_order,
+            Some(vec![("id".to_string(), "DESC".to_string())])
+        );
+        assert!(tokens.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_03_invalid_create_materialized_view_statement() -> Result<()> {
+        let mut without_select =
+            tokenize_query("CREATE MATERIALIZED VIEW v FROM vuelos PRIMARY KEY (id)");
+        assert!(create_materialized_view_statement(&mut without_select).is_err());
+
+        let mut without_primary_key =
+            tokenize_query("CREATE MATERIALIZED VIEW v AS SELECT * FROM vuelos");
+        assert!(create_materialized_view_statement(&mut without_primary_key).is_err());
+
+        let mut invalid_where = tokenize_query(
+            "CREATE MATERIALIZED VIEW v AS SELECT * FROM vuelos WHERE id = 3 PRIMARY KEY (id)",
+        );
+        assert!(create_materialized_view_statement(&mut invalid_where).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_01_basic_drop_materialized_view_statement() -> Result<()> {
+        let query = "DROP MATERIALIZED VIEW IF EXISTS aviones.vuelos_entrantes";
+        let mut tokens = tokenize_query(query);
+
+        let result = drop_materialized_view_statement(&mut tokens)?;
+        let view = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert!(view.view_name.if_exists);
+        assert_eq!(view.view_name.get_keyspace(), Some("aviones".to_string()));
+        assert_eq!(view.view_name.get_name(), "vuelos_entrantes");
+        Ok(())
+    }
+
     // EMPTY INPUT TESTS:
     #[test]
     fn test_01_keyspace_empty_input() -> Result<()> {
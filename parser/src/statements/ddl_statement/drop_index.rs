@@ -0,0 +1,23 @@
+use crate::data_types::identifier::identifier_mod::Identifier;
+
+/// Representa una sentencia CQL `DROP INDEX`.
+#[derive(Debug)]
+pub struct DropIndex {
+    /// Indica si la declaración contiene la cláusula `IF EXISTS`.
+    pub if_exists: bool,
+    /// Keyspace del índice, si se indicó uno.
+    pub keyspace: Option<String>,
+    /// Nombre del índice a eliminar.
+    pub name: Identifier,
+}
+
+impl DropIndex {
+    /// Crea una nueva instancia de `DropIndex`.
+    pub fn new(if_exists: bool, keyspace: Option<String>, name: Identifier) -> Self {
+        DropIndex {
+            if_exists,
+            keyspace,
+            name,
+        }
+    }
+}
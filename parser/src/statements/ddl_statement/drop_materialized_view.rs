@@ -0,0 +1,15 @@
+use crate::table_name::TableName;
+
+/// Representa una sentencia CQL `DROP MATERIALIZED VIEW`.
+#[derive(Debug)]
+pub struct DropMaterializedView {
+    /// Nombre de la vista a eliminar.
+    pub view_name: TableName,
+}
+
+impl DropMaterializedView {
+    /// Crea una nueva instancia de `DropMaterializedView`.
+    pub fn new(view_name: TableName) -> Self {
+        DropMaterializedView { view_name }
+    }
+}
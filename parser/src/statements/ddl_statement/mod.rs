@@ -4,14 +4,22 @@ pub mod alter_keyspace;
 pub mod alter_table;
 /// Módulo para definir columnas.
 pub mod column_definition;
+/// Módulo para crear índices secundarios.
+pub mod create_index;
 /// Módulo para crear keyspaces.
 pub mod create_keyspace;
+/// Módulo para crear vistas materializadas.
+pub mod create_materialized_view;
 /// Módulo para crear tablas.
 pub mod create_table;
 /// Módulo para definir declaraciones DDL.
 pub mod ddl_statement_parser;
+/// Módulo para eliminar índices secundarios.
+pub mod drop_index;
 /// Módulo para eliminar keyspaces.
 pub mod drop_keyspace;
+/// Módulo para eliminar vistas materializadas.
+pub mod drop_materialized_view;
 /// Módulo para eliminar tablas.
 pub mod drop_table;
 /// Módulo para definir opciones.
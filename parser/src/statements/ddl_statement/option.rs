@@ -36,4 +36,28 @@ impl Options {
             "Error de sintaxis en las opciones".to_string(),
         ))
     }
+
+    /// Devuelve el valor de la opción como un _String_.
+    ///
+    /// Los mapas se devuelven con la forma `{clave: valor, ...}`.
+    pub fn get_value_as_string(&self) -> String {
+        match self {
+            Options::Identifier(identifier) => identifier.get_name().to_string(),
+            Options::Constant(term) => term.get_value_as_string(),
+            Options::MapLiteral(map) => {
+                let entries: Vec<String> = map
+                    .get_values()
+                    .iter()
+                    .map(|(key, value)| {
+                        format!(
+                            "{}: {}",
+                            key.get_value_as_string(),
+                            value.get_value_as_string()
+                        )
+                    })
+                    .collect();
+                format!("{{{}}}", entries.join(", "))
+            }
+        }
+    }
 }
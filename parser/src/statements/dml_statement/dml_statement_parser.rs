@@ -1,7 +1,11 @@
 use crate::{
     assignment::Assignment,
     data_types::{
-        identifier::identifier_mod::Identifier, literal::tuple_literal::TupleLiteral, term::Term,
+        bind_marker::BindMarker,
+        constant::Constant,
+        identifier::{identifier_mod::Identifier, unquoted_identifier::UnquotedIdentifier},
+        literal::tuple_literal::TupleLiteral,
+        term::{BindFn, Term},
     },
     statements::{
         ddl_statement::ddl_statement_parser::check_words,
@@ -11,25 +15,26 @@ use crate::{
                 delete::Delete,
                 insert::Insert,
                 select::{
-                    group_by::GroupBy, kind_of_columns::KindOfColumns, limit::Limit,
-                    options::SelectOptions, order_by::OrderBy, ordering::ProtocolOrdering,
-                    per_partition_limit::PerPartitionLimit, select_operation::Select,
-                    selector::Selector,
+                    function_call::FunctionCall, group_by::GroupBy, kind_of_columns::KindOfColumns,
+                    limit::Limit, options::SelectOptions, order_by::OrderBy,
+                    ordering::ProtocolOrdering, per_partition_limit::PerPartitionLimit,
+                    select_operation::Select, selector::Selector,
                 },
                 update::Update,
             },
             r#where::{expression::expression, operator::Operator, r#where_parser::Where},
+            update_parameters::UpdateParameters,
         },
     },
     table_name::TableName,
 };
-use protocol::{aliases::types::Int, errors::error::Error};
+use protocol::errors::error::Error;
 
 /// dml_statement::= select_statement
 /// | insert_statement
 /// | update_statement
 /// | delete_statement
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum DmlStatement {
     /// select_statement::= SELECT [ JSON | DISTINCT ] ( select_clause | '*' )
     /// FROM `table_name`
@@ -43,9 +48,11 @@ pub enum DmlStatement {
     // Tampoco eñ JSON / DISTINCT
     /// insert_statement::= INSERT INTO table_name names_values
     /// [ IF NOT EXISTS ]
+    /// [ USING update_parameter ( AND update_parameter )* ]
     InsertStatement(Insert),
 
     /// update_statement ::= UPDATE table_name
+    ///                      [ USING update_parameter ( AND update_parameter )* ]
     ///                      SET assignment( ',' assignment )*
     ///                      WHERE where_clause
     ///                      [ IF ( EXISTS | condition ( AND condition)*) ]
@@ -53,11 +60,83 @@ pub enum DmlStatement {
 
     /// delete_statement::= DELETE [ simple_selection ( ',' simple_selection ) ]
     ///     FROM table_name
+    ///     [ USING update_parameter ( AND update_parameter )* ]
     ///     WHERE where_clause
     ///     [ IF ( EXISTS | condition ( AND condition)*) ]
     DeleteStatement(Delete),
 }
 
+impl DmlStatement {
+    /// Devuelve los parámetros de escritura indicados con `USING`, si la declaración es una escritura.
+    pub fn update_parameters(&self) -> Option<&UpdateParameters> {
+        match self {
+            DmlStatement::SelectStatement(_) => None,
+            DmlStatement::InsertStatement(insert) => Some(&insert.using),
+            DmlStatement::UpdateStatement(update) => Some(&update.using),
+            DmlStatement::DeleteStatement(delete) => Some(&delete.using),
+        }
+    }
+
+    /// Reemplaza cada marcador de la declaración por la constante que devuelve `bind` para él.
+    ///
+    /// `bind` recibe junto al marcador el nombre de la columna con la que se compara o a la que
+    /// se le asigna su valor, o el de la cláusula si es el número de una (`LIMIT`, `TTL` o
+    /// `TIMESTAMP`). Los marcadores se recorren en el orden de la _query_.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<(), Error> {
+        match self {
+            DmlStatement::SelectStatement(select) => {
+                let options = &mut select.options;
+                if let Some(the_where) = &mut options.the_where {
+                    the_where.bind_markers(bind)?;
+                }
+                if let Some(per_partition_limit) = &mut options.per_partition_limit {
+                    per_partition_limit.limit.bind("LIMIT", bind)?;
+                }
+                if let Some(limit) = &mut options.limit {
+                    limit.limit.bind("LIMIT", bind)?;
+                }
+            }
+            DmlStatement::InsertStatement(insert) => {
+                for (name, value) in insert.names.iter().zip(&mut insert.values.items) {
+                    value.bind(name.get_name(), bind)?;
+                }
+                insert.using.bind_markers(bind)?;
+            }
+            DmlStatement::UpdateStatement(update) => {
+                update.using.bind_markers(bind)?;
+                for assignment in &mut update.set_parameter {
+                    assignment.bind_markers(bind)?;
+                }
+                if let Some(the_where) = &mut update.the_where {
+                    the_where.bind_markers(bind)?;
+                }
+                update.if_condition.bind_markers(bind)?;
+            }
+            DmlStatement::DeleteStatement(delete) => {
+                delete.using.bind_markers(bind)?;
+                if let Some(the_where) = &mut delete.the_where {
+                    the_where.bind_markers(bind)?;
+                }
+                delete.if_condition.bind_markers(bind)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Devuelve los marcadores de la declaración en el orden de la _query_, cada uno con el
+    /// nombre de la columna o de la cláusula a la que corresponde, como los recorre
+    /// [bind_markers](Self::bind_markers).
+    pub fn bind_marker_slots(&self) -> Result<Vec<(BindMarker, String)>, Error> {
+        let mut slots = Vec::new();
+        // Se recorre una copia, así que el valor con el que se reemplaza cada marcador no importa
+        self.clone().bind_markers(&mut |marker, slot| {
+            slots.push((marker.clone(), slot.to_string()));
+            Ok(Constant::NULL)
+        })?;
+        Ok(slots)
+    }
+}
+
 /// Crea el enum `DmlStatement` con el tipo de struct de acuerdo a la sintaxis dada, si la entrada proporcionada no satisface
 /// los requerimientos de los tipos de datos, entonces devuelve None.
 pub fn dml_statement(list: &mut Vec<String>) -> Result<Option<DmlStatement>, Error> {
@@ -128,7 +207,14 @@ fn insert_statement(list: &mut Vec<String>) -> Result<Option<Insert>, Error> {
         }
 
         let if_not_exists = check_words(list, "IF NOT EXISTS");
-        return Ok(Some(Insert::new(table_name, names, values, if_not_exists)));
+        let using = using_clause(list)?;
+        return Ok(Some(Insert::new(
+            table_name,
+            names,
+            values,
+            if_not_exists,
+            using,
+        )));
     }
     Ok(None)
 }
@@ -143,13 +229,20 @@ fn update_statement(list: &mut Vec<String>) -> Result<Option<Update>, Error> {
                 ))
             }
         };
+        let using = using_clause(list)?;
         let set = set_clause(list)?;
         if list.first() != Some(&"WHERE".to_string()) {
             return Err(Error::SyntaxError("Falta la cláusula WHERE".to_string()));
         }
         let r#where = where_clause(list)?;
         let if_condition = check_if_condition(list)?;
-        return Ok(Some(Update::new(table_name, set, r#where, if_condition)));
+        return Ok(Some(Update::new(
+            table_name,
+            set,
+            r#where,
+            if_condition,
+            using,
+        )));
     }
 
     Ok(None)
@@ -172,9 +265,15 @@ fn delete_statement(list: &mut Vec<String>) -> Result<Option<Delete>, Error> {
     }
 
     let from = from_clause(list)?;
+    let using = using_clause(list)?;
+    if using.ttl.is_some() {
+        return Err(Error::Invalid(
+            "No se puede indicar un TTL en un DELETE".to_string(),
+        ));
+    }
     let r#where = where_clause(list)?;
     let if_condition = check_if_condition(list)?;
-    Ok(Some(Delete::new(cols, from, r#where, if_condition)))
+    Ok(Some(Delete::new(cols, from, r#where, if_condition, using)))
 }
 
 fn select_clause(list: &mut Vec<String>) -> Result<Option<Vec<Selector>>, Error> {
@@ -287,32 +386,23 @@ fn ordering_clause(list: &mut Vec<String>) -> Result<Option<OrderBy>, Error> {
 
 fn per_partition_limit_clause(list: &mut Vec<String>) -> Result<Option<PerPartitionLimit>, Error> {
     if check_words(list, "PER PARTITION LIMIT") {
-        let int = list.remove(0);
-        let int = match int.parse::<Int>() {
-            Ok(value) => PerPartitionLimit::new(value),
-            Err(_e) => {
-                return Err(Error::SyntaxError(
-                    "El valor brindado al Per Partition Limit no es un numero".to_string(),
-                ))
-            }
-        };
-        return Ok(Some(int));
+        let limit = PerPartitionLimit::new(clause_value(list, "Per Partition Limit")?);
+        // Los marcadores se validan recién cuando se vinculan
+        if !matches!(limit.limit, Term::BindMarker(_)) {
+            limit.get_limit()?;
+        }
+        return Ok(Some(limit));
     }
     Ok(None)
 }
 
 fn limit_clause(list: &mut Vec<String>) -> Result<Option<Limit>, Error> {
     if check_words(list, "LIMIT") {
-        let int = list.remove(0);
-        let int = match int.parse::<Int>() {
-            Ok(value) => Limit::new(value),
-            Err(_e) => {
-                return Err(Error::SyntaxError(
-                    "El valor brindado al Limit no es un int".to_string(),
-                ))
-            }
-        };
-        return Ok(Some(int));
+        let limit = Limit::new(clause_value(list, "Limit")?);
+        if !matches!(limit.limit, Term::BindMarker(_)) {
+            limit.get_limit()?;
+        }
+        return Ok(Some(limit));
     }
     Ok(None)
 }
@@ -325,6 +415,28 @@ fn allow_filtering_clause(list: &mut Vec<String>) -> Option<bool> {
 }
 
 fn selector(list: &mut Vec<String>) -> Result<Option<Selector>, Error> {
+    let selector = match simple_selector(list)? {
+        Some(selector) => selector,
+        None => return Ok(None),
+    };
+    if check_words(list, "AS") {
+        let alias = match Identifier::check_identifier(list)? {
+            Some(alias) => alias,
+            None => {
+                return Err(Error::SyntaxError(
+                    "Se esperaba un alias luego de AS".to_string(),
+                ))
+            }
+        };
+        return Ok(Some(Selector::Alias(Box::new(selector), alias)));
+    }
+    Ok(Some(selector))
+}
+
+fn simple_selector(list: &mut Vec<String>) -> Result<Option<Selector>, Error> {
+    if let Some(function_call) = function_call(list)? {
+        return Ok(Some(Selector::FunctionCall(function_call)));
+    }
     if let Some(column) = Identifier::check_identifier(list)? {
         return Ok(Some(Selector::ColumnName(column)));
     }
@@ -334,6 +446,45 @@ fn selector(list: &mut Vec<String>) -> Result<Option<Selector>, Error> {
     Ok(None)
 }
 
+fn function_call(list: &mut Vec<String>) -> Result<Option<FunctionCall>, Error> {
+    if list.len() < 2 || list[1] != "(" || !UnquotedIdentifier::check_unquoted_identifier(&list[0])
+    {
+        return Ok(None);
+    }
+    let name = list.remove(0);
+    list.remove(0);
+    let mut args = Vec::new();
+    if check_words(list, "* )") {
+        if !name.eq_ignore_ascii_case("COUNT") {
+            return Err(Error::SyntaxError(format!(
+                "La función {name} no admite * como argumento"
+            )));
+        }
+        return Ok(Some(FunctionCall::new(&name, args)));
+    }
+    while !check_words(list, ")") {
+        if list.is_empty() {
+            return Err(Error::SyntaxError(format!(
+                "Falta cerrar los paréntesis de la función {name}"
+            )));
+        }
+        match simple_selector(list)? {
+            Some(arg) => args.push(arg),
+            None => {
+                return Err(Error::SyntaxError(format!(
+                    "Argumento inválido para la función {name}"
+                )))
+            }
+        }
+        if !check_words(list, ",") && list.first().map(|token| token.as_str()) != Some(")") {
+            return Err(Error::SyntaxError(format!(
+                "Se esperaba ',' o ')' entre los argumentos de la función {name}"
+            )));
+        }
+    }
+    Ok(Some(FunctionCall::new(&name, args)))
+}
+
 fn parse_condition(list: &mut Vec<String>) -> Result<Condition, Error> {
     if list.len() < 3 {
         return Err(Error::SyntaxError("Condición IF incompleta".to_string()));
@@ -424,6 +575,60 @@ fn set_clause(list: &mut Vec<String>) -> Result<Vec<Assignment>, Error> {
     Ok(assignments)
 }
 
+fn using_clause(list: &mut Vec<String>) -> Result<UpdateParameters, Error> {
+    let mut using = UpdateParameters::default();
+    if !check_words(list, "USING") {
+        return Ok(using);
+    }
+    loop {
+        if check_words(list, "TIMESTAMP") {
+            if using.timestamp.is_some() {
+                return Err(Error::SyntaxError(
+                    "El TIMESTAMP se indicó más de una vez".to_string(),
+                ));
+            }
+            using.timestamp = Some(clause_value(list, "TIMESTAMP")?);
+        } else if check_words(list, "TTL") {
+            if using.ttl.is_some() {
+                return Err(Error::SyntaxError(
+                    "El TTL se indicó más de una vez".to_string(),
+                ));
+            }
+            using.ttl = Some(clause_value(list, "TTL")?);
+            using.timestamp_first = using.timestamp.is_some();
+        } else {
+            return Err(Error::SyntaxError(
+                "Se esperaba TIMESTAMP o TTL después de USING".to_string(),
+            ));
+        }
+        if !check_words(list, "AND") {
+            break;
+        }
+    }
+    // Los marcadores se validan recién cuando se vinculan
+    if !matches!(using.timestamp, Some(Term::BindMarker(_))) {
+        using.get_timestamp()?;
+    }
+    if !matches!(using.ttl, Some(Term::BindMarker(_))) {
+        using.get_ttl()?;
+    }
+    Ok(using)
+}
+
+/// Parsea el número que recibe una cláusula, como `LIMIT` o `USING TTL`, que también puede ser
+/// un marcador.
+fn clause_value(list: &mut Vec<String>, clause: &str) -> Result<Term, Error> {
+    match Term::is_term(list) {
+        Ok(Some(
+            term @ (Term::Constant(Constant::Integer(_) | Constant::Double(_))
+            | Term::BindMarker(_)),
+        )) => Ok(term),
+        _ => Err(Error::SyntaxError(format!(
+            "El valor brindado al {clause} no es un numero"
+        ))),
+    }
+}
+
 fn check_if_condition(list: &mut Vec<String>) -> Result<IfCondition, Error> {
     if check_words(list, "IF") {
         if check_words(list, "EXISTS") {
@@ -447,7 +652,9 @@ fn check_if_condition(list: &mut Vec<String>) -> Result<IfCondition, Error> {
 mod tests {
     use super::*;
     use crate::{
+        assignment::{ArithmeticOperator, Assignment},
         data_types::{
+            bind_marker::BindMarker,
             constant::Constant,
             identifier::{
                 quoted_identifier::QuotedIdentifier, unquoted_identifier::UnquotedIdentifier,
@@ -582,7 +789,7 @@ mod tests {
         let select = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
         assert!(select.options.limit.is_some());
         if let Some(limit) = select.options.limit {
-            assert_eq!(limit.limit, 10);
+            assert_eq!(limit.get_limit()?, 10);
         } else {
             return Err(Error::SyntaxError("Expected Some Limit".into()));
         }
@@ -609,6 +816,204 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_09_select_with_keyspace() -> Result<(), Error> {
+        let query = "SELECT * FROM system_traces.events WHERE session_id = 'abc'";
+        let mut tokens = tokenize_query(query);
+
+        let result = select_statement(&mut tokens)?;
+        let select = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+        assert_eq!(
+            select.from.get_keyspace(),
+            Some("system_traces".to_string())
+        );
+        assert_eq!(select.from.get_name(), "events");
+        assert!(select.options.the_where.is_some());
+
+        let mut tokens = tokenize_query("SELECT * FROM mal-nombre.events");
+        assert!(select_statement(&mut tokens).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_10_select_with_per_partition_limit_and_limit() -> Result<(), Error> {
+        let query = "SELECT * FROM vuelos WHERE orig = 'EZE' PER PARTITION LIMIT 2 LIMIT 5";
+        let mut tokens = tokenize_query(query);
+
+        let result = select_statement(&mut tokens)?;
+        let select = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+        assert_eq!(
+            select
+                .options
+                .per_partition_limit
+                .map(|per_partition_limit| per_partition_limit.get_limit())
+                .transpose()?,
+            Some(2)
+        );
+        assert_eq!(
+            select
+                .options
+                .limit
+                .map(|limit| limit.get_limit())
+                .transpose()?,
+            Some(5)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_11_select_with_non_positive_limit() -> Result<(), Error> {
+        let mut tokens = tokenize_query("SELECT * FROM users LIMIT 0");
+        assert!(select_statement(&mut tokens).is_err());
+
+        let mut tokens = tokenize_query("SELECT * FROM users PER PARTITION LIMIT -1");
+        assert!(select_statement(&mut tokens).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_12_select_with_functions_and_aliases() -> Result<(), Error> {
+        let query = "SELECT COUNT(*), MAX(altitud) AS maxima, toTimestamp(now()) FROM vuelos_entrantes WHERE dest = 'EZE'";
+        let mut tokens = tokenize_query(query);
+
+        let result = select_statement(&mut tokens)?;
+        let select = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+        assert!(select.options.the_where.is_some());
+        assert_eq!(
+            select.columns.get_columns(),
+            vec!["count", "maxima", "totimestamp(now())"]
+        );
+        let KindOfColumns::SelectClause(columns) = select.columns else {
+            return Err(Error::SyntaxError("Expected SelectClause".into()));
+        };
+        assert_eq!(
+            columns[0],
+            Selector::FunctionCall(FunctionCall::new("COUNT", Vec::new()))
+        );
+        assert_eq!(columns[1].get_column_names(), vec!["altitud"]);
+        assert!(matches!(
+            columns[1].unaliased(),
+            Selector::FunctionCall(FunctionCall { name, .. }) if name == "max"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_13_select_with_invalid_function_call() -> Result<(), Error> {
+        let mut tokens = tokenize_query("SELECT MAX(*) FROM users");
+        assert!(select_statement(&mut tokens).is_err());
+
+        let mut tokens = tokenize_query("SELECT writetime(name age) FROM users");
+        assert!(select_statement(&mut tokens).is_err());
+
+        let mut tokens = tokenize_query("SELECT name AS FROM users");
+        assert!(select_statement(&mut tokens).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_14_select_with_bind_markers() -> Result<(), Error> {
+        let query = "SELECT * FROM users WHERE id = ? AND name = :name";
+        let mut tokens = tokenize_query(query);
+
+        let result = select_statement(&mut tokens)?;
+        let select = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+        let Some(Expression::And(and_expr)) = select
+            .options
+            .the_where
+            .and_then(|where_clause| where_clause.expression)
+            .map(|expression| *expression)
+        else {
+            return Err(Error::SyntaxError("Expected AND expression".into()));
+        };
+        let (Expression::Relation(first), Expression::Relation(second)) =
+            (*and_expr.first_relation, *and_expr.second_relation)
+        else {
+            return Err(Error::SyntaxError("Expected relations".into()));
+        };
+        assert_eq!(
+            first.term_to_compare,
+            Term::BindMarker(BindMarker::Positional)
+        );
+        assert_eq!(
+            second.term_to_compare,
+            Term::BindMarker(BindMarker::Named("name".to_string()))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_15_select_with_bind_markers_in_limits() -> Result<(), Error> {
+        let query = "SELECT * FROM users WHERE id = ? PER PARTITION LIMIT ? LIMIT :max";
+        let mut tokens = tokenize_query(query);
+
+        let result = select_statement(&mut tokens)?;
+        let select = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+        let (Some(per_partition_limit), Some(limit)) =
+            (select.options.per_partition_limit, select.options.limit)
+        else {
+            return Err(Error::SyntaxError("Expected both limits".into()));
+        };
+
+        assert_eq!(
+            per_partition_limit.limit,
+            Term::BindMarker(BindMarker::Positional)
+        );
+        assert_eq!(
+            limit.limit,
+            Term::BindMarker(BindMarker::Named("max".to_string()))
+        );
+        // Sin vincular no tienen un valor
+        assert!(limit.get_limit().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_16_select_binds_markers_as_typed_constants() -> Result<(), Error> {
+        let query = "SELECT * FROM users WHERE id = ? AND name = :name LIMIT ?";
+        let mut statement = match dml_statement(&mut tokenize_query(query))? {
+            Some(statement) => statement,
+            None => return Err(Error::SyntaxError("Expected Some, got None".into())),
+        };
+
+        let mut slots = Vec::new();
+        statement.bind_markers(&mut |marker, slot| {
+            slots.push((marker.clone(), slot.to_string()));
+            Ok(match slot {
+                "name" => Constant::String("O'Brien".to_string()),
+                _ => Constant::from_number(3.0),
+            })
+        })?;
+        assert_eq!(
+            slots,
+            vec![
+                (BindMarker::Positional, "id".to_string()),
+                (BindMarker::Named("name".to_string()), "name".to_string()),
+                (BindMarker::Positional, "LIMIT".to_string()),
+            ]
+        );
+
+        let DmlStatement::SelectStatement(select) = statement else {
+            return Err(Error::SyntaxError("Expected a SELECT".into()));
+        };
+        assert_eq!(
+            select
+                .options
+                .the_where
+                .and_then(|the_where| the_where.as_cql()),
+            Some("id = 3 AND name = 'O''Brien'".to_string())
+        );
+        assert_eq!(
+            select
+                .options
+                .limit
+                .map(|limit| limit.get_limit())
+                .transpose()?,
+            Some(3)
+        );
+        Ok(())
+    }
+
     /// WHERE TESTS:
     #[test]
     fn test_01_where_clause_with_and() -> Result<(), Error> {
@@ -905,6 +1310,103 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_05_insert_using_timestamp() -> Result<(), Error> {
+        let query =
+            "INSERT INTO users (id, name) VALUES (4, 'Ann') IF NOT EXISTS USING TIMESTAMP 1700000000000000";
+        let mut tokens = tokenize_query(query);
+
+        let result = insert_statement(&mut tokens)?;
+        let insert = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert!(insert.if_not_exists);
+        assert_eq!(insert.using.get_timestamp()?, Some(1700000000000000));
+        assert!(tokens.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_06_insert_using_ttl_and_timestamp() -> Result<(), Error> {
+        let query =
+            "INSERT INTO users (id, name) VALUES (4, 'Ann') USING TTL 86400 AND TIMESTAMP 5";
+        let mut tokens = tokenize_query(query);
+
+        let result = insert_statement(&mut tokens)?;
+        let insert = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(insert.using.get_timestamp()?, Some(5));
+        assert_eq!(insert.using.get_ttl()?, Some(86400));
+        assert!(tokens.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_07_insert_with_invalid_ttl() -> Result<(), Error> {
+        let negative = "INSERT INTO users (id, name) VALUES (4, 'Ann') USING TTL -1";
+        assert!(insert_statement(&mut tokenize_query(negative)).is_err());
+
+        let repeated = "INSERT INTO users (id, name) VALUES (4, 'Ann') USING TTL 1 AND TTL 2";
+        assert!(insert_statement(&mut tokenize_query(repeated)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_08_insert_with_bind_markers() -> Result<(), Error> {
+        let query = "INSERT INTO users (id, name, email) VALUES (?, :name, '?')";
+        let mut tokens = tokenize_query(query);
+
+        let result = insert_statement(&mut tokens)?;
+        let insert = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(
+            insert.values.items,
+            vec![
+                Term::BindMarker(BindMarker::Positional),
+                Term::BindMarker(BindMarker::Named("name".to_string())),
+                Term::Constant(Constant::String("?".to_string())),
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_10_insert_with_bind_markers_in_using() -> Result<(), Error> {
+        let query = "INSERT INTO users (id, name) VALUES (?, ?) USING TTL ? AND TIMESTAMP :ts";
+        let mut tokens = tokenize_query(query);
+
+        let result = insert_statement(&mut tokens)?;
+        let insert = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(
+            insert.using,
+            UpdateParameters::new(
+                Some(Term::BindMarker(BindMarker::Named("ts".to_string()))),
+                Some(Term::BindMarker(BindMarker::Positional)),
+            )
+        );
+        assert!(insert.using.get_ttl().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_09_insert_with_escaped_quotes() -> Result<(), Error> {
+        let query = "INSERT INTO users (id, name, email) VALUES (1, 'O''Brien', '')";
+        let mut tokens = tokenize_query(query);
+
+        let result = insert_statement(&mut tokens)?;
+        let insert = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(
+            insert.values.items[1],
+            Term::Constant(Constant::String("O'Brien".to_string()))
+        );
+        assert_eq!(
+            insert.values.items[2],
+            Term::Constant(Constant::String(String::new()))
+        );
+        Ok(())
+    }
+
     // UPDATE TESTS:
     #[test]
     fn test_01_basic_update() -> Result<(), Error> {
@@ -988,6 +1490,103 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_07_update_using_timestamp() -> Result<(), Error> {
+        let query = "UPDATE users USING TIMESTAMP 42 SET name = 'John' WHERE id = 1";
+        let mut tokens = tokenize_query(query);
+
+        let result = update_statement(&mut tokens)?;
+        let update = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(update.using.get_timestamp()?, Some(42));
+        assert_eq!(update.using.ttl, None);
+        assert_eq!(update.set_parameter.len(), 1);
+        assert!(update.the_where.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_08_update_using_ttl() -> Result<(), Error> {
+        let query = "UPDATE users USING TTL 60 SET name = 'John' WHERE id = 1";
+        let mut tokens = tokenize_query(query);
+
+        let result = update_statement(&mut tokens)?;
+        let update = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(update.using.timestamp, None);
+        assert_eq!(update.using.get_ttl()?, Some(60));
+        assert_eq!(update.set_parameter.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_09_update_with_bind_markers_in_using() -> Result<(), Error> {
+        let query = "UPDATE users USING TIMESTAMP ? AND TTL ? SET name = ? WHERE id = ?";
+        let mut tokens = tokenize_query(query);
+
+        let result = update_statement(&mut tokens)?;
+        let update = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(
+            update.using,
+            UpdateParameters {
+                timestamp_first: true,
+                ..UpdateParameters::new(
+                    Some(Term::BindMarker(BindMarker::Positional)),
+                    Some(Term::BindMarker(BindMarker::Positional)),
+                )
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_10_update_binds_markers_by_column_and_clause() -> Result<(), Error> {
+        let query = "UPDATE users USING TIMESTAMP ? AND TTL ? SET name = ? WHERE id = ? IF age = ?";
+        let mut statement = match dml_statement(&mut tokenize_query(query))? {
+            Some(statement) => statement,
+            None => return Err(Error::SyntaxError("Expected Some, got None".into())),
+        };
+
+        let mut slots = Vec::new();
+        statement.bind_markers(&mut |_, slot| {
+            slots.push(slot.to_string());
+            Ok(Constant::from_number(1_700_000_000_000_000.0))
+        })?;
+        assert_eq!(slots, vec!["TIMESTAMP", "TTL", "name", "id", "age"]);
+        assert_eq!(
+            statement
+                .update_parameters()
+                .map(|using| using.get_timestamp())
+                .transpose()?,
+            Some(Some(1_700_000_000_000_000))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_11_update_counter_keeps_operator() -> Result<(), Error> {
+        let query = "UPDATE visitas SET total = total + 1, bajas = bajas - 2 WHERE id = 1";
+        let mut tokens = tokenize_query(query);
+
+        let result = update_statement(&mut tokens)?;
+        let update = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        let operators: Vec<ArithmeticOperator> = update
+            .set_parameter
+            .iter()
+            .filter_map(|assignment| match assignment {
+                Assignment::ColumnNameColTerm(_, _, operator, _) => Some(*operator),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            operators,
+            vec![ArithmeticOperator::Plus, ArithmeticOperator::Minus]
+        );
+        Ok(())
+    }
+
     // DELETE TESTS:
     #[test]
     fn test_01_basic_delete() -> Result<(), Error> {
@@ -1100,6 +1699,51 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_08_delete_using_timestamp() -> Result<(), Error> {
+        let query = "DELETE FROM users USING TIMESTAMP 42 WHERE id = 1";
+        let mut tokens = tokenize_query(query);
+
+        let result = delete_statement(&mut tokens)?;
+        let delete = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(delete.using.get_timestamp()?, Some(42));
+        assert!(delete.the_where.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_09_delete_with_invalid_timestamp() -> Result<(), Error> {
+        let query = "DELETE FROM users USING TIMESTAMP ayer WHERE id = 1";
+        let mut tokens = tokenize_query(query);
+        assert!(delete_statement(&mut tokens).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_10_delete_using_ttl() -> Result<(), Error> {
+        let query = "DELETE FROM users USING TTL 60 WHERE id = 1";
+        let mut tokens = tokenize_query(query);
+        assert!(delete_statement(&mut tokens).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_11_delete_with_bind_marker_in_using() -> Result<(), Error> {
+        let query = "DELETE FROM users USING TIMESTAMP :ts WHERE id = ?";
+        let mut tokens = tokenize_query(query);
+
+        let result = delete_statement(&mut tokens)?;
+        let delete = result.ok_or(Error::SyntaxError("Expected Some, got None".into()))?;
+
+        assert_eq!(
+            delete.using.timestamp,
+            Some(Term::BindMarker(BindMarker::Named("ts".to_string())))
+        );
+        assert!(delete.using.get_timestamp().is_err());
+        Ok(())
+    }
+
     // EMPTY INPUT TEST:
     #[test]
     fn test_01_empty_input() -> Result<(), Error> {
@@ -1,10 +1,16 @@
-use crate::{
-    data_types::{identifier::identifier_mod::Identifier, term::Term},
-    statements::dml_statement::r#where::operator::Operator,
+use {
+    crate::{
+        data_types::{
+            identifier::identifier_mod::Identifier,
+            term::{BindFn, Term},
+        },
+        statements::dml_statement::r#where::operator::Operator,
+    },
+    protocol::aliases::results::Result,
 };
 
 /// Representa una condición IF en una declaración DML.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum IfCondition {
     /// Representa una condición IF EXISTS.    
     Exists,
@@ -15,7 +21,7 @@ pub enum IfCondition {
 }
 
 /// Representa una condición en una declaración DML.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Condition {
     /// Identificador de la primera columna.
     /// La primera columna es la columna de la izquierda en la condicion.
@@ -43,6 +49,19 @@ impl IfCondition {
     pub fn new_none() -> Self {
         IfCondition::None
     }
+
+    /// Vincula los marcadores de las condiciones, cada uno con un valor para la columna con la
+    /// que se compara.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<()> {
+        if let IfCondition::Conditions(conditions) = self {
+            for condition in conditions {
+                condition
+                    .second_column
+                    .bind(condition.first_column.get_name(), bind)?;
+            }
+        }
+        Ok(())
+    }
 }
 
 impl Condition {
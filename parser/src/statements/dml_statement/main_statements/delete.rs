@@ -1,10 +1,13 @@
 use crate::{
-    statements::dml_statement::{if_condition::IfCondition, r#where::r#where_parser::Where},
+    statements::dml_statement::{
+        if_condition::IfCondition, r#where::r#where_parser::Where,
+        update_parameters::UpdateParameters,
+    },
     table_name::TableName,
 };
 
 /// Representa una declaración DELETE en el analizador.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Delete {
     /// Columnas a eliminar.
     pub cols: Vec<String>,
@@ -14,6 +17,8 @@ pub struct Delete {
     pub the_where: Option<Where>,
     /// Condición de eliminación.
     pub if_condition: IfCondition,
+    /// Parámetros del borrado.
+    pub using: UpdateParameters,
 }
 
 impl Delete {
@@ -23,12 +28,14 @@ impl Delete {
         from: TableName,
         the_where: Option<Where>,
         if_condition: IfCondition,
+        using: UpdateParameters,
     ) -> Delete {
         Delete {
             cols,
             from,
             the_where,
             if_condition,
+            using,
         }
     }
 }
@@ -1,10 +1,11 @@
 use crate::{
     data_types::{identifier::identifier_mod::Identifier, literal::tuple_literal::TupleLiteral},
+    statements::dml_statement::update_parameters::UpdateParameters,
     table_name::TableName,
 };
 
 /// Representa una declaración CQL INSERT.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Insert {
     /// Nombre de la tabla a insertar.
     pub table: TableName,
@@ -14,6 +15,8 @@ pub struct Insert {
     pub values: TupleLiteral,
     /// Indica si la inserción debe realizarse solo si no existe.
     pub if_not_exists: bool,
+    /// Parámetros de la escritura.
+    pub using: UpdateParameters,
 }
 
 impl Insert {
@@ -23,12 +26,14 @@ impl Insert {
         names: Vec<Identifier>,
         values: TupleLiteral,
         if_not_exists: bool,
+        using: UpdateParameters,
     ) -> Insert {
         Insert {
             table,
             names,
             values,
             if_not_exists,
+            using,
         }
     }
 
@@ -0,0 +1,34 @@
+use crate::statements::dml_statement::main_statements::select::selector::Selector;
+
+/// Representa la llamada a una función dentro de un SELECT, como `COUNT(*)` o `writetime(col)`.
+///
+/// function_call::= identifier '(' [ selector ( ',' selector )* | '*' ] ')'
+#[derive(Debug, Clone, PartialEq)]
+pub struct FunctionCall {
+    /// Nombre de la función, en minúsculas.
+    pub name: String,
+    /// Argumentos de la función. En `COUNT(*)` no hay ninguno.
+    pub args: Vec<Selector>,
+}
+
+impl FunctionCall {
+    /// Crea una nueva llamada a función.
+    pub fn new(name: &str, args: Vec<Selector>) -> Self {
+        FunctionCall {
+            name: name.to_lowercase(),
+            args,
+        }
+    }
+
+    /// Obtiene el nombre con el que se muestra el resultado de la función.
+    ///
+    /// `COUNT(*)` se muestra como `count`, y el resto como la función con sus argumentos,
+    /// por ejemplo `max(altitud)`.
+    pub fn get_name(&self) -> String {
+        if self.name == "count" && self.args.is_empty() {
+            return self.name.to_string();
+        }
+        let args: Vec<String> = self.args.iter().map(|arg| arg.get_name()).collect();
+        format!("{}({})", self.name, args.join(", "))
+    }
+}
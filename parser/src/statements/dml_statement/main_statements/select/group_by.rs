@@ -1,7 +1,7 @@
 use crate::data_types::identifier::identifier_mod::Identifier;
 
 /// Representa una cláusula CQL GROUP BY.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct GroupBy {
     /// Columnas por las que se agrupará.
     pub columns: Vec<Identifier>,
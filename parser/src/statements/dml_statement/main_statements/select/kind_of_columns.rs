@@ -1,6 +1,6 @@
 use crate::statements::dml_statement::main_statements::select::selector::Selector;
 
-#[derive(Default, Debug, PartialEq)]
+#[derive(Default, Debug, Clone, PartialEq)]
 
 /// Representa el tipo de columnas a seleccionar.
 pub enum KindOfColumns {
@@ -21,4 +21,15 @@ impl KindOfColumns {
             KindOfColumns::All => vec!["*".to_string()],
         }
     }
+
+    /// Obtiene los nombres de las columnas de la tabla que usan los selectores.
+    pub fn get_column_names(&self) -> Vec<String> {
+        match self {
+            KindOfColumns::SelectClause(columns) => columns
+                .iter()
+                .flat_map(|column| column.get_column_names())
+                .collect(),
+            KindOfColumns::All => vec!["*".to_string()],
+        }
+    }
 }
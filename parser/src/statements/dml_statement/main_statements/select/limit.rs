@@ -1,15 +1,35 @@
-use protocol::aliases::types::Int;
+use {
+    crate::data_types::term::Term,
+    protocol::{
+        aliases::{results::Result, types::Int},
+        errors::error::Error,
+    },
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// Una estructura que representa un límite en una consulta SQL.
 pub struct Limit {
     /// Límite de datos.
-    pub limit: Int, // bind _marker
+    /// Puede ser un marcador, a vincular antes de ejecutar la _query_.
+    pub limit: Term,
 }
 
 impl Limit {
     /// Crea una nueva instancia de `Limit`.
-    pub fn new(limit: Int) -> Self {
+    pub fn new(limit: Term) -> Self {
         Limit { limit }
     }
+
+    /// Obtiene el límite, que tiene que ser un número mayor a 0.
+    pub fn get_limit(&self) -> Result<Int> {
+        let limit = self.limit.get_integer_value("Limit")?;
+        if limit <= 0 {
+            return Err(Error::Invalid(
+                "El Limit tiene que ser mayor a 0".to_string(),
+            ));
+        }
+        Int::try_from(limit).map_err(|_| {
+            Error::Invalid("El valor brindado al Limit es demasiado grande".to_string())
+        })
+    }
 }
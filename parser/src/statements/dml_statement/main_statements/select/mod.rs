@@ -1,3 +1,5 @@
+/// Documentación para el módulo function_call.
+pub mod function_call;
 /// Documentación para el módulo group_by.
 pub mod group_by;
 /// Documentación para el módulo kind_of_columns.
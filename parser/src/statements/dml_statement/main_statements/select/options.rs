@@ -6,7 +6,7 @@ use crate::statements::dml_statement::{
 };
 
 /// Opciones para la declaración SELECT.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 
 pub struct SelectOptions {
     /// Condición de selección.
@@ -8,7 +8,7 @@ use {
 };
 
 /// ordering_clause::= column_name [ ASC | DESC ] ( ',' column_name [ ASC | DESC ] )*
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct OrderBy {
     /// Lista de columnas y dirección de ordenación.
     pub order_columns: Vec<(Identifier, Option<ProtocolOrdering>)>,
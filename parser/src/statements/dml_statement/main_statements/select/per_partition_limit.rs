@@ -1,15 +1,37 @@
-use protocol::aliases::types::Int;
+use {
+    crate::data_types::term::Term,
+    protocol::{
+        aliases::{results::Result, types::Int},
+        errors::error::Error,
+    },
+};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 /// Representa un límite por partición.
 pub struct PerPartitionLimit {
     /// Límite por partición.
-    pub limit: Int, // bind _marker
+    /// Puede ser un marcador, a vincular antes de ejecutar la _query_.
+    pub limit: Term,
 }
 
 impl PerPartitionLimit {
     /// Crea un nuevo límite por partición.
-    pub fn new(limit: Int) -> Self {
+    pub fn new(limit: Term) -> Self {
         PerPartitionLimit { limit }
     }
+
+    /// Obtiene el límite, que tiene que ser un número mayor a 0.
+    pub fn get_limit(&self) -> Result<Int> {
+        let limit = self.limit.get_integer_value("Per Partition Limit")?;
+        if limit <= 0 {
+            return Err(Error::Invalid(
+                "El Per Partition Limit tiene que ser mayor a 0".to_string(),
+            ));
+        }
+        Int::try_from(limit).map_err(|_| {
+            Error::Invalid(
+                "El valor brindado al Per Partition Limit es demasiado grande".to_string(),
+            )
+        })
+    }
 }
@@ -6,7 +6,7 @@ use crate::{
 };
 
 /// Representa una declaración SELECT en el lenguaje de consulta.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Select {
     /// Columnas a seleccionar.
     pub columns: KindOfColumns,
@@ -1,12 +1,21 @@
-use crate::data_types::{identifier::identifier_mod::Identifier, term::Term};
+use crate::{
+    data_types::{identifier::identifier_mod::Identifier, term::Term},
+    statements::dml_statement::main_statements::select::function_call::FunctionCall,
+};
 
 /// Representa un selector en una declaración SQL.
-#[derive(Debug, PartialEq)]
+///
+/// selector::= column_name | term | function_call
+#[derive(Debug, Clone, PartialEq)]
 pub enum Selector {
     /// Nombre de una columna.
     ColumnName(Identifier),
     /// Término.
     Term(Term),
+    /// Llamada a una función.
+    FunctionCall(FunctionCall),
+    /// Selector renombrado con `AS`.
+    Alias(Box<Selector>, Identifier),
 }
 
 impl Selector {
@@ -15,6 +24,30 @@ impl Selector {
         match self {
             Selector::ColumnName(column_name) => column_name.get_name().to_string(),
             Selector::Term(term) => term.get_value_as_string(),
+            Selector::FunctionCall(function_call) => function_call.get_name(),
+            Selector::Alias(_, alias) => alias.get_name().to_string(),
+        }
+    }
+
+    /// Obtiene el selector sin su alias, si es que tiene uno.
+    pub fn unaliased(&self) -> &Selector {
+        match self {
+            Selector::Alias(selector, _) => selector.unaliased(),
+            selector => selector,
+        }
+    }
+
+    /// Obtiene los nombres de las columnas de la tabla que usa el selector.
+    pub fn get_column_names(&self) -> Vec<String> {
+        match self {
+            Selector::ColumnName(column_name) => vec![column_name.get_name().to_string()],
+            Selector::Term(_) => Vec::new(),
+            Selector::FunctionCall(function_call) => function_call
+                .args
+                .iter()
+                .flat_map(|arg| arg.get_column_names())
+                .collect(),
+            Selector::Alias(selector, _) => selector.get_column_names(),
         }
     }
 }
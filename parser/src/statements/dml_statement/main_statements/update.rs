@@ -1,10 +1,13 @@
 use crate::{
     assignment::Assignment,
-    statements::dml_statement::{if_condition::IfCondition, r#where::r#where_parser::Where},
+    statements::dml_statement::{
+        if_condition::IfCondition, r#where::r#where_parser::Where,
+        update_parameters::UpdateParameters,
+    },
     table_name::TableName,
 };
 /// Representa una sentencia CQL UPDATE.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Update {
     /// Nombre de la tabla a actualizar.
     pub table_name: TableName,
@@ -14,6 +17,8 @@ pub struct Update {
     pub the_where: Option<Where>,
     /// Condición de existencia.
     pub if_condition: IfCondition,
+    /// Parámetros de la escritura.
+    pub using: UpdateParameters,
 }
 
 impl Update {
@@ -23,12 +28,14 @@ impl Update {
         set_parameter: Vec<Assignment>,
         the_where: Option<Where>,
         if_condition: IfCondition,
+        using: UpdateParameters,
     ) -> Update {
         Update {
             table_name,
             set_parameter,
             the_where,
             if_condition,
+            using,
         }
     }
 }
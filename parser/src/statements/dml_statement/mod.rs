@@ -7,5 +7,8 @@ pub mod if_condition;
 /// Este módulo contiene el parser de las principales declaraciones de manipulación de datos (DML).
 pub mod main_statements;
 
+/// Este módulo contiene los parámetros de escritura indicados con la cláusula using.
+pub mod update_parameters;
+
 /// Este módulo contiene el parser de las declaraciones de manipulación de datos (DML) para la cláusula where.
 pub mod r#where;
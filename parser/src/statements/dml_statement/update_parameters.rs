@@ -0,0 +1,72 @@
+use {
+    crate::data_types::term::{BindFn, Term},
+    protocol::{
+        aliases::{results::Result, types::Long},
+        errors::error::Error,
+    },
+};
+
+/// Representa los parámetros de una escritura, indicados con `USING` en una declaración DML.
+///
+/// update_parameter ::= ( TIMESTAMP | TTL ) ( `integer` | `bind_marker` )
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct UpdateParameters {
+    /// El timestamp _(en microsegundos)_ de la escritura, si el cliente indicó uno.
+    pub timestamp: Option<Term>,
+
+    /// Los segundos que dura la escritura antes de vencer, si el cliente indicó un TTL.
+    pub ttl: Option<Term>,
+
+    /// Si el TIMESTAMP se indicó antes que el TTL, para recorrer sus marcadores en el orden de
+    /// la _query_.
+    pub timestamp_first: bool,
+}
+
+impl UpdateParameters {
+    /// Crea nuevos parámetros de escritura.
+    pub fn new(timestamp: Option<Term>, ttl: Option<Term>) -> Self {
+        UpdateParameters {
+            timestamp,
+            ttl,
+            timestamp_first: false,
+        }
+    }
+
+    /// Vincula los marcadores del TTL y del timestamp, si los hay, en el orden de la _query_.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<()> {
+        let ttl = self.ttl.as_mut().map(|ttl| (ttl, "TTL"));
+        let timestamp = self
+            .timestamp
+            .as_mut()
+            .map(|timestamp| (timestamp, "TIMESTAMP"));
+        let ordered = if self.timestamp_first {
+            [timestamp, ttl]
+        } else {
+            [ttl, timestamp]
+        };
+        for (term, clause) in ordered.into_iter().flatten() {
+            term.bind(clause, bind)?;
+        }
+        Ok(())
+    }
+
+    /// Obtiene el timestamp _(en microsegundos)_ de la escritura, si el cliente indicó uno.
+    pub fn get_timestamp(&self) -> Result<Option<Long>> {
+        self.timestamp
+            .as_ref()
+            .map(|timestamp| timestamp.get_integer_value("TIMESTAMP"))
+            .transpose()
+    }
+
+    /// Obtiene los segundos que dura la escritura antes de vencer, si el cliente indicó un TTL.
+    pub fn get_ttl(&self) -> Result<Option<Long>> {
+        let Some(ttl) = &self.ttl else {
+            return Ok(None);
+        };
+        let ttl = ttl.get_integer_value("TTL")?;
+        if ttl < 0 {
+            return Err(Error::Invalid("El TTL no puede ser negativo".to_string()));
+        }
+        Ok(Some(ttl))
+    }
+}
@@ -1,8 +1,8 @@
-use crate::statements::dml_statement::r#where::expression::Expression;
+use crate::{data_types::term::BindFn, statements::dml_statement::r#where::expression::Expression};
 use protocol::aliases::results::Result;
 
 /// Representa una operación lógica AND entre dos expresiones.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct And {
     /// Primera expresión.
     /// Puede ser una expresión simple o una expresión compuesta.
@@ -20,6 +20,21 @@ impl And {
         }
     }
 
+    /// Vincula los marcadores de ambas expresiones, en el orden en que aparecen.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<()> {
+        self.first_relation.bind_markers(bind)?;
+        self.second_relation.bind_markers(bind)
+    }
+
+    /// Devuelve la operación escrita como en una _query_.
+    pub fn as_cql(&self) -> String {
+        format!(
+            "{} AND {}",
+            self.first_relation.as_cql(),
+            self.second_relation.as_cql()
+        )
+    }
+
     /// Evalúa la operación lógica AND.
     pub fn evaluate(&self, line_to_review: &[String], general_columns: &[String]) -> Result<bool> {
         let passed_filter: bool = self
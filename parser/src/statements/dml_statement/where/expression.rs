@@ -1,12 +1,12 @@
 use crate::{
     data_types::identifier::identifier_mod::Identifier,
-    data_types::term::Term,
+    data_types::term::{BindFn, Term},
     statements::dml_statement::r#where::{and::And, operator::Operator, relation::Relation},
 };
 use protocol::{aliases::results::Result, errors::error::Error};
 
 /// Representa diferentes tipos de expresiones en el analizador sintáctico.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum Expression {
     /// Representa una expresión simple.
     /// Una expresión simple consta de un solo término.
@@ -42,25 +42,18 @@ pub fn and_recursive(
     lista: &mut Vec<String>,
     a_relation: Box<Expression>,
 ) -> Result<Option<Box<Expression>>> {
-    if !lista.is_empty() {
-        if lista[0] == "IF" || lista[0] == ";" {
-            return Ok(Some(a_relation));
-        }
-
-        let value = lista.remove(0);
-        if value == "AND" {
-            let second_parameter = match relation(lista)? {
-                Some(relation) => relation,
-                None => return Ok(None),
-            };
-            let an_and = And::new(a_relation, second_parameter);
-            let exp = Box::new(Expression::And(an_and));
-            return and_recursive(lista, exp);
-        }
-    } else {
+    // Lo que sigue a la última relación (IF, GROUP BY, LIMIT, etc.) lo parsea quien llamó
+    if lista.first().is_none_or(|value| value != "AND") {
         return Ok(Some(a_relation));
     }
-    Ok(None)
+    lista.remove(0);
+    let second_parameter = match relation(lista)? {
+        Some(relation) => relation,
+        None => return Ok(None),
+    };
+    let an_and = And::new(a_relation, second_parameter);
+    let exp = Box::new(Expression::And(an_and));
+    and_recursive(lista, exp)
 }
 
 /// Parsea una relación entre dos términos.
@@ -92,6 +85,26 @@ pub fn relation(lista: &mut Vec<String>) -> Result<Option<Box<Expression>>> {
 }
 
 impl Expression {
+    /// Vincula los marcadores de la expresión, en el orden en que aparecen.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<()> {
+        match self {
+            Expression::Expression(another_expression) => another_expression.bind_markers(bind),
+            Expression::And(and) => and.bind_markers(bind),
+            Expression::Relation(relation) => relation.bind_markers(bind),
+        }
+    }
+
+    /// Devuelve la expresión escrita como en una _query_.
+    pub fn as_cql(&self) -> String {
+        match self {
+            Expression::Expression(another_expression) => {
+                format!("({})", another_expression.as_cql())
+            }
+            Expression::And(and) => and.as_cql(),
+            Expression::Relation(relation) => relation.as_cql(),
+        }
+    }
+
     /// Evalúa la expresión de la cláusula WHERE.
     pub fn evaluate(&self, line_to_review: &[String], general_columns: &[String]) -> Result<bool> {
         let result = match &self {
@@ -1,5 +1,5 @@
 /// Representa diferentes tipos de operadores en una declaración DML.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Operator {
     /// Representa el operador de igualdad (`=`).
     Equal,
@@ -46,4 +46,19 @@ impl Operator {
             None
         }
     }
+
+    /// Devuelve el operador escrito como en una _query_.
+    pub fn as_cql(&self) -> &str {
+        match self {
+            Operator::Equal => "=",
+            Operator::Minor => "<",
+            Operator::Mayor => ">",
+            Operator::MinorEqual => "<=",
+            Operator::MayorEqual => ">=",
+            Operator::Distinct => "!=",
+            Operator::In => "IN",
+            Operator::Contains => "CONTAINS",
+            Operator::ContainsKey => "CONTAINS KEY",
+        }
+    }
 }
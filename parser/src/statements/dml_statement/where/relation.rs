@@ -1,6 +1,8 @@
 use crate::{
-    data_types::constant::Constant, data_types::identifier::identifier_mod::Identifier,
-    data_types::term::Term, statements::dml_statement::r#where::operator::Operator,
+    data_types::constant::Constant,
+    data_types::identifier::identifier_mod::Identifier,
+    data_types::term::{BindFn, Term},
+    statements::dml_statement::r#where::operator::Operator,
 };
 use protocol::{
     aliases::{
@@ -11,7 +13,7 @@ use protocol::{
 };
 
 /// Representa una relación en una cláusula WHERE con dos columnas y un operador.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Relation {
     /// Identificador de la primera columna.
     /// La primera columna es la columna de la izquierda en la relación.
@@ -33,6 +35,21 @@ impl Relation {
         }
     }
 
+    /// Vincula el término de la relación, si es un marcador, con un valor para su columna.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<()> {
+        self.term_to_compare.bind(self.column.get_name(), bind)
+    }
+
+    /// Devuelve la relación escrita como en una _query_.
+    pub fn as_cql(&self) -> String {
+        format!(
+            "{} {} {}",
+            self.column.as_cql(),
+            self.operator.as_cql(),
+            self.term_to_compare.as_cql()
+        )
+    }
+
     /// Evalúa la relación entre la columna de la tabla y el término dados.
     pub fn evaluate(&self, line_to_review: &[String], general_columns: &[String]) -> Result<bool> {
         let column = self.column.get_name();
@@ -1,9 +1,9 @@
-use crate::statements::dml_statement::r#where::expression::Expression;
+use crate::{data_types::term::BindFn, statements::dml_statement::r#where::expression::Expression};
 use protocol::aliases::results::Result;
 
 /// Representa una cláusula WHERE en una declaración CQL.
 /// La cláusula WHERE se utiliza para filtrar filas de una tabla.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Where {
     /// Expresión que se evaluará para cada fila de la tabla.
     pub expression: Option<Box<Expression>>,
@@ -15,6 +15,21 @@ impl Where {
         Where { expression }
     }
 
+    /// Vincula los marcadores de la cláusula, en el orden en que aparecen.
+    pub fn bind_markers(&mut self, bind: &mut BindFn) -> Result<()> {
+        match &mut self.expression {
+            Some(expression) => expression.bind_markers(bind),
+            None => Ok(()),
+        }
+    }
+
+    /// Devuelve la condición de la cláusula escrita como en una _query_, sin la palabra `WHERE`.
+    pub fn as_cql(&self) -> Option<String> {
+        self.expression
+            .as_ref()
+            .map(|expression| expression.as_cql())
+    }
+
     /// Evalúa la expresión de la cláusula WHERE.
     pub fn filter(&self, line_to_review: &[String], general_columns: &[String]) -> Result<bool> {
         match &self.expression {
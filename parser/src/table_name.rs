@@ -10,7 +10,7 @@ use protocol::{aliases::results::Result, errors::error::Error};
 ///
 /// * `keyspace` - Un `Option<KeyspaceName>` que representa el keyspace opcional.
 /// * `name` - Un `KeyspaceName` que representa el nombre de la tabla.
-#[derive(Debug, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct TableName {
     /// Un `bool` que indica si la tabla existe.
     /// if_exists::= 'IF' 'EXISTS'
@@ -62,7 +62,12 @@ impl TableName {
                     && lista[1] != "VALUES"
                     && lista[1] != "GROUP"
                     && lista[1] != "ORDER"
+                    && lista[1] != "PER"
+                    && lista[1] != "LIMIT"
+                    && lista[1] != "ALLOW"
                     && lista[1] != "IF"
+                    && lista[1] != "USING"
+                    && lista[1] != "AS"
                 {
                     KeyspaceName::check_kind_of_name(lista)?
                 } else {
@@ -71,6 +76,19 @@ impl TableName {
             }
         }
 
+        // El tokenizador deja al keyspace y a la tabla en una misma palabra: `keyspace.tabla`
+        if keyspace.is_none() {
+            if let Some((keyspace_name, table_name)) = lista[0].clone().split_once('.') {
+                keyspace = KeyspaceName::check_kind_of_name(&mut vec![keyspace_name.to_string()])?;
+                if keyspace.is_none() {
+                    return Err(Error::SyntaxError(format!(
+                        "{keyspace_name} no es un nombre de keyspace valido"
+                    )));
+                }
+                lista[0] = table_name.to_string();
+            }
+        }
+
         let name = match KeyspaceName::check_kind_of_name(lista)? {
             Some(value) => value,
             None => {
@@ -0,0 +1,274 @@
+//! Módulo para los algoritmos de compresión del body de un mensaje.
+
+use {
+    crate::{
+        aliases::{
+            results::Result,
+            types::{Byte, Uint},
+        },
+        errors::error::Error,
+        headers::flags::Flag,
+        traits::Maskable,
+    },
+    lz4_flex::block::{compress, decompress},
+    snap::raw::{Decoder, Encoder},
+};
+
+/// La cantidad (en bytes) de los encabezados de un mensaje.
+const HEADERS_SIZE: usize = 9;
+/// El tamaño máximo de un body descomprimido (256 MB, el mismo límite del header Length).
+const MAX_BODY_SIZE: usize = 0x10000000;
+
+/// Un algoritmo con el que se puede comprimir el body de los mensajes.
+///
+/// Se negocia en la opción `COMPRESSION` del [STARTUP](crate::headers::opcode::Opcode::Startup),
+/// y a partir de ahí los mensajes con la flag [Compression](Flag::Compression) llevan el body
+/// comprimido:
+/// ```rust
+/// # use protocol::headers::compression::Compression;
+/// # use protocol::aliases::results::Result;
+/// # fn main() -> Result<()> {
+/// let frame = [0x5, 0x0, 0x0, 0x1, 0x7, 0x0, 0x0, 0x0, 0x4, 0x1, 0x2, 0x3, 0x4];
+/// let compressed = Compression::Lz4.compress_frame(&frame)?;
+///
+/// assert_eq!(compressed[1], 0x1);
+/// assert_eq!(Compression::Lz4.decompress_frame(&compressed)?, frame);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    /// Compresión LZ4, con el body precedido por su largo sin comprimir.
+    Lz4,
+
+    /// Compresión Snappy.
+    Snappy,
+}
+
+impl Compression {
+    /// Los algoritmos de compresión soportados.
+    pub const SUPPORTED: [Self; 2] = [Self::Lz4, Self::Snappy];
+
+    /// El nombre con el que se indica el algoritmo en el [STARTUP](crate::headers::opcode::Opcode::Startup).
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Lz4 => "lz4",
+            Self::Snappy => "snappy",
+        }
+    }
+
+    /// Comprime un body.
+    pub fn compress(&self, body: &[Byte]) -> Result<Vec<Byte>> {
+        match self {
+            Self::Lz4 => {
+                let mut compressed = (body.len() as Uint).to_be_bytes().to_vec();
+                compressed.extend(compress(body));
+                Ok(compressed)
+            }
+            Self::Snappy => Encoder::new().compress_vec(body).map_err(|e| {
+                Error::ProtocolError(format!("No se pudo comprimir el body con snappy: {e}"))
+            }),
+        }
+    }
+
+    /// Descomprime un body comprimido con este algoritmo.
+    pub fn decompress(&self, body: &[Byte]) -> Result<Vec<Byte>> {
+        match self {
+            Self::Lz4 => {
+                if body.len() < 4 {
+                    return Err(Error::ProtocolError(
+                        "El body comprimido con lz4 no indica su largo".to_string(),
+                    ));
+                }
+                let len = Uint::from_be_bytes([body[0], body[1], body[2], body[3]]) as usize;
+                if len > MAX_BODY_SIZE {
+                    return Err(Error::ProtocolError(
+                        "El body descomprimido es muy largo (supera los 256MB)".to_string(),
+                    ));
+                }
+                decompress(&body[4..], len).map_err(|e| {
+                    Error::ProtocolError(format!("No se pudo descomprimir el body con lz4: {e}"))
+                })
+            }
+            Self::Snappy => Decoder::new().decompress_vec(body).map_err(|e| {
+                Error::ProtocolError(format!("No se pudo descomprimir el body con snappy: {e}"))
+            }),
+        }
+    }
+
+    /// Comprime el body de un mensaje completo, marcándolo con la flag [Compression](Flag::Compression)
+    /// y actualizando su largo.
+    ///
+    /// Si el mensaje ya estaba comprimido, se devuelve tal cual. Los bytes que sigan al body
+    /// no se modifican.
+    pub fn compress_frame(&self, frame: &[Byte]) -> Result<Vec<Byte>> {
+        let (body_start, body_end) = body_bounds(frame)?;
+        if Flag::has_mask(&frame[1], &Flag::Compression) {
+            return Ok(frame.to_vec());
+        }
+        let body = self.compress(&frame[body_start..body_end])?;
+        Ok(rebuild_frame(
+            frame,
+            body,
+            body_end,
+            frame[1] | Flag::Compression.collapse(),
+        ))
+    }
+
+    /// Descomprime el body de un mensaje completo si tiene la flag [Compression](Flag::Compression),
+    /// quitándosela y actualizando su largo.
+    ///
+    /// Si el mensaje no estaba comprimido, se devuelve tal cual.
+    pub fn decompress_frame(&self, frame: &[Byte]) -> Result<Vec<Byte>> {
+        let (body_start, body_end) = body_bounds(frame)?;
+        if !Flag::has_mask(&frame[1], &Flag::Compression) {
+            return Ok(frame.to_vec());
+        }
+        let body = self.decompress(&frame[body_start..body_end])?;
+        Ok(rebuild_frame(
+            frame,
+            body,
+            body_end,
+            frame[1] & !Flag::Compression.collapse(),
+        ))
+    }
+}
+
+impl TryFrom<&str> for Compression {
+    type Error = Error;
+    fn try_from(name: &str) -> Result<Self> {
+        match name.to_lowercase().as_str() {
+            "lz4" => Ok(Self::Lz4),
+            "snappy" => Ok(Self::Snappy),
+            _ => Err(Error::ProtocolError(format!(
+                "'{name}' no es un algoritmo de compresión soportado"
+            ))),
+        }
+    }
+}
+
+/// Verifica si el mensaje dado tiene el body comprimido.
+pub fn is_compressed(frame: &[Byte]) -> bool {
+    frame.len() >= HEADERS_SIZE && Flag::has_mask(&frame[1], &Flag::Compression)
+}
+
+/// Obtiene dónde empieza y termina el body de un mensaje, según el largo indicado en sus encabezados.
+fn body_bounds(frame: &[Byte]) -> Result<(usize, usize)> {
+    if frame.len() < HEADERS_SIZE {
+        return Err(Error::Invalid(
+            "Se necesitan al menos 9 bytes para formar los encabezados.".to_string(),
+        ));
+    }
+    let len = Uint::from_be_bytes([frame[5], frame[6], frame[7], frame[8]]) as usize;
+    let body_end = HEADERS_SIZE + len;
+    if frame.len() < body_end {
+        return Err(Error::ProtocolError(
+            "El mensaje es más corto que el largo indicado en sus encabezados".to_string(),
+        ));
+    }
+    Ok((HEADERS_SIZE, body_end))
+}
+
+/// Arma un mensaje con los encabezados del original, las flags y el body dados, y los bytes
+/// que seguían al body original.
+fn rebuild_frame(frame: &[Byte], body: Vec<Byte>, body_end: usize, flags: Byte) -> Vec<Byte> {
+    let mut rebuilt = frame[..HEADERS_SIZE].to_vec();
+    rebuilt[1] = flags;
+    rebuilt.splice(5..9, (body.len() as Uint).to_be_bytes());
+    rebuilt.extend(body);
+    rebuilt.extend_from_slice(&frame[body_end..]);
+    rebuilt
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame_with_body(body: &[Byte]) -> Vec<Byte> {
+        let mut frame = vec![0x5, 0x2, 0x0, 0x1, 0x7];
+        frame.extend((body.len() as Uint).to_be_bytes());
+        frame.extend_from_slice(body);
+        frame
+    }
+
+    #[test]
+    fn test_1_comprimir_y_descomprimir_body() -> Result<()> {
+        let body = "SELECT * FROM vuelos; ".repeat(50).into_bytes();
+
+        for compression in Compression::SUPPORTED {
+            let compressed = compression.compress(&body)?;
+            assert!(compressed.len() < body.len());
+            assert_eq!(compression.decompress(&compressed)?, body);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_lz4_incluye_largo_sin_comprimir() -> Result<()> {
+        let body = vec![0x7; 300];
+        let compressed = Compression::Lz4.compress(&body)?;
+
+        assert_eq!(compressed[..4], [0x0, 0x0, 0x1, 0x2C]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_3_comprimir_frame_marca_flag_y_largo() -> Result<()> {
+        let body = vec![0x1; 500];
+        let frame = frame_with_body(&body);
+
+        for compression in Compression::SUPPORTED {
+            let compressed = compression.compress_frame(&frame)?;
+            let len =
+                Uint::from_be_bytes([compressed[5], compressed[6], compressed[7], compressed[8]]);
+
+            assert!(is_compressed(&compressed));
+            // Se conservan las demás flags
+            assert_eq!(compressed[1], 0x3);
+            assert_eq!(len as usize, compressed.len() - HEADERS_SIZE);
+            assert_eq!(compression.decompress_frame(&compressed)?, frame);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_4_frames_sin_flag_no_se_descomprimen() -> Result<()> {
+        let frame = frame_with_body(&[0x1, 0x2, 0x3]);
+
+        assert!(!is_compressed(&frame));
+        assert_eq!(Compression::Snappy.decompress_frame(&frame)?, frame);
+        Ok(())
+    }
+
+    #[test]
+    fn test_5_se_conservan_bytes_despues_del_body() -> Result<()> {
+        let mut frame = frame_with_body(&[0x4; 100]);
+        frame.extend([0xA, 0xB]);
+
+        let compressed = Compression::Lz4.compress_frame(&frame)?;
+        assert_eq!(compressed[compressed.len() - 2..], [0xA, 0xB]);
+        assert_eq!(Compression::Lz4.decompress_frame(&compressed)?, frame);
+        Ok(())
+    }
+
+    #[test]
+    fn test_6_body_invalido_da_error() {
+        let mut frame = frame_with_body(&[0x0, 0x0, 0x0, 0x9, 0xFF]);
+        frame[1] = 0x1;
+
+        assert!(Compression::Lz4.decompress_frame(&frame).is_err());
+        assert!(Compression::Snappy.decompress_frame(&frame).is_err());
+    }
+
+    #[test]
+    fn test_7_nombres_de_algoritmos() -> Result<()> {
+        assert_eq!(Compression::try_from("LZ4")?, Compression::Lz4);
+        assert_eq!(Compression::try_from("snappy")?, Compression::Snappy);
+        assert!(Compression::try_from("deflate").is_err());
+
+        for compression in Compression::SUPPORTED {
+            assert_eq!(Compression::try_from(compression.name())?, compression);
+        }
+        Ok(())
+    }
+}
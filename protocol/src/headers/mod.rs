@@ -1,5 +1,6 @@
 //! Paquete maestro de objetos de headers de Cassandra.
 
+pub mod compression;
 pub mod flags;
 pub mod length;
 pub mod msg_headers;
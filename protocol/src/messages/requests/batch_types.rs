@@ -7,6 +7,7 @@ use crate::{
 };
 
 /// El tipo de una instrucción BATCH, que es un conjunto de _queries_.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum BatchType {
     /// Equivalente a una instrucción normal de BATCH CQL3.
     Logged,
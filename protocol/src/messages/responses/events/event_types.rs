@@ -21,6 +21,7 @@ use {
 /// Tipos de mensaje [EVENT](crate::headers::opcode::Opcode::Event).
 ///
 /// Un cliente sólo escuchará eventos a los que se ha [registrado](crate::headers::opcode::Opcode::Register).
+#[derive(Clone, Debug)]
 pub enum EventType {
     /// Cambios relacionados a la topología del clúster de nodos.
     /// Por ejemplo, cuando un nodo es agregado o removido.
@@ -50,6 +51,17 @@ pub enum EventType {
     SchemaChange(SchemaChangeType, SchemaChangeTarget, SchemaChangeOption),
 }
 
+impl EventType {
+    /// Devuelve el nombre del tipo de evento, tal como se serializa al principio del cuerpo de un EVENT.
+    pub fn get_name(&self) -> &str {
+        match self {
+            Self::TopologyChange(_, _) => "TOPOLOGY_CHANGE",
+            Self::StatusChange(_, _) => "STATUS_CHANGE",
+            Self::SchemaChange(_, _, _) => "SCHEMA_CHANGE",
+        }
+    }
+}
+
 impl Byteable for EventType {
     fn as_bytes(&self) -> Vec<Byte> {
         let mut bytes_vec = vec![];
@@ -194,4 +206,22 @@ mod tests {
             assert!(matches!(err, Error::ConfigError(_)))
         }
     }
+
+    #[test]
+    fn test_4_nombre_y_cuerpo_son_reversibles() {
+        let event =
+            EventType::StatusChange("DOWN".to_string(), IpAddr::V4(Ipv4Addr::new(127, 0, 0, 4)));
+        let mut bytes = encode_string_to_bytes(event.get_name());
+        bytes.extend(event.as_bytes());
+
+        let parsed = EventType::try_from(&bytes[..]);
+        assert!(parsed.is_ok());
+        if let Ok(parsed_event) = parsed {
+            assert!(matches!(
+                parsed_event,
+                EventType::StatusChange(change_type, ipaddr)
+                    if change_type == "DOWN" && ipaddr == IpAddr::V4(Ipv4Addr::new(127, 0, 0, 4))
+            ));
+        }
+    }
 }
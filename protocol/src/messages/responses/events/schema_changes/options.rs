@@ -15,6 +15,7 @@ use {
 };
 
 /// Denota una opción en un evento [SCHEMA_CHANGE](crate::messages::responses::events::event_types::EventType::SchemaChange).
+#[derive(Clone, Debug)]
 pub enum SchemaChangeOption {
     /// Cuando el [_target_](crate::messages::responses::events::schema_changes::targets::SchemaChangeTarget)
     /// es un [_keyspace_](crate::messages::responses::events::schema_changes::targets::SchemaChangeTarget::Keyspace).
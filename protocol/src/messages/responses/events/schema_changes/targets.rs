@@ -14,6 +14,7 @@ use {
 };
 
 /// Denota un _target_ en un evento [SCHEMA_CHANGE](crate::messages::responses::events::event_types::EventType::SchemaChange).
+#[derive(Clone, Debug)]
 pub enum SchemaChangeTarget {
     /// El _target_ es un __keyspace_.
     Keyspace,
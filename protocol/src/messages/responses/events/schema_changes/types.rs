@@ -14,6 +14,7 @@ use {
 };
 
 /// Denota un tipo de cambio en el evento [SCHEMA_CHANGE](crate::messages::responses::events::event_types::EventType::SchemaChange).
+#[derive(Clone, Debug)]
 pub enum SchemaChangeType {
     /// Denota la creación de un _schema_.
     Created,
@@ -4,7 +4,7 @@ use {
     crate::{
         aliases::{
             results::Result,
-            types::{Byte, Int, ReasonMap, Short},
+            types::{Byte, Int, ReasonMap, Short, SupportedMultiMap, Uuid},
         },
         errors::error::Error,
     },
@@ -242,6 +242,11 @@ pub fn encode_iter_to_bytes(iterator: impl Iterator<Item = Vec<Byte>>) -> Vec<By
 ///     assert_eq!(str_2, string);
 ///     assert_eq!(i_2, 8);
 /// }
+///
+/// // Si la longitud declarada supera a los bytes restantes, es un error
+/// let mut i_3: usize = 0;
+/// assert!(parse_bytes_to_string(&[0x0, 0x9, 0x57, 0x6F], &mut i_3).is_err());
+/// assert_eq!(i_3, 0);
 /// ```
 pub fn parse_bytes_to_string(bytes_vec: &[Byte], i: &mut usize) -> Result<String> {
     let short_int: usize = 2; // los bytes de un short_int
@@ -251,6 +256,12 @@ pub fn parse_bytes_to_string(bytes_vec: &[Byte], i: &mut usize) -> Result<String
         ));
     }
     let string_len = Short::from_le_bytes([bytes_vec[1], bytes_vec[0]]) as usize;
+    if bytes_vec.len() < string_len + short_int {
+        return Err(Error::ProtocolError(format!(
+            "Se esperaba un string de {string_len} bytes, pero sólo quedan {}",
+            bytes_vec.len() - short_int
+        )));
+    }
     *i += string_len + short_int;
     match String::from_utf8(bytes_vec[short_int..(string_len + short_int)].to_vec()) {
         Ok(string) => Ok(string),
@@ -285,6 +296,11 @@ pub fn parse_bytes_to_string(bytes_vec: &[Byte], i: &mut usize) -> Result<String
 ///     assert_eq!(str_2, string);
 ///     assert_eq!(i_2, 10);
 /// }
+///
+/// // Si la longitud declarada supera a los bytes restantes, es un error
+/// let mut i_3: usize = 0;
+/// assert!(parse_bytes_to_long_string(&[0x0, 0x0, 0x0, 0x9, 0x57, 0x6F], &mut i_3).is_err());
+/// assert_eq!(i_3, 0);
 /// ```
 pub fn parse_bytes_to_long_string(bytes_vec: &[Byte], i: &mut usize) -> Result<String> {
     let int_len: usize = 4; // los bytes de un Int
@@ -295,6 +311,12 @@ pub fn parse_bytes_to_long_string(bytes_vec: &[Byte], i: &mut usize) -> Result<S
     }
     let string_len =
         Int::from_le_bytes([bytes_vec[3], bytes_vec[2], bytes_vec[1], bytes_vec[0]]) as usize;
+    if bytes_vec.len() < string_len + int_len {
+        return Err(Error::ProtocolError(format!(
+            "Se esperaba un string de {string_len} bytes, pero sólo quedan {}",
+            bytes_vec.len() - int_len
+        )));
+    }
     *i += string_len + int_len;
     match String::from_utf8(bytes_vec[int_len..(string_len + int_len)].to_vec()) {
         Ok(string) => Ok(string),
@@ -402,15 +424,83 @@ pub fn encode_string_map_to_bytes(string_map: Vec<(String, String)>) -> Vec<Byte
     bytes
 }
 
-/// Parsea un conjunto de [Byte]s de vuelta a un objeto [String].
+/// Transforma un [String Multimap](SupportedMultiMap) a una colección de [Byte]s tal cual como
+/// está especificado en el protocolo de Cassandra.
+///
+/// Las claves se ordenan alfabéticamente, para que el resultado sea siempre el mismo.
+///
+/// ```rust
+/// # use protocol::utils::encode_string_multimap_to_bytes;
+/// # use protocol::aliases::types::SupportedMultiMap;
+/// let mut multimap = SupportedMultiMap::new();
+/// multimap.insert("A".to_string(), vec!["b".to_string(), "c".to_string()]);
+///
+/// assert_eq!(encode_string_multimap_to_bytes(&multimap), vec![0x0, 0x1, /* <- longitud */
+///                                                             0x0, 0x1, 0x41, /* <- clave */
+///                                                             0x0, 0x2, 0x0, 0x1, 0x62, 0x0, 0x1, 0x63]);
+/// ```
+pub fn encode_string_multimap_to_bytes(multimap: &SupportedMultiMap) -> Vec<Byte> {
+    let mut keys: Vec<&String> = multimap.keys().collect();
+    keys.sort();
+
+    let mut bytes: Vec<Byte> = (multimap.len() as Short).to_be_bytes().to_vec();
+    for key in keys {
+        bytes.extend(encode_string_to_bytes(key));
+        let values = &multimap[key];
+        bytes.extend((values.len() as Short).to_be_bytes());
+        for value in values {
+            bytes.extend(encode_string_to_bytes(value));
+        }
+    }
+    bytes
+}
+
+/// Parsea un conjunto de [Byte]s de vuelta a un [String Map].
+///
+/// Esta es la operación recíproca a [encodearlo](encode_string_map_to_bytes).
+///
+/// ```rust
+/// # use protocol::utils::{encode_string_map_to_bytes, parse_bytes_to_string_map};
+/// let string_map = vec![("CQL_VERSION".to_string(), "5.0.0".to_string()),
+///                       ("COMPRESSION".to_string(), "lz4".to_string())];
+/// let bytes = encode_string_map_to_bytes(string_map.clone());
+///
+/// assert_eq!(parse_bytes_to_string_map(&bytes).ok(), Some(string_map));
+/// ```
 pub fn parse_bytes_to_string_map(bytes: &[Byte]) -> Result<Vec<(String, String)>> {
+    if bytes.len() < 2 {
+        return Err(Error::SyntaxError(
+            "Se esperaban 2 bytes que indiquen el tamaño del string map".to_string(),
+        ));
+    }
     let mut string_map: Vec<(String, String)> = Vec::new();
     let length: Short = Short::from_be_bytes([bytes[0], bytes[1]]);
     let mut vec_position = 2;
     for _i in 0..length {
-        let key = parse_bytes_to_string(bytes, &mut vec_position)?;
-        let value = parse_bytes_to_string(bytes, &mut vec_position)?;
+        let key = parse_bytes_to_string(&bytes[vec_position..], &mut vec_position)?;
+        let value = parse_bytes_to_string(&bytes[vec_position..], &mut vec_position)?;
         string_map.push((key, value));
     }
     Ok(string_map)
 }
+
+/// Transforma un [Uuid] a su representación textual estándar, en hexadecimal y separado en
+/// grupos de 8-4-4-4-12 dígitos.
+///
+/// ```rust
+/// # use protocol::utils::encode_uuid_to_string;
+/// let uuid = 0x123e4567_e89b_42d3_a456_426614174000;
+///
+/// assert_eq!(encode_uuid_to_string(uuid), "123e4567-e89b-42d3-a456-426614174000");
+/// ```
+pub fn encode_uuid_to_string(uuid: Uuid) -> String {
+    let hex = format!("{uuid:032x}");
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..]
+    )
+}
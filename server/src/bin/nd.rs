@@ -1,13 +1,32 @@
 //! Módulo para correr un nodo.
 
 use {
-    protocol::aliases::{results::Result, types::Byte},
-    server::nodes::node::Node,
-    std::{env::args, net::IpAddr, path::Path},
+    protocol::aliases::{
+        results::Result,
+        types::{Byte, Long},
+    },
+    server::{
+        modes::ConnectionMode,
+        nodes::{node::Node, node_options::NodeOptions},
+    },
+    std::{env::args, net::IpAddr, path::Path, str::FromStr},
 };
 
 fn main() {
-    let argv = args().collect::<Vec<String>>();
+    let mut argv = args().collect::<Vec<String>>();
+    let options = match take_options(&mut argv) {
+        Ok(options) => options,
+        Err(msg) => {
+            println!("{msg}");
+            return;
+        }
+    };
+    let has_options = options.vnodes.is_some()
+        || options.commit_log_sync.is_some()
+        || options.repair_interval_secs.is_some()
+        || options.phi_convict_threshold.is_some()
+        || options.datacenter.is_some()
+        || options.rack.is_some();
 
     if argv.len() >= 2 {
         if argv[1] == "new" && argv.len() >= 3 {
@@ -37,15 +56,18 @@ fn main() {
                         (format!("127.0.0.{id}"), 3)
                     };
                     println!("Nodo nuevo con id {id} y dirección IP {ip}.");
-                    if argv
+                    let echo = argv
                         .get(echo_idx)
-                        .is_some_and(|s| s.eq_ignore_ascii_case("echo"))
-                    {
+                        .is_some_and(|s| s.eq_ignore_ascii_case("echo"));
+                    match (echo, has_options) {
+                        // "target/debug/nd.exe" new <id> [<ip>] [echo] [<opciones>]
+                        (_, true) => {
+                            print_err(Node::init_new_with_options(id, &ip, mode(echo), options))
+                        }
                         // "target/debug/nd.exe" new <id> [<ip>] echo
-                        print_err(Node::init_new_in_echo_mode(id, &ip))
-                    } else {
+                        (true, false) => print_err(Node::init_new_in_echo_mode(id, &ip)),
                         // "target/debug/nd.exe" new <id> [<ip>]
-                        print_err(Node::init_new_in_parsing_mode(id, &ip))
+                        (false, false) => print_err(Node::init_new_in_parsing_mode(id, &ip)),
                     }
                 }
                 Err(_) => {
@@ -63,16 +85,38 @@ fn main() {
                     println!("El id debe ser un número entero entre 0 y 255.");
                 }
             }
+        } else if argv[1] == "repair" && (argv.len() == 3 || argv.len() == 4) {
+            // "target/debug/nd.exe" repair <id> [<keyspace>[.<tabla>]]
+            match argv[2].parse::<Byte>() {
+                Ok(id) => {
+                    let (keyspace_name, table_name) = match argv.get(3) {
+                        Some(target) => match target.split_once('.') {
+                            Some((keyspace, table)) => {
+                                (Some(keyspace.to_string()), Some(table.to_string()))
+                            }
+                            None => (Some(target.to_string()), None),
+                        },
+                        None => (None, None),
+                    };
+                    println!("Reparando las réplicas del nodo {id}.");
+                    print_err(Node::repair(id, keyspace_name, table_name));
+                }
+                Err(_) => {
+                    println!("El id debe ser un número entero entre 0 y 255.");
+                }
+            }
         } else {
             // "target/debug/nd.exe" <id> [echo]
             match argv[1].parse::<Byte>() {
                 Ok(id) => {
-                    if argv.len() == 3 && argv[2].eq_ignore_ascii_case("echo") {
+                    let echo = argv.len() == 3 && argv[2].eq_ignore_ascii_case("echo");
+                    match (echo, has_options) {
+                        // "target/debug/nd.exe" <id> [echo] [<opciones>]
+                        (_, true) => print_err(Node::init_with_options(id, mode(echo), options)),
                         // "target/debug/nd.exe" <id> echo
-                        print_err(Node::init_in_echo_mode(id))
-                    } else {
+                        (true, false) => print_err(Node::init_in_echo_mode(id)),
                         // "target/debug/nd.exe" <id>
-                        print_err(Node::init_in_parsing_mode(id))
+                        (false, false) => print_err(Node::init_in_parsing_mode(id)),
                     }
                 }
                 Err(_) => {
@@ -81,10 +125,82 @@ fn main() {
             }
         }
     } else {
-        println!("Uso:\n\ncargo run -p server --bin nd [new]/[delete] <id> [<ip>] [echo]\n");
+        println!(
+            "Uso:\n\ncargo run -p server --bin nd [new]/[delete] <id> [<ip>] [echo] [--vnodes <n>] [--commitlog-sync <periodic|batch>] [--repair-interval <segundos>] [--phi-convict-threshold <phi>] [--datacenter <dc>] [--rack <rack>]\ncargo run -p server --bin nd repair <id> [<keyspace>[.<tabla>]]\n"
+        );
     };
 }
 
+/// Quita de los argumentos las opciones de inicio del nodo que estén, y devuelve sus valores.
+fn take_options(argv: &mut Vec<String>) -> std::result::Result<NodeOptions, String> {
+    let vnodes = match take_flag::<usize>(argv, "--vnodes") {
+        Ok(Some(0)) | Err(_) => {
+            return Err("La cantidad de vnodes debe ser un número entero positivo.".to_string())
+        }
+        Ok(vnodes) => vnodes,
+    };
+    let commit_log_sync = take_flag(argv, "--commitlog-sync").map_err(|_| {
+        "El modo de sincronización del commit log debe ser 'periodic' o 'batch'.".to_string()
+    })?;
+    let repair_interval_secs = match take_flag::<Long>(argv, "--repair-interval") {
+        Ok(Some(secs)) if secs < 0 => Err(()),
+        res => res,
+    }
+    .map_err(|_| {
+        "El intervalo de reparación debe ser una cantidad de segundos no negativa.".to_string()
+    })?;
+    let phi_convict_threshold = match take_flag::<f64>(argv, "--phi-convict-threshold") {
+        Ok(Some(phi)) if !phi.is_finite() || phi <= 0.0 => Err(()),
+        res => res,
+    }
+    .map_err(|_| "El umbral del detector de fallas debe ser un número positivo.".to_string())?;
+    let datacenter = take_location_flag(argv, "--datacenter")?;
+    let rack = take_location_flag(argv, "--rack")?;
+    Ok(NodeOptions {
+        vnodes,
+        commit_log_sync,
+        repair_interval_secs,
+        phi_convict_threshold,
+        datacenter,
+        rack,
+    })
+}
+
+/// Quita de los argumentos una opción de la ubicación del nodo, y devuelve su valor.
+///
+/// El valor se guarda en el archivo de IPs, así que no puede estar vacío ni tener comas.
+fn take_location_flag(
+    argv: &mut Vec<String>,
+    flag: &str,
+) -> std::result::Result<Option<String>, String> {
+    match take_flag::<String>(argv, flag) {
+        Ok(Some(value)) if value.is_empty() || value.contains(',') => Err(()),
+        res => res,
+    }
+    .map_err(|_| format!("El valor de {flag} no puede estar vacío ni tener comas."))
+}
+
+/// Quita de los argumentos la opción `<flag> <valor>`, si está, y devuelve su valor.
+fn take_flag<T: FromStr>(argv: &mut Vec<String>, flag: &str) -> std::result::Result<Option<T>, ()> {
+    let Some(pos) = argv.iter().position(|arg| arg == flag) else {
+        return Ok(None);
+    };
+    let value = match argv.get(pos + 1).map(|value| value.parse::<T>()) {
+        Some(Ok(value)) => value,
+        _ => return Err(()),
+    };
+    argv.drain(pos..pos + 2);
+    Ok(Some(value))
+}
+
+fn mode(echo: bool) -> ConnectionMode {
+    if echo {
+        ConnectionMode::Echo
+    } else {
+        ConnectionMode::Parsing
+    }
+}
+
 fn print_err(res: Result<()>) {
     if let Err(err) = res {
         println!("{err}");
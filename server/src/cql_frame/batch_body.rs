@@ -0,0 +1,233 @@
+use crate::cql_frame::{
+    frame::encode_short_bytes,
+    query_body::{read_int, read_n_bytes, read_short_int},
+};
+use protocol::{
+    aliases::{
+        results::Result,
+        types::{Byte, Int, Long, ShortInt},
+    },
+    errors::error::Error,
+    messages::requests::{batch_flags::BatchFlag, batch_types::BatchType},
+    notations::consistency::Consistency,
+    traits::{Byteable, Maskable},
+    utils::{
+        encode_long_string_to_bytes, encode_string_to_bytes, parse_bytes_to_long_string,
+        parse_bytes_to_string,
+    },
+};
+
+/// Una de las _queries_ que componen un BATCH.
+#[derive(Clone)]
+pub enum BatchQuery {
+    /// Una _query_ en texto plano, con los valores de sus marcadores si los tiene.
+    Query {
+        /// La _query_ en sí.
+        query: String,
+        /// Los valores a vincular con los marcadores de la _query_.
+        values: Vec<Vec<Byte>>,
+    },
+
+    /// Una _query_ preparada previamente, con los valores de sus marcadores.
+    Prepared {
+        /// El ID de la _query_ preparada.
+        id: Vec<Byte>,
+        /// Los valores a vincular con los marcadores de la _query_.
+        values: Vec<Vec<Byte>>,
+    },
+}
+
+impl BatchQuery {
+    /// Devuelve los valores a vincular con los marcadores de la _query_.
+    pub fn get_values(&self) -> &[Vec<Byte>] {
+        match self {
+            Self::Query { values, .. } | Self::Prepared { values, .. } => values,
+        }
+    }
+
+    fn set_values(&mut self, new_values: Vec<Vec<Byte>>) {
+        match self {
+            Self::Query { values, .. } | Self::Prepared { values, .. } => *values = new_values,
+        }
+    }
+}
+
+impl Byteable for BatchQuery {
+    fn as_bytes(&self) -> Vec<Byte> {
+        let mut bytes = match self {
+            Self::Query { query, .. } => {
+                let mut bytes = vec![0];
+                bytes.extend(encode_long_string_to_bytes(query));
+                bytes
+            }
+            Self::Prepared { id, .. } => {
+                let mut bytes = vec![1];
+                bytes.extend(encode_short_bytes(id));
+                bytes
+            }
+        };
+        let values = self.get_values();
+        bytes.extend((values.len() as ShortInt).to_be_bytes());
+        for value in values {
+            bytes.extend((value.len() as Int).to_be_bytes());
+            bytes.extend(value);
+        }
+        bytes
+    }
+}
+
+/// Body para los mensajes BATCH.
+///
+/// Tiene la forma `<type><n><query_1>...<query_n><consistency><flags>[<serial_consistency>][<timestamp>][<keyspace>]`.
+pub struct BatchBody {
+    batch_type: BatchType,
+    queries: Vec<BatchQuery>,
+    consistency: Consistency,
+    serial_consistency: Option<Consistency>,
+    timestamp: Option<Long>,
+    keyspace: Option<String>,
+}
+
+impl BatchBody {
+    /// Crea un nuevo body para un BATCH.
+    pub fn new(batch_type: BatchType, queries: Vec<BatchQuery>, consistency: Consistency) -> Self {
+        Self {
+            batch_type,
+            queries,
+            consistency,
+            serial_consistency: None,
+            timestamp: None,
+            keyspace: None,
+        }
+    }
+
+    /// Devuelve el tipo del BATCH.
+    pub fn get_batch_type(&self) -> BatchType {
+        self.batch_type
+    }
+
+    /// Devuelve las _queries_ del BATCH.
+    pub fn get_queries(&self) -> &[BatchQuery] {
+        &self.queries
+    }
+
+    /// Devuelve el _Consistency Level_ del BATCH.
+    pub fn get_consistency_level(&self) -> &Consistency {
+        &self.consistency
+    }
+
+    /// Devuelve el timestamp _(en microsegundos)_ indicado por el cliente para el BATCH, si lo hay.
+    pub fn get_timestamp(&self) -> Option<Long> {
+        self.timestamp
+    }
+
+    /// Devuelve el _keyspace_ explícito del BATCH, si lo tiene.
+    pub fn get_keyspace(&self) -> Option<&str> {
+        self.keyspace.as_deref()
+    }
+}
+
+impl Byteable for BatchBody {
+    fn as_bytes(&self) -> Vec<Byte> {
+        let mut bytes = self.batch_type.as_bytes();
+        bytes.extend((self.queries.len() as ShortInt).to_be_bytes());
+        for query in &self.queries {
+            bytes.extend(query.as_bytes());
+        }
+        bytes.extend(self.consistency.as_bytes());
+
+        let mut flags: Vec<&BatchFlag> = Vec::new();
+        if self.serial_consistency.is_some() {
+            flags.push(&BatchFlag::WithSerialConsistency);
+        }
+        if self.timestamp.is_some() {
+            flags.push(&BatchFlag::WithDefaultTimestamp);
+        }
+        if self.keyspace.is_some() {
+            flags.push(&BatchFlag::WithKeyspace);
+        }
+        bytes.extend(BatchFlag::accumulate(&flags).to_be_bytes());
+
+        if let Some(serial_consistency) = &self.serial_consistency {
+            bytes.extend(serial_consistency.as_bytes());
+        }
+        if let Some(timestamp) = self.timestamp {
+            bytes.extend(timestamp.to_be_bytes());
+        }
+        if let Some(keyspace) = &self.keyspace {
+            bytes.extend(encode_string_to_bytes(keyspace));
+        }
+        bytes
+    }
+}
+
+impl TryFrom<&[Byte]> for BatchBody {
+    type Error = Error;
+
+    fn try_from(bytes: &[Byte]) -> Result<Self> {
+        let mut i = 0;
+        let batch_type = BatchType::try_from(read_n_bytes(bytes, &mut i, 1)?[0])?;
+        let n = read_short_int(bytes, &mut i)?;
+        let mut queries = Vec::new();
+        for _ in 0..n {
+            let kind = read_n_bytes(bytes, &mut i, 1)?[0];
+            let mut displacement = 0;
+            let mut query = match kind {
+                0 => {
+                    let query = parse_bytes_to_long_string(&bytes[i..], &mut displacement)?;
+                    i += displacement;
+                    BatchQuery::Query {
+                        query,
+                        values: Vec::new(),
+                    }
+                }
+                1 => {
+                    let len = read_short_int(bytes, &mut i)?;
+                    BatchQuery::Prepared {
+                        id: read_n_bytes(bytes, &mut i, len.max(0) as usize)?,
+                        values: Vec::new(),
+                    }
+                }
+                _ => {
+                    return Err(Error::ProtocolError(format!(
+                        "{kind} no es un tipo válido de query dentro de un BATCH"
+                    )))
+                }
+            };
+
+            let n_values = read_short_int(bytes, &mut i)?;
+            let mut values = Vec::new();
+            for _ in 0..n_values {
+                let len = read_int(bytes, &mut i)?;
+                if len < 0 {
+                    return Err(Error::Invalid(
+                        "No se admiten valores nulos o sin setear como variables de la query"
+                            .to_string(),
+                    ));
+                }
+                values.push(read_n_bytes(bytes, &mut i, len as usize)?);
+            }
+            query.set_values(values);
+            queries.push(query);
+        }
+
+        let consistency = Consistency::try_from(&read_n_bytes(bytes, &mut i, 2)?[..])?;
+        let mut batch_body = BatchBody::new(batch_type, queries, consistency);
+        let flags = read_int(bytes, &mut i)?;
+        if BatchFlag::has_mask(&flags, &BatchFlag::WithSerialConsistency) {
+            let serial = read_n_bytes(bytes, &mut i, 2)?;
+            batch_body.serial_consistency = Some(Consistency::try_from(&serial[..])?);
+        }
+        if BatchFlag::has_mask(&flags, &BatchFlag::WithDefaultTimestamp) {
+            let ts = read_n_bytes(bytes, &mut i, 8)?;
+            batch_body.timestamp = Some(Long::from_be_bytes([
+                ts[0], ts[1], ts[2], ts[3], ts[4], ts[5], ts[6], ts[7],
+            ]));
+        }
+        if BatchFlag::has_mask(&flags, &BatchFlag::WithKeyspace) {
+            let mut displacement = 0;
+            batch_body.keyspace = Some(parse_bytes_to_string(&bytes[i..], &mut displacement)?);
+        }
+        Ok(batch_body)
+    }
+}
@@ -1,16 +1,21 @@
-use crate::cql_frame::query_body::QueryBody;
+use crate::cql_frame::{
+    batch_body::{BatchBody, BatchQuery},
+    query_body::QueryBody,
+};
 use protocol::{
     aliases::{
         results::Result,
-        types::{Byte, ShortInt, Uint},
+        types::{Byte, Int, Short, ShortInt, Uint},
     },
     errors::error::Error,
     headers::{
         flags::Flag, length::Length, msg_headers::Headers, opcode::Opcode, stream::Stream,
         version::Version,
     },
+    messages::requests::batch_types::BatchType,
     notations::consistency::Consistency,
     traits::Byteable,
+    utils::{encode_long_string_to_bytes, encode_string_to_bytes},
 };
 
 /// Representa un frame del protocolo CQL, tanto para requests como responses
@@ -22,12 +27,67 @@ pub struct Frame {
 impl Frame {
     /// Crea un nuevo frame dada la query y el _Consistency Level_.
     pub fn new(stream_id: ShortInt, query: &str, consistency: Consistency) -> Self {
-        let body = QueryBody::new(query.to_string(), consistency).as_bytes();
+        Self::new_query(stream_id, &QueryBody::new(query.to_string(), consistency))
+    }
+
+    /// Crea un frame QUERY con un body ya armado, por ejemplo con parámetros adicionales.
+    pub fn new_query(stream_id: ShortInt, query_body: &QueryBody) -> Self {
+        Self::with_body(stream_id, Opcode::Query, query_body.as_bytes())
+    }
+
+    /// Crea un frame PREPARE para la query dada.
+    ///
+    /// El body tiene la forma `<query><flags>`, sin _keyspace_ explícito.
+    pub fn new_prepare(stream_id: ShortInt, query: &str) -> Self {
+        let mut body = encode_long_string_to_bytes(query);
+        body.extend((0 as Int).to_be_bytes());
+        Self::with_body(stream_id, Opcode::Prepare, body)
+    }
+
+    /// Crea un frame EXECUTE para una query preparada previamente, vinculando los valores dados.
+    ///
+    /// El body tiene la forma `<id><result_metadata_id><query_parameters>`.
+    pub fn new_execute(
+        stream_id: ShortInt,
+        id: &[Byte],
+        consistency: Consistency,
+        values: Vec<Vec<Byte>>,
+    ) -> Self {
+        let mut query_body = QueryBody::new(String::new(), consistency);
+        query_body.set_values(values);
+
+        let mut body = encode_short_bytes(id);
+        body.extend(encode_short_bytes(id));
+        body.extend(query_body.parameters_as_bytes());
+        Self::with_body(stream_id, Opcode::Execute, body)
+    }
+
+    /// Crea un frame BATCH con las queries dadas, a ejecutar todas con el mismo _Consistency Level_.
+    pub fn new_batch(
+        stream_id: ShortInt,
+        batch_type: BatchType,
+        queries: Vec<BatchQuery>,
+        consistency: Consistency,
+    ) -> Self {
+        let body = BatchBody::new(batch_type, queries, consistency).as_bytes();
+        Self::with_body(stream_id, Opcode::Batch, body)
+    }
+
+    /// Crea un frame REGISTER, para recibir los eventos de los tipos dados.
+    pub fn new_register(stream_id: ShortInt, event_types: &[&str]) -> Self {
+        let mut body = (event_types.len() as Short).to_be_bytes().to_vec();
+        for event_type in event_types {
+            body.extend(encode_string_to_bytes(event_type));
+        }
+        Self::with_body(stream_id, Opcode::Register, body)
+    }
+
+    fn with_body(stream_id: ShortInt, opcode: Opcode, body: Vec<Byte>) -> Self {
         let headers = Headers::new(
             Version::RequestV5,
             vec![Flag::Default],
             Stream::new(stream_id),
-            Opcode::Query,
+            opcode,
             Length::new(body.len() as Uint),
         );
 
@@ -35,6 +95,13 @@ impl Frame {
     }
 }
 
+/// Serializa una secuencia de [Byte]s precedida por su longitud como un [Short](protocol::aliases::types::Short).
+pub fn encode_short_bytes(bytes: &[Byte]) -> Vec<Byte> {
+    let mut encoded = (bytes.len() as Short).to_be_bytes().to_vec();
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
 impl Byteable for Frame {
     fn as_bytes(&self) -> Vec<Byte> {
         let mut bytes = self.headers.as_bytes();
@@ -1,8 +1,14 @@
 //! Paquete para los frames del protocolo CQL.
 
+/// Módulo que contiene el body de los BATCH.
+pub mod batch_body;
+
 /// Módulo que contiene el frame de CQL.
 pub mod frame;
 
+/// Módulo que contiene el estado de paginación de los SELECT.
+pub mod paging_state;
+
 /// Módulo que contiene el body de las queries.
 pub mod query_body;
 
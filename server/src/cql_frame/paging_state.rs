@@ -0,0 +1,218 @@
+use crate::cql_frame::query_body::{read_int, read_n_bytes, read_short_int, QueryBody};
+use protocol::{
+    aliases::{
+        results::Result,
+        types::{Byte, Int, Short, ShortInt, Uint},
+    },
+    errors::error::Error,
+    traits::Byteable,
+    utils::encode_string_to_bytes,
+};
+
+/// El punto desde el cual retomar un SELECT paginado.
+///
+/// Para el cliente es un valor opaco, que devuelve tal cual para pedir la página siguiente.
+/// Se serializa como `<nodo><filas><n><key_1>...<key_n><limite>`, donde `<key_i>` son los valores
+/// de la _primary key_ de la última fila devuelta, de la partición y luego de las columnas de
+/// _clustering_, y `<limite>` es la cantidad de filas que faltan para el LIMIT, o -1 si no hay.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PagingState {
+    /// El nodo cuyas particiones se estaban leyendo.
+    pub node_id: Byte,
+
+    /// La cantidad de filas de ese nodo que ya se devolvieron.
+    ///
+    /// Sólo se usa si la última fila devuelta ya no existe, para retomar aproximadamente
+    /// desde el mismo lugar.
+    pub rows_read: Uint,
+
+    /// La _primary key_ de la última fila devuelta, o vacía si todavía no se leyó ninguna
+    /// fila del nodo.
+    pub last_key: Vec<String>,
+
+    /// La cantidad de filas que todavía se pueden devolver según el LIMIT del SELECT, si tiene.
+    pub limit_left: Option<Uint>,
+}
+
+impl PagingState {
+    /// Crea un estado que retoma la lectura después de la fila con la _primary key_ dada.
+    pub fn new(node_id: Byte, rows_read: Uint, last_key: Vec<String>) -> Self {
+        Self {
+            node_id,
+            rows_read,
+            last_key,
+            limit_left: None,
+        }
+    }
+
+    /// Devuelve el mismo estado, recordando cuántas filas faltan para el LIMIT.
+    pub fn with_limit_left(mut self, limit_left: Option<Uint>) -> Self {
+        self.limit_left = limit_left;
+        self
+    }
+
+    /// Crea un estado que retoma la lectura desde la primera fila de un nodo.
+    pub fn start_of(node_id: Byte) -> Self {
+        Self::new(node_id, 0, Vec::new())
+    }
+
+    /// Devuelve la posición, dentro de las filas ordenadas de su nodo, desde la que hay que
+    /// retomar la lectura.
+    ///
+    /// `key_of` obtiene la _primary key_ de una fila.
+    pub fn resume_position<F>(&self, rows: &[Vec<String>], key_of: F) -> usize
+    where
+        F: Fn(&Vec<String>) -> Vec<String>,
+    {
+        if self.last_key.is_empty() {
+            return 0;
+        }
+        match rows.iter().position(|row| key_of(row) == self.last_key) {
+            Some(position) => position + 1,
+            None => (self.rows_read as usize).min(rows.len()),
+        }
+    }
+}
+
+impl Byteable for PagingState {
+    fn as_bytes(&self) -> Vec<Byte> {
+        let mut bytes = vec![self.node_id];
+        bytes.extend((self.rows_read as Int).to_be_bytes());
+        bytes.extend((self.last_key.len() as ShortInt).to_be_bytes());
+        for value in &self.last_key {
+            bytes.extend(encode_string_to_bytes(value));
+        }
+        let limit_left = self.limit_left.map_or(-1, |limit_left| limit_left as Int);
+        bytes.extend(limit_left.to_be_bytes());
+        bytes
+    }
+}
+
+impl TryFrom<&[Byte]> for PagingState {
+    type Error = Error;
+
+    fn try_from(bytes: &[Byte]) -> Result<Self> {
+        let invalid = || Error::ProtocolError("El paging_state recibido no es válido".to_string());
+        let mut i = 0;
+        let node_id = read_n_bytes(bytes, &mut i, 1).map_err(|_| invalid())?[0];
+        let rows_read = read_int(bytes, &mut i).map_err(|_| invalid())?;
+        let n = read_short_int(bytes, &mut i).map_err(|_| invalid())?;
+        let mut last_key = Vec::new();
+        for _ in 0..n {
+            let len = read_short_int(bytes, &mut i).map_err(|_| invalid())?;
+            let value =
+                read_n_bytes(bytes, &mut i, len as Short as usize).map_err(|_| invalid())?;
+            last_key.push(String::from_utf8(value).map_err(|_| invalid())?);
+        }
+        let limit_left = read_int(bytes, &mut i).map_err(|_| invalid())?;
+        if rows_read < 0 || limit_left < -1 || i != bytes.len() {
+            return Err(invalid());
+        }
+        let limit_left = (limit_left >= 0).then_some(limit_left as Uint);
+        Ok(Self::new(node_id, rows_read as Uint, last_key).with_limit_left(limit_left))
+    }
+}
+
+/// Una página pedida de un SELECT.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Page {
+    /// La cantidad máxima de filas de la página.
+    pub size: usize,
+
+    /// Desde dónde retomar la lectura, o `None` si es la primera página.
+    pub state: Option<PagingState>,
+}
+
+impl Page {
+    /// Crea una página pedida.
+    pub fn new(size: usize, state: Option<PagingState>) -> Self {
+        Self { size, state }
+    }
+
+    /// Obtiene la página pedida en los parámetros de una query, si se pidió paginar.
+    pub fn from_query_body(query_body: &QueryBody) -> Result<Option<Self>> {
+        let Some(page_size) = query_body.get_page_size() else {
+            return Ok(None);
+        };
+        let state = query_body
+            .get_paging_state()
+            .map(PagingState::try_from)
+            .transpose()?;
+        Ok(Some(Self::new(page_size as usize, state)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_of(row: &[String]) -> Vec<String> {
+        row[..2].to_vec()
+    }
+
+    fn rows() -> Vec<Vec<String>> {
+        [("EZE", "1"), ("EZE", "2"), ("JFK", "1"), ("JFK", "3")]
+            .iter()
+            .map(|(partition, id)| vec![partition.to_string(), id.to_string(), "x".to_string()])
+            .collect()
+    }
+
+    #[test]
+    fn test_1_ida_y_vuelta_de_bytes() {
+        let state = PagingState::new(12, 7, vec!["EZE".to_string(), "2".to_string()]);
+        let bytes = state.as_bytes();
+
+        assert_eq!(PagingState::try_from(&bytes[..]).ok(), Some(state));
+    }
+
+    #[test]
+    fn test_2_ida_y_vuelta_con_limite() {
+        let state = PagingState::start_of(12).with_limit_left(Some(5));
+        let bytes = state.as_bytes();
+
+        assert_eq!(
+            PagingState::try_from(&bytes[..])
+                .ok()
+                .map(|state| state.limit_left),
+            Some(Some(5))
+        );
+    }
+
+    #[test]
+    fn test_3_bytes_invalidos() {
+        let mut bytes = PagingState::new(12, 7, vec!["EZE".to_string()]).as_bytes();
+        bytes.push(0x0);
+
+        assert!(matches!(
+            PagingState::try_from(&bytes[..]),
+            Err(Error::ProtocolError(_))
+        ));
+        assert!(PagingState::try_from(&bytes[..3]).is_err());
+    }
+
+    #[test]
+    fn test_4_retomar_desde_el_principio() {
+        let state = PagingState::start_of(12);
+
+        assert_eq!(state.resume_position(&rows(), |row| key_of(row)), 0);
+    }
+
+    #[test]
+    fn test_5_retomar_despues_de_la_ultima_fila() {
+        let state = PagingState::new(12, 2, vec!["EZE".to_string(), "2".to_string()]);
+
+        assert_eq!(state.resume_position(&rows(), |row| key_of(row)), 2);
+    }
+
+    #[test]
+    fn test_6_retomar_si_la_ultima_fila_ya_no_existe() {
+        let state = PagingState::new(12, 3, vec!["JFK".to_string(), "2".to_string()]);
+        assert_eq!(state.resume_position(&rows(), |row| key_of(row)), 3);
+
+        let state = PagingState::new(12, 10, vec!["JFK".to_string(), "2".to_string()]);
+        assert_eq!(
+            state.resume_position(&rows(), |row| key_of(row)),
+            rows().len()
+        );
+    }
+}
@@ -7,15 +7,17 @@ use protocol::{
     errors::error::Error,
     notations::consistency::Consistency,
     traits::Byteable,
-    utils::{encode_long_string_to_bytes, parse_bytes_to_long_string},
+    utils::{encode_long_string_to_bytes, encode_string_to_bytes, parse_bytes_to_long_string},
 };
 
 /// Body para queries individuales
+#[derive(Clone)]
 pub struct QueryBody {
     query: String,
     consistency: Consistency,
     flags: Vec<QueryFlags>,
     values: Option<Vec<Vec<Byte>>>,
+    value_names: Option<Vec<String>>,
     page_size: Option<Int>,
     paging_state: Option<Vec<Byte>>,
     serial_consistency: Option<Consistency>,
@@ -30,6 +32,7 @@ impl QueryBody {
             consistency,
             flags: Vec::new(),
             values: None,
+            value_names: None,
             page_size: None,
             paging_state: None,
             serial_consistency: None,
@@ -46,16 +49,147 @@ impl QueryBody {
     pub fn get_consistency_level(&self) -> &Consistency {
         &self.consistency
     }
-}
 
-impl Byteable for QueryBody {
-    fn as_bytes(&self) -> Vec<Byte> {
-        let mut bytes = Vec::new();
+    /// Agrega valores a vincular con los marcadores de la query.
+    pub fn set_values(&mut self, values: Vec<Vec<Byte>>) {
+        if !self.has_flag(QueryFlags::Values) {
+            self.flags.push(QueryFlags::Values);
+        }
+        self.values = Some(values);
+    }
 
-        // Query string
-        let query_bytes = encode_long_string_to_bytes(&self.query);
-        // bytes.extend((query_bytes.len() as Int).to_be_bytes());
-        bytes.extend(query_bytes);
+    /// Agrega valores con nombre a vincular con los marcadores `:nombre` de la query.
+    pub fn set_named_values(&mut self, values: Vec<(String, Vec<Byte>)>) {
+        if !self.has_flag(QueryFlags::WithNamesForValues) {
+            self.flags.push(QueryFlags::WithNamesForValues);
+        }
+        let (names, values) = values.into_iter().unzip();
+        self.value_names = Some(names);
+        self.set_values(values);
+    }
+
+    /// Devuelve los valores a vincular con los marcadores de la query, si los hay.
+    pub fn get_values(&self) -> Option<&Vec<Vec<Byte>>> {
+        self.values.as_ref()
+    }
+
+    /// Devuelve los nombres de los valores a vincular, si se mandaron con nombre.
+    pub fn get_value_names(&self) -> Option<&[String]> {
+        self.value_names.as_deref()
+    }
+
+    /// Reemplaza la query del body, manteniendo los valores a vincular con sus marcadores.
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+    }
+
+    /// Indica la cantidad máxima de filas a devolver por página, y el estado desde el cual
+    /// retomar la lectura si no es la primera página.
+    pub fn set_paging(&mut self, page_size: Int, paging_state: Option<Vec<Byte>>) {
+        if !self.has_flag(QueryFlags::PageSize) {
+            self.flags.push(QueryFlags::PageSize);
+        }
+        self.page_size = Some(page_size);
+        self.flags
+            .retain(|flag| *flag as Byte != QueryFlags::WithPagingState as Byte);
+        if paging_state.is_some() {
+            self.flags.push(QueryFlags::WithPagingState);
+        }
+        self.paging_state = paging_state;
+    }
+
+    /// Devuelve la cantidad máxima de filas a devolver por página, si se pidió paginar.
+    pub fn get_page_size(&self) -> Option<Int> {
+        self.page_size.filter(|page_size| *page_size > 0)
+    }
+
+    /// Devuelve el estado desde el cual retomar la lectura, si no es la primera página.
+    pub fn get_paging_state(&self) -> Option<&[Byte]> {
+        self.paging_state.as_deref()
+    }
+
+    /// Indica el timestamp _(en microsegundos)_ a usar por defecto en las escrituras de la query.
+    pub fn set_timestamp(&mut self, timestamp: Long) {
+        if !self.has_flag(QueryFlags::WithDefaultTimestamp) {
+            self.flags.push(QueryFlags::WithDefaultTimestamp);
+        }
+        self.timestamp = Some(timestamp);
+    }
+
+    /// Devuelve el timestamp _(en microsegundos)_ a usar por defecto en las escrituras, si lo hay.
+    pub fn get_timestamp(&self) -> Option<Long> {
+        self.timestamp
+    }
+
+    /// Verifica si el body tiene una flag dada.
+    pub fn has_flag(&self, flag: QueryFlags) -> bool {
+        self.flags.iter().any(|f| *f as Byte == flag as Byte)
+    }
+
+    /// Parsea los `<query_parameters>` de un mensaje, es decir, todo lo que sigue a la query en sí.
+    ///
+    /// Es el mismo formato que usan los mensajes QUERY y EXECUTE, por eso la query se recibe aparte.
+    pub fn parse_query_parameters(query: String, bytes: &[Byte]) -> Result<Self> {
+        if bytes.len() < 2 {
+            return Err(Error::ProtocolError(
+                "Se esperaban al menos 2 bytes para el Consistency Level".to_string(),
+            ));
+        }
+        let consistency = Consistency::try_from(&bytes[..2])?;
+        let mut query_body = QueryBody::new(query, consistency);
+        let mut i = 2;
+        let Some(flags_byte) = bytes.get(i) else {
+            return Ok(query_body);
+        };
+        query_body.flags = QueryFlags::decompose(*flags_byte);
+        i += 1;
+
+        if query_body.has_flag(QueryFlags::Values) {
+            let with_names = query_body.has_flag(QueryFlags::WithNamesForValues);
+            let n = read_short_int(bytes, &mut i)?;
+            let mut values = Vec::new();
+            let mut names = Vec::new();
+            for _ in 0..n {
+                if with_names {
+                    names.push(read_string(bytes, &mut i)?);
+                }
+                let len = read_int(bytes, &mut i)?;
+                if len < 0 {
+                    return Err(Error::Invalid(
+                        "No se admiten valores nulos o sin setear como variables de la query"
+                            .to_string(),
+                    ));
+                }
+                values.push(read_n_bytes(bytes, &mut i, len as usize)?);
+            }
+            query_body.values = Some(values);
+            if with_names {
+                query_body.value_names = Some(names);
+            }
+        }
+        if query_body.has_flag(QueryFlags::PageSize) {
+            query_body.page_size = Some(read_int(bytes, &mut i)?);
+        }
+        if query_body.has_flag(QueryFlags::WithPagingState) {
+            let len = read_int(bytes, &mut i)?;
+            query_body.paging_state = Some(read_n_bytes(bytes, &mut i, len.max(0) as usize)?);
+        }
+        if query_body.has_flag(QueryFlags::WithSerialConsistency) {
+            let serial = read_n_bytes(bytes, &mut i, 2)?;
+            query_body.serial_consistency = Some(Consistency::try_from(&serial[..])?);
+        }
+        if query_body.has_flag(QueryFlags::WithDefaultTimestamp) {
+            let ts = read_n_bytes(bytes, &mut i, 8)?;
+            query_body.timestamp = Some(Long::from_be_bytes([
+                ts[0], ts[1], ts[2], ts[3], ts[4], ts[5], ts[6], ts[7],
+            ]));
+        }
+        Ok(query_body)
+    }
+
+    /// Serializa sólo los `<query_parameters>`, sin la query en sí.
+    pub fn parameters_as_bytes(&self) -> Vec<Byte> {
+        let mut bytes = Vec::new();
 
         // Consistency
         bytes.extend(self.consistency.as_bytes());
@@ -64,13 +198,16 @@ impl Byteable for QueryBody {
         let flags_byte = self.flags.iter().fold(0u8, |acc, flag| acc | *flag as Byte);
         bytes.push(flags_byte);
 
-        // Optional values based on flags
-        for flag in &self.flags {
+        // Optional values based on flags, siempre en el orden del protocolo
+        for flag in QueryFlags::decompose(flags_byte) {
             match flag {
                 QueryFlags::Values => {
                     if let Some(values) = &self.values {
                         bytes.extend((values.len() as ShortInt).to_be_bytes());
-                        for value in values {
+                        for (j, value) in values.iter().enumerate() {
+                            if let Some(name) = self.value_names.as_ref().and_then(|n| n.get(j)) {
+                                bytes.extend(encode_string_to_bytes(name));
+                            }
                             bytes.extend((value.len() as Int).to_be_bytes());
                             bytes.extend(value);
                         }
@@ -89,7 +226,7 @@ impl Byteable for QueryBody {
                 }
                 QueryFlags::WithSerialConsistency => {
                     if let Some(consistency) = &self.serial_consistency {
-                        bytes.extend((*consistency as Byte).to_be_bytes());
+                        bytes.extend(consistency.as_bytes());
                     }
                 }
                 QueryFlags::WithDefaultTimestamp => {
@@ -104,22 +241,51 @@ impl Byteable for QueryBody {
     }
 }
 
+pub(crate) fn read_n_bytes(bytes: &[Byte], i: &mut usize, n: usize) -> Result<Vec<Byte>> {
+    match bytes.get(*i..*i + n) {
+        Some(read) => {
+            *i += n;
+            Ok(read.to_vec())
+        }
+        None => Err(Error::ProtocolError(format!(
+            "Se esperaban {n} bytes más en los parámetros de la query"
+        ))),
+    }
+}
+
+pub(crate) fn read_short_int(bytes: &[Byte], i: &mut usize) -> Result<ShortInt> {
+    let read = read_n_bytes(bytes, i, 2)?;
+    Ok(ShortInt::from_be_bytes([read[0], read[1]]))
+}
+
+pub(crate) fn read_string(bytes: &[Byte], i: &mut usize) -> Result<String> {
+    let len = read_short_int(bytes, i)?;
+    let read = read_n_bytes(bytes, i, len.max(0) as usize)?;
+    String::from_utf8(read).map_err(|_| {
+        Error::ProtocolError("El nombre de un valor de la query no es UTF-8 válido".to_string())
+    })
+}
+
+pub(crate) fn read_int(bytes: &[Byte], i: &mut usize) -> Result<Int> {
+    let read = read_n_bytes(bytes, i, 4)?;
+    Ok(Int::from_be_bytes([read[0], read[1], read[2], read[3]]))
+}
+
+impl Byteable for QueryBody {
+    fn as_bytes(&self) -> Vec<Byte> {
+        // Query string
+        let mut bytes = encode_long_string_to_bytes(&self.query);
+        bytes.extend(self.parameters_as_bytes());
+        bytes
+    }
+}
+
 impl TryFrom<&[Byte]> for QueryBody {
     type Error = Error;
 
     fn try_from(bytes: &[Byte]) -> Result<Self> {
         let mut query_lenght: usize = 0;
         let query = parse_bytes_to_long_string(bytes, &mut query_lenght)?;
-        let consistency = Consistency::try_from(&bytes[query_lenght..(query_lenght + 2)])?;
-        Ok(QueryBody {
-            query,
-            consistency,
-            flags: Vec::new(),
-            values: None,
-            page_size: None,
-            paging_state: None,
-            serial_consistency: None,
-            timestamp: None,
-        })
+        QueryBody::parse_query_parameters(query, &bytes[query_lenght..])
     }
 }
@@ -1,3 +1,5 @@
+use protocol::aliases::types::Byte;
+
 /// Flags específicas para queries CQL
 #[derive(Clone, Copy)]
 pub enum QueryFlags {
@@ -20,3 +22,24 @@ pub enum QueryFlags {
     /// Tiempo actual en segundos
     WithNowInSeconds = 0x100,
 }
+
+impl QueryFlags {
+    /// Descompone un byte de flags en las flags que lo componen.
+    ///
+    /// [WithNowInSeconds](QueryFlags::WithNowInSeconds) no entra en un byte, así que nunca se devuelve.
+    pub fn decompose(flags_byte: Byte) -> Vec<Self> {
+        [
+            Self::Values,
+            Self::SkipMetadata,
+            Self::PageSize,
+            Self::WithPagingState,
+            Self::WithSerialConsistency,
+            Self::WithDefaultTimestamp,
+            Self::WithNamesForValues,
+            Self::WithKeyspace,
+        ]
+        .into_iter()
+        .filter(|flag| flags_byte & (*flag as Byte) != 0)
+        .collect()
+    }
+}
@@ -8,9 +8,10 @@ use {
     protocol::{
         aliases::{
             results::Result as SvResult,
-            types::{Byte, Int},
+            types::{Byte, Int, Short, Uuid},
         },
         errors::error::Error,
+        headers::compression::Compression,
         traits::Byteable,
         utils::{
             encode_iter_to_bytes, encode_long_string_to_bytes, encode_string_to_bytes,
@@ -31,6 +32,8 @@ pub type GossipInfo = HashMap<NodeId, HeartbeatState>;
 
 //const ACTION_MASK: Byte = 0xF0;
 const ACTION_MASK: Byte = 0xE0;
+/// El tamaño _(en bytes)_ a partir del cual las acciones se mandan comprimidas a otros nodos.
+const COMPRESSION_THRESHOLD: usize = 1024;
 
 /// Una "acción" de servidor es un mensaje especial que no entra en ninguna especificaión
 /// del protocolo de Cassandra, y en su lugar es usado para acciones especiales fuera
@@ -70,8 +73,9 @@ pub enum SvAction {
     /// Añadir un nuevo vecino.
     NewNeighbour(NodeId, EndpointState),
 
-    /// Pedirle a este nodo que envie su endpoint state a otro nodo, dado el ID de este último.
-    SendEndpointState(NodeId, String),
+    /// Pedirle a este nodo que envie su endpoint state a otro nodo, dados el ID, la IP y la
+    /// ubicación `(datacenter, rack)` de este último.
+    SendEndpointState(NodeId, String, (String, String)),
 
     /// Query enviada internamente por otro nodo.
     InternalQuery(Vec<Byte>),
@@ -125,6 +129,33 @@ pub enum SvAction {
     /// Hace que el nodo receptor compare su tabla de IPs con la del nodo emisor y actualice
     /// la suya si es necesario.
     UpdateIpsTable(String),
+
+    /// Inicia una reparación _anti-entropy_ de las réplicas que guarda el nodo receptor.
+    ///
+    /// _(keyspace, tabla)_: sin keyspace se reparan todos, y sin tabla todas las del keyspace.
+    Repair(Option<String>, Option<String>),
+
+    /// Pide el árbol de Merkle de una réplica de una tabla.
+    ///
+    /// _(table_name, node_id)_
+    MerkleTreeRequest(String, NodeId),
+
+    /// Pide las entradas de una réplica de una tabla que caen en las hojas dadas de su
+    /// árbol de Merkle.
+    ///
+    /// _(table_name, node_id, leaves)_
+    RepairRangesRequest(String, NodeId, Vec<usize>),
+
+    /// Otra acción, con sus bytes comprimidos con LZ4.
+    ///
+    /// Se usa para mandar entre nodos las acciones grandes, como las filas relocalizadas
+    /// o los metadatos.
+    Compressed(Vec<Byte>),
+
+    /// Otra acción, que la réplica debe registrar en la sesión de _tracing_ dada.
+    ///
+    /// _(session_id, action)_
+    Traced(Uuid, Vec<Byte>),
 }
 
 impl SvAction {
@@ -145,6 +176,34 @@ impl SvAction {
         Self::try_from(bytes).ok()
     }
 
+    /// Comprime los bytes de una acción en una acción [Compressed](SvAction::Compressed),
+    /// si superan cierto tamaño.
+    ///
+    /// Si no son una acción, o no se achican al comprimirlos, se devuelven tal cual.
+    pub fn compress_if_large(bytes: Vec<Byte>) -> Vec<Byte> {
+        if bytes.len() <= COMPRESSION_THRESHOLD || !Self::is_action(&bytes) {
+            return bytes;
+        }
+        match Compression::Lz4.compress(&bytes) {
+            Ok(compressed) if compressed.len() + 1 < bytes.len() => {
+                Self::Compressed(compressed).as_bytes()
+            }
+            _ => bytes,
+        }
+    }
+
+    /// Devuelve la acción original si ésta es una acción [Compressed](SvAction::Compressed),
+    /// o la misma acción si no.
+    pub fn decompressed(self) -> SvResult<Self> {
+        match self {
+            Self::Compressed(compressed) => {
+                let bytes = Compression::Lz4.decompress(&compressed)?;
+                Self::try_from(&bytes[..])?.decompressed()
+            }
+            action => Ok(action),
+        }
+    }
+
     /// Serializa la información de _gossip_.
     fn encode_gossip_info_to_bytes(gossip_info: &GossipInfo) -> Vec<Byte> {
         let mut bytes_vec: Vec<Byte> = Vec::new();
@@ -254,10 +313,12 @@ impl Byteable for SvAction {
                 bytes.extend(state.as_bytes());
                 bytes
             }
-            Self::SendEndpointState(id, string) => {
+            Self::SendEndpointState(id, string, (datacenter, rack)) => {
                 let str_as_bytes = encode_string_to_bytes(string);
                 let mut bytes = vec![0xF7, *id];
                 bytes.extend(str_as_bytes);
+                bytes.extend(encode_string_to_bytes(datacenter));
+                bytes.extend(encode_string_to_bytes(rack));
                 bytes
             }
             Self::InternalQuery(query_bytes) => {
@@ -314,6 +375,40 @@ impl Byteable for SvAction {
                 bytes.extend(encode_string_to_bytes(ips_table));
                 bytes
             }
+            Self::Repair(keyspace_name, table_name) => {
+                let mut bytes = vec![0xE8];
+                bytes.extend(encode_string_to_bytes(
+                    keyspace_name.as_deref().unwrap_or(""),
+                ));
+                bytes.extend(encode_string_to_bytes(table_name.as_deref().unwrap_or("")));
+                bytes
+            }
+            Self::MerkleTreeRequest(table_name, node_id) => {
+                let mut bytes = vec![0xE9];
+                bytes.extend(encode_string_to_bytes(table_name));
+                bytes.push(*node_id);
+                bytes
+            }
+            Self::RepairRangesRequest(table_name, node_id, leaves) => {
+                let mut bytes = vec![0xEA];
+                bytes.extend(encode_string_to_bytes(table_name));
+                bytes.push(*node_id);
+                for leaf in leaves {
+                    bytes.extend((*leaf as Short).to_be_bytes());
+                }
+                bytes
+            }
+            Self::Compressed(compressed) => {
+                let mut bytes = vec![0xEB];
+                bytes.extend(compressed);
+                bytes
+            }
+            Self::Traced(session_id, action) => {
+                let mut bytes = vec![0xEC];
+                bytes.extend(session_id.to_be_bytes());
+                bytes.extend(action);
+                bytes
+            }
         }
     }
 }
@@ -401,8 +496,15 @@ impl TryFrom<&[Byte]> for SvAction {
                         "Conjunto de bytes demasiado chico para `SendEndpointState`.".to_string(),
                     ));
                 }
-                let string_ip = parse_bytes_to_string(&bytes[2..], &mut i)?;
-                Ok(Self::SendEndpointState(bytes[1], string_ip))
+                i += 2;
+                let string_ip = parse_bytes_to_string(&bytes[i..], &mut i)?;
+                let datacenter = parse_bytes_to_string(&bytes[i..], &mut i)?;
+                let rack = parse_bytes_to_string(&bytes[i..], &mut i)?;
+                Ok(Self::SendEndpointState(
+                    bytes[1],
+                    string_ip,
+                    (datacenter, rack),
+                ))
             }
             0xF8 => Ok(Self::InternalQuery(bytes[1..].to_vec())),
             0xF9 => Ok(Self::StoreMetadata),
@@ -450,6 +552,48 @@ impl TryFrom<&[Byte]> for SvAction {
                 let string_ips = parse_bytes_to_string(&bytes[1..], &mut i)?;
                 Ok(Self::UpdateIpsTable(string_ips))
             }
+            0xE8 => {
+                i += 1;
+                let keyspace_name = parse_bytes_to_string(&bytes[i..], &mut i)?;
+                let table_name = parse_bytes_to_string(&bytes[i..], &mut i)?;
+                let non_empty = |name: String| (!name.is_empty()).then_some(name);
+                Ok(Self::Repair(
+                    non_empty(keyspace_name),
+                    non_empty(table_name),
+                ))
+            }
+            0xE9 | 0xEA => {
+                i += 1;
+                let table_name = parse_bytes_to_string(&bytes[i..], &mut i)?;
+                let Some(node_id) = bytes.get(i) else {
+                    return Err(Error::ServerError(
+                        "Conjunto de bytes demasiado chico para una reparación.".to_string(),
+                    ));
+                };
+                if first == 0xE9 {
+                    return Ok(Self::MerkleTreeRequest(table_name, *node_id));
+                }
+                let leaves = bytes[i + 1..]
+                    .chunks_exact(2)
+                    .map(|leaf| Short::from_be_bytes([leaf[0], leaf[1]]) as usize)
+                    .collect();
+                Ok(Self::RepairRangesRequest(table_name, *node_id, leaves))
+            }
+            0xEB => Ok(Self::Compressed(bytes[1..].to_vec())),
+            0xEC => {
+                let Some(session_id) = bytes.get(1..17) else {
+                    return Err(Error::ServerError(
+                        "Conjunto de bytes demasiado chico para una acción con tracing."
+                            .to_string(),
+                    ));
+                };
+                let mut uuid_bytes = [0; 16];
+                uuid_bytes.copy_from_slice(session_id);
+                Ok(Self::Traced(
+                    Uuid::from_be_bytes(uuid_bytes),
+                    bytes[17..].to_vec(),
+                ))
+            }
             _ => Err(Error::ServerError(format!(
                 "'{first:#b}' no es un id de acción válida."
             ))),
@@ -471,7 +615,7 @@ impl std::fmt::Display for SvAction {
             }
             Self::Ack2(nodes_map) => write!(f, "Ack2({nodes_map:?})"),
             Self::NewNeighbour(id, state) => write!(f, "NewNeighbour({id}, {state:?})"),
-            Self::SendEndpointState(id, _string_ip) => {
+            Self::SendEndpointState(id, _string_ip, _location) => {
                 write!(f, "SendEndpointState({id}, {_string_ip})")
             }
             Self::InternalQuery(query_bytes) => write!(f, "InternalQuery({query_bytes:?})"),
@@ -507,6 +651,22 @@ impl std::fmt::Display for SvAction {
             Self::UpdateIpsTable(ips_table) => {
                 write!(f, "UpdateIpsTable({ips_table})")
             }
+            Self::Repair(keyspace_name, table_name) => {
+                write!(f, "Repair({keyspace_name:?}, {table_name:?})")
+            }
+            Self::MerkleTreeRequest(table_name, node_id) => {
+                write!(f, "MerkleTreeRequest({table_name}, {node_id})")
+            }
+            Self::RepairRangesRequest(table_name, node_id, leaves) => {
+                write!(
+                    f,
+                    "RepairRangesRequest({table_name}, {node_id}, {leaves:?})"
+                )
+            }
+            Self::Compressed(compressed) => write!(f, "Compressed({} bytes)", compressed.len()),
+            Self::Traced(session_id, action) => {
+                write!(f, "Traced({session_id:x}, {} bytes)", action.len())
+            }
         }
     }
 }
@@ -15,6 +15,13 @@ use {
 
 /// El mapa de los IDs de nodos y sus IPs asociadas.
 pub type NodeIPs = HashMap<Option<NodeId>, IpAddr>;
+/// El mapa de los IDs de nodos y su ubicación, como un par `(datacenter, rack)`.
+pub type NodeLocations = HashMap<NodeId, (String, String)>;
+
+/// El _datacenter_ que se le asume a un nodo que no declara ninguno.
+pub const DEFAULT_DATACENTER: &str = "datacenter1";
+/// El _rack_ que se le asume a un nodo que no declara ninguno.
+pub const DEFAULT_RACK: &str = "rack1";
 
 const NODES_ADDR: &str = "node_ips.csv";
 const CLIENT_ADDR: &str = "client_ips.csv";
@@ -29,6 +36,9 @@ pub struct AddrLoader {
 
     /// Un mapa de los nodos cargado en memoria.
     node_ips: Option<NodeIPs>,
+
+    /// El _datacenter_ y _rack_ de cada nodo, cargados en memoria.
+    node_locations: NodeLocations,
 }
 
 impl AddrLoader {
@@ -37,6 +47,7 @@ impl AddrLoader {
         Self {
             path: path.to_string(),
             node_ips,
+            node_locations: NodeLocations::new(),
         }
     }
 
@@ -84,7 +95,16 @@ impl AddrLoader {
 
     /// Carga el mapa de IDs de nodos más las IPs.
     pub fn load(&self) -> Result<NodeIPs> {
+        Ok(self.load_with_locations()?.0)
+    }
+
+    /// Carga el mapa de IDs de nodos más las IPs, junto con la ubicación de cada nodo.
+    ///
+    /// Cada línea tiene la forma `node_id,ip[,datacenter,rack]`. Si un nodo no declara su
+    /// ubicación, se le asignan [DEFAULT_DATACENTER] y [DEFAULT_RACK].
+    pub fn load_with_locations(&self) -> Result<(NodeIPs, NodeLocations)> {
         let mut node_ips = NodeIPs::new();
+        let mut node_locations = NodeLocations::new();
 
         let file = match OpenOptions::new().write(false).read(true).open(&self.path) {
             Ok(exists) => exists,
@@ -99,7 +119,7 @@ impl AddrLoader {
 
         for line in bufreader.lines().skip(1).map_while(IOResult::ok) {
             let splitted = line.trim().split(",").collect::<Vec<&str>>();
-            if splitted.len() != 2 {
+            if splitted.len() != 2 && splitted.len() != 4 {
                 continue;
             }
             let node_id_str = splitted[0];
@@ -115,15 +135,24 @@ impl AddrLoader {
                 }
             };
 
+            if let Some(id) = node_id {
+                let location = match splitted.get(2..4) {
+                    Some([datacenter, rack]) => (datacenter.to_string(), rack.to_string()),
+                    _ => (DEFAULT_DATACENTER.to_string(), DEFAULT_RACK.to_string()),
+                };
+                node_locations.insert(id, location);
+            }
             node_ips.insert(node_id, ip);
         }
 
-        Ok(node_ips)
+        Ok((node_ips, node_locations))
     }
 
     /// Intenta volver a cargar en memoria la info de IPs.
     pub fn reset(&mut self) -> Result<()> {
-        self.node_ips = Some(self.load()?);
+        let (node_ips, node_locations) = self.load_with_locations()?;
+        self.node_ips = Some(node_ips);
+        self.node_locations = node_locations;
         Ok(())
     }
 
@@ -153,13 +182,17 @@ impl AddrLoader {
             };
             let mut bufwriter = BufWriter::new(file);
 
-            let _ = bufwriter.write_all("node_id,ip".as_bytes());
+            let _ = bufwriter.write_all("node_id,ip,datacenter,rack".as_bytes());
             for (node_id, ip) in node_ips {
-                let node_id_str = match node_id {
-                    Some(id) => id.to_string(),
-                    None => "".to_string(),
+                let (node_id_str, (datacenter, rack)) = match node_id {
+                    Some(id) => (id.to_string(), self.get_location(*id)),
+                    None => (
+                        "".to_string(),
+                        (DEFAULT_DATACENTER.to_string(), DEFAULT_RACK.to_string()),
+                    ),
                 };
-                let _ = bufwriter.write_all(format!("\n{node_id_str},{ip}").as_bytes());
+                let _ = bufwriter
+                    .write_all(format!("\n{node_id_str},{ip},{datacenter},{rack}").as_bytes());
             }
         }
 
@@ -196,6 +229,16 @@ impl AddrLoader {
         )))
     }
 
+    /// Devuelve el _datacenter_ y _rack_ del nodo con el ID dado.
+    ///
+    /// Si el nodo no declaró su ubicación, se devuelven [DEFAULT_DATACENTER] y [DEFAULT_RACK].
+    pub fn get_location(&self, asked_id: NodeId) -> (String, String) {
+        match self.node_locations.get(&asked_id) {
+            Some(location) => location.clone(),
+            None => (DEFAULT_DATACENTER.to_string(), DEFAULT_RACK.to_string()),
+        }
+    }
+
     /// Carga las IDs de nodos, descartando los IPs.
     pub fn get_ids(&self) -> Vec<NodeId> {
         let mut ids = Vec::<NodeId>::new();
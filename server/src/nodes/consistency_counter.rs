@@ -0,0 +1,151 @@
+//! Módulo para contar las confirmaciones de las réplicas según un _Consistency Level_.
+
+use {
+    crate::nodes::node::NodeId,
+    protocol::{
+        aliases::{results::Result, types::Int},
+        errors::{error::Error, write_type::WriteType},
+        notations::consistency::Consistency,
+    },
+    std::{collections::HashMap, fmt},
+};
+
+/// Cuenta las confirmaciones de las réplicas de una partición, hasta cumplir con un
+/// _Consistency Level_.
+///
+/// Los niveles locales ([LocalOne](Consistency::LocalOne) y [LocalQuorum](Consistency::LocalQuorum))
+/// sólo cuentan las réplicas del _datacenter_ del coordinador, y [EachQuorum](Consistency::EachQuorum)
+/// exige una mayoría en cada _datacenter_ con réplicas. El resto de los niveles cuenta las
+/// réplicas de todo el clúster.
+#[derive(Clone)]
+pub struct ConsistencyCounter {
+    /// El _Consistency Level_ a cumplir.
+    consistency_level: Consistency,
+
+    /// El _datacenter_ de cada réplica.
+    replicas_datacenters: HashMap<NodeId, String>,
+
+    /// Las confirmaciones necesarias de cada _datacenter_. `None` representa a todo el clúster.
+    required: HashMap<Option<String>, usize>,
+
+    /// Las confirmaciones recibidas hasta ahora, agrupadas igual que las necesarias.
+    acks: HashMap<Option<String>, usize>,
+}
+
+impl ConsistencyCounter {
+    /// Crea un contador para las réplicas dadas, cada una con su _datacenter_.
+    ///
+    /// `local_datacenter` es el _datacenter_ del nodo coordinador.
+    pub fn new(
+        consistency_level: &Consistency,
+        replicas: &[(NodeId, String)],
+        local_datacenter: &str,
+    ) -> Result<Self> {
+        let mut replicas_per_datacenter: HashMap<Option<String>, usize> = HashMap::new();
+        for (_, datacenter) in replicas {
+            *replicas_per_datacenter
+                .entry(Some(datacenter.clone()))
+                .or_default() += 1;
+        }
+
+        let mut required = HashMap::new();
+        match consistency_level {
+            Consistency::LocalOne | Consistency::LocalQuorum => {
+                let local = Some(local_datacenter.to_string());
+                let local_replicas = match replicas_per_datacenter.get(&local) {
+                    Some(quantity) => *quantity,
+                    None => {
+                        return Err(Error::ServerError(format!(
+                            "No hay réplicas en el datacenter local {local_datacenter} para cumplir con el nivel de consistencia {consistency_level}"
+                        )))
+                    }
+                };
+                required.insert(local, consistency_level.as_usize(local_replicas)?);
+            }
+            Consistency::EachQuorum => {
+                for (datacenter, quantity) in replicas_per_datacenter {
+                    required.insert(datacenter, consistency_level.as_usize(quantity)?);
+                }
+            }
+            _ => {
+                required.insert(None, consistency_level.as_usize(replicas.len())?);
+            }
+        }
+
+        Ok(Self {
+            consistency_level: *consistency_level,
+            replicas_datacenters: replicas.iter().cloned().collect(),
+            acks: required.keys().map(|key| (key.clone(), 0)).collect(),
+            required,
+        })
+    }
+
+    /// Registra la confirmación de una réplica.
+    pub fn ack(&mut self, replica: NodeId) {
+        let datacenter = self.replicas_datacenters.get(&replica).cloned();
+        for (key, acks) in self.acks.iter_mut() {
+            if key.is_none() || (datacenter.is_some() && *key == datacenter) {
+                *acks += 1;
+            }
+        }
+    }
+
+    /// Verifica si ya se cumplió con el _Consistency Level_.
+    pub fn is_reached(&self) -> bool {
+        self.required
+            .iter()
+            .all(|(key, required)| self.acks.get(key).is_some_and(|acks| acks >= required))
+    }
+
+    /// Devuelve la cantidad de confirmaciones necesarias para cumplir con el _Consistency Level_.
+    pub fn blockfor(&self) -> usize {
+        self.required.values().sum()
+    }
+
+    /// Devuelve la cantidad de confirmaciones recibidas que cuentan para el _Consistency Level_.
+    ///
+    /// Las confirmaciones de más de un _datacenter_ no compensan las que faltan en otro.
+    pub fn received(&self) -> usize {
+        self.required
+            .iter()
+            .map(|(key, required)| self.acks.get(key).map_or(0, |acks| *acks.min(required)))
+            .sum()
+    }
+
+    /// Crea el error de una escritura que no cumplió con el _Consistency Level_ a tiempo.
+    pub fn write_timeout(&self, write_type: WriteType) -> Error {
+        Error::WriteTimeout(
+            format!(
+                "Se agotó el tiempo de espera de la escritura con nivel de consistencia {}, solo se logró con {self}",
+                self.consistency_level
+            ),
+            self.consistency_level,
+            self.received() as Int,
+            self.blockfor() as Int,
+            write_type,
+            None,
+        )
+    }
+
+    /// Crea el error de una lectura que no cumplió con el _Consistency Level_ a tiempo.
+    ///
+    /// `data_present` indica si la réplica a la que se le pidieron los datos respondió.
+    pub fn read_timeout(&self, data_present: bool) -> Error {
+        Error::ReadTimeout(
+            format!(
+                "Se agotó el tiempo de espera de la lectura con nivel de consistencia {}, solo se logró con {self}",
+                self.consistency_level
+            ),
+            self.consistency_level,
+            self.received() as Int,
+            self.blockfor() as Int,
+            data_present,
+        )
+    }
+}
+
+impl fmt::Display for ConsistencyCounter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} de {}", self.received(), self.blockfor())
+    }
+}
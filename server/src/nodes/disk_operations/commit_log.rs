@@ -0,0 +1,485 @@
+//! Módulo para el _commit log_ de un nodo.
+
+use {
+    crate::nodes::disk_operations::storage_entry::StorageEntry,
+    protocol::{aliases::results::Result, errors::error::Error},
+    serde::{Deserialize, Serialize},
+    std::{
+        collections::HashMap,
+        fmt,
+        fs::{create_dir_all, read_dir, read_to_string, remove_file, File, OpenOptions},
+        io::Write,
+        str::FromStr,
+        sync::{Arc, Mutex, MutexGuard, OnceLock},
+        thread,
+        time::Duration,
+    },
+};
+
+/// El nombre del directorio, dentro del almacenamiento de un nodo, para los segmentos del _commit log_.
+const COMMIT_LOG_DIR_NAME: &str = "commitlog";
+/// El prefijo de los archivos de los segmentos.
+const SEGMENT_PREFIX: &str = "segment_";
+/// La extensión de los archivos de los segmentos.
+const SEGMENT_EXTENSION: &str = "log";
+/// El tamaño en bytes a partir del cual se empieza un segmento nuevo.
+const MAX_SEGMENT_SIZE: usize = 1024 * 1024;
+/// Cada cuánto se fuerzan a disco las escrituras en el modo [Periodic](CommitLogSync::Periodic).
+const PERIODIC_SYNC_INTERVAL: Duration = Duration::from_secs(1);
+/// La marca que indica que se descartó todo el contenido previo de una réplica.
+const TRUNCATE_MARK: &str = "T";
+
+/// El ID de un segmento del _commit log_. Los segmentos más nuevos tienen IDs mayores.
+pub type SegmentId = u64;
+
+/// Cuándo se fuerzan a disco las escrituras del _commit log_.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum CommitLogSync {
+    /// Se fuerzan a disco periódicamente, en segundo plano. Es más rápido, pero si el nodo
+    /// se cae se pueden perder las últimas escrituras.
+    #[default]
+    Periodic,
+
+    /// Se fuerzan a disco antes de aplicar cada escritura.
+    Batch,
+}
+
+impl FromStr for CommitLogSync {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_lowercase().as_str() {
+            "periodic" => Ok(Self::Periodic),
+            "batch" => Ok(Self::Batch),
+            _ => Err(Error::Invalid(format!(
+                "'{s}' no es un modo de sincronización válido para el commit log"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for CommitLogSync {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Periodic => write!(f, "periodic"),
+            Self::Batch => write!(f, "batch"),
+        }
+    }
+}
+
+/// Una operación registrada en el _commit log_ sobre una réplica.
+pub enum LoggedOperation {
+    /// Se escribió una entrada en la réplica.
+    Write(StorageEntry),
+
+    /// Se descartó todo el contenido previo de la réplica.
+    Truncate,
+}
+
+/// Registro de sólo escritura al final, compartido por todas las réplicas de un nodo, con las
+/// escrituras que todavía pueden no estar volcadas a una SSTable.
+///
+/// Se divide en segmentos: cuando todas las escrituras de un segmento ya están en SSTables,
+/// el segmento se recicla. Al reiniciar el nodo, se vuelven a aplicar los segmentos que quedaron.
+pub struct CommitLog {
+    /// El directorio de almacenamiento del nodo.
+    storage_addr: String,
+
+    /// El modo de sincronización con el disco.
+    sync_mode: CommitLogSync,
+
+    /// El segmento en el que se está escribiendo.
+    active_segment: SegmentId,
+
+    /// El archivo del segmento activo, si ya fue abierto.
+    active_file: Option<File>,
+
+    /// El tamaño actual del segmento activo.
+    active_size: usize,
+
+    /// Si hay escrituras en el segmento activo que todavía no se forzaron a disco.
+    pending_sync: bool,
+
+    /// Si hay un hilo forzando a disco las escrituras en el modo [Periodic](CommitLogSync::Periodic).
+    sync_thread_running: bool,
+}
+
+impl CommitLog {
+    fn open(storage_addr: &str, sync_mode: CommitLogSync) -> Result<Self> {
+        create_dir_all(Self::dir_path(storage_addr)).map_err(|e| {
+            Error::ServerError(format!(
+                "No se pudo crear el directorio del commit log en {storage_addr}: {e}"
+            ))
+        })?;
+        let last_segment = Self::list_segments(storage_addr)?.last().copied();
+        Ok(Self {
+            storage_addr: storage_addr.to_string(),
+            sync_mode,
+            active_segment: last_segment.map_or(1, |segment| segment + 1),
+            active_file: None,
+            active_size: 0,
+            pending_sync: false,
+            sync_thread_running: false,
+        })
+    }
+
+    fn dir_path(storage_addr: &str) -> String {
+        format!("{storage_addr}/{COMMIT_LOG_DIR_NAME}")
+    }
+
+    fn segment_path(storage_addr: &str, segment: SegmentId) -> String {
+        format!(
+            "{}/{SEGMENT_PREFIX}{segment}.{SEGMENT_EXTENSION}",
+            Self::dir_path(storage_addr)
+        )
+    }
+
+    /// Obtiene los IDs de los segmentos que hay en disco, del más viejo al más nuevo.
+    fn list_segments(storage_addr: &str) -> Result<Vec<SegmentId>> {
+        let dir_path = Self::dir_path(storage_addr);
+        let entries = match read_dir(&dir_path) {
+            Ok(entries) => entries,
+            Err(_) => return Ok(Vec::new()),
+        };
+        let mut segments: Vec<SegmentId> = entries
+            .flatten()
+            .filter_map(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .strip_prefix(SEGMENT_PREFIX)
+                    .and_then(|rest| rest.strip_suffix(&format!(".{SEGMENT_EXTENSION}")))
+                    .and_then(|segment| segment.parse::<SegmentId>().ok())
+            })
+            .collect();
+        segments.sort();
+        Ok(segments)
+    }
+
+    /// Devuelve el segmento en el que se está escribiendo.
+    pub fn get_active_segment(&self) -> SegmentId {
+        self.active_segment
+    }
+
+    /// Registra operaciones sobre la réplica en `replica_path`, antes de que se apliquen.
+    ///
+    /// Devuelve el segmento en el que quedaron escritas.
+    pub fn append(
+        &mut self,
+        replica_path: &str,
+        operations: &[LoggedOperation],
+    ) -> Result<SegmentId> {
+        let replica = replica_path
+            .strip_prefix(&format!("{}/", self.storage_addr))
+            .unwrap_or(replica_path);
+        let mut content = String::new();
+        for operation in operations {
+            let line = match operation {
+                LoggedOperation::Write(entry) => entry.to_line(),
+                LoggedOperation::Truncate => TRUNCATE_MARK.to_string(),
+            };
+            content.push_str(&format!("{replica},{line}\n"));
+        }
+
+        if self.active_size > 0 && self.active_size + content.len() > MAX_SEGMENT_SIZE {
+            self.roll_segment()?;
+        }
+        let path = Self::segment_path(&self.storage_addr, self.active_segment);
+        if self.active_file.is_none() {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&path)
+                .map_err(|e| {
+                    Error::ServerError(format!("No se pudo abrir el commit log {path}: {e}"))
+                })?;
+            self.active_file = Some(file);
+        }
+        if let Some(file) = self.active_file.as_mut() {
+            file.write_all(content.as_bytes()).map_err(|e| {
+                Error::ServerError(format!("No se pudo escribir en el commit log {path}: {e}"))
+            })?;
+        }
+        self.active_size += content.len();
+        self.pending_sync = true;
+        if self.sync_mode == CommitLogSync::Batch {
+            self.sync()?;
+        }
+        Ok(self.active_segment)
+    }
+
+    /// Fuerza a disco las escrituras pendientes del segmento activo.
+    pub fn sync(&mut self) -> Result<()> {
+        if !self.pending_sync {
+            return Ok(());
+        }
+        if let Some(file) = &self.active_file {
+            file.sync_data().map_err(|e| {
+                Error::ServerError(format!("No se pudo sincronizar el commit log: {e}"))
+            })?;
+        }
+        self.pending_sync = false;
+        Ok(())
+    }
+
+    /// Da por forzadas a disco las escrituras pendientes, y devuelve una copia del archivo del
+    /// segmento activo para forzarlas sin tener tomado el _commit log_.
+    fn take_pending_sync(&mut self) -> Result<Option<File>> {
+        if !self.pending_sync {
+            return Ok(None);
+        }
+        let file = match &self.active_file {
+            Some(file) => Some(file.try_clone().map_err(|e| {
+                Error::ServerError(format!("No se pudo sincronizar el commit log: {e}"))
+            })?),
+            None => None,
+        };
+        self.pending_sync = false;
+        Ok(file)
+    }
+
+    /// Cierra el segmento activo y empieza uno nuevo.
+    fn roll_segment(&mut self) -> Result<()> {
+        self.sync()?;
+        self.active_file = None;
+        self.active_size = 0;
+        self.active_segment += 1;
+        Ok(())
+    }
+
+    /// Borra los segmentos anteriores a `oldest_needed`, que ya no tienen escrituras sin volcar.
+    ///
+    /// Si `oldest_needed` es `None`, no queda ninguna escritura sin volcar y se reciclan todos
+    /// los segmentos, empezando uno nuevo.
+    pub fn recycle(&mut self, oldest_needed: Option<SegmentId>) -> Result<()> {
+        let oldest_needed = match oldest_needed {
+            Some(segment) => segment,
+            None => {
+                if self.active_size > 0 {
+                    self.roll_segment()?;
+                }
+                self.active_segment
+            }
+        };
+        for segment in Self::list_segments(&self.storage_addr)? {
+            if segment >= oldest_needed || segment >= self.active_segment {
+                break;
+            }
+            let path = Self::segment_path(&self.storage_addr, segment);
+            remove_file(&path).map_err(|e| {
+                Error::ServerError(format!(
+                    "No se pudo reciclar el segmento del commit log {path}: {e}"
+                ))
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Lee las operaciones de todos los segmentos en disco, en el orden en que fueron escritas,
+    /// junto al directorio de la réplica y el segmento de cada una.
+    ///
+    /// Si la última línea de un segmento quedó cortada por una caída del nodo, se descarta.
+    pub fn replay(storage_addr: &str) -> Result<Vec<(String, SegmentId, LoggedOperation)>> {
+        let mut operations = Vec::new();
+        for segment in Self::list_segments(storage_addr)? {
+            let path = Self::segment_path(storage_addr, segment);
+            let content = read_to_string(&path).map_err(|e| {
+                Error::ServerError(format!("No se pudo leer el commit log {path}: {e}"))
+            })?;
+            for line in content.lines() {
+                let Some((replica, operation)) = line.split_once(',') else {
+                    break;
+                };
+                let operation = if operation == TRUNCATE_MARK {
+                    LoggedOperation::Truncate
+                } else {
+                    match StorageEntry::from_line(operation) {
+                        Ok(entry) => LoggedOperation::Write(entry),
+                        Err(_) => break,
+                    }
+                };
+                operations.push((format!("{storage_addr}/{replica}"), segment, operation));
+            }
+        }
+        Ok(operations)
+    }
+}
+
+/// Un _commit log_ compartido, con su propia _lock_.
+type SharedCommitLog = Arc<Mutex<CommitLog>>;
+
+/// Los _commit logs_ abiertos en el proceso, según el directorio de almacenamiento del nodo.
+///
+/// Cada _commit log_ tiene su propia _lock_, y la del mapa sólo se toma para buscarlos.
+fn commit_logs() -> &'static Mutex<HashMap<String, SharedCommitLog>> {
+    static COMMIT_LOGS: OnceLock<Mutex<HashMap<String, SharedCommitLog>>> = OnceLock::new();
+    COMMIT_LOGS.get_or_init(Default::default)
+}
+
+/// Toma la _lock_ del mapa de _commit logs_.
+fn lock_commit_logs() -> Result<MutexGuard<'static, HashMap<String, SharedCommitLog>>> {
+    commit_logs()
+        .lock()
+        .map_err(|e| Error::ServerError(format!("No se pudo acceder al commit log: {e}")))
+}
+
+/// Toma la _lock_ de un _commit log_.
+fn lock_commit_log(log: &SharedCommitLog) -> Result<MutexGuard<'_, CommitLog>> {
+    log.lock()
+        .map_err(|e| Error::ServerError(format!("No se pudo acceder al commit log: {e}")))
+}
+
+/// Abre el _commit log_ del nodo con almacenamiento en `storage_addr`, con el modo de
+/// sincronización dado. Si ya estaba abierto, le cambia el modo.
+///
+/// En el modo [Periodic](CommitLogSync::Periodic), se lanza un hilo que fuerza las escrituras
+/// a disco cada cierto tiempo, si no había uno ya. El hilo fuerza una copia del archivo del
+/// segmento activo sin tener tomado el _commit log_, así las escrituras no esperan a que
+/// termine. Al pasar al modo [Batch](CommitLogSync::Batch), se fuerzan las escrituras pendientes
+/// y el hilo termina.
+pub fn open_commit_log(storage_addr: &str, sync_mode: CommitLogSync) -> Result<()> {
+    let mut logs = lock_commit_logs()?;
+    let log = match logs.get(storage_addr) {
+        Some(log) => log.clone(),
+        None => {
+            let log = Arc::new(Mutex::new(CommitLog::open(storage_addr, sync_mode)?));
+            logs.insert(storage_addr.to_string(), log.clone());
+            log
+        }
+    };
+    drop(logs);
+
+    let mut open_log = lock_commit_log(&log)?;
+    open_log.sync_mode = sync_mode;
+    match sync_mode {
+        CommitLogSync::Batch => open_log.sync(),
+        CommitLogSync::Periodic if !open_log.sync_thread_running => {
+            open_log.sync_thread_running = true;
+            drop(open_log);
+            spawn_periodic_sync(storage_addr.to_string(), log);
+            Ok(())
+        }
+        CommitLogSync::Periodic => Ok(()),
+    }
+}
+
+/// Lanza el hilo que fuerza a disco las escrituras de un _commit log_ en el modo
+/// [Periodic](CommitLogSync::Periodic), hasta que cambie de modo o se descarte.
+fn spawn_periodic_sync(storage_addr: String, log: SharedCommitLog) {
+    thread::spawn(move || loop {
+        thread::sleep(PERIODIC_SYNC_INTERVAL);
+        // Si el commit log se descartó, o se volvió a abrir, lo sincroniza otro hilo
+        let is_open = lock_commit_logs().is_ok_and(|logs| {
+            logs.get(&storage_addr)
+                .is_some_and(|open_log| Arc::ptr_eq(open_log, &log))
+        });
+        if !is_open {
+            break;
+        }
+        let pending = match lock_commit_log(&log) {
+            Ok(mut log) if log.sync_mode == CommitLogSync::Periodic => log.take_pending_sync(),
+            Ok(mut log) => {
+                log.sync_thread_running = false;
+                break;
+            }
+            Err(_) => break,
+        };
+        if let Ok(Some(file)) = pending {
+            if file.sync_data().is_err() {
+                if let Ok(mut log) = lock_commit_log(&log) {
+                    log.pending_sync = true;
+                }
+            }
+        }
+    });
+}
+
+/// Aplica `operation` sobre el _commit log_ del nodo con almacenamiento en `storage_addr`,
+/// abriéndolo con el modo por defecto si todavía no lo estaba.
+pub fn with_commit_log<T, F>(storage_addr: &str, operation: F) -> Result<T>
+where
+    F: FnOnce(&mut CommitLog) -> Result<T>,
+{
+    let open_log = lock_commit_logs()?.get(storage_addr).cloned();
+    let log = match open_log {
+        Some(log) => log,
+        None => {
+            open_commit_log(storage_addr, CommitLogSync::default())?;
+            match lock_commit_logs()?.get(storage_addr) {
+                Some(log) => log.clone(),
+                None => {
+                    return Err(Error::ServerError(format!(
+                        "No se encontró el commit log del almacenamiento {storage_addr}"
+                    )))
+                }
+            }
+        }
+    };
+    let mut log = lock_commit_log(&log)?;
+    operation(&mut log)
+}
+
+/// Descarta de memoria el _commit log_ del nodo con almacenamiento en `storage_addr`.
+pub fn forget_commit_log(storage_addr: &str) {
+    if let Ok(mut logs) = commit_logs().lock() {
+        logs.remove(storage_addr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::fs::remove_dir_all};
+
+    fn storage_addr(name: &str) -> String {
+        let storage_addr =
+            std::env::temp_dir().join(format!("commit_log_{name}_{}", std::process::id()));
+        let _ = remove_dir_all(&storage_addr);
+        storage_addr.to_string_lossy().to_string()
+    }
+
+    fn is_pending_sync(storage_addr: &str) -> Result<bool> {
+        with_commit_log(storage_addr, |commit_log| Ok(commit_log.pending_sync))
+    }
+
+    #[test]
+    fn test_1_al_pasar_de_batch_a_periodic_se_sincroniza_en_segundo_plano() -> Result<()> {
+        let storage_addr = storage_addr("batch_a_periodic");
+        let replica_path = format!("{storage_addr}/vuelos/replica");
+
+        open_commit_log(&storage_addr, CommitLogSync::Batch)?;
+        with_commit_log(&storage_addr, |commit_log| {
+            commit_log.append(&replica_path, &[LoggedOperation::Truncate])
+        })?;
+        assert!(!is_pending_sync(&storage_addr)?);
+
+        open_commit_log(&storage_addr, CommitLogSync::Periodic)?;
+        with_commit_log(&storage_addr, |commit_log| {
+            commit_log.append(&replica_path, &[LoggedOperation::Truncate])
+        })?;
+        assert!(is_pending_sync(&storage_addr)?);
+
+        thread::sleep(PERIODIC_SYNC_INTERVAL * 3);
+        assert!(!is_pending_sync(&storage_addr)?);
+
+        forget_commit_log(&storage_addr);
+        let _ = remove_dir_all(&storage_addr);
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_al_pasar_a_batch_se_fuerzan_las_escrituras_pendientes() -> Result<()> {
+        let storage_addr = storage_addr("periodic_a_batch");
+        let replica_path = format!("{storage_addr}/vuelos/replica");
+
+        open_commit_log(&storage_addr, CommitLogSync::Periodic)?;
+        with_commit_log(&storage_addr, |commit_log| {
+            commit_log.append(&replica_path, &[LoggedOperation::Truncate])
+        })?;
+        open_commit_log(&storage_addr, CommitLogSync::Batch)?;
+        assert!(!is_pending_sync(&storage_addr)?);
+
+        forget_commit_log(&storage_addr);
+        let _ = remove_dir_all(&storage_addr);
+        Ok(())
+    }
+}
@@ -2,21 +2,33 @@
 
 use {
     crate::{
+        cql_frame::paging_state::{Page, PagingState},
         nodes::{
             disk_operations::{
-                row_operations::RowOperations, table_operations::TableOperations,
+                commit_log::{open_commit_log, CommitLogSync},
+                memtable::restore_memtables,
+                row_operations::RowOperations,
+                secondary_index::SecondaryIndex,
+                storage_entry::StorageEntry,
+                table_operations::TableOperations,
                 table_path::TablePath,
             },
             keyspace_metadata::{keyspace::Keyspace, replication_strategy::ReplicationStrategy},
             node::{Node, NodeId},
+            select_functions::{
+                aggregate_rows, evaluate, get_computed_selectors, select_has_aggregates,
+                selector_type, RowContext,
+            },
             table_metadata::{
                 column_config::ColumnConfig, column_data_type::ColumnDataType, table::Table,
             },
+            utils::hash_value,
         },
         utils::store_json,
     },
+    chrono::Utc,
     parser::{
-        assignment::Assignment,
+        assignment::{ArithmeticOperator, Assignment},
         data_types::{
             constant::Constant,
             cql_type::{cql_type_mod::CQLType, native_types::NativeType},
@@ -25,7 +37,10 @@ use {
         primary_key::PrimaryKey,
         statements::{
             ddl_statement::{
-                create_keyspace::CreateKeyspace, create_table::CreateTable, option::Options,
+                alter_table::AlterTableInstruction, column_definition::ColumnDefinition,
+                create_index::CreateIndex, create_keyspace::CreateKeyspace,
+                create_materialized_view::CreateMaterializedView, create_table::CreateTable,
+                option::Options,
             },
             dml_statement::{
                 if_condition::{Condition, IfCondition},
@@ -33,27 +48,29 @@ use {
                     delete::Delete,
                     insert::Insert,
                     select::{
-                        order_by::OrderBy, ordering::ProtocolOrdering, select_operation::Select,
+                        group_by::GroupBy, kind_of_columns::KindOfColumns, order_by::OrderBy,
+                        ordering::ProtocolOrdering, per_partition_limit::PerPartitionLimit,
+                        select_operation::Select,
                     },
                     update::Update,
                 },
-                r#where::operator::Operator,
+                r#where::{expression::Expression, operator::Operator, where_parser::Where},
             },
         },
     },
     protocol::{
         aliases::{
             results::Result,
-            types::{Byte, Int, Long, Uint},
+            types::{Byte, Int, Long, Short, Uint},
         },
         errors::error::Error,
-        messages::responses::result::col_type::ColType,
-        traits::Byteable,
+        messages::responses::result::{col_type::ColType, rows_flags::RowsFlag},
+        traits::{Byteable, Maskable},
         utils::encode_string_to_bytes,
     },
     std::{
-        collections::HashSet,
-        fs::{create_dir, remove_dir_all, remove_file, File, OpenOptions},
+        collections::{HashMap, HashSet},
+        fs::{create_dir, read, read_dir, remove_dir_all, remove_file, File, OpenOptions},
         io::{BufRead, BufReader, BufWriter, Write},
         path::Path,
         str::FromStr,
@@ -70,6 +87,24 @@ const STORAGE_NODE_PATH: &str = "storage_node";
 pub const NODES_METADATA_DIR_NAME: &str = "nodes_metadata";
 /// El nombre individual del directorio de metadatos de un nodo.
 const NODE_METADATA_PATH: &str = "metadata_node";
+/// El nombre del directorio, dentro del almacenamiento de un nodo, para los BATCH pendientes.
+const BATCHLOG_DIR_NAME: &str = "batchlog";
+/// El nombre del directorio, dentro del almacenamiento de un nodo, para los BATCH que coordinó y
+/// que alguna réplica todavía no confirmó.
+const COORDINATOR_BATCHLOG_DIR_NAME: &str = "coordinator_batchlog";
+
+/// Los grupos de _queries_ de un BATCH que coordinó un nodo, cada uno con la réplica que lo aplica.
+pub type BatchLogGroups = Vec<(NodeId, Vec<Byte>)>;
+/// El nombre del directorio, dentro del almacenamiento de un nodo, para los _hints_ pendientes.
+const HINTS_DIR_NAME: &str = "hints";
+/// La extensión de los archivos de los _hints_.
+const HINT_EXTENSION: &str = "hint";
+/// El tiempo máximo, en segundos, durante el cual se guardan _hints_ para un nodo caído (3 horas).
+///
+/// Pasado ese tiempo, se asume que el nodo va a tener que repararse de otra forma.
+pub const MAX_HINT_WINDOW_SECS: Long = 3 * 60 * 60;
+/// El tiempo, en segundos, luego del cual un _hint_ se descarta sin reenviarse (3 días).
+pub const HINT_TTL_SECS: Long = 3 * 24 * 60 * 60;
 /// El nombre del directorio para el almacenamiento de los logs.
 const LOGS_DIR_NAME: &str = "logs";
 /// Obtiene el nombre del archivo de IPs de nodos según el entorno.
@@ -154,8 +189,13 @@ impl DiskHandler {
         store_json(&*node, &Self::get_node_metadata_path(node.get_id())?)
     }
 
-    /// Almacena el ID y la IP de un nuevo nodo en el archivo de IPs correspondiente.
-    pub fn store_new_node_id_and_ip(id: NodeId, ip: &str) -> Result<()> {
+    /// Almacena el ID, la IP y la ubicación de un nuevo nodo en el archivo de IPs correspondiente.
+    pub fn store_new_node_id_and_ip(
+        id: NodeId,
+        ip: &str,
+        datacenter: &str,
+        rack: &str,
+    ) -> Result<()> {
         let file = match get_root_path(nodes_ips_path()) {
             Ok(path) => OpenOptions::new()
                 .create(true)
@@ -173,7 +213,7 @@ impl DiskHandler {
 
         let mut writer = BufWriter::new(&file);
         writer
-            .write_all(format!("{id},{ip}\n").as_bytes())
+            .write_all(format!("{id},{ip},{datacenter},{rack}\n").as_bytes())
             .map_err(|e| Error::ServerError(e.to_string()))?;
         Ok(())
     }
@@ -251,53 +291,25 @@ impl DiskHandler {
         Ok(())
     }
 
-    /// Escribe _new_rows_ al final de la tabla dada.
+    /// Agrega _new_rows_ a la réplica dada de una tabla.
     pub fn append_new_rows(
-        mut new_rows: String,
+        new_rows: &str,
         storage_addr: &str,
-        keyspace_name: &str,
-        table_name: &str,
+        table: &Table,
         node_number: NodeId,
     ) -> Result<()> {
-        let table_addr =
-            format!("{storage_addr}/{keyspace_name}/{table_name}_replica_node_{node_number}.csv");
-        let file = OpenOptions::new()
-            .append(true)
-            .open(&table_addr)
-            .map_err(|e| Error::ServerError(format!("La ruta {} no existe: {}", &table_addr, e)))?;
-
-        let mut writer = BufWriter::new(&file);
-        if !new_rows.is_empty() {
-            new_rows.push('\n');
-        }
-        writer.write_all(new_rows.as_bytes()).map_err(|e| {
-            Error::ServerError(format!(
-                "No se pudo escribir en la ruta {}: {}",
-                &table_addr, e
-            ))
-        })?;
-        Ok(())
-    }
-
-    /// Ordena las filas que contiene la tabla dada.
-    pub fn sort_rows(table: &Table, storage_addr: &str, node_number: Byte) -> Result<()> {
-        let mut rows = Self::get_all_rows(
-            &table.name,
-            storage_addr,
-            &table.keyspace,
-            &table.keyspace,
-            node_number,
-        )?;
         let path = TablePath::new(
             storage_addr,
-            Some(table.keyspace.to_string()),
-            &table.name,
-            &table.keyspace,
+            Some(table.get_keyspace().to_string()),
+            table.get_name(),
+            table.get_keyspace(),
             node_number,
         );
-        let table_ops = TableOperations::new(path)?;
-
-        Self::order_and_save_rows(&table_ops, &mut rows, table)
+        let table_ops =
+            TableOperations::new(path)?.with_gc_grace_seconds(table.get_gc_grace_seconds());
+        let rows = Self::parse_rows(new_rows);
+        Self::index_rows(storage_addr, table, node_number, &[], &rows)?;
+        table_ops.upsert_rows(rows, &table.get_position_of_primary_key()?)
     }
 
     /// Crea un nuevo keyspace en el caso que corresponda.
@@ -369,6 +381,7 @@ impl DiskHandler {
             NativeType::Int => Ok(ColumnDataType::Int),
             NativeType::Text => Ok(ColumnDataType::String),
             NativeType::TimeStamp => Ok(ColumnDataType::Timestamp),
+            NativeType::Counter => Ok(ColumnDataType::Counter),
             _ => Err(Error::SyntaxError(
                 "No se proporciono un tipo de dato soportado".to_string(),
             )),
@@ -408,7 +421,7 @@ impl DiskHandler {
             .map(|c| c.get_name())
             .collect::<Vec<String>>();
 
-        Self::create_table_csv_file(
+        Self::create_table_replica(
             storage_addr,
             &keyspace_name,
             &table_name,
@@ -428,54 +441,821 @@ impl DiskHandler {
         )))
     }
 
-    /// Crea el archivo csv de la tabla dada.
-    pub fn create_table_csv_file(
+    /// Crea la réplica dada de una vista materializada de la tabla base, vacía.
+    ///
+    /// La vista toma los tipos de sus columnas y las opciones de la tabla base. Su _primary key_
+    /// debe contener toda la de la tabla base y, a lo sumo, una columna más, para que cada fila
+    /// de la tabla base corresponda a una única fila de la vista.
+    pub fn create_view(
+        statement: &CreateMaterializedView,
+        base_table: &Table,
+        storage_addr: &str,
+        node_number: Byte,
+    ) -> Result<Table> {
+        let keyspace_name = base_table.get_keyspace().to_string();
+        if statement
+            .get_keyspace()
+            .is_some_and(|keyspace| keyspace != keyspace_name)
+        {
+            return Err(Error::Invalid(
+                "La vista debe estar en el mismo keyspace que su tabla base".to_string(),
+            ));
+        }
+
+        let mut columns: Vec<ColumnConfig> = Vec::new();
+        if statement.columns.is_empty() {
+            columns.extend(base_table.columns.iter().cloned());
+        }
+        for column_name in &statement.columns {
+            let column = match base_table
+                .columns
+                .iter()
+                .find(|column| &column.get_name() == column_name)
+            {
+                Some(column) => column,
+                None => {
+                    return Err(Error::Invalid(format!(
+                        "La columna {column_name} no existe en la tabla {}",
+                        base_table.get_name()
+                    )))
+                }
+            };
+            if !columns.iter().any(|c| &c.get_name() == column_name) {
+                columns.push(column.clone());
+            }
+        }
+
+        let primary_key = &statement.primary_key;
+        let view_key: Vec<&String> = primary_key
+            .partition_key
+            .iter()
+            .chain(primary_key.clustering_columns.iter())
+            .collect();
+        for column_name in &view_key {
+            if !columns.iter().any(|c| &&c.get_name() == column_name) {
+                return Err(Error::Invalid(format!(
+                    "La columna {column_name} de la primary key no está seleccionada en la vista"
+                )));
+            }
+        }
+        for column_name in base_table.get_primary_key_columns() {
+            if !view_key.contains(&&column_name) {
+                return Err(Error::Invalid(format!(
+                    "La primary key de la vista debe incluir la columna {column_name} de la tabla base"
+                )));
+            }
+        }
+        let extra_columns = view_key
+            .iter()
+            .filter(|column_name| !base_table.is_primary_key_column(column_name))
+            .count();
+        if extra_columns > 1 {
+            return Err(Error::Invalid(
+                "La primary key de la vista sólo puede agregar una columna a la de la tabla base"
+                    .to_string(),
+            ));
+        }
+
+        let mut clustering_keys_and_order: Vec<(String, ProtocolOrdering)> = primary_key
+            .clustering_columns
+            .iter()
+            .map(|key| (key.clone(), ProtocolOrdering::Asc))
+            .collect();
+        if let Some(clustering_order) = &statement.clustering_order {
+            Self::update_clustering_order(&mut clustering_keys_and_order, clustering_order)?;
+        }
+
+        let columns_names: Vec<String> = columns.iter().map(|c| c.get_name()).collect();
+        let view_name = statement.get_name();
+        Self::create_table_replica(
+            storage_addr,
+            &keyspace_name,
+            &view_name,
+            &columns_names,
+            node_number,
+        )?;
+
+        let mut view = Table::new(
+            view_name,
+            keyspace_name,
+            columns,
+            primary_key.partition_key.clone(),
+            (!clustering_keys_and_order.is_empty()).then_some(clustering_keys_and_order),
+        );
+        view.options = base_table.options.clone();
+        view.base_table = Some(base_table.get_name().to_string());
+        Ok(view)
+    }
+
+    /// Crea el directorio de la réplica dada de una tabla, sin filas.
+    pub fn create_table_replica(
         storage_addr: &str,
         keyspace_name: &str,
         table_name: &str,
         columns_names: &[String],
         node_number: Byte,
     ) -> Result<()> {
-        let table_addr =
-            format!("{storage_addr}/{keyspace_name}/{table_name}_replica_node_{node_number}.csv");
-        let file = OpenOptions::new()
-            .write(true)
-            .create_new(true)
-            .open(&table_addr)
-            .map_err(|e| {
-                Error::ServerError(format!(
-                    "No se pudo crear la tabla con dirección `{table_addr}` para escritura: {e}"
-                ))
-            })?;
+        let path = TablePath::new(
+            storage_addr,
+            Some(keyspace_name.to_string()),
+            table_name,
+            keyspace_name,
+            node_number,
+        );
+        let mut columns = columns_names.to_vec();
+        columns.push("row_timestamp".to_string());
+        TableOperations::create(&path, &columns)
+    }
 
-        let mut writer = BufWriter::new(&file);
-        writer
-            .write_all(columns_names.join(",").as_bytes())
-            .map_err(|e| {
-                Error::ServerError(format!(
-                    "No se pudo escribir las columnas en la tabla con dirección `{table_addr}`: {e}"
-                ))
-            })?;
-        writer
-            .write_all((",row_timestamp\n").as_bytes())
-            .map_err(|e| {
-                Error::ServerError(format!(
-                    "No se pudo escribir las columnas en la tabla con dirección `{table_addr}`: {e}"
-                ))
-            })?;
+    /// Borra el directorio de la réplica dada de una tabla.
+    pub fn delete_table_replica(
+        storage_addr: &str,
+        keyspace_name: &str,
+        table_name: &str,
+        node_number: Byte,
+    ) -> Result<()> {
+        let path = TablePath::new(
+            storage_addr,
+            Some(keyspace_name.to_string()),
+            table_name,
+            keyspace_name,
+            node_number,
+        );
+        TableOperations::drop_replica(&path)
+    }
+
+    /// Borra los directorios de la réplica dada de todos los índices secundarios de una tabla.
+    pub fn delete_index_replicas(
+        storage_addr: &str,
+        table: &Table,
+        node_number: Byte,
+    ) -> Result<()> {
+        for index_name in table.indexes.keys() {
+            SecondaryIndex::drop(storage_addr, table, index_name, node_number)?;
+        }
         Ok(())
     }
 
-    /// Borra el archivo csv de la tabla dada.
-    pub fn delete_table_csv_file(
+    /// Abre el _commit log_ del nodo con el modo de sincronización dado, y reconstruye las
+    /// _memtables_ de sus réplicas a partir de los segmentos que no fueron reciclados.
+    pub fn replay_commit_log(storage_addr: &str, sync_mode: CommitLogSync) -> Result<()> {
+        open_commit_log(storage_addr, sync_mode)?;
+        restore_memtables(storage_addr)
+    }
+
+    /// Migra al formato de SSTables las réplicas de las tablas dadas que todavía se guarden
+    /// como un único archivo CSV.
+    pub fn migrate_csv_tables<'a>(
+        storage_addr: &str,
+        tables: impl Iterator<Item = &'a Table>,
+    ) -> Result<()> {
+        for table in tables {
+            let keyspace_addr = format!("{storage_addr}/{}", table.get_keyspace());
+            let Ok(entries) = read_dir(&keyspace_addr) else {
+                continue;
+            };
+            let prefix = format!("{}_replica_node_", table.get_name());
+            let key_positions = table.get_position_of_primary_key()?;
+            for entry in entries.flatten() {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let node_number = file_name
+                    .strip_prefix(&prefix)
+                    .and_then(|rest| rest.strip_suffix(".csv"))
+                    .and_then(|number| number.parse::<Byte>().ok());
+                if let Some(node_number) = node_number {
+                    let path = TablePath::new(
+                        storage_addr,
+                        Some(table.get_keyspace().to_string()),
+                        table.get_name(),
+                        table.get_keyspace(),
+                        node_number,
+                    );
+                    TableOperations::migrate_from_csv(path, &key_positions)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Obtiene los números de réplica de una tabla que tienen un directorio en el almacenamiento del nodo.
+    pub fn get_table_replicas_numbers(
         storage_addr: &str,
         keyspace_name: &str,
         table_name: &str,
+    ) -> Result<Vec<Byte>> {
+        let keyspace_addr = format!("{storage_addr}/{keyspace_name}");
+        let entries = read_dir(&keyspace_addr).map_err(|e| {
+            Error::ServerError(format!(
+                "No se pudo leer el directorio del keyspace {keyspace_name}: {e}"
+            ))
+        })?;
+        let prefix = format!("{table_name}_replica_node_");
+
+        let mut replicas_numbers = Vec::new();
+        for entry in entries.flatten() {
+            if !entry.path().is_dir() {
+                continue;
+            }
+            let file_name = entry.file_name().to_string_lossy().to_string();
+            let node_number = file_name
+                .strip_prefix(&prefix)
+                .and_then(|number| number.parse::<Byte>().ok());
+            if let Some(node_number) = node_number {
+                replicas_numbers.push(node_number);
+            }
+        }
+        Ok(replicas_numbers)
+    }
+
+    /// Altera una tabla según la instrucción dada, actualizando tanto sus metadatos como
+    /// los archivos de todas sus réplicas en el nodo.
+    ///
+    /// Devuelve `false` si la instrucción no generó ningún cambio.
+    pub fn alter_table(
+        table: &mut Table,
+        instruction: &AlterTableInstruction,
+        storage_addr: &str,
+    ) -> Result<bool> {
+        match instruction {
+            AlterTableInstruction::AddColumns(if_not_exists, columns) => {
+                Self::add_columns(table, columns, *if_not_exists, storage_addr)
+            }
+            AlterTableInstruction::DropColumns(if_exists, columns) => {
+                Self::drop_columns(table, columns, *if_exists, storage_addr)
+            }
+            AlterTableInstruction::RenameColumns(if_exists, renames) => {
+                Self::rename_columns(table, renames, *if_exists, storage_addr)
+            }
+            AlterTableInstruction::WithOptions(_, options) => {
+                for (option_name, value) in options {
+                    table.set_option(option_name, value.get_value_as_string())?;
+                }
+                Ok(!options.is_empty())
+            }
+        }
+    }
+
+    fn add_columns(
+        table: &mut Table,
+        columns: &[ColumnDefinition],
+        if_not_exists: bool,
+        storage_addr: &str,
+    ) -> Result<bool> {
+        let table_columns = table.get_columns_names();
+        let mut new_columns = Vec::new();
+        for column in columns {
+            let column_name = column.get_column_name();
+            if table_columns.contains(&column_name)
+                || new_columns
+                    .iter()
+                    .any(|c: &ColumnConfig| c.name == column_name)
+            {
+                if if_not_exists {
+                    continue;
+                }
+                return Err(Error::Invalid(format!(
+                    "La columna {column_name} ya existe en la tabla {}",
+                    table.get_name()
+                )));
+            }
+            if column.primary_key {
+                return Err(Error::Invalid(format!(
+                    "No se puede agregar la columna {column_name} a la primary key de una tabla existente"
+                )));
+            }
+            let data_type = match column.get_data_type() {
+                CQLType::NativeType(native_type) => Self::get_cql_type(native_type)?,
+                _ => {
+                    return Err(Error::Invalid(
+                        "Solo es soportado el tipo de dato nativo.".to_string(),
+                    ))
+                }
+            };
+            new_columns.push(ColumnConfig::new(column_name, data_type));
+        }
+        if new_columns.is_empty() {
+            return Ok(false);
+        }
+
+        Self::rewrite_table_replicas(table, storage_addr, |columns, rows| {
+            // Las columnas nuevas van antes de la del timestamp, y arrancan vacías
+            let timestamp_position = columns.len() - 1;
+            for (i, new_column) in new_columns.iter().enumerate() {
+                columns.insert(timestamp_position + i, new_column.get_name());
+                for row in rows.iter_mut() {
+                    let position = (timestamp_position + i).min(row.len());
+                    row.insert(position, String::new());
+                }
+            }
+        })?;
+        table.columns.extend(new_columns);
+        Ok(true)
+    }
+
+    fn drop_columns(
+        table: &mut Table,
+        columns: &[String],
+        if_exists: bool,
+        storage_addr: &str,
+    ) -> Result<bool> {
+        let table_columns = table.get_columns_names();
+        let mut dropped_columns = Vec::new();
+        for column_name in columns {
+            if !table_columns.contains(column_name) {
+                if if_exists {
+                    continue;
+                }
+                return Err(Error::Invalid(format!(
+                    "La columna {column_name} no existe en la tabla {}",
+                    table.get_name()
+                )));
+            }
+            if table.is_primary_key_column(column_name) {
+                return Err(Error::Invalid(format!(
+                    "No se puede borrar la columna {column_name} porque forma parte de la primary key"
+                )));
+            }
+            if let Some(index_name) = table.get_index_on(column_name) {
+                return Err(Error::Invalid(format!(
+                    "No se puede borrar la columna {column_name} porque la usa el índice {index_name}"
+                )));
+            }
+            dropped_columns.push(column_name.to_string());
+        }
+        if dropped_columns.is_empty() {
+            return Ok(false);
+        }
+
+        Self::rewrite_table_replicas(table, storage_addr, |columns, rows| {
+            for dropped_column in &dropped_columns {
+                if let Some(position) = columns.iter().position(|c| c == dropped_column) {
+                    columns.remove(position);
+                    for row in rows.iter_mut() {
+                        if position < row.len() {
+                            row.remove(position);
+                        }
+                    }
+                }
+            }
+        })?;
+        table
+            .columns
+            .retain(|column| !dropped_columns.contains(&column.name));
+        Ok(true)
+    }
+
+    fn rename_columns(
+        table: &mut Table,
+        renames: &[(String, String)],
+        if_exists: bool,
+        storage_addr: &str,
+    ) -> Result<bool> {
+        let mut table_columns = table.get_columns_names();
+        let mut applied_renames = Vec::new();
+        for (old_name, new_name) in renames {
+            if !table_columns.contains(old_name) {
+                if if_exists {
+                    continue;
+                }
+                return Err(Error::Invalid(format!(
+                    "La columna {old_name} no existe en la tabla {}",
+                    table.get_name()
+                )));
+            }
+            if table_columns.contains(new_name) {
+                return Err(Error::Invalid(format!(
+                    "La columna {new_name} ya existe en la tabla {}",
+                    table.get_name()
+                )));
+            }
+            if let Some(index_name) = table.get_index_on(old_name) {
+                return Err(Error::Invalid(format!(
+                    "No se puede renombrar la columna {old_name} porque la usa el índice {index_name}"
+                )));
+            }
+            for column in table_columns.iter_mut() {
+                if column == old_name {
+                    *column = new_name.to_string();
+                }
+            }
+            applied_renames.push((old_name.to_string(), new_name.to_string()));
+        }
+        if applied_renames.is_empty() {
+            return Ok(false);
+        }
+
+        Self::rewrite_table_replicas(table, storage_addr, |columns, _| {
+            for (old_name, new_name) in &applied_renames {
+                for column in columns.iter_mut() {
+                    if column == old_name {
+                        *column = new_name.to_string();
+                    }
+                }
+            }
+        })?;
+        for (old_name, new_name) in &applied_renames {
+            for column in table.columns.iter_mut() {
+                if &column.name == old_name {
+                    column.name = new_name.to_string();
+                }
+            }
+            for partition_column in table.partition_key.iter_mut() {
+                if partition_column == old_name {
+                    *partition_column = new_name.to_string();
+                }
+            }
+            if let Some(clustering_columns) = table.clustering_key_and_order.as_mut() {
+                for (clustering_column, _) in clustering_columns.iter_mut() {
+                    if clustering_column == old_name {
+                        *clustering_column = new_name.to_string();
+                    }
+                }
+            }
+        }
+        Ok(true)
+    }
+
+    /// Reescribe todas las réplicas de una tabla en el nodo, aplicando la modificación dada
+    /// a sus columnas y filas.
+    fn rewrite_table_replicas<F>(table: &Table, storage_addr: &str, modify: F) -> Result<()>
+    where
+        F: Fn(&mut Vec<String>, &mut Vec<Vec<String>>),
+    {
+        let keyspace_name = table.get_keyspace();
+        for node_number in
+            Self::get_table_replicas_numbers(storage_addr, keyspace_name, table.get_name())?
+        {
+            let path = TablePath::new(
+                storage_addr,
+                Some(keyspace_name.to_string()),
+                table.get_name(),
+                keyspace_name,
+                node_number,
+            );
+            let mut table_ops =
+                TableOperations::new(path)?.with_gc_grace_seconds(table.get_gc_grace_seconds());
+            table_ops.rewrite(&modify)?;
+        }
+        Ok(())
+    }
+
+    /// Borra los directorios de todas las réplicas de una tabla en el nodo.
+    pub fn drop_table(table: &Table, storage_addr: &str) -> Result<()> {
+        let keyspace_name = table.get_keyspace();
+        for node_number in
+            Self::get_table_replicas_numbers(storage_addr, keyspace_name, table.get_name())?
+        {
+            Self::delete_index_replicas(storage_addr, table, node_number)?;
+            Self::delete_table_replica(storage_addr, keyspace_name, table.get_name(), node_number)?;
+        }
+        Ok(())
+    }
+
+    /// Borra todas las filas de las réplicas de una tabla en el nodo, conservando sus columnas.
+    pub fn truncate_table(table: &Table, storage_addr: &str) -> Result<()> {
+        let keyspace_name = table.get_keyspace();
+        for node_number in
+            Self::get_table_replicas_numbers(storage_addr, keyspace_name, table.get_name())?
+        {
+            let path = TablePath::new(
+                storage_addr,
+                Some(keyspace_name.to_string()),
+                table.get_name(),
+                keyspace_name,
+                node_number,
+            );
+            TableOperations::new(path)?.clear()?;
+            for index in SecondaryIndex::open_all(storage_addr, table, node_number)? {
+                index.clear()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Crea un índice secundario sobre una columna de la tabla, indexando las filas que ya
+    /// tengan sus réplicas en el nodo.
+    ///
+    /// Devuelve `false` si el índice ya existía y la _query_ admitía que así fuera.
+    pub fn create_index(
+        table: &mut Table,
+        statement: &CreateIndex,
+        storage_addr: &str,
+    ) -> Result<bool> {
+        let index_name = statement.get_index_name();
+        let column_name = statement.column.get_name();
+        let existing_index = match table.get_index_column(&index_name) {
+            Some(_) => Some(index_name.as_str()),
+            None => table.get_index_on(column_name),
+        };
+        if let Some(existing_index) = existing_index {
+            if statement.if_not_exists {
+                return Ok(false);
+            }
+            return Err(Error::Invalid(format!(
+                "Ya existe el índice {existing_index} en la tabla {}",
+                table.get_name()
+            )));
+        }
+        if !table.get_columns_names().iter().any(|c| c == column_name) {
+            return Err(Error::Invalid(format!(
+                "La columna {column_name} no existe en la tabla {}",
+                table.get_name()
+            )));
+        }
+        if table.is_primary_key_column(column_name) {
+            return Err(Error::Invalid(format!(
+                "No se puede indexar la columna {column_name} porque forma parte de la primary key"
+            )));
+        }
+
+        table
+            .indexes
+            .insert(index_name.to_string(), column_name.to_string());
+        if let Err(err) = Self::build_index(table, &index_name, storage_addr) {
+            table.indexes.remove(&index_name);
+            return Err(err);
+        }
+        Ok(true)
+    }
+
+    /// Arma desde cero las réplicas de un índice de la tabla con las filas de cada réplica.
+    fn build_index(table: &Table, index_name: &str, storage_addr: &str) -> Result<()> {
+        let keyspace_name = table.get_keyspace();
+        for node_number in
+            Self::get_table_replicas_numbers(storage_addr, keyspace_name, table.get_name())?
+        {
+            let path = TablePath::new(
+                storage_addr,
+                Some(keyspace_name.to_string()),
+                table.get_name(),
+                keyspace_name,
+                node_number,
+            );
+            let rows = TableOperations::new(path)?.read_rows(false)?;
+            SecondaryIndex::open_or_create(storage_addr, table, index_name, node_number)?
+                .rebuild(&rows)?;
+        }
+        Ok(())
+    }
+
+    /// Borra un índice secundario de la tabla, junto a sus réplicas en el nodo.
+    pub fn drop_index(table: &mut Table, index_name: &str, storage_addr: &str) -> Result<()> {
+        for node_number in
+            Self::get_table_replicas_numbers(storage_addr, table.get_keyspace(), table.get_name())?
+        {
+            SecondaryIndex::drop(storage_addr, table, index_name, node_number)?;
+        }
+        table.indexes.remove(index_name);
+        Ok(())
+    }
+
+    /// Actualiza los índices secundarios de la réplica dada con las nuevas versiones de las
+    /// filas, que reemplazan a las anteriores con la misma _primary key_.
+    fn index_rows(
+        storage_addr: &str,
+        table: &Table,
         node_number: Byte,
+        old_rows: &[Vec<String>],
+        new_rows: &[Vec<String>],
     ) -> Result<()> {
-        let table_addr =
-            format!("{storage_addr}/{keyspace_name}/{table_name}_replica_node_{node_number}.csv");
-        std::fs::remove_file(table_addr).map_err(|e| Error::ServerError(e.to_string()))
+        for index in SecondaryIndex::open_all(storage_addr, table, node_number)? {
+            index.index_rows(old_rows, new_rows)?;
+        }
+        Ok(())
+    }
+
+    /// Borra de los índices secundarios de la réplica dada las filas borradas. Si sólo se
+    /// borraron algunas de sus columnas, se actualizan únicamente los índices sobre ellas.
+    fn unindex_rows(
+        storage_addr: &str,
+        table: &Table,
+        node_number: Byte,
+        rows: &[Vec<String>],
+        columns: Option<&[String]>,
+        timestamp: Long,
+    ) -> Result<()> {
+        for index in SecondaryIndex::open_all(storage_addr, table, node_number)? {
+            if columns.is_none_or(|columns| columns.iter().any(|c| c == index.get_column())) {
+                index.unindex_rows(rows, timestamp)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Guarda en el _batch log_ del nodo un BATCH a punto de aplicarse, forzando su escritura a disco.
+    ///
+    /// Si el nodo se cae a mitad de la aplicación, el BATCH se vuelve a aplicar al reiniciarlo.
+    pub fn store_batch_log(storage_addr: &str, batch_id: &str, batch: &[Byte]) -> Result<()> {
+        Self::write_log_file(
+            &format!("{storage_addr}/{BATCHLOG_DIR_NAME}"),
+            batch_id,
+            batch,
+        )
+    }
+
+    /// Borra un BATCH del _batch log_ del nodo, una vez que fue aplicado completamente.
+    pub fn delete_batch_log(storage_addr: &str, batch_id: &str) -> Result<()> {
+        remove_file(format!("{storage_addr}/{BATCHLOG_DIR_NAME}/{batch_id}.log"))
+            .map_err(|e| Error::ServerError(format!("No se pudo borrar el batch log: {e}")))
+    }
+
+    /// Obtiene los BATCH que quedaron en el _batch log_ sin terminar de aplicarse, junto a sus IDs.
+    pub fn get_pending_batch_logs(storage_addr: &str) -> Result<Vec<(String, Vec<Byte>)>> {
+        Self::read_log_files(&format!("{storage_addr}/{BATCHLOG_DIR_NAME}"))
+    }
+
+    /// Guarda en el _batch log_ del coordinador los grupos de un BATCH que todavía tiene que
+    /// confirmar cada réplica, forzando su escritura a disco. Si ya estaba guardado, se reemplaza.
+    ///
+    /// Los grupos se guardan como `<n><replica_1><largo_1><request_1>...<replica_n><largo_n><request_n>`.
+    pub fn store_coordinator_batch_log(
+        storage_addr: &str,
+        batch_id: &str,
+        groups: &[(NodeId, Vec<Byte>)],
+    ) -> Result<()> {
+        let mut batch = (groups.len() as Short).to_be_bytes().to_vec();
+        for (replica, request) in groups {
+            batch.push(*replica);
+            batch.extend((request.len() as Uint).to_be_bytes());
+            batch.extend(request);
+        }
+        Self::write_log_file(
+            &format!("{storage_addr}/{COORDINATOR_BATCHLOG_DIR_NAME}"),
+            batch_id,
+            &batch,
+        )
+    }
+
+    /// Borra un BATCH del _batch log_ del coordinador, una vez que todas sus réplicas lo confirmaron.
+    pub fn delete_coordinator_batch_log(storage_addr: &str, batch_id: &str) -> Result<()> {
+        remove_file(format!(
+            "{storage_addr}/{COORDINATOR_BATCHLOG_DIR_NAME}/{batch_id}.log"
+        ))
+        .map_err(|e| Error::ServerError(format!("No se pudo borrar el batch log: {e}")))
+    }
+
+    /// Obtiene los BATCH del _batch log_ del coordinador que alguna réplica todavía no confirmó,
+    /// junto a sus IDs y a los grupos pendientes de cada réplica.
+    ///
+    /// Los que quedaron a medio escribir se ignoran, porque no se llegaron a enviar.
+    pub fn get_pending_coordinator_batch_logs(
+        storage_addr: &str,
+    ) -> Result<Vec<(String, BatchLogGroups)>> {
+        let pending =
+            Self::read_log_files(&format!("{storage_addr}/{COORDINATOR_BATCHLOG_DIR_NAME}"))?;
+        Ok(pending
+            .into_iter()
+            .filter_map(|(batch_id, batch)| {
+                Some((batch_id, Self::parse_coordinator_batch_log(&batch)?))
+            })
+            .collect())
+    }
+
+    /// Parsea los grupos de un BATCH guardado en el _batch log_ del coordinador. Si está
+    /// incompleto, devuelve `None`.
+    fn parse_coordinator_batch_log(batch: &[Byte]) -> Option<BatchLogGroups> {
+        let groups_count = Short::from_be_bytes(batch.get(..2)?.try_into().ok()?);
+        let mut groups = Vec::new();
+        let mut i = 2;
+        for _ in 0..groups_count {
+            let replica = *batch.get(i)?;
+            let len = Uint::from_be_bytes(batch.get(i + 1..i + 5)?.try_into().ok()?) as usize;
+            i += 5;
+            groups.push((replica, batch.get(i..i + len)?.to_vec()));
+            i += len;
+        }
+        Some(groups)
+    }
+
+    /// Escribe un BATCH en el directorio de _batch log_ dado, forzando su escritura a disco.
+    fn write_log_file(batchlog_addr: &str, batch_id: &str, batch: &[Byte]) -> Result<()> {
+        Self::create_directory(batchlog_addr)?;
+        let mut file = File::create(format!("{batchlog_addr}/{batch_id}.log"))
+            .map_err(|e| Error::ServerError(format!("No se pudo crear el batch log: {e}")))?;
+        file.write_all(batch)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| Error::ServerError(format!("No se pudo escribir el batch log: {e}")))
+    }
+
+    /// Lee los BATCH del directorio de _batch log_ dado, junto a sus IDs.
+    fn read_log_files(batchlog_addr: &str) -> Result<Vec<(String, Vec<Byte>)>> {
+        if !Path::new(batchlog_addr).is_dir() {
+            return Ok(Vec::new());
+        }
+        let entries = read_dir(batchlog_addr)
+            .map_err(|e| Error::ServerError(format!("No se pudo leer el batch log: {e}")))?;
+
+        let mut pending = Vec::new();
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let Some(batch_id) = path.file_stem().and_then(|stem| stem.to_str()) else {
+                continue;
+            };
+            let batch = read(&path)
+                .map_err(|e| Error::ServerError(format!("No se pudo leer el batch log: {e}")))?;
+            pending.push((batch_id.to_string(), batch));
+        }
+        Ok(pending)
+    }
+
+    /// Guarda un _hint_ con una mutación que no se pudo enviar al nodo `target`, forzando su
+    /// escritura a disco, para reenviársela cuando vuelva a estar disponible.
+    ///
+    /// Devuelve `false` si no se guardó porque el nodo lleva caído más de [MAX_HINT_WINDOW_SECS],
+    /// lo que se estima a partir de su _hint_ pendiente más viejo.
+    pub fn store_hint(
+        storage_addr: &str,
+        target: NodeId,
+        timestamp: Long,
+        mutation: &[Byte],
+    ) -> Result<bool> {
+        let hints_addr = Self::hints_path(storage_addr, target);
+        let oldest_hint = Self::list_hints(&hints_addr)?
+            .first()
+            .map(|(hint_timestamp, _)| *hint_timestamp);
+        if oldest_hint.is_some_and(|oldest| timestamp - oldest > MAX_HINT_WINDOW_SECS) {
+            return Ok(false);
+        }
+        Self::create_directory(&format!("{storage_addr}/{HINTS_DIR_NAME}"))?;
+        Self::create_directory(&hints_addr)?;
+        let hint_id = format!("{timestamp}_{:016x}", hash_value((timestamp, mutation)));
+        let mut file = File::create(format!("{hints_addr}/{hint_id}.{HINT_EXTENSION}"))
+            .map_err(|e| Error::ServerError(format!("No se pudo crear el hint: {e}")))?;
+        file.write_all(mutation)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| Error::ServerError(format!("No se pudo escribir el hint: {e}")))?;
+        Ok(true)
+    }
+
+    /// Obtiene los IDs de los nodos para los que hay _hints_ pendientes.
+    pub fn get_hinted_nodes(storage_addr: &str) -> Result<Vec<NodeId>> {
+        let Ok(entries) = read_dir(format!("{storage_addr}/{HINTS_DIR_NAME}")) else {
+            return Ok(Vec::new());
+        };
+        Ok(entries
+            .flatten()
+            .filter_map(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .strip_prefix("node_")
+                    .and_then(|id| id.parse::<NodeId>().ok())
+            })
+            .collect())
+    }
+
+    /// Obtiene los _hints_ pendientes para el nodo `target`, del más viejo al más nuevo, junto
+    /// a sus IDs.
+    ///
+    /// Los que superan [HINT_TTL_SECS] se borran sin devolverse.
+    pub fn get_pending_hints(
+        storage_addr: &str,
+        target: NodeId,
+        now: Long,
+    ) -> Result<Vec<(String, Vec<Byte>)>> {
+        let hints_addr = Self::hints_path(storage_addr, target);
+        let mut pending = Vec::new();
+        for (hint_timestamp, hint_id) in Self::list_hints(&hints_addr)? {
+            if now - hint_timestamp > HINT_TTL_SECS {
+                Self::delete_hint(storage_addr, target, &hint_id)?;
+                continue;
+            }
+            let hint = read(format!("{hints_addr}/{hint_id}.{HINT_EXTENSION}"))
+                .map_err(|e| Error::ServerError(format!("No se pudo leer el hint: {e}")))?;
+            pending.push((hint_id, hint));
+        }
+        Ok(pending)
+    }
+
+    /// Borra un _hint_ del nodo `target`, una vez que fue reenviado o venció.
+    pub fn delete_hint(storage_addr: &str, target: NodeId, hint_id: &str) -> Result<()> {
+        remove_file(format!(
+            "{}/{hint_id}.{HINT_EXTENSION}",
+            Self::hints_path(storage_addr, target)
+        ))
+        .map_err(|e| Error::ServerError(format!("No se pudo borrar el hint: {e}")))
+    }
+
+    fn hints_path(storage_addr: &str, target: NodeId) -> String {
+        format!("{storage_addr}/{HINTS_DIR_NAME}/node_{target}")
+    }
+
+    /// Lista los _hints_ de un directorio según su timestamp y su ID, del más viejo al más nuevo.
+    fn list_hints(hints_addr: &str) -> Result<Vec<(Long, String)>> {
+        let Ok(entries) = read_dir(hints_addr) else {
+            return Ok(Vec::new());
+        };
+        let mut hints: Vec<(Long, String)> = entries
+            .flatten()
+            .filter_map(|entry| {
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let hint_id = file_name.strip_suffix(&format!(".{HINT_EXTENSION}"))?;
+                let (timestamp, _) = hint_id.split_once('_')?;
+                Some((timestamp.parse::<Long>().ok()?, hint_id.to_string()))
+            })
+            .collect();
+        hints.sort();
+        Ok(hints)
     }
 
     /// Trunca las filas de la tabla con las filas pasadas por parámetro.
@@ -484,32 +1264,30 @@ impl DiskHandler {
     /// tener cuidado al utilizarla.
     pub fn truncate_rows(
         storage_addr: &str,
-        table_name: &str,
-        keyspace_name: &str,
+        table: &Table,
         default_keyspace: &str,
         node_number: Byte,
         repaired_rows: &str,
     ) -> Result<()> {
-        let rows_as_string: Vec<Vec<String>> = repaired_rows
-            .split('\n')
-            .map(|row| row.split(',').map(|s| s.to_string()).collect())
-            .collect();
-
         let path = TablePath::new(
             storage_addr,
-            Some(keyspace_name.to_string()),
-            table_name,
+            Some(table.get_keyspace().to_string()),
+            table.get_name(),
             default_keyspace,
             node_number,
         );
 
-        let table_ops = TableOperations::new(path)?;
-        table_ops.write_rows(&rows_as_string)?;
-
-        Ok(())
+        let table_ops =
+            TableOperations::new(path)?.with_gc_grace_seconds(table.get_gc_grace_seconds());
+        let rows = Self::parse_rows(repaired_rows);
+        for index in SecondaryIndex::open_all(storage_addr, table, node_number)? {
+            index.rebuild(&rows)?;
+        }
+        table_ops.replace_rows(rows, &table.get_position_of_primary_key()?)
     }
 
-    /// Repara las filas de la tabla con las filas recibidas por parámetro.
+    /// Repara las filas de la tabla con las entradas recibidas por parámetro, que pueden
+    /// incluir _tombstones_.
     pub fn repair_rows(
         storage_addr: &str,
         table: &Table,
@@ -517,10 +1295,6 @@ impl DiskHandler {
         node_number: Byte,
         repaired_rows: &str,
     ) -> Result<()> {
-        let rows_as_string: Vec<Vec<String>> = repaired_rows
-            .split('\n')
-            .map(|row| row.split(',').map(|s| s.to_string()).collect())
-            .collect();
         let path = TablePath::new(
             storage_addr,
             Some(table.get_keyspace().to_string()),
@@ -528,12 +1302,28 @@ impl DiskHandler {
             default_keyspace,
             node_number,
         );
-        let table_ops = TableOperations::new(path)?;
-        for new_row in rows_as_string {
-            let rows = table_ops.read_rows(false)?;
-            Self::insert_new_row(rows, new_row, table, &table_ops)?;
-        }
-        Ok(())
+        let table_ops =
+            TableOperations::new(path)?.with_gc_grace_seconds(table.get_gc_grace_seconds());
+        let entries = repaired_rows
+            .split('\n')
+            .filter(|line| !line.trim().is_empty())
+            .map(StorageEntry::from_line)
+            .collect::<Result<Vec<StorageEntry>>>()?;
+        let live_rows: Vec<Vec<String>> = entries
+            .iter()
+            .filter(|entry| entry.is_live())
+            .map(|entry| entry.row.clone())
+            .collect();
+        Self::index_rows(storage_addr, table, node_number, &[], &live_rows)?;
+        table_ops.repair_entries(entries)
+    }
+
+    /// Separa en filas y columnas un conjunto de filas serializadas, ignorando las líneas vacías.
+    fn parse_rows(rows: &str) -> Vec<Vec<String>> {
+        rows.split('\n')
+            .filter(|row| !row.trim().is_empty())
+            .map(|row| row.split(',').map(|s| s.to_string()).collect())
+            .collect()
     }
 
     /// Inserta una nueva fila en una tabla en el caso que corresponda.
@@ -552,16 +1342,43 @@ impl DiskHandler {
             default_keyspace,
             node_number,
         );
-        let table_ops = TableOperations::new(path)?;
+        let table_ops =
+            TableOperations::new(path)?.with_gc_grace_seconds(table.get_gc_grace_seconds());
         table_ops.validate_columns(&statement.get_columns_names())?;
-        let rows = table_ops.read_rows(false)?;
         let values = statement.get_values();
         let new_row = Self::generate_row_values(statement, &table_ops, &values, timestamp);
 
-        Self::insert_new_row(rows, new_row, table, &table_ops)
+        let mut entry = StorageEntry::live(new_row, &table.get_position_of_primary_key()?);
+        if let Some(expires_at) = Self::expiration_of(statement.using.get_ttl()?, table) {
+            entry.expires_at = Some(expires_at);
+            entry.expiring_cells = statement
+                .get_columns_names()
+                .into_iter()
+                .filter(|column| !table.is_primary_key_column(column))
+                .map(|column| (column, expires_at))
+                .collect();
+        }
+        if !table.indexes.is_empty() {
+            let old_rows = table_ops.read_partition_rows(entry.partition(), false)?;
+            Self::index_rows(
+                storage_addr,
+                table,
+                node_number,
+                &old_rows,
+                std::slice::from_ref(&entry.row),
+            )?;
+        }
+        table_ops.upsert_entries(vec![entry])
+    }
+
+    /// Calcula el momento _(en segundos desde la época UNIX)_ en que vence una escritura con el
+    /// TTL dado, o con el de la tabla si no se indicó uno. Un TTL de 0 significa que no vence.
+    fn expiration_of(ttl: Option<Long>, table: &Table) -> Option<Long> {
+        let ttl = ttl.unwrap_or_else(|| table.get_default_time_to_live());
+        (ttl > 0).then(|| Utc::now().timestamp() + ttl)
     }
 
-    /// Filtra las filas repetidas de la tabla indicada y la deja ordenada
+    /// Compacta la réplica indicada de la tabla, dejando una única versión de cada fila.
     pub fn remove_repeated_rows(
         storage_addr: &str,
         table: &Table,
@@ -575,74 +1392,18 @@ impl DiskHandler {
             default_keyspace,
             node_number,
         );
-        let table_ops = TableOperations::new(path)?;
-        let rows = table_ops.read_rows(false)?;
-        let mut seen: HashSet<Vec<String>> = HashSet::new();
-        let mut filtered_rows = Vec::new();
-        let partition_key_positions = table.get_position_of_primary_key()?;
-        for row in rows.iter() {
-            let mut unique_columns: Vec<String> = Vec::new();
-            for partition_key_pos in &partition_key_positions {
-                if let Some(unique_value) = row.get(*partition_key_pos) {
-                    unique_columns.push(unique_value.to_string());
-                }
-            }
-            if seen.insert(unique_columns) {
-                filtered_rows.push(row.to_vec());
-            }
-        }
-        Self::order_and_save_rows(&table_ops, &mut filtered_rows, table)?;
-        Ok(())
-    }
-
-    fn insert_new_row(
-        mut rows: Vec<Vec<String>>,
-        new_row: Vec<String>,
-        table: &Table,
-        table_ops: &TableOperations,
-    ) -> Result<()> {
-        let mut primary_keys_indexs: Vec<usize> = Vec::new();
-        let table_cols = &table.columns;
-        if let Some(i) = table_cols
-            .iter()
-            .position(|c| c.get_name() == *table.partition_key[0])
-        {
-            primary_keys_indexs.push(i);
-        }
-        if let Some(clustering_key_and_order) = &table.clustering_key_and_order {
-            for (key, _) in clustering_key_and_order {
-                if let Some(i) = table_cols.iter().position(|c| c.get_name() == *key) {
-                    primary_keys_indexs.push(i);
-                }
-            }
-        }
-
-        let mut primary_key_is_equal = true;
-        let mut index_to_update = 0;
-        for (i, row) in rows.iter().enumerate() {
-            primary_key_is_equal = true;
-            for key_index in &primary_keys_indexs {
-                if row[*key_index] != new_row[*key_index] {
-                    primary_key_is_equal = false;
-                }
-            }
-            if primary_key_is_equal {
-                index_to_update = i;
-                break;
-            }
-        }
-        if primary_key_is_equal && !rows.is_empty() {
-            rows[index_to_update] = new_row;
-            table_ops.write_rows(&rows)
-        } else {
-            rows.push(new_row);
-            Self::order_and_save_rows(table_ops, &mut rows, table)
-        }
+        TableOperations::new(path)?
+            .with_gc_grace_seconds(table.get_gc_grace_seconds())
+            .compact()
     }
 
-    /// Devuelve las filas de la tabla como un string
-    pub fn get_rows_with_timestamp_as_string(
+    /// Devuelve como un string las entradas de la tabla consultadas por la _query_, con sus
+    /// timestamps y _tombstones_, para que el coordinador pueda reparar las réplicas.
+    ///
+    /// Los _tombstones_ de filas borradas se incluyen siempre que sean de la partición consultada.
+    pub fn get_entries_as_string(
         storage_addr: &str,
+        table: &Table,
         default_keyspace: &str,
         statement: &Select,
         node_number: Byte,
@@ -655,27 +1416,43 @@ impl DiskHandler {
             node_number,
         );
         let table_ops = TableOperations::new(path)?;
-        let query_cols = vec!["*".to_string()];
-        let mut rows = table_ops.read_rows(false)?;
+        let mut entries = Self::read_entries_for_where(
+            statement.options.the_where.as_ref(),
+            &table_ops,
+            (storage_addr, table, node_number),
+        )?;
 
         if let Some(the_where) = &statement.options.the_where {
-            rows.retain(|row| matches!(the_where.filter(row, &table_ops.columns), Ok(true)));
+            entries.retain(|entry| {
+                !entry.is_live()
+                    || matches!(the_where.filter(&entry.row, &table_ops.columns), Ok(true))
+            });
         }
 
-        if let Some(order) = &statement.options.order_by {
-            order.order(&mut rows, &table_ops.columns);
-        }
-        let result_rows: Vec<Vec<String>> = rows
-            .into_iter()
-            .map(|row| Self::generate_row_to_select(&row, &table_ops.columns, &query_cols, false))
-            .collect();
-
-        let rows_as_string = result_rows
+        let entries_as_string = entries
             .iter()
-            .map(|row| row.join(","))
+            .map(|entry| entry.to_line())
             .collect::<Vec<String>>()
             .join("\n");
-        Ok(rows_as_string)
+        Ok(entries_as_string)
+    }
+
+    /// Obtiene todas las entradas de una réplica de la tabla dada, con sus filas y borrados,
+    /// ordenadas por _primary key_.
+    pub fn get_replica_entries(
+        storage_addr: &str,
+        table: &Table,
+        default_keyspace: &str,
+        node_number: Byte,
+    ) -> Result<Vec<StorageEntry>> {
+        let path = TablePath::new(
+            storage_addr,
+            Some(table.get_keyspace().to_string()),
+            table.get_name(),
+            default_keyspace,
+            node_number,
+        );
+        TableOperations::new(path)?.read_entries(None)
     }
 
     /// Obtiene todas las filas de la tabla dada.
@@ -699,12 +1476,20 @@ impl DiskHandler {
     }
 
     /// Selecciona filas en una tabla en el caso que corresponda.
+    ///
+    /// Si se pide una página, sólo se devuelven sus filas, junto al estado desde el cual
+    /// retomar la lectura si quedan más.
+    ///
+    /// Si el SELECT usa funciones de agregación, se devuelven las filas sin agregar, con todas
+    /// las columnas de la tabla y las calculadas, para que el coordinador las agregue junto a
+    /// las de las otras réplicas.
     pub fn do_select(
         statement: &Select,
         storage_addr: &str,
         table: &Table,
         default_keyspace: &str,
         node_number: Byte,
+        page: Option<&Page>,
     ) -> Result<Vec<Byte>> {
         let path = TablePath::new(
             storage_addr,
@@ -719,33 +1504,301 @@ impl DiskHandler {
         let query_cols = statement.columns.get_columns();
 
         if query_cols.len() != 1 && query_cols[0] != "*" {
-            table_ops.validate_columns(&query_cols)?;
+            table_ops.validate_columns(&statement.columns.get_column_names())?;
         }
-        let mut result = Vec::new();
-        let mut rows = table_ops.read_rows(true)?;
+        let mut result_cols = Self::get_result_columns(statement, &table_ops.columns, table)?;
+        let entries = Self::read_entries_for_where(
+            statement.options.the_where.as_ref(),
+            &table_ops,
+            (storage_addr, table, node_number),
+        )?;
+        let table_rows = entries
+            .iter()
+            .filter(|entry| entry.is_live())
+            .map(|entry| {
+                let row = entry.row.split_last().map(|(_, row)| row.to_vec());
+                (row.unwrap_or_default(), Some(entry))
+            })
+            .collect();
+        let (mut rows, columns) =
+            Self::add_computed_columns(statement, table_rows, &table_ops.columns)?;
         if let Some(the_where) = &statement.options.the_where {
-            rows.retain(|row| matches!(the_where.filter(row, &table_ops.columns), Ok(true)));
+            rows.retain(|row| matches!(the_where.filter(row, &columns), Ok(true)));
         }
 
-        if let Some(order) = &statement.options.order_by {
-            order.order(&mut rows, &table_ops.columns);
+        match &statement.options.order_by {
+            Some(order) => order.order(&mut rows, &columns),
+            None => Self::get_table_ordering(table).order(&mut rows, &columns),
         }
+        let rows = Self::limit_rows(statement, rows, &columns, table)?;
+        // Las filas sin agregar se devuelven todas juntas, sin importar la página pedida
+        let page = page.filter(|_| !select_has_aggregates(&statement.columns));
+        let (rows, paging_state) = match page {
+            Some(page) => Self::select_page(
+                rows,
+                page,
+                &table.get_position_of_primary_key()?,
+                node_number,
+            ),
+            None => (rows, None),
+        };
 
+        let selected_cols = if select_has_aggregates(&statement.columns) {
+            result_cols =
+                Self::get_partial_aggregation_columns(statement, &table_ops.columns, table)?;
+            columns.clone()
+        } else {
+            Self::get_source_columns(statement)
+        };
         let result_rows: Vec<Vec<String>> = rows
             .into_iter()
-            .map(|row| Self::generate_row_to_select(&row, &table_ops.columns, &query_cols, true))
+            .map(|row| Self::generate_row_to_select(&row, &columns, &selected_cols, true))
             .collect();
 
-        result.extend(result_rows);
-
         Ok(Self::serialize_select_result(
-            result,
-            &query_cols,
-            &table_ops.columns,
-            table,
+            result_rows,
+            &result_cols,
+            paging_state.as_ref(),
         ))
     }
 
+    /// Agrega las filas juntadas para un SELECT con funciones de agregación, y aplica después
+    /// su LIMIT.
+    ///
+    /// Las filas tienen las columnas de la tabla seguidas de las calculadas, como las devuelve
+    /// [DiskHandler::do_select] en ese caso.
+    pub fn aggregate_select(
+        statement: &Select,
+        columns: &[String],
+        rows: Vec<Vec<String>>,
+        table: &Table,
+    ) -> Result<Vec<Byte>> {
+        let KindOfColumns::SelectClause(selectors) = &statement.columns else {
+            return Err(Error::Invalid(
+                "El SELECT no tiene funciones de agregación".to_string(),
+            ));
+        };
+        let group_positions = match &statement.options.group_by {
+            Some(group_by) => Some(Self::get_group_by_positions(group_by, columns, table)?),
+            None => None,
+        };
+        let mut rows =
+            aggregate_rows(selectors, columns, &rows, group_positions.as_deref(), table)?;
+        if let Some(limit) = &statement.options.limit {
+            rows.truncate(limit.get_limit()? as usize);
+        }
+        let result_cols = Self::get_result_columns(statement, columns, table)?;
+        Ok(Self::serialize_select_result(rows, &result_cols, None))
+    }
+
+    /// Agrega al final de cada fila los valores de los selectores que se calculan fila por
+    /// fila, y devuelve las filas junto a los nombres de todas sus columnas.
+    fn add_computed_columns(
+        statement: &Select,
+        rows: Vec<(Vec<String>, Option<&StorageEntry>)>,
+        table_cols: &[String],
+    ) -> Result<(Vec<Vec<String>>, Vec<String>)> {
+        let computed = get_computed_selectors(&statement.columns);
+        let mut columns = table_cols.to_vec();
+        columns.extend(computed.iter().map(|selector| selector.get_name()));
+        let rows = rows
+            .into_iter()
+            .map(|(mut row, entry)| {
+                let context = RowContext::new(table_cols, &row, entry);
+                let values = computed
+                    .iter()
+                    .map(|selector| evaluate(selector, &context))
+                    .collect::<Result<Vec<String>>>()?;
+                row.extend(values);
+                Ok(row)
+            })
+            .collect::<Result<Vec<Vec<String>>>>()?;
+        Ok((rows, columns))
+    }
+
+    /// Obtiene las columnas de las filas de las que sale cada columna del resultado.
+    fn get_source_columns(statement: &Select) -> Vec<String> {
+        match &statement.columns {
+            KindOfColumns::SelectClause(selectors) => selectors
+                .iter()
+                .map(|selector| selector.unaliased().get_name())
+                .collect(),
+            KindOfColumns::All => vec!["*".to_string()],
+        }
+    }
+
+    /// Obtiene el nombre y el tipo de las columnas del resultado de un SELECT.
+    fn get_result_columns(
+        statement: &Select,
+        table_cols: &[String],
+        table: &Table,
+    ) -> Result<Vec<(String, ColType)>> {
+        match &statement.columns {
+            KindOfColumns::SelectClause(selectors) => selectors
+                .iter()
+                .map(|selector| Ok((selector.get_name(), selector_type(selector, table)?)))
+                .collect(),
+            KindOfColumns::All => Ok(Self::get_table_columns_types(table_cols, table)),
+        }
+    }
+
+    /// Obtiene el nombre y el tipo de las columnas de las filas sin agregar de un SELECT con
+    /// funciones de agregación: las de la tabla y las calculadas.
+    fn get_partial_aggregation_columns(
+        statement: &Select,
+        table_cols: &[String],
+        table: &Table,
+    ) -> Result<Vec<(String, ColType)>> {
+        let mut columns = Self::get_table_columns_types(table_cols, table);
+        for selector in get_computed_selectors(&statement.columns) {
+            columns.push((selector.get_name(), selector_type(selector, table)?));
+        }
+        Ok(columns)
+    }
+
+    /// Obtiene el tipo de las columnas dadas de la tabla.
+    fn get_table_columns_types(table_cols: &[String], table: &Table) -> Vec<(String, ColType)> {
+        let cols_name_and_type = table.get_columns_name_and_data_type();
+        table_cols
+            .iter()
+            .filter_map(|col| {
+                cols_name_and_type
+                    .iter()
+                    .find(|(name, _)| name == col)
+                    .map(|(name, data_type)| (name.to_string(), data_type.into()))
+            })
+            .collect()
+    }
+
+    /// Aplica a las filas ya filtradas y ordenadas el GROUP BY, el PER PARTITION LIMIT y el
+    /// LIMIT del SELECT, en ese orden.
+    ///
+    /// Sin funciones de agregación, de cada grupo queda sólo su primera fila. Con ellas, el
+    /// GROUP BY y el LIMIT se aplican recién al agregar las filas de todas las réplicas.
+    fn limit_rows(
+        statement: &Select,
+        mut rows: Vec<Vec<String>>,
+        table_cols: &[String],
+        table: &Table,
+    ) -> Result<Vec<Vec<String>>> {
+        let options = &statement.options;
+        if select_has_aggregates(&statement.columns) {
+            return match &options.per_partition_limit {
+                Some(per_partition_limit) => {
+                    Self::limit_per_partition(rows, per_partition_limit, table_cols, table)
+                }
+                None => Ok(rows),
+            };
+        }
+        if let Some(group_by) = &options.group_by {
+            let positions = Self::get_group_by_positions(group_by, table_cols, table)?;
+            let mut seen_groups = HashSet::new();
+            rows.retain(|row| seen_groups.insert(Self::values_at(row, &positions)));
+        }
+        if let Some(per_partition_limit) = &options.per_partition_limit {
+            rows = Self::limit_per_partition(rows, per_partition_limit, table_cols, table)?;
+        }
+        if let Some(limit) = &options.limit {
+            rows.truncate(limit.get_limit()? as usize);
+        }
+        Ok(rows)
+    }
+
+    /// Se queda con las primeras filas de cada partición, según el PER PARTITION LIMIT.
+    fn limit_per_partition(
+        mut rows: Vec<Vec<String>>,
+        per_partition_limit: &PerPartitionLimit,
+        table_cols: &[String],
+        table: &Table,
+    ) -> Result<Vec<Vec<String>>> {
+        let positions = Self::get_positions(&table.get_partition_key(), table_cols)?;
+        let limit = per_partition_limit.get_limit()? as usize;
+        let mut rows_per_partition: HashMap<Vec<String>, usize> = HashMap::new();
+        rows.retain(|row| {
+            let count = rows_per_partition
+                .entry(Self::values_at(row, &positions))
+                .or_default();
+            *count += 1;
+            *count <= limit
+        });
+        Ok(rows)
+    }
+
+    /// Obtiene las posiciones de las columnas de un GROUP BY, validando que sean un prefijo de
+    /// la _primary key_ que incluya a toda la _partition key_.
+    fn get_group_by_positions(
+        group_by: &GroupBy,
+        table_cols: &[String],
+        table: &Table,
+    ) -> Result<Vec<usize>> {
+        let mut primary_key = table.get_partition_key();
+        if let Some(clustering_key_and_order) = &table.clustering_key_and_order {
+            primary_key.extend(clustering_key_and_order.iter().map(|(col, _)| col.clone()));
+        }
+        let group_cols: Vec<String> = group_by
+            .columns
+            .iter()
+            .map(|col| col.get_name().to_string())
+            .collect();
+        if group_cols.len() < table.partition_key.len() || !primary_key.starts_with(&group_cols) {
+            return Err(Error::Invalid(format!(
+                "El GROUP BY sólo admite un prefijo de la primary key ({}) que incluya a toda la partition key",
+                primary_key.join(", ")
+            )));
+        }
+        Self::get_positions(&group_cols, table_cols)
+    }
+
+    /// Obtiene las posiciones de las columnas dadas entre las columnas de la tabla.
+    fn get_positions(cols: &[String], table_cols: &[String]) -> Result<Vec<usize>> {
+        cols.iter()
+            .map(|col| {
+                table_cols
+                    .iter()
+                    .position(|table_col| table_col == col)
+                    .ok_or_else(|| Error::Invalid(format!("La tabla no contiene la columna {col}")))
+            })
+            .collect()
+    }
+
+    /// Obtiene los valores de una fila en las posiciones dadas.
+    fn values_at(row: &[String], positions: &[usize]) -> Vec<String> {
+        positions
+            .iter()
+            .map(|position| row.get(*position).cloned().unwrap_or_default())
+            .collect()
+    }
+
+    /// Se queda con las filas de la página pedida, de entre todas las filas ordenadas de la
+    /// réplica. Si quedan filas después de la página, devuelve además el estado desde el cual
+    /// retomar la lectura.
+    fn select_page(
+        rows: Vec<Vec<String>>,
+        page: &Page,
+        key_positions: &[usize],
+        node_number: Byte,
+    ) -> (Vec<Vec<String>>, Option<PagingState>) {
+        let key_of = |row: &Vec<String>| -> Vec<String> {
+            key_positions
+                .iter()
+                .map(|position| row.get(*position).cloned().unwrap_or_default())
+                .collect()
+        };
+        let start = page
+            .state
+            .as_ref()
+            .map_or(0, |state| state.resume_position(&rows, key_of));
+        let end = (start + page.size).min(rows.len());
+        let paging_state = match end.checked_sub(1).and_then(|last| rows.get(last)) {
+            Some(last_row) if end < rows.len() => {
+                Some(PagingState::new(node_number, end as Uint, key_of(last_row)))
+            }
+            _ => None,
+        };
+        let rows = rows.into_iter().skip(start).take(end - start).collect();
+        (rows, paging_state)
+    }
+
     /// Actualiza filas en una tabla en el caso que corresponda.
     pub fn do_update(
         statement: &Update,
@@ -762,16 +1815,30 @@ impl DiskHandler {
             default_keyspace,
             node_number,
         );
-        let table_ops = TableOperations::new(path)?;
+        let table_ops =
+            TableOperations::new(path)?.with_gc_grace_seconds(table.get_gc_grace_seconds());
         Self::validate_update_columns(&table_ops, &statement.set_parameter)?;
-        let mut rows = table_ops.read_rows(false)?;
+        Self::validate_counter_assignments(&statement.set_parameter, table)?;
+        // Se leen las entradas, y no sólo las filas, para conservar los vencimientos de las
+        // celdas que no se actualizan.
+        let partition = Self::get_partition_from_where(statement.the_where.as_ref(), table);
+        let entries: Vec<StorageEntry> = table_ops
+            .read_entries(partition.as_deref())?
+            .into_iter()
+            .filter(|entry| entry.is_live())
+            .collect();
+        let mut rows: Vec<Vec<String>> = entries.iter().map(|entry| entry.row.clone()).collect();
 
         if matches!(statement.if_condition, IfCondition::Exists) && rows.is_empty() {
             return Ok(Vec::new());
         }
 
+        let mut old_rows = Vec::new();
         let mut updated_rows = Vec::new();
+        let mut updated_entries = Vec::new();
         let mut should_write = false;
+        let key_positions = table.get_position_of_primary_key()?;
+        let expires_at = Self::expiration_of(statement.using.get_ttl()?, table);
 
         if matches!(statement.if_condition, IfCondition::Conditions(_))
             && statement.the_where.is_none()
@@ -785,35 +1852,82 @@ impl DiskHandler {
                 should_write = true;
             }
         }
-        for row in rows.iter_mut() {
+        for (row, old_entry) in rows.iter_mut().zip(&entries) {
             if RowOperations::should_process_row(
                 row,
                 &statement.if_condition,
                 &table_ops.columns,
                 statement.the_where.as_ref(),
             )? {
+                old_rows.push(old_entry.row.clone());
                 for assignment in &statement.set_parameter {
                     Self::update_row_value(row, assignment, &table_ops.columns)?;
                 }
                 row.pop();
                 row.push(timestamp.to_string());
                 updated_rows.push(row.clone());
+                updated_entries.push(Self::updated_entry(
+                    old_entry,
+                    row.clone(),
+                    &key_positions,
+                    &statement.set_parameter,
+                    expires_at,
+                ));
                 should_write = true;
             }
         }
 
         if should_write {
-            Self::order_and_save_rows(&table_ops, &mut rows, table)?;
+            Self::index_rows(storage_addr, table, node_number, &old_rows, &updated_rows)?;
+            table_ops.upsert_entries(updated_entries)?;
         }
         Ok(updated_rows.iter().map(|row| row.join(",")).collect())
     }
 
-    /// Elimina filas en una tabla en el caso que corresponda.
+    /// Arma la nueva versión de una entrada actualizada, con los vencimientos de las celdas
+    /// asignadas según el TTL de la actualización.
+    ///
+    /// Si la actualización no vence, tampoco lo hace la fila entera; si vence, la fila vence
+    /// cuando lo haga la última de sus celdas.
+    fn updated_entry(
+        old_entry: &StorageEntry,
+        row: Vec<String>,
+        key_positions: &[usize],
+        assignments: &[Assignment],
+        expires_at: Option<Long>,
+    ) -> StorageEntry {
+        let mut entry = StorageEntry::live(row, key_positions);
+        entry.expiring_cells = old_entry.expiring_cells.clone();
+        for assignment in assignments {
+            let column = match assignment {
+                Assignment::ColumnNameTerm(col, _)
+                | Assignment::ColumnNameColTerm(col, _, _, _)
+                | Assignment::ColumnNameListCol(col, _, _) => col.get_name().to_string(),
+            };
+            match expires_at {
+                Some(expires_at) => entry.expiring_cells.insert(column, expires_at),
+                None => entry.expiring_cells.remove(&column),
+            };
+        }
+        entry.expires_at = match (old_entry.expires_at, expires_at) {
+            (Some(old), Some(new)) => Some(old.max(new)),
+            _ => None,
+        };
+        entry
+    }
+
+    /// Elimina filas en una tabla en el caso que corresponda, dejando _tombstones_ con el
+    /// timestamp dado.
+    ///
+    /// Si el WHERE especifica toda la _primary key_ (o sólo la _partition key_, para borrar
+    /// la partición entera) y no hay condiciones IF, el _tombstone_ se escribe aunque la
+    /// réplica no tenga la fila, para que no reaparezca si otra réplica todavía la tiene.
     pub fn do_delete(
         statement: &Delete,
         storage_addr: &str,
         table: &Table,
         default_keyspace: &str,
+        timestamp: Long,
         node_number: Byte,
     ) -> Result<Vec<String>> {
         let path = TablePath::new(
@@ -824,27 +1938,72 @@ impl DiskHandler {
             node_number,
         );
 
-        let table_ops = TableOperations::new(path)?;
-        let rows = table_ops.read_rows(false)?;
+        let table_ops =
+            TableOperations::new(path)?.with_gc_grace_seconds(table.get_gc_grace_seconds());
+        let rows = match Self::get_partition_from_where(statement.the_where.as_ref(), table) {
+            Some(partition) => table_ops.read_partition_rows(&partition, false)?,
+            None => table_ops.read_rows(false)?,
+        };
 
         if matches!(statement.if_condition, IfCondition::Exists) && rows.is_empty() {
             return Ok(Vec::new());
         }
 
-        let result = if statement.cols.is_empty() {
-            Self::process_full_row_delete(statement, &rows, &table_ops)?
-        } else {
-            Self::process_partial_row_delete(statement, &rows, &table_ops)?
+        let key_positions = table.get_position_of_primary_key()?;
+        let key_of = |row: &Vec<String>| -> Vec<String> {
+            key_positions
+                .iter()
+                .map(|position| row.get(*position).cloned().unwrap_or_default())
+                .collect()
         };
-
-        let (modified_rows, deleted_data) = result;
-
-        if !deleted_data.is_empty() || modified_rows.is_empty() {
-            let mut rows_to_write = modified_rows.clone();
-            Self::order_and_save_rows(&table_ops, &mut rows_to_write, table)?;
+        let where_key = match statement.if_condition {
+            IfCondition::None => Self::get_key_from_where(statement.the_where.as_ref(), table),
+            _ => None,
+        };
+        if statement.cols.is_empty() {
+            let deleted_rows = Self::process_full_row_delete(statement, &rows, &table_ops)?;
+            Self::unindex_rows(
+                storage_addr,
+                table,
+                node_number,
+                &deleted_rows,
+                None,
+                timestamp,
+            )?;
+            let deleted_data = deleted_rows.iter().map(|row| row.join(",")).collect();
+            let keys = match where_key {
+                Some(key) => vec![key],
+                None => deleted_rows.iter().map(key_of).collect(),
+            };
+            table_ops.delete_rows(keys, timestamp)?;
+            Ok(deleted_data)
+        } else {
+            if let Some(column) = statement
+                .cols
+                .iter()
+                .find(|column| table.is_primary_key_column(column))
+            {
+                return Err(Error::Invalid(format!(
+                    "No se puede borrar la columna {column} porque es parte de la primary key"
+                )));
+            }
+            let (deleted_rows, deleted_data) =
+                Self::process_partial_row_delete(statement, &rows, &table_ops)?;
+            Self::unindex_rows(
+                storage_addr,
+                table,
+                node_number,
+                &deleted_rows,
+                Some(&statement.cols),
+                timestamp,
+            )?;
+            let keys = match where_key {
+                Some(key) if key.len() == key_positions.len() => vec![key],
+                _ => deleted_rows.iter().map(key_of).collect(),
+            };
+            table_ops.delete_cells(keys, &statement.cols, timestamp)?;
+            Ok(deleted_data)
         }
-
-        Ok(deleted_data)
     }
 
     fn generate_row_values(
@@ -867,35 +2026,24 @@ impl DiskHandler {
         .collect()
     }
 
-    fn order_and_save_rows(
-        table_ops: &TableOperations,
-        rows: &mut [Vec<String>],
-        table: &Table,
-    ) -> Result<()> {
-        let order_by = Self::get_table_ordering(table);
-        order_by.order(rows, &table_ops.columns);
-        table_ops.write_rows(rows)
-    }
-
+    /// Devuelve las filas a borrar.
     fn process_full_row_delete(
         statement: &Delete,
         rows: &[Vec<String>],
         table_ops: &TableOperations,
-    ) -> Result<(Vec<Vec<String>>, Vec<String>)> {
-        let mut modified_rows = Vec::new();
-        let mut deleted_data = Vec::new();
-
+    ) -> Result<Vec<Vec<String>>> {
         if statement.the_where.is_none() {
             if let IfCondition::Conditions(conditions) = &statement.if_condition {
                 let all_conditions_met =
                     RowOperations::verify_row_conditions(rows, conditions, &table_ops.columns)?;
                 if !all_conditions_met {
-                    return Ok((rows.to_vec(), Vec::new()));
+                    return Ok(Vec::new());
                 }
-                return Ok((Vec::new(), rows.iter().map(|row| row.join(",")).collect()));
+                return Ok(rows.to_vec());
             }
         }
 
+        let mut deleted_rows = Vec::new();
         for row in rows {
             if RowOperations::should_process_row(
                 row,
@@ -903,15 +2051,14 @@ impl DiskHandler {
                 &table_ops.columns,
                 statement.the_where.as_ref(),
             )? {
-                deleted_data.push(row.join(","));
-            } else {
-                modified_rows.push(row.to_vec());
+                deleted_rows.push(row.to_vec());
             }
         }
 
-        Ok((modified_rows, deleted_data))
+        Ok(deleted_rows)
     }
 
+    /// Devuelve las filas a las que se les borran celdas, junto a los valores borrados.
     fn process_partial_row_delete(
         statement: &Delete,
         rows: &[Vec<String>],
@@ -933,7 +2080,6 @@ impl DiskHandler {
                 &table_ops.columns,
                 statement.the_where.as_ref(),
             )? {
-                let mut new_row = row.to_vec();
                 let deleted_values: Vec<String> = columns_to_modify
                     .iter()
                     .filter_map(|&idx| row.get(idx))
@@ -941,14 +2087,6 @@ impl DiskHandler {
                     .collect();
 
                 deleted_data.push(deleted_values.join(","));
-
-                for &col_idx in &columns_to_modify {
-                    if col_idx < new_row.len() {
-                        new_row[col_idx] = String::new();
-                    }
-                }
-                modified_rows.push(new_row);
-            } else {
                 modified_rows.push(row.to_vec());
             }
         }
@@ -979,6 +2117,12 @@ impl DiskHandler {
                 Term::Constant(Constant::Uuid(u)) => u.to_string(),
                 Term::Constant(Constant::Blob(b)) => b.to_string(),
                 Term::Constant(Constant::NULL) => "NULL".to_string(),
+                Term::BindMarker(marker) => {
+                    return Err(Error::Invalid(format!(
+                        "El marcador {} no tiene un valor vinculado",
+                        marker.get_value_as_string()
+                    )))
+                }
             };
 
             let matches = match condition.operator {
@@ -1027,10 +2171,7 @@ impl DiskHandler {
                     } else if term1.get_value() == "class"
                         && term2.get_value() == "NetworkTopologyStrategy"
                     {
-                        // No es necesario implementar NetworkTopologyStrategy
-                        return Err(Error::Invalid(
-                            "La replicación NetworkTopologyStrategy no está soportada.".to_string(),
-                        ));
+                        return Self::get_network_topology_strategy_replication(values);
                     }
                 }
                 _ => break,
@@ -1061,6 +2202,32 @@ impl DiskHandler {
         }
     }
 
+    fn get_network_topology_strategy_replication(
+        values: &[(Term, Term)],
+    ) -> Result<Option<ReplicationStrategy>> {
+        let mut datacenters = Vec::new();
+        for (datacenter, replicas) in &values[1..] {
+            let datacenter = datacenter.get_value();
+            let replicas = match replicas.get_value().parse::<Uint>() {
+                Ok(replicas) => replicas,
+                Err(_) => {
+                    return Err(Error::Invalid(format!(
+                        "El valor de réplicas del datacenter '{datacenter}' debe ser un número"
+                    )));
+                }
+            };
+            datacenters.push((datacenter, replicas));
+        }
+        if datacenters.is_empty() {
+            return Err(Error::Invalid(
+                "Faltó indicar la cantidad de réplicas de al menos un datacenter".to_string(),
+            ));
+        }
+        Ok(Some(ReplicationStrategy::NetworkTopologyStrategy(
+            datacenters,
+        )))
+    }
+
     fn validate_and_get_keyspace_table_names(
         statement: &CreateTable,
         default_keyspace: &str,
@@ -1148,6 +2315,119 @@ impl DiskHandler {
         Ok(())
     }
 
+    /// Obtiene el valor de la _partition key_ si el WHERE la compara por igualdad, para poder
+    /// leer sólo esa partición.
+    pub fn get_partition_from_where(the_where: Option<&Where>, table: &Table) -> Option<String> {
+        Self::get_equality_from_where(the_where, table.partition_key.first()?)
+    }
+
+    /// Obtiene el índice secundario que puede usar el WHERE, junto al valor que busca, si no
+    /// compara por igualdad la _partition key_ pero sí alguna columna indexada.
+    pub fn get_index_for_where<'a>(
+        the_where: Option<&Where>,
+        table: &'a Table,
+    ) -> Option<(&'a str, String)> {
+        if Self::get_partition_from_where(the_where, table).is_some() {
+            return None;
+        }
+        let mut indexes: Vec<(&String, &String)> = table.indexes.iter().collect();
+        indexes.sort();
+        indexes.into_iter().find_map(|(index_name, column)| {
+            Some((
+                index_name.as_str(),
+                Self::get_equality_from_where(the_where, column)?,
+            ))
+        })
+    }
+
+    /// Lee de la réplica las entradas que puede seleccionar el WHERE: las de una sola partición
+    /// si compara por igualdad la _partition key_, las de las particiones que indique un índice
+    /// secundario si compara por igualdad una columna indexada, o todas si no.
+    ///
+    /// Las entradas leídas igual deben filtrarse con el WHERE.
+    fn read_entries_for_where(
+        the_where: Option<&Where>,
+        table_ops: &TableOperations,
+        replica: (&str, &Table, Byte),
+    ) -> Result<Vec<StorageEntry>> {
+        let (storage_addr, table, node_number) = replica;
+        if let Some(partition) = Self::get_partition_from_where(the_where, table) {
+            return table_ops.read_entries(Some(&partition));
+        }
+        if let Some((index_name, value)) = Self::get_index_for_where(the_where, table) {
+            if let Some(index) = SecondaryIndex::open(storage_addr, table, index_name, node_number)?
+            {
+                let mut entries = Vec::new();
+                for partition in index.get_partitions(&value)? {
+                    entries.extend(table_ops.read_entries(Some(&partition))?);
+                }
+                return Ok(entries);
+            }
+        }
+        table_ops.read_entries(None)
+    }
+
+    /// Obtiene el valor con el que el WHERE compara por igualdad a la columna dada, si lo hace.
+    fn get_equality_from_where(the_where: Option<&Where>, column: &str) -> Option<String> {
+        let mut expressions: Vec<&Expression> = vec![the_where?.expression.as_deref()?];
+        while let Some(expression) = expressions.pop() {
+            match expression {
+                Expression::Expression(inner) => expressions.push(inner),
+                Expression::And(and) => {
+                    expressions.push(&and.first_relation);
+                    expressions.push(&and.second_relation);
+                }
+                Expression::Relation(relation) => {
+                    if matches!(relation.operator, Operator::Equal)
+                        && relation.column.get_name() == column
+                    {
+                        return Some(relation.term_to_compare.get_value().to_string());
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// Obtiene los valores de la _primary key_ si el WHERE sólo compara por igualdad todas sus
+    /// columnas, o sólo la _partition key_, para poder borrar sin leer las filas.
+    fn get_key_from_where(the_where: Option<&Where>, table: &Table) -> Option<Vec<String>> {
+        let mut values: HashMap<String, String> = HashMap::new();
+        let mut expressions: Vec<&Expression> = vec![the_where?.expression.as_deref()?];
+        while let Some(expression) = expressions.pop() {
+            match expression {
+                Expression::Expression(inner) => expressions.push(inner),
+                Expression::And(and) => {
+                    expressions.push(&and.first_relation);
+                    expressions.push(&and.second_relation);
+                }
+                Expression::Relation(relation) => {
+                    if !matches!(relation.operator, Operator::Equal) {
+                        return None;
+                    }
+                    let column = relation.column.get_name().to_string();
+                    let value = relation.term_to_compare.get_value().to_string();
+                    if values.insert(column, value).is_some() {
+                        return None;
+                    }
+                }
+            }
+        }
+        let primary_key = table.get_primary_key_columns();
+        let covered = primary_key
+            .iter()
+            .take_while(|column| values.contains_key(*column))
+            .count();
+        if values.len() != covered || (covered != 1 && covered != primary_key.len()) {
+            return None;
+        }
+        primary_key
+            .iter()
+            .take(covered)
+            .map(|column| values.remove(column))
+            .collect()
+    }
+
     fn get_table_ordering(table: &Table) -> OrderBy {
         let partition_key = table.get_partition_key();
         let mut order_criteria = vec![];
@@ -1184,32 +2464,27 @@ impl DiskHandler {
 
     fn serialize_select_result(
         result: Vec<Vec<String>>,
-        query_cols: &[String],
-        table_cols: &[String],
-        table: &Table,
+        result_cols: &[(String, ColType)],
+        paging_state: Option<&PagingState>,
     ) -> Vec<Byte> {
         let mut res: Vec<Byte> = vec![0x0, 0x0, 0x0, 0x2];
         let mut metadata: Vec<Byte> = Vec::new();
-        let flags: Int = 0;
-        metadata.append(&mut flags.to_be_bytes().to_vec());
-
-        let selected_cols = if query_cols[0] == "*" {
-            table_cols
-        } else {
-            query_cols
+        let flags: Int = match paging_state {
+            Some(_) => RowsFlag::accumulate(&[&RowsFlag::HasMorePages]),
+            None => 0,
         };
+        metadata.append(&mut flags.to_be_bytes().to_vec());
 
-        metadata.append(&mut (selected_cols.len() as Int).to_be_bytes().to_vec());
+        metadata.append(&mut (result_cols.len() as Int).to_be_bytes().to_vec());
+        if let Some(paging_state) = paging_state {
+            let mut state_bytes = paging_state.as_bytes();
+            metadata.append(&mut (state_bytes.len() as Int).to_be_bytes().to_vec());
+            metadata.append(&mut state_bytes);
+        }
 
-        let cols_name_and_type = table.get_columns_name_and_data_type();
-        for col_name in selected_cols {
-            if let Some((_, data_type)) =
-                cols_name_and_type.iter().find(|(name, _)| name == col_name)
-            {
-                let col_type: ColType = data_type.into();
-                metadata.append(&mut encode_string_to_bytes(col_name));
-                metadata.append(&mut col_type.as_bytes());
-            }
+        for (col_name, col_type) in result_cols {
+            metadata.append(&mut encode_string_to_bytes(col_name));
+            metadata.append(&mut col_type.as_bytes());
         }
 
         let rows_count = result.len() as Int;
@@ -1266,7 +2541,7 @@ impl DiskHandler {
                 Assignment::ColumnNameTerm(col, _) => {
                     table_ops.validate_columns(&[col.get_name().to_string()])?;
                 }
-                Assignment::ColumnNameColTerm(target_col, source_col, _) => {
+                Assignment::ColumnNameColTerm(target_col, source_col, _, _) => {
                     table_ops.validate_columns(&[
                         target_col.get_name().to_string(),
                         source_col.get_name().to_string(),
@@ -1283,6 +2558,74 @@ impl DiskHandler {
         Ok(())
     }
 
+    /// Verifica que las asignaciones respeten los contadores de la tabla: un contador sólo se
+    /// puede incrementar o decrementar a partir de sí mismo (`c = c + n`), y nada más que un
+    /// contador se puede actualizar así.
+    pub fn validate_counter_assignments(assignments: &[Assignment], table: &Table) -> Result<()> {
+        let data_types: HashMap<String, ColumnDataType> =
+            table.get_columns_name_and_data_type().into_iter().collect();
+        let is_counter =
+            |column: &str| matches!(data_types.get(column), Some(ColumnDataType::Counter));
+        for assignment in assignments {
+            match assignment {
+                Assignment::ColumnNameColTerm(target_col, source_col, _, _) => {
+                    if !is_counter(target_col.get_name()) {
+                        return Err(Error::Invalid(format!(
+                            "La columna {} no es un contador, y sólo se puede incrementar o decrementar un contador",
+                            target_col.get_name()
+                        )));
+                    }
+                    if target_col.get_name() != source_col.get_name() {
+                        return Err(Error::Invalid(format!(
+                            "El contador {} sólo se puede actualizar a partir de sí mismo",
+                            target_col.get_name()
+                        )));
+                    }
+                }
+                Assignment::ColumnNameTerm(col, _) | Assignment::ColumnNameListCol(col, _, _) => {
+                    if is_counter(col.get_name()) {
+                        return Err(Error::Invalid(format!(
+                            "El contador {} sólo se puede incrementar o decrementar",
+                            col.get_name()
+                        )));
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Suma o resta el término al valor actual de un contador. Un contador sin valor cuenta
+    /// desde 0.
+    fn apply_counter_delta(
+        current: &str,
+        operator: ArithmeticOperator,
+        term: &Term,
+    ) -> Result<String> {
+        let current = match current {
+            "" => 0,
+            value => value.parse::<Long>().map_err(|_| {
+                Error::ServerError(format!("El contador tiene un valor inválido: {value}"))
+            })?,
+        };
+        let delta = term.get_value().parse::<Long>().map_err(|_| {
+            Error::Invalid(format!(
+                "Un contador sólo se puede modificar con enteros, y se recibió {}",
+                term.get_value()
+            ))
+        })?;
+        let updated = match operator {
+            ArithmeticOperator::Plus => current.checked_add(delta),
+            ArithmeticOperator::Minus => current.checked_sub(delta),
+        };
+        match updated {
+            Some(updated) => Ok(updated.to_string()),
+            None => Err(Error::Invalid(
+                "El contador se pasaría de su valor máximo o mínimo".to_string(),
+            )),
+        }
+    }
+
     fn update_row_value(
         row: &mut [String],
         assignment: &Assignment,
@@ -1297,12 +2640,12 @@ impl DiskHandler {
                     row[col_index] = term.get_value().to_string();
                 }
             }
-            Assignment::ColumnNameColTerm(target_col, _, term) => {
+            Assignment::ColumnNameColTerm(target_col, _, operator, term) => {
                 if let Some(col_index) = columns
                     .iter()
                     .position(|c| c == &target_col.get_name().to_string())
                 {
-                    row[col_index] = term.get_value().to_string();
+                    row[col_index] = Self::apply_counter_delta(&row[col_index], *operator, term)?;
                 }
             }
             Assignment::ColumnNameListCol(target_col, _, source_col) => {
@@ -1356,3 +2699,43 @@ impl DiskHandler {
         Ok(result)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {super::*, tokenizer::tok::tokenize_query};
+
+    fn counter_assignment(query: &str) -> Result<Assignment> {
+        match Assignment::check_kind_of_assignment(&mut tokenize_query(query))? {
+            Some(assignment) => Ok(assignment),
+            None => Err(Error::SyntaxError(format!("No es un assignment: {query}"))),
+        }
+    }
+
+    #[test]
+    fn test_1_dos_incrementos_suman_dos() -> Result<()> {
+        let columns = vec!["id".to_string(), "total".to_string()];
+        let mut row = vec!["1".to_string(), String::new()];
+        let increment = counter_assignment("total = total + 1")?;
+
+        DiskHandler::update_row_value(&mut row, &increment, &columns)?;
+        DiskHandler::update_row_value(&mut row, &increment, &columns)?;
+
+        assert_eq!(row[1], "2");
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_decremento_resta() -> Result<()> {
+        let columns = vec!["id".to_string(), "total".to_string()];
+        let mut row = vec!["1".to_string(), "5".to_string()];
+
+        DiskHandler::update_row_value(
+            &mut row,
+            &counter_assignment("total = total - 7")?,
+            &columns,
+        )?;
+
+        assert_eq!(row[1], "-2");
+        Ok(())
+    }
+}
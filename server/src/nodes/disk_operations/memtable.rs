@@ -0,0 +1,133 @@
+//! Módulo para las _memtables_ de las réplicas de las tablas.
+
+use {
+    crate::nodes::disk_operations::{
+        commit_log::{with_commit_log, CommitLog, LoggedOperation, SegmentId},
+        storage_entry::{SortedEntries, StorageEntry},
+    },
+    protocol::{aliases::results::Result, errors::error::Error},
+    std::{
+        collections::HashMap,
+        path::Path,
+        sync::{Mutex, OnceLock},
+    },
+};
+
+/// La cantidad de filas a partir de la cual una _memtable_ se vuelca a una SSTable.
+pub const MEMTABLE_FLUSH_THRESHOLD: usize = 1024;
+
+/// Las escrituras más recientes de una réplica que todavía no fueron volcadas a una SSTable,
+/// ordenadas por _primary key_.
+#[derive(Default)]
+pub struct Memtable {
+    /// La versión más reciente de cada fila.
+    entries: SortedEntries,
+
+    /// El segmento más viejo del _commit log_ con escrituras de esta _memtable_, si tiene alguna.
+    first_segment: Option<SegmentId>,
+}
+
+impl Memtable {
+    /// Agrega una entrada, si es más reciente que la versión que ya había de esa fila.
+    ///
+    /// `segment` es el segmento del _commit log_ en el que quedó registrada.
+    pub fn insert(&mut self, entry: StorageEntry, segment: SegmentId) {
+        entry.merge_into(&mut self.entries);
+        self.first_segment.get_or_insert(segment);
+    }
+
+    /// Verifica si la _memtable_ ya debería volcarse a disco.
+    pub fn is_full(&self) -> bool {
+        self.entries.len() >= MEMTABLE_FLUSH_THRESHOLD
+    }
+
+    /// Devuelve las entradas, ordenadas por _primary key_.
+    pub fn get_entries(&self) -> impl Iterator<Item = &StorageEntry> {
+        self.entries.values()
+    }
+
+    /// Devuelve las entradas de una partición.
+    pub fn get_partition<'a>(
+        &'a self,
+        partition: &'a str,
+    ) -> impl Iterator<Item = &'a StorageEntry> {
+        self.entries
+            .range(vec![partition.to_string()]..)
+            .map(|(_, entry)| entry)
+            .take_while(move |entry| entry.partition() == partition)
+    }
+
+    /// Vacía la _memtable_, devolviendo sus entradas ordenadas.
+    pub fn drain(&mut self) -> Vec<StorageEntry> {
+        self.first_segment = None;
+        std::mem::take(&mut self.entries).into_values().collect()
+    }
+}
+
+/// Las _memtables_ de todas las réplicas abiertas en el proceso, según el directorio de la réplica.
+fn memtables() -> &'static Mutex<HashMap<String, Memtable>> {
+    static MEMTABLES: OnceLock<Mutex<HashMap<String, Memtable>>> = OnceLock::new();
+    MEMTABLES.get_or_init(Default::default)
+}
+
+/// Aplica `operation` sobre la _memtable_ de la réplica en `replica_path`.
+pub fn with_memtable<T, F>(replica_path: &str, operation: F) -> Result<T>
+where
+    F: FnOnce(&mut Memtable) -> Result<T>,
+{
+    let mut memtables = memtables()
+        .lock()
+        .map_err(|e| Error::ServerError(format!("No se pudo acceder a las memtables: {e}")))?;
+    operation(memtables.entry(replica_path.to_string()).or_default())
+}
+
+/// Reconstruye las _memtables_ de las réplicas del nodo con almacenamiento en `storage_addr`
+/// a partir de los segmentos de su _commit log_.
+///
+/// Las escrituras sobre réplicas que ya no existen se descartan.
+pub fn restore_memtables(storage_addr: &str) -> Result<()> {
+    let mut memtables = memtables()
+        .lock()
+        .map_err(|e| Error::ServerError(format!("No se pudo acceder a las memtables: {e}")))?;
+    for (replica_path, segment, operation) in CommitLog::replay(storage_addr)? {
+        if !Path::new(&replica_path).is_dir() {
+            continue;
+        }
+        let memtable = memtables.entry(replica_path).or_default();
+        match operation {
+            LoggedOperation::Write(entry) => memtable.insert(entry, segment),
+            LoggedOperation::Truncate => {
+                memtable.drain();
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Recicla los segmentos del _commit log_ del nodo con almacenamiento en `storage_addr` cuyas
+/// escrituras ya fueron volcadas a SSTables por todas las _memtables_.
+pub fn recycle_commit_log(storage_addr: &str) -> Result<()> {
+    // Se mantiene tomada la lock de las memtables para que no se registren escrituras nuevas
+    // mientras se decide qué segmentos borrar.
+    let memtables = memtables()
+        .lock()
+        .map_err(|e| Error::ServerError(format!("No se pudo acceder a las memtables: {e}")))?;
+    let replicas_prefix = format!("{storage_addr}/");
+    let oldest_needed = memtables
+        .iter()
+        .filter(|(replica_path, _)| replica_path.starts_with(&replicas_prefix))
+        .filter_map(|(_, memtable)| memtable.first_segment)
+        .min();
+    with_commit_log(storage_addr, |commit_log| commit_log.recycle(oldest_needed))
+}
+
+/// Descarta de memoria la _memtable_ de la réplica en `path`, o las de todas las réplicas
+/// dentro de ese directorio.
+pub fn forget_memtables(path: &str) {
+    if let Ok(mut memtables) = memtables().lock() {
+        let dir_prefix = format!("{path}/");
+        memtables.retain(|replica_path, _| {
+            replica_path != path && !replica_path.starts_with(&dir_prefix)
+        });
+    }
+}
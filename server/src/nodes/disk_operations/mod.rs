@@ -1,6 +1,11 @@
 //! Paquete para las operaciones de disco.
 
+pub mod commit_log;
 pub mod disk_handler;
+pub mod memtable;
 pub mod row_operations;
+pub mod secondary_index;
+pub mod sstable;
+pub mod storage_entry;
 pub mod table_operations;
 pub mod table_path;
@@ -0,0 +1,232 @@
+//! Módulo que detalla los índices secundarios de las tablas.
+
+use {
+    crate::nodes::{
+        disk_operations::{
+            storage_entry::StorageEntry, table_operations::TableOperations, table_path::TablePath,
+        },
+        table_metadata::table::Table,
+    },
+    protocol::{
+        aliases::{
+            results::Result,
+            types::{Byte, Long},
+        },
+        errors::error::Error,
+    },
+    std::{collections::BTreeSet, path::Path},
+};
+
+/// Índice secundario sobre una columna de una réplica de una tabla.
+///
+/// Se guarda en el nodo como una réplica oculta más, con el nombre `<tabla>.<índice>`, cuya
+/// _partition key_ es el valor de la columna indexada, seguido de la _primary key_ de la fila.
+/// Así, las filas con un valor dado se encuentran leyendo una sola partición del índice.
+///
+/// El índice puede quedar con entradas de filas que ya no tienen ese valor, por ejemplo si una
+/// réplica recibe una escritura más vieja que la que ya tenía, así que quien lo use debe volver
+/// a filtrar las filas que lee de la tabla.
+pub struct SecondaryIndex {
+    /// Operaciones sobre la réplica del índice.
+    table_ops: TableOperations,
+    /// Nombre de la columna indexada.
+    column: String,
+    /// Posición de la columna indexada en las filas de la tabla.
+    column_position: usize,
+    /// Posiciones de la _primary key_ en las filas de la tabla.
+    key_positions: Vec<usize>,
+}
+
+impl SecondaryIndex {
+    /// Devuelve la ruta de la réplica dada de un índice de la tabla.
+    fn path(storage_addr: &str, table: &Table, index_name: &str, node_number: Byte) -> TablePath {
+        TablePath::new(
+            storage_addr,
+            Some(table.get_keyspace().to_string()),
+            &format!("{}.{index_name}", table.get_name()),
+            table.get_keyspace(),
+            node_number,
+        )
+    }
+
+    /// Abre la réplica dada de un índice de la tabla, o devuelve `None` si todavía no existe.
+    pub fn open(
+        storage_addr: &str,
+        table: &Table,
+        index_name: &str,
+        node_number: Byte,
+    ) -> Result<Option<Self>> {
+        let path = Self::path(storage_addr, table, index_name, node_number);
+        if !Path::new(&path.full_path()).is_dir() {
+            return Ok(None);
+        }
+        let column = match table.get_index_column(index_name) {
+            Some(column) => column.to_string(),
+            None => {
+                return Err(Error::ServerError(format!(
+                    "El índice {index_name} no existe en la tabla {}",
+                    table.get_name()
+                )))
+            }
+        };
+        let column_position = match table.get_columns_names().iter().position(|c| c == &column) {
+            Some(position) => position,
+            None => {
+                return Err(Error::ServerError(format!(
+                    "La tabla {} no contiene la columna indexada {column}",
+                    table.get_name()
+                )))
+            }
+        };
+        Ok(Some(Self {
+            table_ops: TableOperations::new(path)?
+                .with_gc_grace_seconds(table.get_gc_grace_seconds()),
+            column,
+            column_position,
+            key_positions: table.get_position_of_primary_key()?,
+        }))
+    }
+
+    /// Abre la réplica dada de un índice de la tabla, creándola vacía si todavía no existe.
+    pub fn open_or_create(
+        storage_addr: &str,
+        table: &Table,
+        index_name: &str,
+        node_number: Byte,
+    ) -> Result<Self> {
+        if let Some(index) = Self::open(storage_addr, table, index_name, node_number)? {
+            return Ok(index);
+        }
+        let path = Self::path(storage_addr, table, index_name, node_number);
+        let mut columns = vec![table.get_index_column(index_name).unwrap_or("").to_string()];
+        columns.extend(table.get_primary_key_columns());
+        columns.push("row_timestamp".to_string());
+        TableOperations::create(&path, &columns)?;
+        match Self::open(storage_addr, table, index_name, node_number)? {
+            Some(index) => Ok(index),
+            None => Err(Error::ServerError(format!(
+                "No se pudo crear el índice {index_name}"
+            ))),
+        }
+    }
+
+    /// Abre, o crea si hace falta, las réplicas dadas de todos los índices de la tabla.
+    pub fn open_all(storage_addr: &str, table: &Table, node_number: Byte) -> Result<Vec<Self>> {
+        table
+            .indexes
+            .keys()
+            .map(|index_name| Self::open_or_create(storage_addr, table, index_name, node_number))
+            .collect()
+    }
+
+    /// Borra la réplica dada de un índice de la tabla, si existe.
+    pub fn drop(
+        storage_addr: &str,
+        table: &Table,
+        index_name: &str,
+        node_number: Byte,
+    ) -> Result<()> {
+        let path = Self::path(storage_addr, table, index_name, node_number);
+        if !Path::new(&path.full_path()).is_dir() {
+            return Ok(());
+        }
+        TableOperations::drop_replica(&path)
+    }
+
+    /// Obtiene el nombre de la columna indexada.
+    pub fn get_column(&self) -> &str {
+        &self.column
+    }
+
+    /// Obtiene las particiones de la tabla que tienen alguna fila con el valor dado en la
+    /// columna indexada.
+    pub fn get_partitions(&self, value: &str) -> Result<Vec<String>> {
+        let partitions: BTreeSet<String> = self
+            .table_ops
+            .read_entries(Some(value))?
+            .into_iter()
+            .filter(|entry| entry.is_live())
+            .filter_map(|entry| entry.key.get(1).cloned())
+            .collect();
+        Ok(partitions.into_iter().collect())
+    }
+
+    /// Indexa las nuevas versiones de las filas dadas.
+    ///
+    /// Si alguna tenía una versión anterior con otro valor en la columna indexada, se borra
+    /// su entrada con el timestamp de la nueva versión.
+    pub fn index_rows(&self, old_rows: &[Vec<String>], new_rows: &[Vec<String>]) -> Result<()> {
+        let mut entries = Vec::new();
+        for row in new_rows {
+            let old_row = old_rows
+                .iter()
+                .find(|old_row| self.primary_key_of(old_row) == self.primary_key_of(row));
+            if let Some(old_row) = old_row {
+                if old_row.get(self.column_position) != row.get(self.column_position) {
+                    if let Some(key) = self.key_of(old_row) {
+                        entries.push(StorageEntry::row_tombstone(key, Self::timestamp_of(row)));
+                    }
+                }
+            }
+            if let Some(entry) = self.entry_of(row) {
+                entries.push(entry);
+            }
+        }
+        if entries.is_empty() {
+            return Ok(());
+        }
+        self.table_ops.upsert_entries(entries)
+    }
+
+    /// Borra las entradas de las filas dadas con el timestamp del borrado.
+    pub fn unindex_rows(&self, rows: &[Vec<String>], timestamp: Long) -> Result<()> {
+        let keys: Vec<Vec<String>> = rows.iter().filter_map(|row| self.key_of(row)).collect();
+        if keys.is_empty() {
+            return Ok(());
+        }
+        self.table_ops.delete_rows(keys, timestamp)
+    }
+
+    /// Reemplaza todas las entradas del índice por las de las filas dadas.
+    pub fn rebuild(&self, rows: &[Vec<String>]) -> Result<()> {
+        self.table_ops.clear()?;
+        self.index_rows(&[], rows)
+    }
+
+    /// Borra todas las entradas del índice.
+    pub fn clear(&self) -> Result<()> {
+        self.table_ops.clear()
+    }
+
+    /// Arma la entrada del índice de una fila, si tiene un valor en la columna indexada.
+    fn entry_of(&self, row: &[String]) -> Option<StorageEntry> {
+        let mut index_row = self.key_of(row)?;
+        index_row.push(Self::timestamp_of(row).to_string());
+        let key_positions: Vec<usize> = (0..=self.key_positions.len()).collect();
+        Some(StorageEntry::live(index_row, &key_positions))
+    }
+
+    /// Devuelve la clave de la entrada del índice de una fila: el valor indexado seguido de la
+    /// _primary key_. Las filas sin valor en la columna indexada no se indexan.
+    fn key_of(&self, row: &[String]) -> Option<Vec<String>> {
+        let value = row
+            .get(self.column_position)
+            .filter(|value| !value.is_empty())?;
+        let mut key = vec![value.to_string()];
+        key.extend(self.primary_key_of(row));
+        Some(key)
+    }
+
+    fn primary_key_of(&self, row: &[String]) -> Vec<String> {
+        self.key_positions
+            .iter()
+            .map(|position| row.get(*position).cloned().unwrap_or_default())
+            .collect()
+    }
+
+    fn timestamp_of(row: &[String]) -> Long {
+        row.last()
+            .and_then(|timestamp| timestamp.parse::<Long>().ok())
+            .unwrap_or(0)
+    }
+}
@@ -0,0 +1,348 @@
+//! Módulo para las SSTables de las réplicas de las tablas.
+
+use {
+    crate::nodes::disk_operations::storage_entry::StorageEntry,
+    protocol::{aliases::results::Result, errors::error::Error},
+    std::{
+        fs::{read_dir, read_to_string, remove_file, rename, File},
+        io::{ErrorKind, Read, Seek, SeekFrom, Write},
+        path::Path,
+    },
+};
+
+/// El prefijo de los archivos de las SSTables.
+const SSTABLE_PREFIX: &str = "sstable_";
+/// La extensión de los archivos con las filas de una SSTable.
+const DATA_EXTENSION: &str = "data";
+/// La extensión de los archivos con el índice de particiones de una SSTable.
+const INDEX_EXTENSION: &str = "index";
+/// La extensión de los archivos que todavía se están escribiendo.
+const TMP_EXTENSION: &str = "tmp";
+/// El nombre del archivo, dentro del directorio de una réplica, que registra un reemplazo de
+/// SSTables en curso.
+const REPLACEMENT_FILE_NAME: &str = "sstables_replacement";
+
+/// Una tabla inmutable de entradas ordenadas por _primary key_, volcada desde una _memtable_.
+///
+/// Se compone de un archivo de datos, con una entrada por línea, y un índice con la posición
+/// de cada partición dentro del mismo, de forma que leer una partición no requiera leer todo el archivo.
+pub struct SSTable {
+    /// El directorio de la réplica a la que pertenece.
+    replica_path: String,
+
+    /// La generación de la SSTable. Las de mayor generación tienen los datos más nuevos.
+    generation: usize,
+}
+
+impl SSTable {
+    /// Obtiene las SSTables de una réplica, de la más vieja a la más nueva.
+    ///
+    /// Si quedó a medias un [reemplazo](SSTable::replace), primero se termina.
+    pub fn list(replica_path: &str) -> Result<Vec<Self>> {
+        Self::finish_replacement(replica_path)?;
+        let entries = read_dir(replica_path).map_err(|e| {
+            Error::ServerError(format!(
+                "No se pudo leer el directorio de la réplica {replica_path}: {e}"
+            ))
+        })?;
+        let mut generations: Vec<usize> = entries
+            .flatten()
+            .filter_map(|entry| {
+                entry
+                    .file_name()
+                    .to_string_lossy()
+                    .strip_prefix(SSTABLE_PREFIX)
+                    .and_then(|rest| rest.strip_suffix(&format!(".{DATA_EXTENSION}")))
+                    .and_then(|generation| generation.parse::<usize>().ok())
+            })
+            .collect();
+        generations.sort();
+        Ok(generations
+            .into_iter()
+            .map(|generation| Self {
+                replica_path: replica_path.to_string(),
+                generation,
+            })
+            .collect())
+    }
+
+    /// Escribe una nueva SSTable con las entradas dadas, que deben estar ordenadas por _primary key_.
+    ///
+    /// Los archivos se escriben primero con otro nombre y se fuerzan a disco antes de
+    /// renombrarlos, para que una caída a mitad de la escritura no deje una SSTable incompleta.
+    /// Al volver, la SSTable ya está en disco y se pueden reciclar los segmentos del
+    /// [commit log](super::commit_log::CommitLog) con sus escrituras.
+    pub fn write(replica_path: &str, generation: usize, entries: &[StorageEntry]) -> Result<Self> {
+        let sstable = Self {
+            replica_path: replica_path.to_string(),
+            generation,
+        };
+        let mut data = String::new();
+        let mut index: Vec<(String, usize, usize)> = Vec::new();
+        for entry in entries {
+            let offset = data.len();
+            data.push_str(&entry.to_line());
+            data.push('\n');
+            let len = data.len() - offset;
+            match index.last_mut() {
+                Some((partition, _, partition_len)) if partition == entry.partition() => {
+                    *partition_len += len;
+                }
+                _ => index.push((entry.partition().to_string(), offset, len)),
+            }
+        }
+        let index: String = index
+            .iter()
+            .map(|(partition, offset, len)| format!("{partition},{offset},{len}\n"))
+            .collect();
+
+        for (extension, content) in [(INDEX_EXTENSION, index), (DATA_EXTENSION, data)] {
+            let path = sstable.file_path(extension);
+            let tmp_path = format!("{path}.{TMP_EXTENSION}");
+            File::create(&tmp_path)
+                .and_then(|mut file| {
+                    file.write_all(content.as_bytes())?;
+                    file.sync_all()
+                })
+                .and_then(|_| rename(&tmp_path, &path))
+                .map_err(|e| {
+                    Error::ServerError(format!("No se pudo escribir la SSTable {path}: {e}"))
+                })?;
+        }
+        // Los renombres recién quedan en disco cuando se sincroniza el directorio
+        sync_dir(replica_path)?;
+        Ok(sstable)
+    }
+
+    /// Reemplaza las SSTables dadas por una nueva con las entradas dadas, de la generación
+    /// dada, o por ninguna si no hay entradas.
+    ///
+    /// Antes de escribir se registra en disco qué generaciones se descartan y cuál las
+    /// reemplaza, así si el nodo se cae a mitad del reemplazo, al volver a listar las SSTables
+    /// se termina de borrar las viejas en vez de volver a leer sus filas.
+    pub fn replace(
+        replica_path: &str,
+        obsolete: Vec<Self>,
+        generation: usize,
+        entries: &[StorageEntry],
+    ) -> Result<()> {
+        let new_generation = (!entries.is_empty()).then_some(generation);
+        let obsolete_generations: Vec<String> = obsolete
+            .iter()
+            .map(|sstable| sstable.generation.to_string())
+            .collect();
+        let replacement = format!(
+            "{}\n{}\n",
+            new_generation.map_or(String::new(), |generation| generation.to_string()),
+            obsolete_generations.join(",")
+        );
+        let path = format!("{replica_path}/{REPLACEMENT_FILE_NAME}");
+        let tmp_path = format!("{path}.{TMP_EXTENSION}");
+        File::create(&tmp_path)
+            .and_then(|mut file| {
+                file.write_all(replacement.as_bytes())?;
+                file.sync_all()
+            })
+            .and_then(|_| rename(&tmp_path, &path))
+            .map_err(|e| {
+                Error::ServerError(format!("No se pudo registrar el reemplazo {path}: {e}"))
+            })?;
+        sync_dir(replica_path)?;
+
+        if let Some(generation) = new_generation {
+            Self::write(replica_path, generation, entries)?;
+        }
+        Self::finish_replacement(replica_path)
+    }
+
+    /// Termina un [reemplazo](SSTable::replace) registrado en la réplica, si hay alguno.
+    ///
+    /// Si la SSTable nueva llegó a escribirse entera, se borran las viejas. Si no, el
+    /// reemplazo no llegó a hacerse y se conservan.
+    fn finish_replacement(replica_path: &str) -> Result<()> {
+        let path = format!("{replica_path}/{REPLACEMENT_FILE_NAME}");
+        let replacement = match read_to_string(&path) {
+            Ok(replacement) => replacement,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(e) => {
+                return Err(Error::ServerError(format!(
+                    "No se pudo leer el reemplazo {path}: {e}"
+                )))
+            }
+        };
+        let mut lines = replacement.lines();
+        let new_generation = lines.next().unwrap_or_default();
+        let new_is_written = new_generation.is_empty()
+            || Path::new(&format!(
+                "{replica_path}/{SSTABLE_PREFIX}{new_generation}.{DATA_EXTENSION}"
+            ))
+            .is_file();
+        if new_is_written {
+            let obsolete = lines
+                .next()
+                .unwrap_or_default()
+                .split(',')
+                .filter_map(|generation| generation.parse::<usize>().ok());
+            for generation in obsolete {
+                let sstable = Self {
+                    replica_path: replica_path.to_string(),
+                    generation,
+                };
+                // Si el reemplazo se cortó a mitad de los borrados, algunos ya no están
+                for extension in [DATA_EXTENSION, INDEX_EXTENSION] {
+                    let file_path = sstable.file_path(extension);
+                    match remove_file(&file_path) {
+                        Err(e) if e.kind() != ErrorKind::NotFound => {
+                            return Err(Error::ServerError(format!(
+                                "No se pudo borrar la SSTable {file_path}: {e}"
+                            )))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            sync_dir(replica_path)?;
+        }
+        remove_file(&path).map_err(|e| {
+            Error::ServerError(format!("No se pudo borrar el reemplazo {path}: {e}"))
+        })?;
+        sync_dir(replica_path)
+    }
+
+    /// Devuelve la generación de la SSTable.
+    pub fn get_generation(&self) -> usize {
+        self.generation
+    }
+
+    fn file_path(&self, extension: &str) -> String {
+        format!(
+            "{}/{SSTABLE_PREFIX}{}.{extension}",
+            self.replica_path, self.generation
+        )
+    }
+
+    /// Lee todas las entradas de la SSTable.
+    pub fn read_all(&self) -> Result<Vec<StorageEntry>> {
+        let path = self.file_path(DATA_EXTENSION);
+        let content = read_to_string(&path)
+            .map_err(|e| Error::ServerError(format!("No se pudo leer la SSTable {path}: {e}")))?;
+        content.lines().map(StorageEntry::from_line).collect()
+    }
+
+    /// Lee sólo las entradas de una partición, usando el índice para ubicarlas.
+    pub fn read_partition(&self, partition: &str) -> Result<Vec<StorageEntry>> {
+        let index_path = self.file_path(INDEX_EXTENSION);
+        let index = read_to_string(&index_path).map_err(|e| {
+            Error::ServerError(format!(
+                "No se pudo leer el índice de la SSTable {index_path}: {e}"
+            ))
+        })?;
+        // Cada línea del índice tiene la forma `<partition>,<offset>,<len>`
+        let position = index.lines().find_map(|line| {
+            let mut values = line.rsplitn(3, ',');
+            let len = values.next()?.parse::<u64>().ok()?;
+            let offset = values.next()?.parse::<u64>().ok()?;
+            (values.next()? == partition).then_some((offset, len))
+        });
+        let Some((offset, len)) = position else {
+            return Ok(Vec::new());
+        };
+
+        let data_path = self.file_path(DATA_EXTENSION);
+        let mut content = String::new();
+        File::open(&data_path)
+            .and_then(|mut file| {
+                file.seek(SeekFrom::Start(offset))?;
+                file.take(len).read_to_string(&mut content)
+            })
+            .map_err(|e| {
+                Error::ServerError(format!("No se pudo leer la SSTable {data_path}: {e}"))
+            })?;
+        content.lines().map(StorageEntry::from_line).collect()
+    }
+}
+
+/// Fuerza a disco las entradas del directorio dado, como los archivos creados, renombrados o
+/// borrados en él.
+fn sync_dir(dir_path: &str) -> Result<()> {
+    File::open(dir_path)
+        .and_then(|dir| dir.sync_all())
+        .map_err(|e| {
+            Error::ServerError(format!(
+                "No se pudo sincronizar el directorio {dir_path}: {e}"
+            ))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        std::fs::{create_dir_all, remove_dir_all, write},
+    };
+
+    fn replica_path(name: &str) -> Result<String> {
+        let path = std::env::temp_dir().join(format!("sstable_{name}_{}", std::process::id()));
+        let _ = remove_dir_all(&path);
+        create_dir_all(&path).map_err(|e| Error::ServerError(e.to_string()))?;
+        Ok(path.to_string_lossy().to_string())
+    }
+
+    fn entry(partition: &str) -> StorageEntry {
+        StorageEntry::live(vec![partition.to_string(), "1".to_string()], &[0])
+    }
+
+    fn generations(replica_path: &str) -> Result<Vec<usize>> {
+        Ok(SSTable::list(replica_path)?
+            .iter()
+            .map(SSTable::get_generation)
+            .collect())
+    }
+
+    #[test]
+    fn test_1_un_reemplazo_cortado_despues_de_escribir_la_nueva_borra_las_viejas() -> Result<()> {
+        let replica_path = replica_path("reemplazo_escrito")?;
+        SSTable::write(&replica_path, 1, &[entry("a")])?;
+        SSTable::write(&replica_path, 2, &[entry("b")])?;
+        // Como si el nodo se cayera tras escribir la SSTable nueva, antes de borrar las viejas
+        SSTable::write(&replica_path, 3, &[entry("c")])?;
+        write(
+            format!("{replica_path}/{REPLACEMENT_FILE_NAME}"),
+            "3\n1,2\n",
+        )
+        .map_err(|e| Error::ServerError(e.to_string()))?;
+
+        assert_eq!(generations(&replica_path)?, vec![3]);
+        let _ = remove_dir_all(&replica_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_un_reemplazo_cortado_antes_de_escribir_la_nueva_conserva_las_viejas() -> Result<()> {
+        let replica_path = replica_path("reemplazo_sin_escribir")?;
+        SSTable::write(&replica_path, 1, &[entry("a")])?;
+        SSTable::write(&replica_path, 2, &[entry("b")])?;
+        write(
+            format!("{replica_path}/{REPLACEMENT_FILE_NAME}"),
+            "3\n1,2\n",
+        )
+        .map_err(|e| Error::ServerError(e.to_string()))?;
+
+        assert_eq!(generations(&replica_path)?, vec![1, 2]);
+        let _ = remove_dir_all(&replica_path);
+        Ok(())
+    }
+
+    #[test]
+    fn test_3_reemplazar_sin_entradas_borra_todo() -> Result<()> {
+        let replica_path = replica_path("reemplazo_vacio")?;
+        SSTable::write(&replica_path, 1, &[entry("a")])?;
+        let obsolete = SSTable::list(&replica_path)?;
+
+        SSTable::replace(&replica_path, obsolete, 2, &[])?;
+
+        assert!(generations(&replica_path)?.is_empty());
+        let _ = remove_dir_all(&replica_path);
+        Ok(())
+    }
+}
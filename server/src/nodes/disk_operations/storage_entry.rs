@@ -0,0 +1,400 @@
+//! Módulo para las entradas del motor de almacenamiento.
+
+use {
+    chrono::Utc,
+    protocol::{
+        aliases::{results::Result, types::Long},
+        errors::error::Error,
+    },
+    std::{cmp::Ordering, collections::BTreeMap},
+};
+
+/// La marca de una entrada con una fila vigente y sin _tombstones_.
+const LIVE_MARK: &str = "U";
+/// La marca con la que se guardaban las filas borradas antes de que existieran los _tombstones_.
+const LEGACY_DELETED_MARK: &str = "D";
+/// La marca de una entrada con _tombstones_.
+const TOMBSTONE_MARK: &str = "B";
+/// La marca de una entrada con vencimientos, y quizás también _tombstones_.
+const EXPIRING_MARK: &str = "E";
+
+/// La cantidad de microsegundos en un segundo, la unidad de los timestamps de las escrituras.
+const MICROS_PER_SECOND: Long = 1_000_000;
+
+/// Las entradas de una réplica, ordenadas por _primary key_.
+pub type SortedEntries = BTreeMap<Vec<String>, StorageEntry>;
+
+/// Los _tombstones_ de las celdas de una fila: el timestamp del borrado según el nombre de la columna.
+pub type CellTombstones = BTreeMap<String, Long>;
+
+/// Los vencimientos de las celdas de una fila: el momento _(en segundos desde la época UNIX)_
+/// en que vence cada una, según el nombre de la columna.
+pub type CellExpirations = BTreeMap<String, Long>;
+
+/// Lo que se sabe de una fila, identificada por su _primary key_: su versión más reciente
+/// y los borrados que la afectan.
+///
+/// Es la unidad que se guarda tanto en la _memtable_ como en el _commit log_ y las SSTables.
+///
+/// Si la clave es sólo el valor de la _partition key_, el _tombstone_ de la fila borra toda
+/// la partición.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StorageEntry {
+    /// Los valores de la _primary key_ de la fila, empezando por el de la _partition key_.
+    pub key: Vec<String>,
+
+    /// La fila completa, con la columna del timestamp al final.
+    ///
+    /// Está vacía si la entrada sólo registra borrados.
+    pub row: Vec<String>,
+
+    /// El timestamp del borrado de la fila, si fue borrada.
+    pub deleted_at: Option<Long>,
+
+    /// Los borrados de celdas individuales de la fila.
+    pub deleted_cells: CellTombstones,
+
+    /// El momento _(en segundos desde la época UNIX)_ en que vence la fila entera, si se
+    /// escribió con TTL.
+    pub expires_at: Option<Long>,
+
+    /// Los vencimientos de celdas individuales de la fila.
+    pub expiring_cells: CellExpirations,
+}
+
+impl StorageEntry {
+    /// Crea una entrada para una fila vigente.
+    pub fn live(row: Vec<String>, key_positions: &[usize]) -> Self {
+        Self {
+            key: key_positions
+                .iter()
+                .map(|position| row.get(*position).cloned().unwrap_or_default())
+                .collect(),
+            row,
+            deleted_at: None,
+            deleted_cells: CellTombstones::new(),
+            expires_at: None,
+            expiring_cells: CellExpirations::new(),
+        }
+    }
+
+    /// Crea un _tombstone_ que borra la fila, o toda la partición si `key` es sólo el valor
+    /// de la _partition key_.
+    pub fn row_tombstone(key: Vec<String>, timestamp: Long) -> Self {
+        Self {
+            key,
+            row: Vec::new(),
+            deleted_at: Some(timestamp),
+            deleted_cells: CellTombstones::new(),
+            expires_at: None,
+            expiring_cells: CellExpirations::new(),
+        }
+    }
+
+    /// Crea _tombstones_ que borran las celdas de las columnas dadas de una fila.
+    pub fn cell_tombstones(key: Vec<String>, columns: &[String], timestamp: Long) -> Self {
+        Self {
+            key,
+            row: Vec::new(),
+            deleted_at: None,
+            deleted_cells: columns
+                .iter()
+                .map(|column| (column.to_string(), timestamp))
+                .collect(),
+            expires_at: None,
+            expiring_cells: CellExpirations::new(),
+        }
+    }
+
+    /// Devuelve el valor de la _partition key_ de la fila.
+    pub fn partition(&self) -> &str {
+        self.key.first().map(|value| value.as_str()).unwrap_or("")
+    }
+
+    /// Verifica si la entrada tiene una fila vigente.
+    pub fn is_live(&self) -> bool {
+        !self.row.is_empty()
+    }
+
+    /// Verifica si la entrada registra algún borrado.
+    pub fn has_tombstones(&self) -> bool {
+        self.deleted_at.is_some() || !self.deleted_cells.is_empty()
+    }
+
+    /// Verifica si la fila, o alguna de sus celdas, vence.
+    pub fn has_expirations(&self) -> bool {
+        self.expires_at.is_some() || !self.expiring_cells.is_empty()
+    }
+
+    /// Devuelve el timestamp de la fila, o 0 si no tiene uno válido.
+    pub fn timestamp(&self) -> Long {
+        self.row
+            .last()
+            .and_then(|timestamp| timestamp.parse::<Long>().ok())
+            .unwrap_or(0)
+    }
+
+    /// Combina esta entrada con una versión más nueva de la misma fila.
+    ///
+    /// Queda la fila de mayor timestamp con sus vencimientos, junto a los borrados más recientes
+    /// de ambas. A igual timestamp queda la que vence más tarde, ya que cada réplica calcula los
+    /// vencimientos por su cuenta, y si vencen igual, la más nueva.
+    pub fn merge(mut self, newer: Self) -> Self {
+        let newer_wins = newer.is_live()
+            && (!self.is_live()
+                || match newer.timestamp().cmp(&self.timestamp()) {
+                    Ordering::Greater => true,
+                    Ordering::Less => false,
+                    Ordering::Equal => newer.expiration_order() >= self.expiration_order(),
+                });
+        if newer_wins {
+            self.row = newer.row;
+            self.expires_at = newer.expires_at;
+            self.expiring_cells = newer.expiring_cells;
+        }
+        self.deleted_at = self.deleted_at.max(newer.deleted_at);
+        for (column, timestamp) in newer.deleted_cells {
+            let deleted_at = self.deleted_cells.entry(column).or_insert(timestamp);
+            *deleted_at = (*deleted_at).max(timestamp);
+        }
+        self
+    }
+
+    /// Convierte en _tombstones_ a la fila o a las celdas que ya vencieron en el momento `now`
+    /// _(en segundos desde la época UNIX)_.
+    ///
+    /// Los _tombstones_ llevan el timestamp de la fila, por lo que no ocultan a las escrituras
+    /// posteriores.
+    pub fn expire(&mut self, now: Long) {
+        if !self.is_live() {
+            self.expires_at = None;
+            self.expiring_cells.clear();
+            return;
+        }
+        let row_timestamp = self.timestamp();
+        if self.expires_at.is_some_and(|expires_at| expires_at <= now) {
+            self.deleted_at = self.deleted_at.max(Some(row_timestamp));
+            self.expires_at = None;
+            self.expiring_cells.clear();
+            return;
+        }
+        let expired: Vec<String> = self
+            .expiring_cells
+            .iter()
+            .filter(|(_, expires_at)| **expires_at <= now)
+            .map(|(column, _)| column.to_string())
+            .collect();
+        for column in expired {
+            self.expiring_cells.remove(&column);
+            let deleted_at = self.deleted_cells.entry(column).or_insert(row_timestamp);
+            *deleted_at = (*deleted_at).max(row_timestamp);
+        }
+    }
+
+    /// El orden en que se comparan los vencimientos de dos versiones con el mismo timestamp,
+    /// de la que vence antes a la que vence después.
+    fn expiration_order(&self) -> (Long, Vec<Long>) {
+        (
+            self.expires_at.unwrap_or(Long::MAX),
+            self.expiring_cells.values().copied().collect(),
+        )
+    }
+
+    /// Agrega la entrada a un conjunto ordenado, combinándola con la versión que ya había.
+    pub fn merge_into(self, entries: &mut SortedEntries) {
+        let entry = match entries.remove(&self.key) {
+            Some(older) => older.merge(self),
+            None => self,
+        };
+        entries.insert(entry.key.clone(), entry);
+    }
+
+    /// Serializa la entrada como una línea.
+    ///
+    /// Las filas sin borrados ni vencimientos tienen la forma `U,<n>,<key_1>,...,<key_n>,<fila>`,
+    /// las que tienen borrados pero no vencimientos
+    /// `B,<n>,<key_1>,...,<key_n>,<borrado>,<m>,<columna_1>,<borrado_1>,...,<columna_m>,<borrado_m>,<fila>`,
+    /// donde `<borrado>` está vacío si la fila no fue borrada, y el resto
+    /// `E,<n>,<key_1>,...,<key_n>,<borrado>,<m>,...,<vence>,<k>,<columna_1>,<vence_1>,...,<columna_k>,<vence_k>,<fila>`,
+    /// donde `<vence>` está vacío si la fila entera no vence.
+    pub fn to_line(&self) -> String {
+        let mark = if self.has_expirations() {
+            EXPIRING_MARK
+        } else if self.has_tombstones() {
+            TOMBSTONE_MARK
+        } else {
+            LIVE_MARK
+        };
+        let mut values = vec![mark.to_string(), self.key.len().to_string()];
+        values.extend(self.key.iter().cloned());
+        if mark != LIVE_MARK {
+            values.push(
+                self.deleted_at
+                    .map(|timestamp| timestamp.to_string())
+                    .unwrap_or_default(),
+            );
+            values.push(self.deleted_cells.len().to_string());
+            for (column, timestamp) in &self.deleted_cells {
+                values.push(column.to_string());
+                values.push(timestamp.to_string());
+            }
+        }
+        if mark == EXPIRING_MARK {
+            values.push(
+                self.expires_at
+                    .map(|expires_at| expires_at.to_string())
+                    .unwrap_or_default(),
+            );
+            values.push(self.expiring_cells.len().to_string());
+            for (column, expires_at) in &self.expiring_cells {
+                values.push(column.to_string());
+                values.push(expires_at.to_string());
+            }
+        }
+        values.extend(self.row.iter().cloned());
+        values.join(",")
+    }
+
+    /// Parsea una entrada serializada con [to_line](Self::to_line).
+    pub fn from_line(line: &str) -> Result<Self> {
+        let values: Vec<&str> = line.trim_end_matches(['\r', '\n']).split(',').collect();
+        let invalid = || Error::ServerError(format!("La entrada `{line}` no es válida"));
+        let parse_len = |position: usize| {
+            values
+                .get(position)
+                .and_then(|len| len.parse::<usize>().ok())
+                .ok_or_else(invalid)
+        };
+        let mark = *values.first().ok_or_else(invalid)?;
+        let key_len = parse_len(1)?;
+        let key_end = 2 + key_len;
+        if values.len() < key_end {
+            return Err(invalid());
+        }
+        let mut entry = Self {
+            key: values[2..key_end].iter().map(|v| v.to_string()).collect(),
+            row: Vec::new(),
+            deleted_at: None,
+            deleted_cells: CellTombstones::new(),
+            expires_at: None,
+            expiring_cells: CellExpirations::new(),
+        };
+        let parse_optional = |position: usize| -> Result<Option<Long>> {
+            match *values.get(position).ok_or_else(invalid)? {
+                "" => Ok(None),
+                value => value.parse::<Long>().map(Some).map_err(|_| invalid()),
+            }
+        };
+        let parse_cells = |start: usize, cells: &mut BTreeMap<String, Long>| -> Result<usize> {
+            let cells_start = start + 2;
+            let cells_end = cells_start + 2 * parse_len(start + 1)?;
+            if values.len() < cells_end {
+                return Err(invalid());
+            }
+            for cell in values[cells_start..cells_end].chunks(2) {
+                let value = cell[1].parse::<Long>().map_err(|_| invalid())?;
+                cells.insert(cell[0].to_string(), value);
+            }
+            Ok(cells_end)
+        };
+        let row_start = match mark {
+            LIVE_MARK => key_end,
+            LEGACY_DELETED_MARK => {
+                // Las filas borradas se guardaban enteras, con el timestamp de su última escritura
+                entry.deleted_at = values.last().and_then(|ts| ts.parse::<Long>().ok());
+                return Ok(entry);
+            }
+            TOMBSTONE_MARK => {
+                entry.deleted_at = parse_optional(key_end)?;
+                parse_cells(key_end, &mut entry.deleted_cells)?
+            }
+            EXPIRING_MARK => {
+                entry.deleted_at = parse_optional(key_end)?;
+                let expirations_start = parse_cells(key_end, &mut entry.deleted_cells)?;
+                entry.expires_at = parse_optional(expirations_start)?;
+                parse_cells(expirations_start, &mut entry.expiring_cells)?
+            }
+            _ => return Err(invalid()),
+        };
+        entry.row = values[row_start..].iter().map(|v| v.to_string()).collect();
+        Ok(entry)
+    }
+}
+
+/// Aplica los borrados de las entradas, que deben estar ordenadas, sobre las filas que ocultan.
+///
+/// Las filas borradas quedan vacías y las celdas borradas quedan sin valor, pero los borrados
+/// se conservan para que se sigan propagando entre réplicas. Un borrado prevalece sobre una
+/// escritura con el mismo timestamp.
+pub fn apply_tombstones(entries: SortedEntries, columns: &[String]) -> SortedEntries {
+    let mut partition_tombstone: Option<(String, Long)> = None;
+    entries
+        .into_iter()
+        .map(|(key, mut entry)| {
+            if key.len() == 1 {
+                partition_tombstone = entry
+                    .deleted_at
+                    .map(|timestamp| (entry.partition().to_string(), timestamp));
+            }
+            let partition_deleted_at = match &partition_tombstone {
+                Some((partition, timestamp)) if partition == entry.partition() => Some(*timestamp),
+                _ => None,
+            };
+            if entry.is_live() {
+                let row_timestamp = entry.timestamp();
+                let deleted_at = entry.deleted_at.max(partition_deleted_at);
+                if deleted_at.is_some_and(|timestamp| timestamp >= row_timestamp) {
+                    entry.row.clear();
+                    entry.expires_at = None;
+                    entry.expiring_cells.clear();
+                } else {
+                    for (column, timestamp) in &entry.deleted_cells {
+                        let position = columns.iter().position(|col| col == column);
+                        if let Some(cell) = position.and_then(|pos| entry.row.get_mut(pos)) {
+                            if *timestamp >= row_timestamp {
+                                cell.clear();
+                            }
+                        }
+                    }
+                }
+            }
+            (key, entry)
+        })
+        .collect()
+}
+
+/// Convierte en _tombstones_ las filas y celdas de las entradas que ya vencieron en el momento
+/// `now` _(en segundos desde la época UNIX)_.
+pub fn expire_entries(entries: SortedEntries, now: Long) -> SortedEntries {
+    entries
+        .into_iter()
+        .map(|(key, mut entry)| {
+            entry.expire(now);
+            (key, entry)
+        })
+        .collect()
+}
+
+/// Descarta los borrados con más de `gc_grace_seconds` de antigüedad, junto a las entradas
+/// que ya no tengan ni una fila ni borrados.
+///
+/// Se asume que las entradas ya pasaron por [apply_tombstones], por lo que los borrados
+/// descartados ya no ocultan ninguna fila.
+pub fn purge_tombstones(entries: SortedEntries, gc_grace_seconds: Long) -> SortedEntries {
+    let purge_before = Utc::now().timestamp_micros() - gc_grace_seconds * MICROS_PER_SECOND;
+    entries
+        .into_iter()
+        .filter_map(|(key, mut entry)| {
+            if entry
+                .deleted_at
+                .is_some_and(|timestamp| timestamp < purge_before)
+            {
+                entry.deleted_at = None;
+            }
+            entry
+                .deleted_cells
+                .retain(|_, timestamp| *timestamp >= purge_before);
+            (entry.is_live() || entry.has_tombstones()).then_some((key, entry))
+        })
+        .collect()
+}
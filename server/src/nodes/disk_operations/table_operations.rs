@@ -1,40 +1,84 @@
 //! Módulo que detalla las operaciones de tablas
 
 use {
-    crate::nodes::disk_operations::table_path::TablePath,
-    protocol::{aliases::results::Result, errors::error::Error},
+    crate::nodes::disk_operations::{
+        commit_log::{with_commit_log, LoggedOperation},
+        memtable::{forget_memtables, recycle_commit_log, with_memtable},
+        sstable::SSTable,
+        storage_entry::{
+            apply_tombstones, expire_entries, purge_tombstones, SortedEntries, StorageEntry,
+        },
+        table_path::TablePath,
+    },
+    crate::nodes::table_metadata::table::DEFAULT_GC_GRACE_SECONDS,
+    chrono::Utc,
+    protocol::{
+        aliases::{results::Result, types::Long},
+        errors::error::Error,
+    },
     std::{
-        fs::OpenOptions,
-        io::{BufRead, BufReader},
+        fs::{create_dir, read_to_string, remove_dir_all, remove_file, write},
+        path::Path,
     },
 };
 
+/// El nombre del archivo, dentro del directorio de una réplica, con las columnas de la tabla.
+const COLUMNS_FILE_NAME: &str = "columns.csv";
+/// La cantidad máxima de SSTables de una réplica antes de compactarlas en una sola.
+const MAX_SSTABLES: usize = 4;
+
 /// Estructura para manejar operaciones comunes sobre tablas
+///
+/// Cada réplica de una tabla se guarda como un motor LSM: las escrituras se agregan al final
+/// del [commit log](super::commit_log::CommitLog) del nodo y a una _memtable_ en memoria, que al llenarse se vuelca a una [SSTable]
+/// inmutable. Las lecturas combinan todas esas fuentes, quedándose con la versión más
+/// reciente de cada fila según su timestamp.
+///
+/// Los borrados se guardan como _tombstones_ con timestamp, que ocultan a las versiones
+/// anteriores de las filas, particiones o celdas que borran. Se descartan al compactar
+/// una vez pasados `gc_grace_seconds`.
+///
+/// Las filas y celdas escritas con TTL se dejan de leer al vencer, y al compactar se
+/// convierten en _tombstones_.
 pub struct TableOperations {
     /// Ruta de la tabla
     pub path: TablePath,
     /// Columnas de la tabla
     pub columns: Vec<String>,
+    /// Segundos que se conservan los _tombstones_ antes de poder descartarlos al compactar.
+    gc_grace_seconds: Long,
 }
 
 impl TableOperations {
+    /// Crea el directorio de una réplica nueva, con las columnas dadas y sin filas.
+    pub fn create(path: &TablePath, columns: &[String]) -> Result<()> {
+        let replica_path = path.full_path();
+        create_dir(&replica_path).map_err(|e| {
+            Error::ServerError(format!(
+                "No se pudo crear la tabla con dirección `{replica_path}`: {e}"
+            ))
+        })?;
+        Self::write_columns(&replica_path, columns)
+    }
+
+    /// Borra el directorio de una réplica junto a todas sus filas.
+    pub fn drop_replica(path: &TablePath) -> Result<()> {
+        let replica_path = path.full_path();
+        // Se registra el borrado para que, si se vuelve a crear la tabla, al reiniciar el nodo
+        // no reaparezcan las escrituras viejas que sigan en el commit log.
+        with_commit_log(&path.storage_addr, |commit_log| {
+            commit_log.append(&replica_path, &[LoggedOperation::Truncate])
+        })?;
+        forget_memtables(&replica_path);
+        remove_dir_all(&replica_path).map_err(|e| Error::ServerError(e.to_string()))
+    }
+
     /// Crea una nueva instancia de `TableOperations`.
     pub fn new(path: TablePath) -> Result<Self> {
-        let file = OpenOptions::new()
-            .read(true)
-            .open(path.full_path())
-            .map_err(|_| {
-                Error::ServerError(format!(
-                    "No se pudo abrir {} para lectura",
-                    path.full_path()
-                ))
-            })?;
-
-        let mut reader = BufReader::new(&file);
-        let mut header = String::new();
-        reader.read_line(&mut header).map_err(|_| {
+        let columns_path = format!("{}/{COLUMNS_FILE_NAME}", path.full_path());
+        let header = read_to_string(&columns_path).map_err(|_| {
             Error::ServerError(format!(
-                "No se pudo abrir {} para escritura",
+                "No se pudo abrir {} para lectura",
                 path.full_path()
             ))
         })?;
@@ -48,7 +92,54 @@ impl TableOperations {
 
         let columns: Vec<String> = header.trim().split(',').map(|s| s.to_string()).collect();
 
-        Ok(Self { path, columns })
+        Ok(Self {
+            path,
+            columns,
+            gc_grace_seconds: DEFAULT_GC_GRACE_SECONDS,
+        })
+    }
+
+    /// Configura los segundos que se conservan los _tombstones_ antes de poder descartarlos.
+    pub fn with_gc_grace_seconds(mut self, gc_grace_seconds: Long) -> Self {
+        self.gc_grace_seconds = gc_grace_seconds;
+        self
+    }
+
+    /// Migra una réplica guardada como un único archivo CSV al formato de SSTables.
+    ///
+    /// Devuelve `false` si la réplica no tenía un CSV para migrar.
+    pub fn migrate_from_csv(path: TablePath, key_positions: &[usize]) -> Result<bool> {
+        let csv_path = path.legacy_csv_path();
+        if !Path::new(&csv_path).is_file() {
+            return Ok(false);
+        }
+        let content = read_to_string(&csv_path)
+            .map_err(|e| Error::ServerError(format!("No se pudo leer la tabla {csv_path}: {e}")))?;
+        let mut lines = content.lines();
+        let columns: Vec<String> = match lines.next() {
+            Some(header) if !header.trim().is_empty() => {
+                header.trim().split(',').map(|s| s.to_string()).collect()
+            }
+            _ => {
+                return Err(Error::ServerError(format!(
+                    "No se pudo leer la tabla con ruta {csv_path}"
+                )))
+            }
+        };
+        let rows: Vec<Vec<String>> = lines
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.trim().split(',').map(|s| s.to_string()).collect())
+            .collect();
+
+        if !Path::new(&path.full_path()).is_dir() {
+            Self::create(&path, &columns)?;
+        }
+        let table_ops = Self::new(path)?;
+        table_ops.replace_rows(rows, key_positions)?;
+        remove_file(&csv_path).map_err(|e| {
+            Error::ServerError(format!("No se pudo borrar la tabla {csv_path}: {e}"))
+        })?;
+        Ok(true)
     }
 
     /// Valida que las columnas existan en la tabla.
@@ -70,48 +161,287 @@ impl TableOperations {
         self.columns.pop();
     }
 
-    /// Lee las filas de la tabla, sin contar la columna extra del timestamp.
+    /// Lee las filas de la tabla, ordenadas por _primary key_.
+    ///
+    /// Si `without_timestamp` es verdadero, no se incluye la columna extra del timestamp.
     pub fn read_rows(&self, without_timestamp: bool) -> Result<Vec<Vec<String>>> {
-        let file = OpenOptions::new()
-            .read(true)
-            .open(self.path.full_path())
-            .map_err(|_| {
-                Error::ServerError(format!(
-                    "No se pudo abrir {} para lectura",
-                    self.path.full_path()
-                ))
-            })?;
+        let entries = self.resolved_entries(None)?;
+        Ok(Self::live_rows(entries, without_timestamp))
+    }
+
+    /// Lee sólo las filas de una partición, usando los índices de las SSTables.
+    pub fn read_partition_rows(
+        &self,
+        partition: &str,
+        without_timestamp: bool,
+    ) -> Result<Vec<Vec<String>>> {
+        let entries = self.resolved_entries(Some(partition))?;
+        Ok(Self::live_rows(entries, without_timestamp))
+    }
+
+    /// Lee las entradas de la tabla, o de una sola partición, con los borrados ya aplicados
+    /// sobre las filas pero sin descartarlos.
+    pub fn read_entries(&self, partition: Option<&str>) -> Result<Vec<StorageEntry>> {
+        Ok(self
+            .resolved_entries(partition)?
+            .into_values()
+            .filter(|entry| entry.is_live() || entry.has_tombstones())
+            .collect())
+    }
 
-        let reader = BufReader::new(file);
-        let mut rows = Vec::new();
+    /// Inserta o reemplaza filas, identificadas por los valores en `key_positions`.
+    ///
+    /// Si ya había una versión de una fila con un timestamp más nuevo, se conserva esa.
+    pub fn upsert_rows(&self, rows: Vec<Vec<String>>, key_positions: &[usize]) -> Result<()> {
+        let entries = rows
+            .into_iter()
+            .map(|row| StorageEntry::live(row, key_positions))
+            .collect();
+        self.apply(entries)
+    }
+
+    /// Inserta o reemplaza entradas ya armadas, con sus vencimientos.
+    ///
+    /// Si ya había una versión de una fila con un timestamp más nuevo, se conserva esa.
+    pub fn upsert_entries(&self, entries: Vec<StorageEntry>) -> Result<()> {
+        self.apply(entries)
+    }
+
+    /// Borra las filas con las _primary keys_ dadas, dejando un _tombstone_ con el timestamp
+    /// del borrado. Si una clave es sólo el valor de la _partition key_, se borra toda la partición.
+    pub fn delete_rows(&self, keys: Vec<Vec<String>>, timestamp: Long) -> Result<()> {
+        let entries = keys
+            .into_iter()
+            .map(|key| StorageEntry::row_tombstone(key, timestamp))
+            .collect();
+        self.apply(entries)
+    }
 
-        for line in reader.lines().skip(1) {
-            let line = line.map_err(|e| Error::ServerError(e.to_string()))?;
-            if !line.trim().is_empty() {
-                let mut line_separated: Vec<String> =
-                    line.trim().split(',').map(|s| s.to_string()).collect();
+    /// Borra las celdas de las columnas dadas de las filas con las _primary keys_ dadas,
+    /// dejando un _tombstone_ por celda con el timestamp del borrado.
+    pub fn delete_cells(
+        &self,
+        keys: Vec<Vec<String>>,
+        columns: &[String],
+        timestamp: Long,
+    ) -> Result<()> {
+        let entries = keys
+            .into_iter()
+            .map(|key| StorageEntry::cell_tombstones(key, columns, timestamp))
+            .collect();
+        self.apply(entries)
+    }
+
+    /// Combina con las de la tabla entradas recibidas de otra réplica, con sus filas y borrados.
+    pub fn repair_entries(&self, entries: Vec<StorageEntry>) -> Result<()> {
+        self.apply(entries)
+    }
+
+    /// Reemplaza todas las filas de la tabla por las dadas.
+    ///
+    /// **PRECAUCIÓN**: El contenido previo de la tabla es irrecuperable luego de su uso.
+    pub fn replace_rows(&self, rows: Vec<Vec<String>>, key_positions: &[usize]) -> Result<()> {
+        let entries = rows
+            .into_iter()
+            .map(|row| StorageEntry::live(row, key_positions))
+            .collect();
+        self.replace_entries(entries)
+    }
+
+    /// Reescribe las columnas y todas las filas de la tabla según la modificación dada.
+    ///
+    /// La modificación no debe agregar, quitar ni reordenar filas, ni cambiar su _primary key_.
+    /// Los borrados se conservan, salvo los de celdas de columnas que ya no existan.
+    pub fn rewrite<F>(&mut self, modify: F) -> Result<()>
+    where
+        F: Fn(&mut Vec<String>, &mut Vec<Vec<String>>),
+    {
+        let (mut live, tombstones): (Vec<StorageEntry>, Vec<StorageEntry>) = self
+            .resolved_entries(None)?
+            .into_values()
+            .partition(|entry| entry.is_live());
+        let mut rows: Vec<Vec<String>> = live
+            .iter_mut()
+            .map(|entry| std::mem::take(&mut entry.row))
+            .collect();
+        modify(&mut self.columns, &mut rows);
+        Self::write_columns(&self.path.full_path(), &self.columns)?;
+
+        let mut entries = tombstones;
+        for (mut entry, row) in live.into_iter().zip(rows) {
+            entry.row = row;
+            entries.push(entry);
+        }
+        for entry in entries.iter_mut() {
+            entry
+                .deleted_cells
+                .retain(|column, _| self.columns.contains(column));
+            entry
+                .expiring_cells
+                .retain(|column, _| self.columns.contains(column));
+        }
+        self.replace_entries(entries)
+    }
+
+    /// Borra todas las filas de la tabla, conservando sus columnas.
+    pub fn clear(&self) -> Result<()> {
+        self.replace_entries(Vec::new())
+    }
+
+    /// Compacta todas las SSTables de la tabla en una sola, descartando las versiones viejas
+    /// de las filas, las filas borradas y los borrados vencidos.
+    pub fn compact(&self) -> Result<()> {
+        let replica_path = self.path.full_path();
+        with_memtable(&replica_path, |_| self.compact_sstables())
+    }
+
+    fn write_columns(replica_path: &str, columns: &[String]) -> Result<()> {
+        let columns_path = format!("{replica_path}/{COLUMNS_FILE_NAME}");
+        write(&columns_path, format!("{}\n", columns.join(","))).map_err(|e| {
+            Error::ServerError(format!(
+                "No se pudo escribir las columnas en la tabla con dirección `{replica_path}`: {e}"
+            ))
+        })
+    }
+
+    fn live_rows(entries: SortedEntries, without_timestamp: bool) -> Vec<Vec<String>> {
+        entries
+            .into_values()
+            .filter(|entry| entry.is_live())
+            .map(|mut entry| {
                 if without_timestamp {
-                    line_separated.pop(); // saco la columna del timestamp
+                    entry.row.pop(); // saco la columna del timestamp
+                }
+                entry.row
+            })
+            .collect()
+    }
+
+    /// Combina las entradas de las SSTables y la _memtable_, de la más vieja a la más nueva.
+    fn merged_entries(&self, partition: Option<&str>) -> Result<SortedEntries> {
+        let replica_path = self.path.full_path();
+        with_memtable(&replica_path, |memtable| {
+            let mut entries = SortedEntries::new();
+            for sstable in SSTable::list(&replica_path)? {
+                let sstable_entries = match partition {
+                    Some(partition) => sstable.read_partition(partition)?,
+                    None => sstable.read_all()?,
+                };
+                for entry in sstable_entries {
+                    entry.merge_into(&mut entries);
                 }
-                rows.push(line_separated);
             }
+            let memtable_entries: Vec<&StorageEntry> = match partition {
+                Some(partition) => memtable.get_partition(partition).collect(),
+                None => memtable.get_entries().collect(),
+            };
+            for entry in memtable_entries {
+                entry.clone().merge_into(&mut entries);
+            }
+            Ok(entries)
+        })
+    }
+
+    /// Combina las entradas de la réplica y aplica sus borrados y vencimientos sobre las filas.
+    fn resolved_entries(&self, partition: Option<&str>) -> Result<SortedEntries> {
+        Ok(apply_tombstones(
+            expire_entries(self.merged_entries(partition)?, Utc::now().timestamp()),
+            &self.columns,
+        ))
+    }
+
+    /// Escribe entradas nuevas en el _commit log_ y la _memtable_, volcando esta última a
+    /// una SSTable si se llenó.
+    fn apply(&self, entries: Vec<StorageEntry>) -> Result<()> {
+        if entries.is_empty() {
+            return Ok(());
         }
+        let replica_path = self.path.full_path();
+        let flushed = with_memtable(&replica_path, |memtable| {
+            let operations: Vec<LoggedOperation> =
+                entries.into_iter().map(LoggedOperation::Write).collect();
+            let segment = with_commit_log(&self.path.storage_addr, |commit_log| {
+                commit_log.append(&replica_path, &operations)
+            })?;
+            for operation in operations {
+                if let LoggedOperation::Write(entry) = operation {
+                    memtable.insert(entry, segment);
+                }
+            }
+            if !memtable.is_full() {
+                return Ok(false);
+            }
 
-        Ok(rows)
+            let sstables = SSTable::list(&replica_path)?;
+            SSTable::write(
+                &replica_path,
+                Self::next_generation(&sstables),
+                &memtable.drain(),
+            )?;
+            if sstables.len() >= MAX_SSTABLES {
+                self.compact_sstables()?;
+            }
+            Ok(true)
+        })?;
+        // Las escrituras volcadas ya están en disco en la SSTable, así que se pueden borrar
+        // los segmentos que las registraban
+        if flushed {
+            recycle_commit_log(&self.path.storage_addr)?;
+        }
+        Ok(())
     }
 
-    /// Escribe las filas en la tabla.
-    pub fn write_rows(&self, rows: &[Vec<String>]) -> Result<()> {
-        let mut content = self.columns.join(",");
-        content.push('\n');
+    /// Reemplaza todo el contenido de la réplica por una única SSTable con las entradas dadas.
+    fn replace_entries(&self, entries: Vec<StorageEntry>) -> Result<()> {
+        let replica_path = self.path.full_path();
+        with_memtable(&replica_path, |memtable| {
+            with_commit_log(&self.path.storage_addr, |commit_log| {
+                commit_log.append(&replica_path, &[LoggedOperation::Truncate])
+            })?;
+            let mut sorted = SortedEntries::new();
+            for entry in entries {
+                entry.merge_into(&mut sorted);
+            }
+            let sstables = SSTable::list(&replica_path)?;
+            let generation = Self::next_generation(&sstables);
+            let sorted: Vec<StorageEntry> = sorted.into_values().collect();
+            SSTable::replace(&replica_path, sstables, generation, &sorted)?;
+            memtable.drain();
+            Ok(())
+        })?;
+        recycle_commit_log(&self.path.storage_addr)
+    }
 
-        for row in rows {
-            content.push_str(&row.join(","));
-            content.push('\n');
+    fn compact_sstables(&self) -> Result<()> {
+        let replica_path = self.path.full_path();
+        let sstables = SSTable::list(&replica_path)?;
+        if sstables.is_empty() {
+            return Ok(());
+        }
+        let mut entries = SortedEntries::new();
+        for sstable in &sstables {
+            for entry in sstable.read_all()? {
+                entry.merge_into(&mut entries);
+            }
         }
+        // Los borrados se conservan durante `gc_grace_seconds`, para que las réplicas que
+        // no se enteraron de ellos no vuelvan a propagar las filas borradas. Lo que ya venció
+        // pasa a ser un borrado más.
+        let entries = purge_tombstones(
+            apply_tombstones(
+                expire_entries(entries, Utc::now().timestamp()),
+                &self.columns,
+            ),
+            self.gc_grace_seconds,
+        );
+        let entries: Vec<StorageEntry> = entries.into_values().collect();
+        let generation = Self::next_generation(&sstables);
+        SSTable::replace(&replica_path, sstables, generation, &entries)
+    }
 
-        std::fs::write(self.path.full_path(), content).map_err(|_| {
-            Error::ServerError(format!("No se pudo escribir en {}", self.path.full_path()))
-        })
+    fn next_generation(sstables: &[SSTable]) -> usize {
+        sstables
+            .last()
+            .map_or(1, |sstable| sstable.get_generation() + 1)
     }
 }
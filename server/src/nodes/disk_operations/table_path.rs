@@ -32,11 +32,16 @@ impl TablePath {
         }
     }
 
-    /// Devuelve el path completo de la tabla.
+    /// Devuelve el path completo del directorio de la réplica de la tabla.
     pub fn full_path(&self) -> String {
         format!(
-            "{}/{}/{}.csv",
+            "{}/{}/{}",
             self.storage_addr, self.keyspace, self.table_name
         )
     }
+
+    /// Devuelve el path del archivo CSV en el que se guardaba la réplica antes de usar SSTables.
+    pub fn legacy_csv_path(&self) -> String {
+        format!("{}.csv", self.full_path())
+    }
 }
@@ -0,0 +1,224 @@
+//! Módulo para el detector de fallas _phi accrual_ de los nodos.
+
+use {
+    crate::nodes::{node::NodeId, states::heartbeat::HeartbeatState},
+    std::{
+        collections::{HashMap, VecDeque},
+        time::Instant,
+    },
+};
+
+/// El umbral de sospecha por defecto a partir del cual se considera caído a un nodo.
+pub const DEFAULT_PHI_CONVICT_THRESHOLD: f64 = 8.0;
+/// La cantidad máxima de intervalos entre _heartbeats_ que se recuerdan por nodo.
+const MAX_SAMPLES: usize = 100;
+/// El intervalo _(en milisegundos)_ que se asume entre _heartbeats_ de un nodo del que todavía
+/// no se conoce ninguno.
+///
+/// Es mayor al intervalo de los _heartbeats_, ya que estos llegan por _gossip_ y no directamente.
+const INITIAL_INTERVAL_MILLIS: f64 = 2000.0;
+/// El intervalo máximo _(en milisegundos)_ entre _heartbeats_ que se tiene en cuenta.
+///
+/// Los intervalos más largos se deben a que el nodo estuvo caído o reiniciándose, y no
+/// representan la frecuencia normal con la que llegan sus _heartbeats_.
+const MAX_INTERVAL_MILLIS: f64 = 30000.0;
+
+/// Los _heartbeats_ recibidos de un nodo.
+struct ArrivalWindow {
+    /// El _heartbeat_ más nuevo que se recibió.
+    last_heartbeat: HeartbeatState,
+
+    /// El momento en que se recibió el _heartbeat_ más nuevo.
+    last_arrival: Instant,
+
+    /// Los últimos intervalos _(en milisegundos)_ entre llegadas de _heartbeats_ nuevos.
+    intervals: VecDeque<f64>,
+}
+
+impl ArrivalWindow {
+    fn new(heartbeat: HeartbeatState, now: Instant) -> Self {
+        Self {
+            last_heartbeat: heartbeat,
+            last_arrival: now,
+            intervals: VecDeque::from([INITIAL_INTERVAL_MILLIS]),
+        }
+    }
+
+    fn add(&mut self, heartbeat: HeartbeatState, now: Instant) {
+        let interval = now.duration_since(self.last_arrival).as_secs_f64() * 1000.0;
+        if interval <= MAX_INTERVAL_MILLIS {
+            if self.intervals.len() == MAX_SAMPLES {
+                self.intervals.pop_front();
+            }
+            self.intervals.push_back(interval);
+        }
+        self.last_heartbeat = heartbeat;
+        self.last_arrival = now;
+    }
+
+    /// Calcula el nivel de sospecha de que el nodo esté caído.
+    ///
+    /// Se asume que los intervalos entre _heartbeats_ siguen una distribución exponencial, por
+    /// lo que `phi = -log10(P(intervalo > t))`, siendo `t` el tiempo desde el último _heartbeat_.
+    fn phi(&self, now: Instant) -> f64 {
+        let mean = self.intervals.iter().sum::<f64>() / self.intervals.len() as f64;
+        let elapsed = now.duration_since(self.last_arrival).as_secs_f64() * 1000.0;
+        elapsed / mean * std::f64::consts::LOG10_E
+    }
+}
+
+/// Un detector de fallas _phi accrual_.
+///
+/// En vez de decidir si un nodo está caído según si respondió o no un mensaje, se registra cuándo
+/// llegan las versiones nuevas de su _heartbeat_ por _gossip_, y a partir de la frecuencia con
+/// la que suelen llegar se calcula un nivel de sospecha `phi` que crece mientras no llegue
+/// ninguna. El nodo se considera caído cuando `phi` supera el umbral configurado.
+pub struct FailureDetector {
+    /// El umbral de sospecha a partir del cual se considera caído a un nodo.
+    phi_convict_threshold: f64,
+
+    /// Los _heartbeats_ recibidos de cada nodo.
+    arrivals: HashMap<NodeId, ArrivalWindow>,
+}
+
+impl FailureDetector {
+    /// Crea un detector de fallas con el umbral de sospecha dado.
+    pub fn new(phi_convict_threshold: f64) -> Self {
+        Self {
+            phi_convict_threshold,
+            arrivals: HashMap::new(),
+        }
+    }
+
+    /// Registra un _heartbeat_ de un nodo, si es más nuevo que el último que se conocía.
+    pub fn report(&mut self, node_id: NodeId, heartbeat: &HeartbeatState, now: Instant) {
+        match self.arrivals.get_mut(&node_id) {
+            Some(window) if heartbeat > &window.last_heartbeat => {
+                window.add(heartbeat.clone(), now)
+            }
+            Some(_) => {}
+            None => {
+                self.arrivals
+                    .insert(node_id, ArrivalWindow::new(heartbeat.clone(), now));
+            }
+        }
+    }
+
+    /// Calcula el nivel de sospecha de que un nodo esté caído.
+    ///
+    /// Si todavía no se recibió ningún _heartbeat_ del nodo, no hay motivos para sospechar de él.
+    pub fn phi(&self, node_id: NodeId, now: Instant) -> f64 {
+        self.arrivals
+            .get(&node_id)
+            .map_or(0.0, |window| window.phi(now))
+    }
+
+    /// Verifica si un nodo se considera vivo, es decir, si su nivel de sospecha no supera el umbral.
+    pub fn is_alive(&self, node_id: NodeId, now: Instant) -> bool {
+        self.phi(node_id, now) <= self.phi_convict_threshold
+    }
+
+    /// Olvida los _heartbeats_ de un nodo, por ejemplo porque dejó el clúster.
+    pub fn forget(&mut self, node_id: NodeId) {
+        self.arrivals.remove(&node_id);
+    }
+}
+
+impl Default for FailureDetector {
+    fn default() -> Self {
+        Self::new(DEFAULT_PHI_CONVICT_THRESHOLD)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {super::*, std::time::Duration};
+
+    /// Reporta `n` _heartbeats_ del nodo, uno cada `interval`, y devuelve el momento del último.
+    fn report_beats(
+        detector: &mut FailureDetector,
+        node_id: NodeId,
+        start: Instant,
+        n: u64,
+        interval: Duration,
+    ) -> Instant {
+        let mut now = start;
+        for version in 1..=n {
+            detector.report(node_id, &HeartbeatState::new(0, version), now);
+            now += interval;
+        }
+        now - interval
+    }
+
+    #[test]
+    fn test_1_nodo_desconocido_no_es_sospechoso() {
+        let detector = FailureDetector::default();
+
+        assert_eq!(detector.phi(10, Instant::now()), 0.0);
+        assert!(detector.is_alive(10, Instant::now()));
+    }
+
+    #[test]
+    fn test_2_phi_crece_sin_heartbeats() {
+        let mut detector = FailureDetector::default();
+        let last = report_beats(
+            &mut detector,
+            10,
+            Instant::now(),
+            20,
+            Duration::from_millis(1000),
+        );
+
+        let phi_1 = detector.phi(10, last + Duration::from_millis(500));
+        let phi_2 = detector.phi(10, last + Duration::from_millis(2000));
+        let phi_3 = detector.phi(10, last + Duration::from_millis(5000));
+
+        assert!(phi_1 < phi_2);
+        assert!(phi_2 < phi_3);
+    }
+
+    #[test]
+    fn test_3_se_condena_al_superar_el_umbral() {
+        let mut detector = FailureDetector::new(DEFAULT_PHI_CONVICT_THRESHOLD);
+        let last = report_beats(
+            &mut detector,
+            10,
+            Instant::now(),
+            20,
+            Duration::from_millis(1000),
+        );
+
+        assert!(detector.is_alive(10, last + Duration::from_millis(1000)));
+        let later = last + Duration::from_secs(30);
+        assert!(detector.phi(10, later) > DEFAULT_PHI_CONVICT_THRESHOLD);
+        assert!(!detector.is_alive(10, later));
+    }
+
+    #[test]
+    fn test_4_heartbeat_viejo_no_reinicia_la_espera() {
+        let mut detector = FailureDetector::default();
+        let start = Instant::now();
+        let last = report_beats(&mut detector, 10, start, 5, Duration::from_millis(1000));
+
+        let later = last + Duration::from_secs(10);
+        let phi_before = detector.phi(10, later);
+        detector.report(10, &HeartbeatState::new(0, 1), later);
+
+        assert_eq!(detector.phi(10, later), phi_before);
+    }
+
+    #[test]
+    fn test_5_olvidar_un_nodo() {
+        let mut detector = FailureDetector::default();
+        let last = report_beats(
+            &mut detector,
+            10,
+            Instant::now(),
+            5,
+            Duration::from_millis(1000),
+        );
+        detector.forget(10);
+
+        assert!(detector.is_alive(10, last + Duration::from_secs(60)));
+    }
+}
@@ -7,7 +7,9 @@ use {
             addr::loader::AddrLoader,
             node::{Node, NodeHandle, NodeId, N_NODES},
             port_type::PortType,
-            session_handler::{make_error_response, SessionHandler},
+            session_handler::{
+                compress_response, make_error_response_for, negotiate_compression, SessionHandler,
+            },
             utils::send_to_node,
         },
         utils::handle_pem_file_iter,
@@ -15,10 +17,10 @@ use {
     protocol::{
         aliases::{
             results::Result,
-            types::{Byte, Ulong},
+            types::{Byte, Uint, Ulong},
         },
         errors::error::Error,
-        headers::opcode::Opcode,
+        headers::{compression::Compression, opcode::Opcode},
         traits::Byteable,
     },
     rand::{distributions::WeightedIndex, prelude::Distribution, thread_rng},
@@ -28,11 +30,11 @@ use {
     },
     std::{
         collections::HashSet,
-        io::{BufRead, BufReader, Read, Write},
+        io::{BufRead, BufReader, Cursor, ErrorKind, Read, Write},
         net::{SocketAddr, TcpListener, TcpStream},
         sync::{
             atomic::{AtomicBool, Ordering},
-            mpsc::Receiver,
+            mpsc::{channel, Receiver, Sender},
             Arc, Mutex,
         },
         thread::{sleep, spawn, Builder},
@@ -50,6 +52,17 @@ const HANDSHAKE_NEIGHBOURS: Byte = 3;
 const HEARTBEAT_SLEEP_MILLIS: Ulong = 1000;
 /// Cantidad de tiempo _(en milisegundos)_ que duerme el hilo de _gossip_.
 const GOSSIP_SLEEP_MILLIS: Ulong = 350;
+/// Cantidad de tiempo _(en milisegundos)_ que espera una conexión con un cliente por una
+/// _request_ antes de revisar si hay eventos para enviarle.
+const EVENTS_POLL_MILLIS: Ulong = 200;
+/// Cantidad de tiempo _(en milisegundos)_ que espera una conexión con un cliente por una
+/// _request_ mientras tiene otras en proceso, antes de revisar si ya están sus respuestas.
+const RESPONSES_POLL_MILLIS: Ulong = 5;
+/// La cantidad máxima de _requests_ de una misma conexión que se procesan a la vez. Las que
+/// lleguen de más se procesan de a una hasta que terminen las anteriores.
+const MAX_IN_FLIGHT_REQUESTS: usize = 128;
+/// La cantidad (en bytes) de los encabezados de un mensaje.
+const HEADERS_SIZE: usize = 9;
 
 /// El número de hilos para el [ThreadPool].
 ///
@@ -212,7 +225,7 @@ fn listen_priv_port(
                 if is_exit(&bytes_vec[..]) {
                     break;
                 }
-                session_handler.process_stream(&mut tcp_stream, bytes_vec, true)?;
+                session_handler.process_stream(&mut tcp_stream, bytes_vec, true, None)?;
             }
         }
     }
@@ -233,45 +246,180 @@ fn listen_single_client(
             ));
         }
     };
+    let (events_sender, events_receiver) = channel::<Vec<Byte>>();
+    let client_addr = tcp_stream.peer_addr().map_err(|e| {
+        Error::ServerError(format!("No se pudo obtener la dirección del cliente: {e}"))
+    })?;
+    let session_handler = session_handler.with_events_sender(client_addr, events_sender);
+    tcp_stream
+        .set_read_timeout(Some(Duration::from_millis(EVENTS_POLL_MILLIS)))
+        .map_err(|e| Error::ServerError(format!("No se pudo configurar el timeout: {e}")))?;
+
     let mut buffered_stream = clone_tcp_stream(&tcp_stream)?;
     let mut tls_stream: TlsStream = Stream::new(&mut server_conn, &mut buffered_stream);
     let tls = &mut tls_stream;
     let mut is_logged = false;
+    let mut compression: Option<Compression> = None;
+    // Las requests se procesan en paralelo, y sus respuestas llegan por este canal
+    let (responses_sender, responses_receiver) = channel::<Vec<Byte>>();
+    let mut in_flight: usize = 0;
+    let mut pending: Vec<Byte> = Vec::new();
+
+    'connection: loop {
+        for response in responses_receiver.try_iter() {
+            in_flight -= 1;
+            if tls.write_all(&response).is_err() {
+                return Err(Error::ServerError(
+                    "No se pudo enviar una respuesta al cliente".to_string(),
+                ));
+            }
+        }
+        let poll_millis = if in_flight > 0 {
+            RESPONSES_POLL_MILLIS
+        } else {
+            EVENTS_POLL_MILLIS
+        };
+        tcp_stream
+            .set_read_timeout(Some(Duration::from_millis(poll_millis)))
+            .map_err(|e| Error::ServerError(format!("No se pudo configurar el timeout: {e}")))?;
 
-    loop {
         let mut buffer: Vec<Byte> = vec![0; 2048];
         let size = match tls.read(&mut buffer) {
             Ok(value) => value,
+            Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                // No llegó ninguna request, así que aprovechamos para mandar los eventos pendientes
+                for event in events_receiver.try_iter() {
+                    if tls
+                        .write_all(&compress_response(event, compression))
+                        .is_err()
+                    {
+                        return Err(Error::ServerError(
+                            "No se pudo enviar un evento al cliente".to_string(),
+                        ));
+                    }
+                }
+                continue;
+            }
             Err(_err) => {
                 return Err(Error::ServerError("No se pudo leer el stream".to_string()));
             }
         };
+        if size == 0 {
+            // El cliente cerró la conexión
+            break;
+        }
+        pending.extend_from_slice(&buffer[..size]);
+
+        for frame in take_complete_frames(&mut pending) {
+            if is_exit(&frame[..]) {
+                match arc_exit.lock() {
+                    Ok(mut locked_in) => *locked_in = true,
+                    Err(poison_err) => {
+                        println!("Error de lock envenenado:\n\n{}", &poison_err);
+                        arc_exit.clear_poison();
+                    }
+                }
+                break 'connection;
+            }
 
-        buffer.truncate(size);
-        if is_exit(&buffer[..]) {
-            match arc_exit.lock() {
-                Ok(mut locked_in) => *locked_in = true,
-                Err(poison_err) => {
-                    println!("Error de lock envenenado:\n\n{}", &poison_err);
-                    arc_exit.clear_poison();
+            if !session_handler.node_is_responsive()? {
+                let error = make_error_response_for(
+                    &frame,
+                    Error::ServerError(
+                        "Se esta cambiando la estructura de los nodos, vuelva luego.".to_string(),
+                    ),
+                );
+                let _ = tls.write_all(&error);
+                break 'connection;
+            }
+            if in_flight < MAX_IN_FLIGHT_REQUESTS && is_concurrent_request(&frame) {
+                spawn_request(
+                    &session_handler,
+                    frame,
+                    (is_logged, compression),
+                    responses_sender.clone(),
+                )?;
+                in_flight += 1;
+            } else {
+                let res =
+                    session_handler.process_stream(tls, frame.to_vec(), is_logged, compression)?;
+                if res.len() >= 9 && res[4] == Opcode::AuthSuccess.as_bytes()[0] {
+                    is_logged = true;
                 }
+                negotiate_compression(&frame, &res, &mut compression);
             }
-            break;
         }
+    }
+    Ok(())
+}
 
-        if !session_handler.node_is_responsive()? {
-            let error = make_error_response(Error::ServerError(
-                "Se esta cambiando la estructura de los nodos, vuelva luego.".to_string(),
-            ));
-            let _ = tls.write_all(&error);
+/// Separa los mensajes completos que mandó un cliente, dejando en `pending` los bytes de un
+/// mensaje que todavía no llegó entero.
+///
+/// Las acciones de servidor no indican su largo, así que se toman enteras.
+fn take_complete_frames(pending: &mut Vec<Byte>) -> Vec<Vec<Byte>> {
+    let mut frames = Vec::new();
+    while !pending.is_empty() {
+        if SvAction::is_action(pending) {
+            frames.push(std::mem::take(pending));
+            break;
+        }
+        if pending.len() < HEADERS_SIZE {
             break;
-        } else {
-            let res = session_handler.process_stream(tls, buffer.to_vec(), is_logged)?;
-            if res.len() >= 9 && res[4] == Opcode::AuthSuccess.as_bytes()[0] {
-                is_logged = true;
-            }
         }
+        let length = Uint::from_be_bytes([pending[5], pending[6], pending[7], pending[8]]);
+        let frame_size = HEADERS_SIZE + length as usize;
+        if pending.len() < frame_size {
+            break;
+        }
+        frames.push(pending.drain(..frame_size).collect());
     }
+    frames
+}
+
+/// Verifica si un mensaje de un cliente se puede procesar en paralelo con otros.
+///
+/// Los mensajes que cambian el estado de la conexión (como el STARTUP o el AUTH_RESPONSE) se
+/// procesan en orden.
+fn is_concurrent_request(frame: &[Byte]) -> bool {
+    frame.len() >= HEADERS_SIZE
+        && matches!(
+            Opcode::try_from(frame[4]),
+            Ok(Opcode::Query | Opcode::Prepare | Opcode::Execute | Opcode::Batch)
+        )
+}
+
+/// Procesa una request de un cliente en un hilo propio, y manda su respuesta por el canal dado
+/// para que se la escriba la conexión.
+///
+/// La respuesta lleva el mismo ID de _stream_ que la request, para que el cliente sepa a cuál
+/// corresponde.
+fn spawn_request(
+    session_handler: &SessionHandler,
+    frame: Vec<Byte>,
+    connection_state: (bool, Option<Compression>),
+    responses_sender: Sender<Vec<Byte>>,
+) -> Result<()> {
+    let (is_logged, compression) = connection_state;
+    let session_handler = session_handler.clone();
+    let stream_id = u16::from_be_bytes([frame[2], frame[3]]);
+    Builder::new()
+        .name(format!("client_request_{stream_id}"))
+        .spawn(move || {
+            let mut response = Cursor::new(Vec::new());
+            let response = match session_handler.process_stream(
+                &mut response,
+                frame.clone(),
+                is_logged,
+                compression,
+            ) {
+                Ok(_) => response.into_inner(),
+                Err(err) => make_error_response_for(&frame, err),
+            };
+            // Si la conexión ya se cerró, no hay a quién mandarle la respuesta
+            let _ = responses_sender.send(response);
+        })
+        .map_err(|e| Error::ServerError(format!("No se pudo procesar la request: {e}")))?;
     Ok(())
 }
 
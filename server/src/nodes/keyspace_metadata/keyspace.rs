@@ -26,10 +26,9 @@ impl Keyspace {
         &self.name
     }
 
-    /// Obtiene la cantidad de réplicas de la estrategia de replicación simple.
-    /// Si no es estrategia simple, retorna None.
-    pub fn simple_replicas(&self) -> Option<Uint> {
-        self.replication.simple_replicas()
+    /// Obtiene la cantidad total de réplicas de la estrategia de replicación.
+    pub fn total_replicas(&self) -> Uint {
+        self.replication.total_replicas()
     }
 
     /// Establece la estrategia de replicación del keyspace.
@@ -15,12 +15,31 @@ pub enum ReplicationStrategy {
 }
 
 impl ReplicationStrategy {
-    /// Obtiene la cantidad de réplicas de la estrategia de replicación simple.
-    /// Si no es estrategia simple, retorna None.
-    pub fn simple_replicas(&self) -> Option<Uint> {
+    /// Obtiene la cantidad total de réplicas de la estrategia de replicación.
+    ///
+    /// En una [NetworkTopologyStrategy](ReplicationStrategy::NetworkTopologyStrategy), es la suma
+    /// de las réplicas de todos los _datacenters_.
+    pub fn total_replicas(&self) -> Uint {
         match self {
-            ReplicationStrategy::SimpleStrategy(replicas) => Some(*replicas),
-            _ => None,
+            ReplicationStrategy::SimpleStrategy(replicas) => *replicas,
+            ReplicationStrategy::NetworkTopologyStrategy(datacenters) => {
+                datacenters.iter().map(|(_, replicas)| replicas).sum()
+            }
+        }
+    }
+
+    /// Obtiene la cantidad de réplicas que debe tener un _datacenter_ dado.
+    ///
+    /// Si es estrategia simple, retorna None, ya que las réplicas no dependen del _datacenter_.
+    pub fn datacenter_replicas(&self, datacenter: &str) -> Option<Uint> {
+        match self {
+            ReplicationStrategy::SimpleStrategy(_) => None,
+            ReplicationStrategy::NetworkTopologyStrategy(datacenters) => Some(
+                datacenters
+                    .iter()
+                    .find(|(name, _)| name == datacenter)
+                    .map_or(0, |(_, replicas)| *replicas),
+            ),
         }
     }
 }
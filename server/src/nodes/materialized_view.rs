@@ -0,0 +1,251 @@
+//! Módulo para mantener las vistas materializadas a partir de las filas de su tabla base.
+
+use {
+    crate::nodes::table_metadata::{column_data_type::ColumnDataType, table::Table},
+    parser::statements::dml_statement::{
+        main_statements::insert::Insert, update_parameters::UpdateParameters,
+    },
+    protocol::{
+        aliases::{results::Result, types::Long},
+        errors::error::Error,
+    },
+    std::{
+        collections::{HashMap, HashSet},
+        sync::{Condvar, Mutex, PoisonError},
+    },
+};
+
+/// Una fila de una tabla, con el valor de cada columna según su nombre. Las columnas sin valor
+/// quedan vacías.
+pub type NamedRow = HashMap<String, String>;
+
+/// Las filas de una tabla base que puede modificar una escritura, leídas antes de aplicarla para
+/// después llevar sus vistas materializadas a las nuevas versiones.
+pub struct BaseRows {
+    /// Nombre de la tabla base.
+    pub table_name: String,
+    /// Versiones de las filas antes de la escritura.
+    pub old_rows: Vec<NamedRow>,
+    /// Versiones de las filas después de la escritura, si se conocen sin volver a leerlas.
+    pub new_rows: Option<Vec<NamedRow>>,
+    /// Condición sobre la _primary key_ de la fila que escribe un INSERT, para poder releerla.
+    pub inserted_key: Option<String>,
+    /// El TTL con el que se escriben las filas en las vistas, según [get_view_ttl].
+    pub ttl: Option<Long>,
+}
+
+/// Los _locks_ con los que un coordinador serializa las escrituras sobre cada partición de las
+/// tablas con vistas materializadas, desde que lee las filas viejas hasta que actualiza las
+/// vistas, para que dos escrituras concurrentes no dejen filas huérfanas en ellas.
+#[derive(Default)]
+pub struct PartitionLocks {
+    /// Las particiones bloqueadas, como `<keyspace>.<tabla>` junto al valor de la partición.
+    locked: Mutex<HashSet<(String, String)>>,
+    /// Avisa cuando se libera alguna partición.
+    released: Condvar,
+}
+
+impl PartitionLocks {
+    /// Bloquea juntas todas las particiones dadas, esperando a que se liberen las que estén
+    /// bloqueadas. Se liberan cuando se descarta el [PartitionGuard] devuelto.
+    pub fn lock(&self, partitions: Vec<(String, String)>) -> Result<PartitionGuard<'_>> {
+        let mut locked = self
+            .locked
+            .lock()
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+        while partitions
+            .iter()
+            .any(|partition| locked.contains(partition))
+        {
+            locked = self
+                .released
+                .wait(locked)
+                .map_err(|e| Error::ServerError(e.to_string()))?;
+        }
+        locked.extend(partitions.iter().cloned());
+        Ok(PartitionGuard {
+            locks: self,
+            partitions,
+        })
+    }
+}
+
+/// Mantiene bloqueadas unas particiones de [PartitionLocks] hasta que se descarta.
+pub struct PartitionGuard<'a> {
+    /// Los _locks_ de donde se tomaron las particiones.
+    locks: &'a PartitionLocks,
+    /// Las particiones bloqueadas.
+    partitions: Vec<(String, String)>,
+}
+
+impl Drop for PartitionGuard<'_> {
+    fn drop(&mut self) {
+        let mut locked = self
+            .locks
+            .locked
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        for partition in &self.partitions {
+            locked.remove(partition);
+        }
+        self.locks.released.notify_all();
+    }
+}
+
+/// Obtiene el TTL con el que se escriben en las vistas las filas de una escritura sobre su tabla
+/// base: el de la escritura si indicó uno, o si no el TTL por defecto de la tabla base.
+///
+/// Si ninguno de los dos vence, devuelve `None` y las filas vencen según el TTL por defecto de
+/// cada vista.
+pub fn get_view_ttl(using: Option<&UpdateParameters>, base_table: &Table) -> Result<Option<Long>> {
+    let default_ttl = base_table.get_default_time_to_live();
+    let ttl = match using {
+        Some(using) => using.get_ttl()?,
+        None => None,
+    };
+    Ok(ttl.or((default_ttl > 0).then_some(default_ttl)))
+}
+
+/// Arma la fila que escribe un INSERT sobre la tabla. Las columnas que no se insertan quedan
+/// vacías, porque el INSERT reemplaza a la fila entera.
+pub fn get_inserted_row(insert: &Insert, table: &Table) -> NamedRow {
+    let mut row: NamedRow = table
+        .get_columns_names()
+        .into_iter()
+        .map(|column| (column, String::new()))
+        .collect();
+    row.extend(
+        insert
+            .get_columns_names()
+            .into_iter()
+            .zip(insert.get_values()),
+    );
+    row
+}
+
+/// Obtiene las columnas de la _primary key_ de la tabla, empezando por las de la _partition key_.
+fn get_key_columns(table: &Table) -> Vec<String> {
+    let mut key_columns = table.get_partition_key();
+    if let Some(clustering_columns) = &table.clustering_key_and_order {
+        key_columns.extend(clustering_columns.iter().map(|(column, _)| column.clone()));
+    }
+    key_columns
+}
+
+/// Transforma un valor de una columna a un literal de CQL.
+fn as_literal(value: &str, data_type: &ColumnDataType) -> String {
+    match data_type {
+        // Las comillas del valor se duplican para que el tokenizador no corte el string
+        ColumnDataType::String => format!("'{}'", value.replace('\'', "''")),
+        _ => value.to_string(),
+    }
+}
+
+/// Arma la condición `columna = valor AND ...` que identifica a la fila en la tabla por su
+/// _primary key_. Si a la fila le falta alguno de los valores, devuelve `None`.
+pub fn get_key_condition(row: &NamedRow, table: &Table) -> Option<String> {
+    let data_types: HashMap<String, ColumnDataType> =
+        table.get_columns_name_and_data_type().into_iter().collect();
+    let conditions: Option<Vec<String>> = get_key_columns(table)
+        .iter()
+        .map(|column| {
+            let value = row.get(column).filter(|value| !value.is_empty())?;
+            let literal = as_literal(value, data_types.get(column)?);
+            Some(format!("{column} = {literal}"))
+        })
+        .collect();
+    Some(conditions?.join(" AND "))
+}
+
+/// Arma las _queries_ que llevan a una vista desde las versiones viejas de las filas de su
+/// tabla base a las nuevas.
+///
+/// Si la fila cambió de _primary key_ en la vista, o dejó de existir, se borra la anterior; si
+/// la nueva versión tiene todas las columnas de la _primary key_ de la vista, se inserta.
+pub fn get_view_mutations(
+    view: &Table,
+    base_table: &Table,
+    old_rows: &[NamedRow],
+    new_rows: &[NamedRow],
+    ttl: Option<Long>,
+) -> Vec<String> {
+    let base_key_columns = get_key_columns(base_table);
+    let base_key_of = |row: &NamedRow| -> Vec<String> {
+        base_key_columns
+            .iter()
+            .map(|column| row.get(column).cloned().unwrap_or_default())
+            .collect()
+    };
+    let view_name = format!("{}.{}", view.get_keyspace(), view.get_name());
+
+    let mut mutations = Vec::new();
+    for old_row in old_rows {
+        let Some(old_condition) = get_key_condition(old_row, view) else {
+            continue;
+        };
+        let new_condition = new_rows
+            .iter()
+            .find(|new_row| base_key_of(new_row) == base_key_of(old_row))
+            .and_then(|new_row| get_key_condition(new_row, view));
+        if new_condition.as_ref() != Some(&old_condition) {
+            mutations.push(format!("DELETE FROM {view_name} WHERE {old_condition}"));
+        }
+    }
+
+    let data_types = view.get_columns_name_and_data_type();
+    for new_row in new_rows {
+        if get_key_condition(new_row, view).is_none() {
+            continue;
+        }
+        let (columns, values): (Vec<&str>, Vec<String>) = data_types
+            .iter()
+            .filter_map(|(column, data_type)| {
+                let value = new_row.get(column).filter(|value| !value.is_empty())?;
+                Some((column.as_str(), as_literal(value, data_type)))
+            })
+            .unzip();
+        let using_ttl = match ttl {
+            Some(ttl) => format!(" USING TTL {ttl}"),
+            None => String::new(),
+        };
+        mutations.push(format!(
+            "INSERT INTO {view_name} ({}) VALUES ({}){using_ttl}",
+            columns.join(", "),
+            values.join(", ")
+        ));
+    }
+    mutations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::Arc, thread, time::Duration};
+
+    fn partition(value: &str) -> (String, String) {
+        ("ks.vuelos".to_string(), value.to_string())
+    }
+
+    #[test]
+    fn test_1_se_pueden_bloquear_particiones_distintas_a_la_vez() -> Result<()> {
+        let locks = PartitionLocks::default();
+        let _eze = locks.lock(vec![partition("EZE")])?;
+        let _mad = locks.lock(vec![partition("MAD")])?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_una_particion_bloqueada_espera_a_que_se_libere() -> Result<()> {
+        let locks = Arc::new(PartitionLocks::default());
+        let guard = locks.lock(vec![partition("EZE"), partition("MAD")])?;
+
+        let other_locks = Arc::clone(&locks);
+        let waiter = thread::spawn(move || other_locks.lock(vec![partition("MAD")]).is_ok());
+        thread::sleep(Duration::from_millis(50));
+        assert!(!waiter.is_finished());
+
+        drop(guard);
+        assert!(waiter.join().unwrap_or(false));
+        Ok(())
+    }
+}
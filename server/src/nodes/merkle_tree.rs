@@ -0,0 +1,166 @@
+//! Módulo para los árboles de Merkle usados en la reparación de réplicas.
+
+use {
+    crate::nodes::{disk_operations::storage_entry::StorageEntry, utils::hash_value},
+    protocol::{
+        aliases::{
+            results::Result,
+            types::{Byte, Ulong},
+        },
+        errors::error::Error,
+        traits::Byteable,
+    },
+    std::convert::TryFrom,
+};
+
+/// La profundidad del árbol. Tiene `2^MERKLE_TREE_DEPTH` hojas.
+const MERKLE_TREE_DEPTH: u32 = 10;
+/// La cantidad de hojas del árbol.
+const LEAVES: usize = 1 << MERKLE_TREE_DEPTH;
+
+/// Un árbol de Merkle sobre las entradas de una réplica.
+///
+/// Cada hoja cubre un rango contiguo de _tokens_ del anillo, y guarda el hash de las entradas
+/// cuyas particiones caen en ese rango. Cada nodo interno guarda el hash de sus dos hijos,
+/// así que dos réplicas con el mismo contenido tienen la misma raíz, y si difieren alcanza con
+/// bajar por las ramas distintas para encontrar los rangos a reparar.
+#[derive(Debug, PartialEq)]
+pub struct MerkleTree {
+    /// Los hashes del árbol, guardado como un _heap_: la raíz está en la posición 0 y los hijos
+    /// del nodo `i` en las posiciones `2i + 1` y `2i + 2`.
+    hashes: Vec<Ulong>,
+}
+
+impl MerkleTree {
+    /// Arma el árbol de las entradas dadas, que deben estar ordenadas por _primary key_.
+    pub fn build(entries: &[StorageEntry]) -> Self {
+        let mut leaves: Vec<Vec<String>> = vec![Vec::new(); LEAVES];
+        for entry in entries {
+            leaves[Self::leaf_of(entry.partition())].push(entry.to_line());
+        }
+        let mut hashes = vec![0; 2 * LEAVES - 1];
+        for (leaf, lines) in leaves.iter().enumerate() {
+            hashes[LEAVES - 1 + leaf] = hash_value(lines);
+        }
+        for node in (0..LEAVES - 1).rev() {
+            hashes[node] = hash_value((hashes[2 * node + 1], hashes[2 * node + 2]));
+        }
+        Self { hashes }
+    }
+
+    /// Devuelve la hoja que cubre el _token_ de una partición.
+    pub fn leaf_of(partition: &str) -> usize {
+        (hash_value(partition) >> (Ulong::BITS - MERKLE_TREE_DEPTH)) as usize
+    }
+
+    /// Compara el árbol con el de otra réplica, y devuelve las hojas que difieren.
+    pub fn differing_leaves(&self, other: &Self) -> Vec<usize> {
+        let mut differing = Vec::new();
+        let mut pending = vec![0];
+        while let Some(node) = pending.pop() {
+            if self.hashes[node] == other.hashes[node] {
+                continue;
+            }
+            if node >= LEAVES - 1 {
+                differing.push(node - (LEAVES - 1));
+            } else {
+                pending.push(2 * node + 2);
+                pending.push(2 * node + 1);
+            }
+        }
+        differing
+    }
+}
+
+impl Byteable for MerkleTree {
+    fn as_bytes(&self) -> Vec<Byte> {
+        self.hashes
+            .iter()
+            .flat_map(|hash| hash.to_be_bytes())
+            .collect()
+    }
+}
+
+impl TryFrom<&[Byte]> for MerkleTree {
+    type Error = Error;
+    fn try_from(bytes: &[Byte]) -> Result<Self> {
+        let hash_size = size_of::<Ulong>();
+        if bytes.len() != (2 * LEAVES - 1) * hash_size {
+            return Err(Error::ServerError(format!(
+                "Se esperaba un árbol de Merkle de {} bytes, pero se recibieron {}",
+                (2 * LEAVES - 1) * hash_size,
+                bytes.len()
+            )));
+        }
+        let mut hashes = Vec::with_capacity(2 * LEAVES - 1);
+        for chunk in bytes.chunks_exact(hash_size) {
+            let mut hash = [0; 8];
+            hash.copy_from_slice(chunk);
+            hashes.push(Ulong::from_be_bytes(hash));
+        }
+        Ok(Self { hashes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(partition: &str, value: &str) -> StorageEntry {
+        StorageEntry::live(
+            vec![partition.to_string(), value.to_string(), "1".to_string()],
+            &[0],
+        )
+    }
+
+    #[test]
+    fn test_1_arboles_iguales_no_difieren() {
+        let entries = vec![entry("EZE", "AR1234"), entry("JFK", "AA100")];
+        let tree = MerkleTree::build(&entries);
+        let other = MerkleTree::build(&entries);
+
+        assert_eq!(tree, other);
+        assert!(tree.differing_leaves(&other).is_empty());
+    }
+
+    #[test]
+    fn test_2_difiere_solo_la_hoja_de_la_particion_cambiada() {
+        let tree = MerkleTree::build(&[entry("EZE", "AR1234"), entry("JFK", "AA100")]);
+        let other = MerkleTree::build(&[entry("EZE", "AR1234"), entry("JFK", "AA200")]);
+
+        assert_eq!(
+            tree.differing_leaves(&other),
+            vec![MerkleTree::leaf_of("JFK")]
+        );
+    }
+
+    #[test]
+    fn test_3_particion_faltante_difiere() {
+        let tree = MerkleTree::build(&[entry("EZE", "AR1234")]);
+        let other = MerkleTree::build(&[]);
+
+        assert_eq!(
+            tree.differing_leaves(&other),
+            vec![MerkleTree::leaf_of("EZE")]
+        );
+    }
+
+    #[test]
+    fn test_4_ida_y_vuelta_de_bytes() {
+        let tree = MerkleTree::build(&[entry("EZE", "AR1234"), entry("JFK", "AA100")]);
+        let bytes = tree.as_bytes();
+
+        assert_eq!(bytes.len(), (2 * LEAVES - 1) * size_of::<Ulong>());
+        assert_eq!(MerkleTree::try_from(&bytes[..]).ok(), Some(tree));
+    }
+
+    #[test]
+    fn test_5_bytes_de_largo_invalido() {
+        let bytes = MerkleTree::build(&[]).as_bytes();
+
+        assert!(matches!(
+            MerkleTree::try_from(&bytes[1..]),
+            Err(Error::ServerError(_))
+        ));
+    }
+}
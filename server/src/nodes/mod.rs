@@ -2,12 +2,22 @@
 
 pub mod actions;
 pub mod addr;
+mod consistency_counter;
 pub mod disk_operations;
+mod failure_detector;
 mod internal_threads;
 mod keyspace_metadata;
+mod materialized_view;
+mod merkle_tree;
 pub mod node;
+pub mod node_options;
 pub mod port_type;
+mod prepared_statement;
+mod replica_responses;
+mod select_functions;
 mod session_handler;
 pub mod states;
 pub mod table_metadata;
+mod token_ring;
+mod trace_session;
 mod utils;
@@ -1,33 +1,54 @@
 //! Módulo de nodos.
 
 use {
-    super::session_handler::get_partition_value_from_insert,
+    super::session_handler::{
+        get_dml_statement_table_name, get_partition_value_from_insert, parse_batch_statement,
+    },
     crate::{
+        cql_frame::{
+            batch_body::{BatchBody, BatchQuery},
+            paging_state::Page,
+        },
         modes::ConnectionMode,
         nodes::{
             actions::opcode::SvAction,
-            addr::loader::AddrLoader,
-            disk_operations::disk_handler::DiskHandler,
+            addr::loader::{AddrLoader, DEFAULT_DATACENTER, DEFAULT_RACK},
+            consistency_counter::ConsistencyCounter,
+            disk_operations::{commit_log::CommitLogSync, disk_handler::DiskHandler},
+            failure_detector::{FailureDetector, DEFAULT_PHI_CONVICT_THRESHOLD},
             internal_threads::{beater, create_client_and_private_conexion, gossiper},
-            keyspace_metadata::keyspace::Keyspace,
+            keyspace_metadata::{keyspace::Keyspace, replication_strategy::ReplicationStrategy},
+            node_options::NodeOptions,
             port_type::PortType,
+            prepared_statement::{PreparedCache, PreparedId, PreparedStatement},
             states::{
                 appstatus::AppStatus,
                 endpoints::EndpointState,
-                heartbeat::{GenType, VerType},
+                heartbeat::{GenType, HeartbeatState, VerType},
             },
             table_metadata::table::Table,
-            utils::{divide_range, hash_value, n_th_node_in_the_cluster, send_to_node},
+            token_ring::{Token, TokenRing, DEFAULT_VNODES},
+            utils::{hash_value, next_node_in_the_cluster, send_to_node},
         },
         utils::load_json,
     },
+    chrono::Utc,
     parser::{
         data_types::keyspace_name::KeyspaceName,
         statements::{
             ddl_statement::{
-                alter_keyspace::AlterKeyspace, create_keyspace::CreateKeyspace,
-                create_table::CreateTable, ddl_statement_parser::DdlStatement,
+                alter_keyspace::AlterKeyspace,
+                alter_table::{AlterTable, AlterTableInstruction},
+                create_index::CreateIndex,
+                create_keyspace::CreateKeyspace,
+                create_materialized_view::CreateMaterializedView,
+                create_table::CreateTable,
+                ddl_statement_parser::DdlStatement,
+                drop_index::DropIndex,
                 drop_keyspace::DropKeyspace,
+                drop_materialized_view::DropMaterializedView,
+                drop_table::DropTable,
+                truncate::Truncate,
             },
             dml_statement::{
                 dml_statement_parser::DmlStatement,
@@ -41,22 +62,38 @@ use {
     protocol::{
         aliases::{
             results::Result,
-            types::{Byte, Int, Long, Short, Uint, Ulong},
+            types::{Byte, Int, Long, Short, Uint},
         },
         errors::error::Error,
         headers::{flags::Flag, length::Length, opcode::Opcode, stream::Stream, version::Version},
-        messages::responses::result_kinds::ResultKind,
-        traits::Byteable,
+        messages::{
+            requests::batch_types::BatchType,
+            responses::{
+                events::{
+                    event_types::EventType,
+                    schema_changes::{
+                        options::SchemaChangeOption, targets::SchemaChangeTarget,
+                        types::SchemaChangeType,
+                    },
+                },
+                result::rows_flags::RowsFlag,
+                result_kinds::ResultKind,
+            },
+        },
+        notations::consistency::Consistency,
+        traits::{Byteable, Maskable},
+        utils::{encode_string_to_bytes, parse_bytes_to_string},
     },
     rand::{seq::SliceRandom, thread_rng},
     serde::{Deserialize, Serialize},
     serde_json::{json, Value},
     std::{
-        collections::HashMap,
-        net::{IpAddr, TcpStream},
+        collections::{HashMap, HashSet},
+        net::{IpAddr, SocketAddr, TcpStream},
         path::Path,
         sync::mpsc::{channel, Receiver, Sender},
         thread::JoinHandle,
+        time::Instant,
     },
 };
 
@@ -69,6 +106,12 @@ pub type NodesMap = HashMap<NodeId, EndpointState>;
 pub type OpenConnectionsMap = HashMap<Stream, TcpStream>;
 /// El handle donde vive una operación de nodo.
 pub type NodeHandle = JoinHandle<Result<()>>;
+/// Los clientes registrados a eventos, identificados por su dirección.
+///
+/// Para cada uno se guardan los tipos de evento que escucha y el canal por donde enviárselos.
+pub type EventListeners = HashMap<SocketAddr, (HashSet<String>, Sender<Vec<Byte>>)>;
+/// Las declaraciones de un BATCH, junto al nodo dueño de la partición de cada una.
+pub type BatchStatements = Vec<(DmlStatement, NodeId)>;
 
 /// Cantidad inicial de nodos en el clúster.
 ///
@@ -77,8 +120,6 @@ pub type NodeHandle = JoinHandle<Result<()>>;
 ///
 /// Lo que NO es posible, es que sea menor.
 pub const N_NODES: Byte = 5;
-/// El límite posible para los rangos de los nodos.
-pub const NODES_RANGE_END: Ulong = 18446744073709551615;
 
 /// Un nodo es una instancia de parser que se conecta con otros nodos para procesar _queries_.
 #[derive(Serialize, Deserialize)]
@@ -124,6 +165,48 @@ pub struct Node {
     /// Los pesos de los nodos.
     nodes_weights: Vec<usize>,
 
+    /// Los _tokens_ de los nodos virtuales de este nodo en el anillo.
+    #[serde(default)]
+    tokens: Vec<Token>,
+
+    /// El anillo de _tokens_ armado con los nodos actuales del clúster.
+    #[serde(skip)]
+    token_ring: TokenRing,
+
+    /// Los nodos del clúster, con sus _tokens_, con los que se armó el anillo.
+    ///
+    /// Sirve para saber si hay que volver a armarlo.
+    #[serde(skip)]
+    token_ring_members: Vec<(NodeId, Vec<Token>)>,
+
+    /// Los IDs de los nodos del clúster la última vez que se relocalizaron las filas.
+    ///
+    /// Sirve para saber si cambiaron las réplicas de este nodo.
+    #[serde(default)]
+    last_ring_nodes: Vec<NodeId>,
+
+    /// El modo de sincronización con el disco del _commit log_ del nodo.
+    #[serde(default)]
+    commit_log_sync: CommitLogSync,
+
+    /// Cada cuántos segundos el nodo repara sus réplicas por su cuenta, si se configuró.
+    #[serde(default)]
+    repair_interval_secs: Option<Long>,
+
+    /// El timestamp de la última reparación programada del nodo.
+    #[serde(default)]
+    last_scheduled_repair: Long,
+
+    /// El umbral de sospecha del detector de fallas a partir del cual se considera caído a un nodo.
+    ///
+    /// Si no se configuró, se usa [DEFAULT_PHI_CONVICT_THRESHOLD].
+    #[serde(default)]
+    phi_convict_threshold: Option<f64>,
+
+    /// El detector de fallas con el que se decide si los demás nodos están vivos.
+    #[serde(skip)]
+    failure_detector: FailureDetector,
+
     /// Indica si es un nodo distinto a los N_NODES originales.
     #[serde(skip)]
     pub is_new_node: bool,
@@ -131,6 +214,14 @@ pub struct Node {
     /// Canales que sirven para enviar un booleano que frene los hilos _gossiper_ y _beater_ del nodo.
     #[serde(skip)]
     pub stoppers: Vec<Sender<bool>>,
+
+    /// Las _queries_ preparadas por los clientes de este nodo.
+    #[serde(skip)]
+    prepared_statements: PreparedCache,
+
+    /// Los clientes de este nodo que se registraron para recibir eventos.
+    #[serde(skip)]
+    event_listeners: EventListeners,
 }
 
 impl Node {
@@ -157,8 +248,19 @@ impl Node {
             tables_and_partitions_keys_values: HashMap::new(),
             open_connections: OpenConnectionsMap::new(),
             nodes_weights: Vec::new(),
+            tokens: Vec::new(),
+            token_ring: TokenRing::new(),
+            token_ring_members: Vec::new(),
+            last_ring_nodes: Self::get_all_nodes_ids(),
+            commit_log_sync: CommitLogSync::default(),
+            repair_interval_secs: None,
+            last_scheduled_repair: 0,
+            phi_convict_threshold: None,
+            failure_detector: FailureDetector::default(),
             is_new_node,
             stoppers,
+            prepared_statements: PreparedCache::new(),
+            event_listeners: EventListeners::new(),
         })
     }
 
@@ -182,32 +284,61 @@ impl Node {
         self.open_connections = OpenConnectionsMap::new();
         self.is_new_node = is_new;
         self.stoppers = stoppers;
+        self.prepared_statements = PreparedCache::new();
+        self.event_listeners = EventListeners::new();
 
         Ok(())
     }
 
     /// Inicia un nodo con un ID específico en modo de conexión _parsing_.
     pub fn init_in_parsing_mode(id: NodeId) -> Result<()> {
-        Self::init(id, ConnectionMode::Parsing, false, None)
+        Self::init(
+            id,
+            ConnectionMode::Parsing,
+            false,
+            None,
+            NodeOptions::default(),
+        )
     }
 
     /// Inicia un nodo con un ID específico en modo de conexión _echo_.
     pub fn init_in_echo_mode(id: NodeId) -> Result<()> {
-        Self::init(id, ConnectionMode::Echo, false, None)
+        Self::init(
+            id,
+            ConnectionMode::Echo,
+            false,
+            None,
+            NodeOptions::default(),
+        )
+    }
+
+    /// Inicia un nodo con un ID específico y las opciones dadas.
+    pub fn init_with_options(id: NodeId, mode: ConnectionMode, options: NodeOptions) -> Result<()> {
+        Self::init(id, mode, false, None, options)
     }
 
     /// Inicia un nuevo nodo con un ID específico en modo de conexión _parsing_.
     pub fn init_new_in_parsing_mode(id: NodeId, ip: &str) -> Result<()> {
-        Self::init_new(id, ip, ConnectionMode::Parsing)
+        Self::init_new(id, ip, ConnectionMode::Parsing, NodeOptions::default())
     }
 
     /// Inicia un nuevo nodo con un ID específico en modo de conexión _echo_.
     pub fn init_new_in_echo_mode(id: NodeId, ip: &str) -> Result<()> {
-        Self::init_new(id, ip, ConnectionMode::Echo)
+        Self::init_new(id, ip, ConnectionMode::Echo, NodeOptions::default())
+    }
+
+    /// Inicia un nuevo nodo con un ID específico y las opciones dadas.
+    pub fn init_new_with_options(
+        id: NodeId,
+        ip: &str,
+        mode: ConnectionMode,
+        options: NodeOptions,
+    ) -> Result<()> {
+        Self::init_new(id, ip, mode, options)
     }
 
     /// Agrega un nuevo nodo al clúster con un ID e IP específicos.
-    fn init_new(id: NodeId, ip: &str, mode: ConnectionMode) -> Result<()> {
+    fn init_new(id: NodeId, ip: &str, mode: ConnectionMode, options: NodeOptions) -> Result<()> {
         if Self::id_exists(&id) {
             return Err(Error::ServerError(format!(
                 "El ID {id} ya está en uso por otro nodo."
@@ -221,13 +352,21 @@ impl Node {
                 )));
             }
         }
-        DiskHandler::store_new_node_id_and_ip(id, ip)?;
+        let datacenter = options.datacenter.as_deref().unwrap_or(DEFAULT_DATACENTER);
+        let rack = options.rack.as_deref().unwrap_or(DEFAULT_RACK);
+        DiskHandler::store_new_node_id_and_ip(id, ip, datacenter, rack)?;
 
-        Self::init(id, mode, true, Some(ip))
+        Self::init(id, mode, true, Some(ip), options)
     }
 
     /// Crea un nodo con un ID específico.
-    fn init(id: NodeId, mode: ConnectionMode, is_new: bool, ip: Option<&str>) -> Result<()> {
+    fn init(
+        id: NodeId,
+        mode: ConnectionMode,
+        is_new: bool,
+        ip: Option<&str>,
+        options: NodeOptions,
+    ) -> Result<()> {
         let mut nodes_weights: Vec<usize> = Vec::new();
         let (gossiper_stopper, gossiper_receiver) = channel::<bool>();
         let (beater_stopper, beater_receiver) = channel::<bool>();
@@ -247,7 +386,7 @@ impl Node {
             ],
             cli_listener_receiver,
             priv_listener_receiver,
-            ip,
+            (ip, options),
         )?;
 
         let gossiper_handle = gossiper(id, &nodes_weights, gossiper_receiver)?;
@@ -269,8 +408,9 @@ impl Node {
         stoppers: Vec<Sender<bool>>,
         cli_listener_receiver: Receiver<bool>,
         priv_listener_receiver: Receiver<bool>,
-        ip: Option<&str>,
+        ip_and_options: (Option<&str>, NodeOptions),
     ) -> Result<Vec<Option<NodeHandle>>> {
+        let (ip, options) = ip_and_options;
         let nodes_ids = Self::get_all_nodes_ids();
         if !nodes_ids.contains(&id) {
             return Err(Error::ServerError(format!(
@@ -285,10 +425,18 @@ impl Node {
         let mut node = if node_metadata_path.exists() {
             let mut node: Node = load_json(&metadata_path)?;
             node.set_default_fields(id, mode, is_new, stoppers)?;
+            node.replay_commit_log(options.commit_log_sync)?;
+            DiskHandler::migrate_csv_tables(&node.storage_addr, node.tables.values())?;
+            node.replay_batch_logs()?;
             node
         } else {
-            Self::new(id, mode, is_new, stoppers)?
+            let mut node = Self::new(id, mode, is_new, stoppers)?;
+            node.replay_commit_log(options.commit_log_sync)?;
+            node
         };
+        node.initialize_tokens(options.vnodes);
+        node.configure_repair_interval(options.repair_interval_secs);
+        node.configure_failure_detector(options.phi_convict_threshold);
         node.inicialize_nodes_weights(Self::get_all_n_nodes());
         *nodes_weights = node.nodes_weights.clone();
         // let max_weight_id = node.max_weight();
@@ -312,6 +460,7 @@ impl Node {
     }
 
     fn notify_new_node(id: NodeId, ip: Option<&str>) {
+        let location = AddrLoader::default_runtime().get_location(id);
         for node_id in Self::get_all_nodes_ids() {
             if id == node_id {
                 continue;
@@ -319,7 +468,7 @@ impl Node {
             if let Some(ip) = ip {
                 if send_to_node(
                     node_id,
-                    SvAction::SendEndpointState(id, ip.to_string()).as_bytes(),
+                    SvAction::SendEndpointState(id, ip.to_string(), location.clone()).as_bytes(),
                     PortType::Priv,
                 )
                 .is_err()
@@ -332,6 +481,69 @@ impl Node {
         }
     }
 
+    /// Abre el _commit log_ del nodo y vuelve a aplicar las escrituras que no llegaron a
+    /// volcarse a disco antes de que se apagara.
+    ///
+    /// Si no se especifica un modo de sincronización, se usa el guardado en los metadatos del nodo.
+    fn replay_commit_log(&mut self, sync_mode: Option<CommitLogSync>) -> Result<()> {
+        if let Some(sync_mode) = sync_mode {
+            self.commit_log_sync = sync_mode;
+        }
+        DiskHandler::replay_commit_log(&self.storage_addr, self.commit_log_sync)
+    }
+
+    /// Configura cada cuántos segundos el nodo repara sus réplicas por su cuenta. Con 0 se
+    /// desactivan las reparaciones programadas.
+    ///
+    /// Si no se especifica, se usa el intervalo guardado en los metadatos del nodo.
+    fn configure_repair_interval(&mut self, interval_secs: Option<Long>) {
+        if let Some(interval_secs) = interval_secs {
+            self.repair_interval_secs = (interval_secs > 0).then_some(interval_secs);
+        }
+        if self.last_scheduled_repair == 0 {
+            self.last_scheduled_repair = Utc::now().timestamp();
+        }
+    }
+
+    /// Crea el detector de fallas del nodo con el umbral de sospecha dado.
+    ///
+    /// Si no se especifica, se usa el umbral guardado en los metadatos del nodo.
+    fn configure_failure_detector(&mut self, phi_convict_threshold: Option<f64>) {
+        if phi_convict_threshold.is_some() {
+            self.phi_convict_threshold = phi_convict_threshold;
+        }
+        self.failure_detector = FailureDetector::new(
+            self.phi_convict_threshold
+                .unwrap_or(DEFAULT_PHI_CONVICT_THRESHOLD),
+        );
+    }
+
+    /// Verifica si ya corresponde hacer la reparación programada del nodo, y de ser así la
+    /// da por iniciada.
+    pub fn repair_is_due(&mut self, now: Long) -> bool {
+        match self.repair_interval_secs {
+            Some(interval_secs)
+                if self.is_responsive() && now - self.last_scheduled_repair >= interval_secs =>
+            {
+                self.last_scheduled_repair = now;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Genera los _tokens_ del nodo si todavía no los tiene, y los publica en su [EndpointState]
+    /// para que se propaguen por _gossip_.
+    fn initialize_tokens(&mut self, vnodes: Option<usize>) {
+        if self.tokens.is_empty() {
+            self.tokens = TokenRing::generate_tokens(self.id, vnodes.unwrap_or(DEFAULT_VNODES));
+        }
+        self.endpoint_state.set_tokens(self.tokens.clone());
+        self.neighbours_states
+            .insert(self.id, self.endpoint_state.clone());
+        self.refresh_token_ring();
+    }
+
     fn inicialize_nodes_weights(&mut self, actual_n_nodes: usize) {
         self.nodes_weights = vec![1; actual_n_nodes];
         if actual_n_nodes >= N_NODES as usize {
@@ -368,6 +580,26 @@ impl Node {
         Ok(())
     }
 
+    /// Le pide al nodo del ID dado que repare sus réplicas del keyspace y la tabla dados.
+    ///
+    /// Sin keyspace se reparan todos, y sin tabla todas las del keyspace.
+    pub fn repair(
+        id: NodeId,
+        keyspace_name: Option<String>,
+        table_name: Option<String>,
+    ) -> Result<()> {
+        if !Self::id_exists(&id) {
+            return Err(Error::ServerError(format!(
+                "El ID {id} no está en el archivo de IPs de los nodos."
+            )));
+        }
+        send_to_node(
+            id,
+            SvAction::Repair(keyspace_name, table_name).as_bytes(),
+            PortType::Priv,
+        )
+    }
+
     pub fn notify_node_is_gonna_be_deleted(&self, id_to_delete: NodeId) -> Result<()> {
         if !Self::id_exists(&id_to_delete) {
             return Err(Error::ServerError(format!(
@@ -417,6 +649,168 @@ impl Node {
         }
     }
 
+    /// Prepara una _query_ y la guarda en la caché del nodo, devolviendo su ID.
+    ///
+    /// Si la _query_ ya había sido preparada en el mismo _keyspace_, se reutiliza.
+    pub fn prepare_statement(
+        &mut self,
+        query: &str,
+        statement: DmlStatement,
+        keyspace: &str,
+    ) -> Result<PreparedId> {
+        let id = PreparedStatement::generate_id(query, keyspace);
+        if !self.prepared_statements.contains_key(&id) {
+            let table = self.get_table(&get_dml_statement_table_name(&statement))?;
+            let prepared = PreparedStatement::new(query, statement, keyspace, table)?;
+            self.prepared_statements.insert(id.clone(), prepared);
+        }
+        Ok(id)
+    }
+
+    /// Busca una _query_ preparada dado su ID.
+    pub fn get_prepared_statement(&self, id: &[Byte]) -> Option<&PreparedStatement> {
+        self.prepared_statements.get(id)
+    }
+
+    /// Borra de la caché las _queries_ preparadas sobre una tabla que cambió o dejó de existir,
+    /// ya que pueden haber quedado con columnas viejas.
+    ///
+    /// Quien intente ejecutarlas recibe un error `Unprepared`, y debe volver a prepararlas.
+    fn forget_prepared_statements(&mut self, table_name: &str) {
+        self.prepared_statements
+            .retain(|_, prepared| prepared.get_table_name() != table_name);
+    }
+
+    /// Vincula los marcadores de una declaración DML con los valores recibidos junto a su
+    /// _query_, según los tipos de las columnas de su tabla.
+    pub fn bind_statement(
+        &self,
+        query: &str,
+        statement: DmlStatement,
+        values: &[Vec<Byte>],
+        names: Option<&[String]>,
+    ) -> Result<DmlStatement> {
+        if statement.bind_marker_slots()?.is_empty() {
+            if !values.is_empty() {
+                return Err(Error::Invalid(format!(
+                    "La query no tiene marcadores, pero se recibieron {} valores",
+                    values.len()
+                )));
+            }
+            return Ok(statement);
+        }
+        let keyspace = self.get_default_keyspace_name()?;
+        let table = self.get_table(&get_dml_statement_table_name(&statement))?;
+        PreparedStatement::new(query, statement, &keyspace, table)?.bind(values, names)
+    }
+
+    /// Registra a un cliente para que reciba los eventos de los tipos dados.
+    ///
+    /// Si el cliente ya estaba registrado, se suman los nuevos tipos a los que ya escuchaba.
+    pub fn register_event_listener(
+        &mut self,
+        client_addr: SocketAddr,
+        event_types: Vec<String>,
+        sender: Sender<Vec<Byte>>,
+    ) {
+        let (listened_types, listener_sender) = self
+            .event_listeners
+            .entry(client_addr)
+            .or_insert_with(|| (HashSet::new(), sender.clone()));
+        listened_types.extend(event_types);
+        *listener_sender = sender;
+    }
+
+    /// Envía un evento a todos los clientes registrados a su tipo.
+    ///
+    /// Los clientes cuyo canal ya está cerrado se dan de baja.
+    pub fn notify_event(&mut self, event: EventType) {
+        let event_bytes = Self::create_event(&event);
+        self.event_listeners.retain(|_, (listened_types, sender)| {
+            !listened_types.contains(event.get_name()) || sender.send(event_bytes.clone()).is_ok()
+        });
+    }
+
+    /// Crea un mensaje EVENT, que siempre tiene el stream `-1`.
+    pub fn create_event(event: &EventType) -> Vec<Byte> {
+        let mut body = encode_string_to_bytes(event.get_name());
+        body.extend(event.as_bytes());
+
+        let mut response: Vec<Byte> = Vec::new();
+        response.append(&mut Version::ResponseV5.as_bytes());
+        response.append(&mut Flag::Default.as_bytes());
+        response.append(&mut Stream::new(-1).as_bytes());
+        response.append(&mut Opcode::Event.as_bytes());
+        response.append(&mut Length::new(body.len() as Uint).as_bytes());
+        response.append(&mut body);
+        response
+    }
+
+    /// Notifica a los clientes registrados si el cambio de estado de un vecino implica
+    /// un cambio de topología o de estado del clúster.
+    fn notify_neighbour_change(
+        &mut self,
+        node_id: NodeId,
+        old_status: Option<AppStatus>,
+        new_state: &EndpointState,
+    ) {
+        if node_id == self.id {
+            return;
+        }
+        let addr = *new_state.get_addr();
+        let new_status = new_state.get_appstate_status();
+        let is_gone = |status: &AppStatus| matches!(status, AppStatus::Left | AppStatus::Remove);
+        let event = match old_status {
+            None if !is_gone(new_status) => EventType::TopologyChange("NEW_NODE".to_string(), addr),
+            Some(old) if !is_gone(&old) && is_gone(new_status) => {
+                EventType::TopologyChange("REMOVED_NODE".to_string(), addr)
+            }
+            Some(old) if old != AppStatus::Offline && *new_status == AppStatus::Offline => {
+                EventType::StatusChange("DOWN".to_string(), addr)
+            }
+            Some(AppStatus::Offline)
+                if *new_status != AppStatus::Offline && !is_gone(new_status) =>
+            {
+                EventType::StatusChange("UP".to_string(), addr)
+            }
+            _ => return,
+        };
+        self.notify_event(event);
+    }
+
+    /// Notifica a los clientes registrados un cambio en un _keyspace_ o tabla.
+    fn notify_schema_change(
+        &mut self,
+        change_type: SchemaChangeType,
+        keyspace_name: &str,
+        table_name: Option<&str>,
+    ) {
+        self.notify_event(Self::schema_change_event(
+            change_type,
+            keyspace_name,
+            table_name,
+        ));
+    }
+
+    /// Arma el evento de un cambio de _schema_ sobre un _keyspace_ o, si se indica, sobre una tabla.
+    fn schema_change_event(
+        change_type: SchemaChangeType,
+        keyspace_name: &str,
+        table_name: Option<&str>,
+    ) -> EventType {
+        let (target, option) = match table_name {
+            Some(table_name) => (
+                SchemaChangeTarget::Table,
+                SchemaChangeOption::TableOrType(keyspace_name.to_string(), table_name.to_string()),
+            ),
+            None => (
+                SchemaChangeTarget::Keyspace,
+                SchemaChangeOption::Keyspace(keyspace_name.to_string()),
+            ),
+        };
+        EventType::SchemaChange(change_type, target, option)
+    }
+
     /// Responde si una tabla existe o no dado su nombre.
     pub fn table_exists(&self, table_name: &str) -> bool {
         self.tables.contains_key(table_name)
@@ -572,20 +966,57 @@ impl Node {
         AddrLoader::default_runtime().get_ips().contains(ip)
     }
 
-    /// Selecciona un ID de nodo conforme al _hashing_ del valor del _partition key_ y los rangos de los nodos.
+    /// Selecciona un ID de nodo conforme al _hashing_ del valor del _partition key_ y el anillo de _tokens_.
     pub fn select_node(&self, value: &str) -> NodeId {
-        let nodes_ids = self.get_nodes_ids();
-        let hash_val = hash_value(value);
+        let token = hash_value(value);
+        let owner = if self.token_ring.is_empty() {
+            Self::build_token_ring(&self.get_token_ring_members()).owner_of(token)
+        } else {
+            self.token_ring.owner_of(token)
+        };
+        owner.unwrap_or(self.id)
+    }
 
-        let nodes_ranges = divide_range(0, NODES_RANGE_END, nodes_ids.len());
-        let mut i = 0;
-        for (a, b) in nodes_ranges {
-            if a <= hash_val && hash_val < b {
-                return nodes_ids[i];
-            }
-            i += 1;
+    /// Devuelve los _tokens_ de un nodo dado.
+    ///
+    /// Si todavía no se recibieron por _gossip_, se asume que el nodo usa la cantidad de nodos
+    /// virtuales por defecto.
+    fn get_tokens_of(&self, node_id: NodeId) -> Vec<Token> {
+        if node_id == self.id {
+            return self.tokens.clone();
+        }
+        match self.neighbours_states.get(&node_id) {
+            Some(state) if !state.get_tokens().is_empty() => state.get_tokens().to_vec(),
+            _ => TokenRing::generate_tokens(node_id, DEFAULT_VNODES),
+        }
+    }
+
+    /// Devuelve los nodos actuales del clúster, con sus _tokens_.
+    fn get_token_ring_members(&self) -> Vec<(NodeId, Vec<Token>)> {
+        self.get_nodes_ids()
+            .into_iter()
+            .map(|node_id| (node_id, self.get_tokens_of(node_id)))
+            .collect()
+    }
+
+    /// Arma el anillo de _tokens_ con los nodos dados.
+    fn build_token_ring(members: &[(NodeId, Vec<Token>)]) -> TokenRing {
+        let mut token_ring = TokenRing::new();
+        for (node_id, tokens) in members {
+            token_ring.add_node(*node_id, tokens);
+        }
+        token_ring
+    }
+
+    /// Vuelve a armar el anillo de _tokens_, sólo si cambiaron los nodos del clúster o sus
+    /// _tokens_ desde la última vez que se armó.
+    fn refresh_token_ring(&mut self) {
+        let members = self.get_token_ring_members();
+        if members == self.token_ring_members && !self.token_ring.is_empty() {
+            return;
         }
-        nodes_ids[i]
+        self.token_ring = Self::build_token_ring(&members);
+        self.token_ring_members = members;
     }
 
     /// Compara si el _heartbeat_ de un nodo es más nuevo que otro.
@@ -594,11 +1025,14 @@ impl Node {
     }
 
     /// Envia su endpoint state al nodo del ID correspondiente.
-    pub fn send_endpoint_state(&self, id: NodeId, ip: String) {
+    ///
+    /// Antes lo agrega al archivo de IPs con la ubicación `(datacenter, rack)` que declaró.
+    pub fn send_endpoint_state(&self, id: NodeId, ip: String, location: (String, String)) {
         if Self::id_exists(&id) {
             return;
         }
-        let _ = DiskHandler::store_new_node_id_and_ip(id, &ip);
+        let (datacenter, rack) = &location;
+        let _ = DiskHandler::store_new_node_id_and_ip(id, &ip, datacenter, rack);
         let _ = send_to_node(
             id,
             SvAction::NewNeighbour(self.id, self.get_endpoint_state().clone()).as_bytes(),
@@ -622,11 +1056,11 @@ impl Node {
             )
         });
         if !self.is_new_node {
-            self.send_new_neighbours(id, ip);
+            self.send_new_neighbours(id, ip, location);
         }
     }
 
-    fn send_new_neighbours(&self, new_id: NodeId, new_ip: String) {
+    fn send_new_neighbours(&self, new_id: NodeId, new_ip: String, location: (String, String)) {
         let mut new_nodes = NodesMap::new();
         let original_nodes = AddrLoader::default_runtime().get_ids();
         for (node_id, endpoint_state) in &self.neighbours_states {
@@ -637,7 +1071,7 @@ impl Node {
         for (node_id, _endpoint_state) in new_nodes {
             let _ = send_to_node(
                 node_id,
-                SvAction::SendEndpointState(new_id, new_ip.clone()).as_bytes(),
+                SvAction::SendEndpointState(new_id, new_ip.clone(), location.clone()).as_bytes(),
                 PortType::Priv,
             )
             .inspect_err(|e| {
@@ -679,12 +1113,14 @@ impl Node {
                         .map_err(|e: std::num::ParseIntError| Error::ServerError(e.to_string()))?;
                     DiskHandler::delete_node_id_and_ip(node_id)?;
                     self.neighbours_states.remove(&node_id);
+                    self.failure_detector.forget(node_id);
                     if let Some(index) = self.nodes_weights.iter().position(|&x| x == 1) {
                         self.nodes_weights.remove(index);
                     }
                 }
             }
         }
+        self.refresh_token_ring();
         Ok(())
     }
 
@@ -740,7 +1176,12 @@ impl Node {
             && *state.get_appstate().get_status() != AppStatus::Remove
             && *state.get_appstate().get_status() != AppStatus::Offline
         {
-            DiskHandler::store_new_node_id_and_ip(id, state.get_addr().to_string().as_str())?;
+            DiskHandler::store_new_node_id_and_ip(
+                id,
+                state.get_addr().to_string().as_str(),
+                state.get_datacenter(),
+                state.get_rack(),
+            )?;
             actual_n_nodes = self.get_actual_n_nodes();
         }
         if !self.has_endpoint_state_by_id(&id)
@@ -750,8 +1191,10 @@ impl Node {
             if actual_n_nodes > N_NODES as usize && self.nodes_weights.len() < actual_n_nodes {
                 self.nodes_weights.push(1);
             }
+            self.notify_neighbour_change(id, None, &state);
             self.neighbours_states.insert(id, state);
         }
+        self.refresh_token_ring();
         Ok(())
     }
 
@@ -772,6 +1215,8 @@ impl Node {
                 DiskHandler::store_new_node_id_and_ip(
                     node_id,
                     endpoint_state.get_addr().to_string().as_str(),
+                    endpoint_state.get_datacenter(),
+                    endpoint_state.get_rack(),
                 )?;
                 actual_n_nodes = self.get_actual_n_nodes();
             }
@@ -794,16 +1239,68 @@ impl Node {
                 continue;
             }
 
+            let old_status = self
+                .neighbours_states
+                .get(&node_id)
+                .map(|old_state| old_state.get_appstate_status().clone());
+            self.notify_neighbour_change(node_id, old_status, &endpoint_state);
             self.neighbours_states.insert(node_id, endpoint_state);
         }
+        self.refresh_token_ring();
         Ok(())
     }
 
+    /// Registra en el detector de fallas los _heartbeats_ de otros nodos recibidos por _gossip_.
+    pub fn report_heartbeats<'a>(
+        &mut self,
+        heartbeats: impl IntoIterator<Item = (&'a NodeId, &'a HeartbeatState)>,
+    ) {
+        let now = Instant::now();
+        for (node_id, heartbeat) in heartbeats {
+            if *node_id != self.id {
+                self.failure_detector.report(*node_id, heartbeat, now);
+            }
+        }
+    }
+
+    /// Verifica si el detector de fallas considera vivo a un nodo.
+    pub fn is_alive(&self, node_id: NodeId) -> bool {
+        node_id == self.id || self.failure_detector.is_alive(node_id, Instant::now())
+    }
+
+    /// Marca como caídos _(Offline)_ a los vecinos cuyo nivel de sospecha superó el umbral
+    /// del detector de fallas, y devuelve sus IDs.
+    ///
+    /// Vuelven a estar disponibles cuando llega por _gossip_ un estado suyo más nuevo.
+    pub fn convict_dead_neighbours(&mut self) -> Vec<NodeId> {
+        let now = Instant::now();
+        let convicted: Vec<NodeId> = self
+            .neighbours_states
+            .iter()
+            .filter(|(node_id, state)| {
+                **node_id != self.id
+                    && !matches!(
+                        state.get_appstate_status(),
+                        AppStatus::Offline | AppStatus::Left | AppStatus::Remove
+                    )
+                    && !self.failure_detector.is_alive(**node_id, now)
+            })
+            .map(|(node_id, _)| *node_id)
+            .collect();
+        for node_id in &convicted {
+            self.acknowledge_offline_neighbour(*node_id);
+        }
+        convicted
+    }
+
     /// Actualiza el estado del nodo recibido a _Offline_.
     pub fn acknowledge_offline_neighbour(&mut self, node_id: NodeId) {
         if let Some(endpoint_state) = self.neighbours_states.get_mut(&node_id) {
-            if *endpoint_state.get_appstate_status() != AppStatus::Remove {
+            let old_status = endpoint_state.get_appstate_status().clone();
+            if old_status != AppStatus::Remove {
                 endpoint_state.set_appstate_status(AppStatus::Offline);
+                let new_state = endpoint_state.clone();
+                self.notify_neighbour_change(node_id, Some(old_status), &new_state);
             }
         }
     }
@@ -814,10 +1311,13 @@ impl Node {
     }
 
     /// Avanza el tiempo para el nodo.
+    ///
+    /// Aprovecha para volver a armar el anillo de _tokens_ si cambiaron los nodos del clúster.
     pub fn beat(&mut self) -> VerType {
         self.endpoint_state.beat();
         self.neighbours_states
             .insert(self.id, self.endpoint_state.clone());
+        self.refresh_token_ring();
         self.get_beat().1
     }
 
@@ -865,15 +1365,34 @@ impl Node {
                     "No se paso metadata necesaria".to_string(),
                 )),
             },
-            DdlStatement::AlterTableStatement(_alter_table) => Err(Error::Invalid(
-                "Alter Table Statement no está soportado.".to_string(),
-            )),
-            DdlStatement::DropTableStatement(_drop_table) => Err(Error::Invalid(
-                "Drop Table Statement no está soportado.".to_string(),
-            )),
-            DdlStatement::TruncateStatement(_truncate) => Err(Error::Invalid(
-                "Truncate Statement no está soportado.".to_string(),
-            )),
+            DdlStatement::AlterTableStatement(alter_table) => {
+                self.process_internal_alter_table_statement(&alter_table)
+            }
+            DdlStatement::DropTableStatement(drop_table) => {
+                self.process_internal_drop_table_statement(&drop_table)
+            }
+            DdlStatement::TruncateStatement(truncate) => {
+                self.process_internal_truncate_statement(&truncate)
+            }
+            DdlStatement::CreateIndexStatement(create_index) => {
+                self.process_internal_create_index_statement(&create_index)
+            }
+            DdlStatement::DropIndexStatement(drop_index) => {
+                self.process_internal_drop_index_statement(&drop_index)
+            }
+            DdlStatement::CreateMaterializedViewStatement(create_view) => {
+                match internal_metadata.1 {
+                    Some(node_number) => {
+                        self.process_internal_create_view_statement(&create_view, node_number)
+                    }
+                    None => Err(Error::ServerError(
+                        "No se paso metadata necesaria".to_string(),
+                    )),
+                }
+            }
+            DdlStatement::DropMaterializedViewStatement(drop_view) => {
+                self.process_internal_drop_view_statement(&drop_view)
+            }
         }
     }
 
@@ -909,7 +1428,11 @@ impl Node {
         create_keyspace: &CreateKeyspace,
     ) -> Result<Vec<Byte>> {
         match DiskHandler::create_keyspace(create_keyspace, &self.storage_addr) {
-            Ok(Some(keyspace)) => self.add_keyspace(keyspace),
+            Ok(Some(keyspace)) => {
+                let keyspace_name = keyspace.get_name().to_string();
+                self.add_keyspace(keyspace);
+                self.notify_schema_change(SchemaChangeType::Created, &keyspace_name, None);
+            }
             Ok(None) => return Ok(Self::create_result_void()),
             Err(err) => return Err(err),
         };
@@ -929,6 +1452,7 @@ impl Node {
                 {
                     keyspace.set_replication(new_replication);
                 }
+                self.notify_schema_change(SchemaChangeType::Updated, keyspace_name, None);
                 Ok(Self::create_result_void())
             }
             None => {
@@ -952,7 +1476,10 @@ impl Node {
         if self.keyspaces.contains_key(keyspace_name) {
             self.keyspaces.remove(keyspace_name);
             match DiskHandler::drop_keyspace(keyspace_name, &self.storage_addr) {
-                Ok(_) => Ok(Self::create_result_void()),
+                Ok(_) => {
+                    self.notify_schema_change(SchemaChangeType::Dropped, keyspace_name, None);
+                    Ok(Self::create_result_void())
+                }
                 Err(e) => Err(e),
             }
         } else if drop_keyspace.if_exists {
@@ -979,7 +1506,18 @@ impl Node {
             node_number,
         ) {
             Ok(Some(table)) => {
+                // La tabla se crea una vez por cada réplica, pero se notifica sólo la primera
+                let is_new_table = !self.table_exists(table.get_name());
+                let keyspace_name = table.get_keyspace().to_string();
+                let table_name = table.get_name().to_string();
                 self.add_table(table);
+                if is_new_table {
+                    self.notify_schema_change(
+                        SchemaChangeType::Created,
+                        &keyspace_name,
+                        Some(&table_name),
+                    );
+                }
             }
             Ok(None) => return Err(Error::ServerError("No se pudo crear la tabla".to_string())),
             Err(err) => return Err(err),
@@ -987,60 +1525,367 @@ impl Node {
         Ok(Self::create_result_void())
     }
 
-    /// Maneja una declaración DML interna.
-    pub fn handle_internal_dml_statement(
+    /// Procesa una declaración ALTER TABLE interna.
+    ///
+    /// Se actualizan los metadatos de la tabla y los archivos de todas sus réplicas en el nodo.
+    pub fn process_internal_alter_table_statement(
         &mut self,
-        dml_statement: DmlStatement,
-        internal_metadata: (Option<Long>, Option<Byte>),
+        alter_table: &AlterTable,
     ) -> Result<Vec<Byte>> {
-        let node_number = get_node_replica_number_from_internal_metadata(internal_metadata)?;
-        match dml_statement {
-            DmlStatement::SelectStatement(select) => self.process_select(&select, node_number),
-            DmlStatement::InsertStatement(insert) => {
-                let timestamp = get_timestamp_from_internal_metadata(internal_metadata)?;
-                self.process_insert(&insert, timestamp, node_number)
+        let table_name = alter_table.name.get_name();
+        self.check_alter_table_with_views(alter_table)?;
+        let table = match self.tables.get_mut(&table_name) {
+            Some(table) => table,
+            None if alter_table.name.if_exists => return Ok(Self::create_result_void()),
+            None => {
+                return Err(Error::ServerError(format!(
+                    "La tabla {table_name} no existe"
+                )))
             }
-            DmlStatement::UpdateStatement(update) => {
-                let timestamp = get_timestamp_from_internal_metadata(internal_metadata)?;
-                self.process_update(&update, timestamp, node_number)
+        };
+        let keyspace_name = table.get_keyspace().to_string();
+        if DiskHandler::alter_table(table, &alter_table.instruction, &self.storage_addr)? {
+            self.forget_prepared_statements(&table_name);
+            self.notify_schema_change(SchemaChangeType::Updated, &keyspace_name, Some(&table_name));
+        }
+        Ok(Self::create_result_void())
+    }
+
+    /// Verifica que un ALTER TABLE sólo cambie las opciones de una vista materializada, y que no
+    /// borre o renombre columnas que usa alguna vista de la tabla.
+    pub fn check_alter_table_with_views(&self, alter_table: &AlterTable) -> Result<()> {
+        let table_name = alter_table.name.get_name();
+        if self.tables.get(&table_name).is_some_and(|t| t.is_view())
+            && !matches!(
+                alter_table.instruction,
+                AlterTableInstruction::WithOptions(..)
+            )
+        {
+            return Err(Error::Invalid(format!(
+                "De la vista materializada {table_name} sólo se pueden alterar sus opciones"
+            )));
+        }
+        let columns: Vec<&String> = match &alter_table.instruction {
+            AlterTableInstruction::DropColumns(_, columns) => columns.iter().collect(),
+            AlterTableInstruction::RenameColumns(_, renames) => {
+                renames.iter().map(|(column, _)| column).collect()
+            }
+            _ => return Ok(()),
+        };
+        for view_name in self.get_views_names(&table_name) {
+            let view = self.get_table(&view_name)?;
+            if let Some(column) = columns
+                .iter()
+                .find(|column| view.get_columns_names().contains(column))
+            {
+                return Err(Error::Invalid(format!(
+                    "No se puede modificar la columna {column} porque la usa la vista materializada {view_name}"
+                )));
             }
-            DmlStatement::DeleteStatement(delete) => self.process_delete(&delete, node_number),
         }
+        Ok(())
     }
 
-    /// Procesa una declaración SELECT.
-    pub fn process_select(&self, select: &Select, node_id: Byte) -> Result<Vec<Byte>> {
-        let table = self.get_table(&select.from.get_name())?;
+    /// Procesa una declaración DROP TABLE interna.
+    ///
+    /// Se borran los archivos de todas las réplicas de la tabla en el nodo junto a sus metadatos.
+    pub fn process_internal_drop_table_statement(
+        &mut self,
+        drop_table: &DropTable,
+    ) -> Result<Vec<Byte>> {
+        let table_name = drop_table.table_name.get_name();
+        match self.tables.get(&table_name) {
+            Some(table) if table.is_view() => {
+                return Err(Error::Invalid(format!(
+                    "{table_name} es una vista materializada, se debe usar DROP MATERIALIZED VIEW"
+                )))
+            }
+            Some(_) => {
+                let views = self.get_views_names(&table_name);
+                if !views.is_empty() {
+                    return Err(Error::Invalid(format!(
+                        "No se puede borrar la tabla {table_name} porque tiene las vistas materializadas {}",
+                        views.join(", ")
+                    )));
+                }
+            }
+            None => {}
+        }
+        self.drop_table(&table_name, drop_table.table_name.if_exists)
+    }
 
-        // SIEMPRE ANTES DE UN DISKHANDLER HACER UN LOCK/WRITE
-        let mut res = DiskHandler::do_select(
-            select,
-            &self.storage_addr,
-            table,
-            &self.get_default_keyspace_name()?,
-            node_id,
-        )?;
+    /// Procesa una declaración DROP MATERIALIZED VIEW interna.
+    ///
+    /// Se borran las réplicas de la vista en el nodo, conservando las de su tabla base.
+    pub fn process_internal_drop_view_statement(
+        &mut self,
+        drop_view: &DropMaterializedView,
+    ) -> Result<Vec<Byte>> {
+        let view_name = drop_view.view_name.get_name();
+        if self
+            .tables
+            .get(&view_name)
+            .is_some_and(|table| !table.is_view())
+        {
+            return Err(Error::Invalid(format!(
+                "{view_name} no es una vista materializada"
+            )));
+        }
+        self.drop_table(&view_name, drop_view.view_name.if_exists)
+    }
 
-        Ok(Self::create_result_select(&mut res))
+    fn drop_table(&mut self, table_name: &str, if_exists: bool) -> Result<Vec<Byte>> {
+        let table = match self.tables.get(table_name) {
+            Some(table) => table,
+            None if if_exists => return Ok(Self::create_result_void()),
+            None => {
+                return Err(Error::ServerError(format!(
+                    "La tabla {table_name} no existe"
+                )))
+            }
+        };
+        let keyspace_name = table.get_keyspace().to_string();
+        DiskHandler::drop_table(table, &self.storage_addr)?;
+
+        self.tables.remove(table_name);
+        self.tables_and_partitions_keys_values.remove(table_name);
+        self.forget_prepared_statements(table_name);
+        self.notify_schema_change(SchemaChangeType::Dropped, &keyspace_name, Some(table_name));
+        Ok(Self::create_result_schema_change(
+            SchemaChangeType::Dropped,
+            &keyspace_name,
+            Some(table_name),
+        ))
     }
 
-    /// Crea un result de tipo select.
-    pub fn create_result_select(res: &mut Vec<Byte>) -> Vec<Byte> {
-        let mut response: Vec<Byte> = Vec::new();
-        response.append(&mut Version::ResponseV5.as_bytes());
-        response.append(&mut Flag::Default.as_bytes());
-        response.append(&mut Stream::new(0).as_bytes());
-        response.append(&mut Opcode::Result.as_bytes());
-        response.append(&mut Length::new(res.len() as Uint).as_bytes());
-        response.append(res);
-        response
+    /// Procesa una declaración TRUNCATE interna.
+    ///
+    /// Se borran todas las filas de las réplicas de la tabla en el nodo, manteniendo su estructura.
+    pub fn process_internal_truncate_statement(
+        &mut self,
+        truncate: &Truncate,
+    ) -> Result<Vec<Byte>> {
+        let table_name = truncate.table_name.get_name();
+        let table = match self.tables.get(&table_name) {
+            Some(table) => table,
+            None if truncate.table_name.if_exists => return Ok(Self::create_result_void()),
+            None => {
+                return Err(Error::ServerError(format!(
+                    "La tabla {table_name} no existe"
+                )))
+            }
+        };
+        if table.is_view() {
+            return Err(Error::Invalid(format!(
+                "No se puede truncar la vista materializada {table_name}, sino su tabla base"
+            )));
+        }
+        // Las vistas se vacían junto a su tabla base
+        let mut truncated_tables = self.get_views_names(&table_name);
+        truncated_tables.push(table_name);
+        for table_name in truncated_tables {
+            DiskHandler::truncate_table(self.get_table(&table_name)?, &self.storage_addr)?;
+            if let Some(partition_keys) =
+                self.tables_and_partitions_keys_values.get_mut(&table_name)
+            {
+                partition_keys.clear();
+            }
+        }
+        Ok(Self::create_result_void())
     }
 
-    /// Procesa una declaración INSERT.
-    pub fn process_insert(
+    /// Procesa una declaración CREATE MATERIALIZED VIEW interna.
+    ///
+    /// Se crea la réplica dada de la vista vacía; sus filas las escribe el coordinador a partir
+    /// de las de la tabla base.
+    pub fn process_internal_create_view_statement(
         &mut self,
-        insert: &Insert,
-        timestamp: Long,
+        create_view: &CreateMaterializedView,
+        node_number: Byte,
+    ) -> Result<Vec<Byte>> {
+        let base_table_name = create_view.base_table.get_name();
+        let base_table = self.get_table(&base_table_name)?;
+        if base_table.is_view() {
+            return Err(Error::Invalid(format!(
+                "No se puede crear una vista materializada de la vista {base_table_name}"
+            )));
+        }
+        let view =
+            DiskHandler::create_view(create_view, base_table, &self.storage_addr, node_number)?;
+
+        // Igual que las tablas, la vista se crea una vez por cada réplica
+        let is_new_view = !self.table_exists(view.get_name());
+        let keyspace_name = view.get_keyspace().to_string();
+        let view_name = view.get_name().to_string();
+        self.add_table(view);
+        if is_new_view {
+            self.notify_schema_change(SchemaChangeType::Created, &keyspace_name, Some(&view_name));
+        }
+        Ok(Self::create_result_void())
+    }
+
+    /// Obtiene los nombres de las vistas materializadas de la tabla base dada.
+    pub fn get_views_names(&self, base_table: &str) -> Vec<String> {
+        let mut views: Vec<String> = self
+            .tables
+            .values()
+            .filter(|table| table.is_view_of(base_table))
+            .map(|table| table.get_name().to_string())
+            .collect();
+        views.sort();
+        views
+    }
+
+    /// Procesa una declaración CREATE INDEX interna.
+    ///
+    /// Se indexan las filas de todas las réplicas de la tabla en el nodo.
+    pub fn process_internal_create_index_statement(
+        &mut self,
+        create_index: &CreateIndex,
+    ) -> Result<Vec<Byte>> {
+        let table_name = create_index.table_name.get_name();
+        let index_name = create_index.get_index_name();
+        let keyspace_name = self.get_table(&table_name)?.get_keyspace().to_string();
+        // Los nombres de los índices no se pueden repetir dentro de un keyspace
+        if let Some(other_table) = self
+            .get_index_table_name(Some(&keyspace_name), &index_name)
+            .filter(|other_table| other_table != &table_name)
+        {
+            if create_index.if_not_exists {
+                return Ok(Self::create_result_void());
+            }
+            return Err(Error::Invalid(format!(
+                "Ya existe el índice {index_name} en la tabla {other_table}"
+            )));
+        }
+        let table = match self.tables.get_mut(&table_name) {
+            Some(table) => table,
+            None => {
+                return Err(Error::ServerError(format!(
+                    "La tabla {table_name} no existe"
+                )))
+            }
+        };
+        if DiskHandler::create_index(table, create_index, &self.storage_addr)? {
+            self.notify_schema_change(SchemaChangeType::Updated, &keyspace_name, Some(&table_name));
+        }
+        Ok(Self::create_result_void())
+    }
+
+    /// Procesa una declaración DROP INDEX interna.
+    ///
+    /// Se borran las réplicas del índice en el nodo, conservando las filas de la tabla.
+    pub fn process_internal_drop_index_statement(
+        &mut self,
+        drop_index: &DropIndex,
+    ) -> Result<Vec<Byte>> {
+        let index_name = drop_index.name.get_name();
+        let table_name = match self.get_index_table_name(drop_index.keyspace.as_deref(), index_name)
+        {
+            Some(table_name) => table_name,
+            None if drop_index.if_exists => return Ok(Self::create_result_void()),
+            None => return Err(Error::Invalid(format!("El índice {index_name} no existe"))),
+        };
+        let table = match self.tables.get_mut(&table_name) {
+            Some(table) => table,
+            None => {
+                return Err(Error::ServerError(format!(
+                    "La tabla {table_name} no existe"
+                )))
+            }
+        };
+        let keyspace_name = table.get_keyspace().to_string();
+        DiskHandler::drop_index(table, index_name, &self.storage_addr)?;
+        self.notify_schema_change(SchemaChangeType::Updated, &keyspace_name, Some(&table_name));
+        Ok(Self::create_result_void())
+    }
+
+    /// Obtiene el nombre de la tabla a la que pertenece un índice secundario, buscándolo en el
+    /// keyspace dado o, si no se indica uno, en el keyspace por defecto.
+    pub fn get_index_table_name(&self, keyspace: Option<&str>, index_name: &str) -> Option<String> {
+        let keyspace = match keyspace {
+            Some(keyspace) => Some(keyspace.to_string()),
+            None => self.get_default_keyspace_name().ok(),
+        };
+        self.tables
+            .values()
+            .filter(|table| {
+                keyspace
+                    .as_deref()
+                    .is_none_or(|k| table.get_keyspace() == k)
+            })
+            .find(|table| table.get_index_column(index_name).is_some())
+            .map(|table| table.get_name().to_string())
+    }
+
+    /// Maneja una declaración DML interna.
+    ///
+    /// La página sólo se tiene en cuenta en los SELECT.
+    pub fn handle_internal_dml_statement(
+        &mut self,
+        dml_statement: DmlStatement,
+        internal_metadata: (Option<Long>, Option<Byte>),
+        page: Option<&Page>,
+    ) -> Result<Vec<Byte>> {
+        let node_number = get_node_replica_number_from_internal_metadata(internal_metadata)?;
+        match dml_statement {
+            DmlStatement::SelectStatement(select) => {
+                self.process_select(&select, node_number, page)
+            }
+            DmlStatement::InsertStatement(insert) => {
+                let timestamp = get_timestamp_from_internal_metadata(internal_metadata)?;
+                self.process_insert(&insert, timestamp, node_number)
+            }
+            DmlStatement::UpdateStatement(update) => {
+                let timestamp = get_timestamp_from_internal_metadata(internal_metadata)?;
+                self.process_update(&update, timestamp, node_number)
+            }
+            DmlStatement::DeleteStatement(delete) => {
+                let timestamp = get_timestamp_from_internal_metadata(internal_metadata)?;
+                self.process_delete(&delete, timestamp, node_number)
+            }
+        }
+    }
+
+    /// Procesa una declaración SELECT, devolviendo sólo la página pedida si la hay.
+    pub fn process_select(
+        &self,
+        select: &Select,
+        node_id: Byte,
+        page: Option<&Page>,
+    ) -> Result<Vec<Byte>> {
+        let table = self.get_table(&select.from.get_name())?;
+
+        // SIEMPRE ANTES DE UN DISKHANDLER HACER UN LOCK/WRITE
+        let mut res = DiskHandler::do_select(
+            select,
+            &self.storage_addr,
+            table,
+            &self.get_default_keyspace_name()?,
+            node_id,
+            page,
+        )?;
+
+        Ok(Self::create_result_select(&mut res))
+    }
+
+    /// Crea un result de tipo select.
+    pub fn create_result_select(res: &mut Vec<Byte>) -> Vec<Byte> {
+        let mut response: Vec<Byte> = Vec::new();
+        response.append(&mut Version::ResponseV5.as_bytes());
+        response.append(&mut Flag::Default.as_bytes());
+        response.append(&mut Stream::new(0).as_bytes());
+        response.append(&mut Opcode::Result.as_bytes());
+        response.append(&mut Length::new(res.len() as Uint).as_bytes());
+        response.append(res);
+        response
+    }
+
+    /// Procesa una declaración INSERT.
+    pub fn process_insert(
+        &mut self,
+        insert: &Insert,
+        timestamp: Long,
         node_number: Byte,
     ) -> Result<Vec<Byte>> {
         let table = self.get_table(&insert.table.get_name())?;
@@ -1108,7 +1953,12 @@ impl Node {
     }
 
     /// Procesa una declaración DELETE.
-    pub fn process_delete(&mut self, delete: &Delete, node_number: Byte) -> Result<Vec<Byte>> {
+    pub fn process_delete(
+        &mut self,
+        delete: &Delete,
+        timestamp: Long,
+        node_number: Byte,
+    ) -> Result<Vec<Byte>> {
         let table = self.get_table(&delete.from.get_name())?;
 
         DiskHandler::do_delete(
@@ -1116,12 +1966,116 @@ impl Node {
             &self.storage_addr,
             table,
             &self.get_default_keyspace_name()?,
+            timestamp,
             node_number,
         )?;
 
         Ok(Self::create_result_void())
     }
 
+    /// Aplica en este nodo las _queries_ de un BATCH enviado por el coordinador.
+    ///
+    /// Si el BATCH es de tipo [Logged](BatchType::Logged), se guarda en el _batch log_ antes de
+    /// aplicarse y se borra recién cuando todas sus _queries_ fueron aplicadas.
+    pub fn process_internal_batch(&mut self, request: &[Byte]) -> Result<Vec<Byte>> {
+        let (batch_type, statements, timestamp) = self.parse_internal_batch(request)?;
+        let is_logged = batch_type == BatchType::Logged;
+        let batch_id = format!("{:016x}", hash_value((timestamp, request)));
+
+        if is_logged {
+            DiskHandler::store_batch_log(&self.storage_addr, &batch_id, request)?;
+        }
+        self.apply_batch(statements, timestamp)?;
+        if is_logged {
+            DiskHandler::delete_batch_log(&self.storage_addr, &batch_id)?;
+        }
+        Ok(Self::create_result_void())
+    }
+
+    /// Vuelve a aplicar los BATCH que quedaron a medio aplicar en el _batch log_. Se llama al
+    /// iniciar el nodo y periódicamente mientras está activo.
+    ///
+    /// Los que no se puedan aplicar quedan en el _batch log_ para el próximo intento.
+    pub fn replay_batch_logs(&mut self) -> Result<()> {
+        for (batch_id, request) in DiskHandler::get_pending_batch_logs(&self.storage_addr)? {
+            let applied = self
+                .parse_internal_batch(&request)
+                .and_then(|(_, statements, timestamp)| self.apply_batch(statements, timestamp));
+            if applied.is_ok() {
+                DiskHandler::delete_batch_log(&self.storage_addr, &batch_id)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Parsea un BATCH interno, de la forma `<frame><timestamp><node_id_1>...<node_id_n>`, donde
+    /// cada `<node_id>` indica a qué nodo pertenece la partición de cada _query_.
+    ///
+    /// Verifica que todas las _queries_ sean válidas antes de aplicar cualquiera de ellas.
+    fn parse_internal_batch(&self, request: &[Byte]) -> Result<(BatchType, BatchStatements, Long)> {
+        if request.len() < 9 {
+            return Err(Error::ProtocolError(
+                "El BATCH interno no tiene un header válido".to_string(),
+            ));
+        }
+        let body_end = Length::try_from(request[5..9].to_vec())?.len as usize + 9;
+        let batch = BatchBody::try_from(&request[9..body_end.min(request.len())])?;
+        let queries = batch.get_queries();
+        let metadata = &request[body_end.min(request.len())..];
+        if metadata.len() != 8 + queries.len() {
+            return Err(Error::ServerError(
+                "No se paso la informacion del BATCH en la metadata interna".to_string(),
+            ));
+        }
+        let timestamp = Long::from_be_bytes([
+            metadata[0],
+            metadata[1],
+            metadata[2],
+            metadata[3],
+            metadata[4],
+            metadata[5],
+            metadata[6],
+            metadata[7],
+        ]);
+
+        let mut statements = Vec::new();
+        for (query, node_number) in queries.iter().zip(&metadata[8..]) {
+            let BatchQuery::Query { query, values } = query else {
+                return Err(Error::ServerError(
+                    "Un BATCH interno no puede tener queries preparadas".to_string(),
+                ));
+            };
+            let statement = self.bind_statement(
+                query,
+                parse_batch_statement(query, batch.get_batch_type())?,
+                values,
+                None,
+            )?;
+            self.get_table(&get_dml_statement_table_name(&statement))?;
+            statements.push((statement, *node_number));
+        }
+        Ok((batch.get_batch_type(), statements, timestamp))
+    }
+
+    /// Aplica todas las _queries_ de un BATCH, con el mismo _timestamp_.
+    fn apply_batch(&mut self, statements: BatchStatements, timestamp: Long) -> Result<()> {
+        for (statement, node_number) in statements {
+            // Un `USING TIMESTAMP` de la query tiene prioridad sobre el timestamp del BATCH
+            let timestamp = statement
+                .update_parameters()
+                .map(|using| using.get_timestamp())
+                .transpose()?
+                .flatten()
+                .unwrap_or(timestamp);
+            self.handle_internal_dml_statement(
+                statement,
+                (Some(timestamp), Some(node_number)),
+                None,
+            )?;
+        }
+        Ok(())
+    }
+
     /// Crea un result de tipo void.
     pub fn create_result_void() -> Vec<Byte> {
         let mut response: Vec<Byte> = Vec::new();
@@ -1134,6 +2088,25 @@ impl Node {
         response
     }
 
+    /// Crea un result de tipo schema change.
+    pub fn create_result_schema_change(
+        change_type: SchemaChangeType,
+        keyspace_name: &str,
+        table_name: Option<&str>,
+    ) -> Vec<Byte> {
+        let mut body = ResultKind::SchemaChange.as_bytes();
+        body.extend(Self::schema_change_event(change_type, keyspace_name, table_name).as_bytes());
+
+        let mut response: Vec<Byte> = Vec::new();
+        response.append(&mut Version::ResponseV5.as_bytes());
+        response.append(&mut Flag::Default.as_bytes());
+        response.append(&mut Stream::new(0).as_bytes());
+        response.append(&mut Opcode::Result.as_bytes());
+        response.append(&mut Length::new(body.len() as Uint).as_bytes());
+        response.append(&mut body);
+        response
+    }
+
     /// Obtiene los valores de las _partition keys_ de una tabla dado su nombre.
     pub fn get_partition_keys_values(&self, table_name: &String) -> Result<&Vec<String>> {
         match self.tables_and_partitions_keys_values.get(table_name) {
@@ -1147,10 +2120,181 @@ impl Node {
     /// Dado el nombre de una tabla, obtiene la cantidad de replicación del keyspace al que pertenece.
     pub fn get_replicas_from_table_name(&self, table_name: &str) -> Result<Uint> {
         let keyspace = self.get_keyspace(table_name)?;
-        match keyspace.simple_replicas() {
-            Some(replication_factor) => Ok(replication_factor),
-            None => Err(Error::ServerError("No es una simple strategy".to_string())),
+        Ok(keyspace.total_replicas())
+    }
+
+    /// Devuelve el _datacenter_ y el _rack_ de un nodo dado.
+    ///
+    /// Se prioriza lo informado por _gossip_, y si no se conoce el estado del nodo se consulta
+    /// el archivo de IPs.
+    pub fn get_location_of(&self, node_id: NodeId) -> (String, String) {
+        let endpoint_state = if node_id == self.id {
+            Some(&self.endpoint_state)
+        } else {
+            self.neighbours_states.get(&node_id)
+        };
+        match endpoint_state {
+            Some(state) => (
+                state.get_datacenter().to_string(),
+                state.get_rack().to_string(),
+            ),
+            None => AddrLoader::default_runtime().get_location(node_id),
+        }
+    }
+
+    /// Devuelve los IDs de los nodos que guardan las réplicas de las particiones de `owner`,
+    /// según la estrategia de replicación del keyspace.
+    ///
+    /// Con [SimpleStrategy](ReplicationStrategy::SimpleStrategy) las réplicas son `owner` y los
+    /// nodos siguientes del anillo. Con [NetworkTopologyStrategy](ReplicationStrategy::NetworkTopologyStrategy)
+    /// se recorre el anillo desde `owner` hasta completar las réplicas de cada _datacenter_,
+    /// prefiriendo nodos de _racks_ distintos.
+    pub fn get_replicas_nodes(
+        &self,
+        owner: NodeId,
+        keyspace: &Keyspace,
+        nodes_ids: &[NodeId],
+    ) -> Vec<NodeId> {
+        let mut replicas = Vec::new();
+        if nodes_ids.is_empty() {
+            return replicas;
+        }
+        if let ReplicationStrategy::SimpleStrategy(replication_factor) = &keyspace.replication {
+            let mut node_to_replicate = owner;
+            for _ in 0..*replication_factor {
+                replicas.push(node_to_replicate);
+                node_to_replicate = next_node_in_the_cluster(node_to_replicate, nodes_ids);
+            }
+            return replicas;
+        }
+
+        let mut placed: HashMap<String, Uint> = HashMap::new();
+        let mut racks_used: HashMap<String, HashSet<String>> = HashMap::new();
+        let mut skipped: Vec<(NodeId, String)> = Vec::new();
+        let mut node_to_replicate = owner;
+        for _ in 0..nodes_ids.len() {
+            let (datacenter, rack) = self.get_location_of(node_to_replicate);
+            let wanted = keyspace
+                .replication
+                .datacenter_replicas(&datacenter)
+                .unwrap_or(0);
+            let already_placed = placed.entry(datacenter.clone()).or_default();
+            if *already_placed < wanted {
+                if racks_used
+                    .entry(datacenter.clone())
+                    .or_default()
+                    .insert(rack)
+                {
+                    replicas.push(node_to_replicate);
+                    *already_placed += 1;
+                } else {
+                    skipped.push((node_to_replicate, datacenter));
+                }
+            }
+            node_to_replicate = next_node_in_the_cluster(node_to_replicate, nodes_ids);
+        }
+        // Si no alcanzan los racks distintos, se completa con los nodos salteados.
+        for (node_id, datacenter) in skipped {
+            let wanted = keyspace
+                .replication
+                .datacenter_replicas(&datacenter)
+                .unwrap_or(0);
+            let already_placed = placed.entry(datacenter).or_default();
+            if *already_placed < wanted {
+                replicas.push(node_id);
+                *already_placed += 1;
+            }
+        }
+        replicas
+    }
+
+    /// Dado el nombre de una tabla, devuelve los IDs de los nodos que guardan las réplicas
+    /// de las particiones de `owner`.
+    pub fn get_replicas_nodes_from_table_name(
+        &self,
+        owner: NodeId,
+        table_name: &str,
+    ) -> Result<Vec<NodeId>> {
+        let keyspace = self.get_keyspace(table_name)?;
+        Ok(self.get_replicas_nodes(owner, keyspace, &self.get_nodes_ids()))
+    }
+
+    /// Devuelve los IDs de los nodos de cuyas particiones este nodo guarda una réplica,
+    /// en un keyspace dado.
+    fn get_owners_replicated_here(
+        &self,
+        keyspace_name: &str,
+        nodes_ids: &[NodeId],
+    ) -> Result<Vec<NodeId>> {
+        let keyspace = self.get_keyspace_from_name(keyspace_name)?;
+        Ok(nodes_ids
+            .iter()
+            .filter(|owner| {
+                self.get_replicas_nodes(**owner, keyspace, nodes_ids)
+                    .contains(&self.id)
+            })
+            .copied()
+            .collect())
+    }
+
+    /// Devuelve las réplicas de este nodo a reparar, de las tablas del keyspace y la tabla dados.
+    ///
+    /// Por cada tabla y cada nodo cuyas particiones se replican acá, se devuelven los otros
+    /// nodos que guardan esa misma réplica: _(table_name, owner, peers)_.
+    pub fn get_replicas_to_repair(
+        &self,
+        keyspace_name: Option<&str>,
+        table_name: Option<&str>,
+    ) -> Result<Vec<(String, NodeId, Vec<NodeId>)>> {
+        if let Some(keyspace_name) = keyspace_name {
+            self.get_keyspace_from_name(keyspace_name)?;
         }
+        if let Some(table_name) = table_name {
+            let table_keyspace = self.get_table(table_name)?.get_keyspace();
+            if keyspace_name.is_some_and(|keyspace_name| keyspace_name != table_keyspace) {
+                return Err(Error::Invalid(format!(
+                    "La tabla `{table_name}` no pertenece al keyspace `{}`",
+                    keyspace_name.unwrap_or_default()
+                )));
+            }
+        }
+
+        let nodes_ids = self.get_nodes_ids();
+        let mut to_repair = Vec::new();
+        for table in self.tables.values() {
+            if keyspace_name.is_some_and(|keyspace_name| keyspace_name != table.get_keyspace())
+                || table_name.is_some_and(|table_name| table_name != table.get_name())
+            {
+                continue;
+            }
+            let keyspace = self.get_keyspace_from_name(table.get_keyspace())?;
+            for owner in self.get_owners_replicated_here(table.get_keyspace(), &nodes_ids)? {
+                let peers = self
+                    .get_replicas_nodes(owner, keyspace, &nodes_ids)
+                    .into_iter()
+                    .filter(|node_id| *node_id != self.id)
+                    .collect();
+                to_repair.push((table.get_name().to_string(), owner, peers));
+            }
+        }
+        Ok(to_repair)
+    }
+
+    /// Crea un [ConsistencyCounter] para las réplicas dadas, tomando a este nodo como coordinador.
+    pub fn new_consistency_counter(
+        &self,
+        consistency_level: &Consistency,
+        replicas: &[NodeId],
+    ) -> Result<ConsistencyCounter> {
+        let replicas_datacenters: Vec<(NodeId, String)> = replicas
+            .iter()
+            .map(|replica| (*replica, self.get_location_of(*replica).0))
+            .collect();
+        ConsistencyCounter::new(
+            consistency_level,
+            &replicas_datacenters,
+            self.endpoint_state.get_datacenter(),
+        )
     }
 
     /// Obtiene la cantidad de filas de un result.
@@ -1169,6 +2313,15 @@ impl Node {
         ])
     }
 
+    /// Obtiene la cantidad de filas de un result de filas, o 0 si no es uno.
+    pub fn get_rows_count(&self, result: &[Byte]) -> Int {
+        let rows_quantity_position = self.get_columns_metadata_length(result);
+        if rows_quantity_position == 0 || result.len() < rows_quantity_position + 4 {
+            return 0;
+        }
+        self.get_quantity_of_rows(result, rows_quantity_position)
+    }
+
     /// Obtiene la cantidad de columnas de un result, que se encuentra en su metadata.
     pub fn get_columns_metadata_length(&self, results_from_another_nodes: &[Byte]) -> usize {
         let mut total_length_from_metadata: usize = 21;
@@ -1183,6 +2336,9 @@ impl Node {
             column_quantity[2],
             column_quantity[3],
         ]);
+        if let Some(paging_state) = Self::get_paging_state(results_from_another_nodes) {
+            total_length_from_metadata += 4 + paging_state.len();
+        }
         for _ in 0..column_quantity {
             let name_length = &results_from_another_nodes
                 [total_length_from_metadata..(total_length_from_metadata + 2)]; // Consigo el largo del [String]
@@ -1192,6 +2348,125 @@ impl Node {
         total_length_from_metadata
     }
 
+    /// Obtiene el estado de paginación de un result de filas, si tiene más páginas.
+    pub fn get_paging_state(result: &[Byte]) -> Option<Vec<Byte>> {
+        if *result.get(9..13)? != ResultKind::Rows.as_bytes()[..] {
+            return None;
+        }
+        let flags = Int::from_be_bytes(result.get(13..17)?.try_into().ok()?);
+        if !RowsFlag::has_mask(&flags, &RowsFlag::HasMorePages) {
+            return None;
+        }
+        let length = Int::from_be_bytes(result.get(21..25)?.try_into().ok()?);
+        Some(result.get(25..25 + length.max(0) as usize)?.to_vec())
+    }
+
+    /// Saca el estado de paginación de un result de filas, devolviéndolo si lo tenía.
+    pub fn take_paging_state(result: &mut Vec<Byte>) -> Option<Vec<Byte>> {
+        let paging_state = Self::get_paging_state(result)?;
+        result.drain(21..25 + paging_state.len());
+        let flags = Int::from_be_bytes([result[13], result[14], result[15], result[16]])
+            & !RowsFlag::HasMorePages.collapse();
+        result[13..17].copy_from_slice(&flags.to_be_bytes());
+        Self::update_result_length(result);
+        Some(paging_state)
+    }
+
+    /// Le agrega a un result de filas el estado desde el cual retomar la lectura.
+    pub fn set_paging_state(result: &mut Vec<Byte>, paging_state: &[Byte]) {
+        if result.len() < 21 || Self::get_paging_state(result).is_some() {
+            return;
+        }
+        let flags = Int::from_be_bytes([result[13], result[14], result[15], result[16]])
+            | RowsFlag::HasMorePages.collapse();
+        result[13..17].copy_from_slice(&flags.to_be_bytes());
+        let mut state_bytes = (paging_state.len() as Int).to_be_bytes().to_vec();
+        state_bytes.extend_from_slice(paging_state);
+        result.splice(21..21, state_bytes);
+        Self::update_result_length(result);
+    }
+
+    /// Deja en un result de filas sólo sus primeras `max_rows` filas.
+    pub fn truncate_rows(&self, result: &mut Vec<Byte>, max_rows: usize) {
+        let rows_quantity_position = self.get_columns_metadata_length(result);
+        if rows_quantity_position == 0 || result.len() < rows_quantity_position + 4 {
+            return;
+        }
+        let rows_count = self.get_quantity_of_rows(result, rows_quantity_position);
+        if rows_count.max(0) as usize <= max_rows {
+            return;
+        }
+        let columns_count = Int::from_be_bytes([result[17], result[18], result[19], result[20]]);
+        let mut position = rows_quantity_position + 4;
+        for _ in 0..max_rows * columns_count.max(0) as usize {
+            let Some(length) = result.get(position..position + 4) else {
+                return;
+            };
+            let length = Int::from_be_bytes([length[0], length[1], length[2], length[3]]);
+            position += 4 + length.max(0) as usize;
+        }
+        if position > result.len() {
+            return;
+        }
+        result.truncate(position);
+        result[rows_quantity_position..rows_quantity_position + 4]
+            .copy_from_slice(&(max_rows as Int).to_be_bytes());
+        Self::update_result_length(result);
+    }
+
+    /// Obtiene los nombres de las columnas y los valores de las filas de un result de filas.
+    ///
+    /// Si el result está vacío, no hay columnas ni filas.
+    pub fn get_columns_and_rows(&self, result: &[Byte]) -> Result<(Vec<String>, Vec<Vec<String>>)> {
+        let rows_quantity_position = self.get_columns_metadata_length(result);
+        if rows_quantity_position == 0 || result.len() < rows_quantity_position + 4 {
+            return Ok((Vec::new(), Vec::new()));
+        }
+        let columns_count = Int::from_be_bytes([result[17], result[18], result[19], result[20]]);
+        let mut position = 21;
+        if let Some(paging_state) = Self::get_paging_state(result) {
+            position += 4 + paging_state.len();
+        }
+        let mut columns = Vec::new();
+        for _ in 0..columns_count {
+            let mut displacement = 0;
+            columns.push(parse_bytes_to_string(
+                &result[position..],
+                &mut displacement,
+            )?);
+            position += displacement + 2; // el nombre y el [Option] del tipo
+        }
+
+        let rows_count = self.get_quantity_of_rows(result, rows_quantity_position);
+        position = rows_quantity_position + 4;
+        let mut rows = Vec::new();
+        for _ in 0..rows_count {
+            let mut row = Vec::new();
+            for _ in 0..columns_count {
+                let value = result
+                    .get(position..position + 4)
+                    .and_then(|length| {
+                        let length = Int::from_be_bytes(length.try_into().ok()?).max(0) as usize;
+                        position += 4;
+                        let value = result.get(position..position + length)?;
+                        position += length;
+                        String::from_utf8(value.to_vec()).ok()
+                    })
+                    .ok_or_else(|| {
+                        Error::ServerError("El result de filas está incompleto".to_string())
+                    })?;
+                row.push(value);
+            }
+            rows.push(row);
+        }
+        Ok((columns, rows))
+    }
+
+    fn update_result_length(result: &mut [Byte]) {
+        let length = (result.len() - 9) as Uint;
+        result[5..9].copy_from_slice(&length.to_be_bytes());
+    }
+
     fn _get_ordered_new_res_bytes(
         &self,
         results_from_another_nodes: &[Byte],
@@ -1223,22 +2498,12 @@ impl Node {
         Ok(new_ordered_res.as_bytes().to_vec())
     }
 
-    /// Obtiene la cantidad de replicas de un keyspace.
-    /// Devuelve error si no se usa una estrategia de replicación simple.
+    /// Obtiene la cantidad total de replicas de un keyspace.
     pub fn get_quantity_of_replicas_from_keyspace(&self, keyspace: &Keyspace) -> Result<Uint> {
-        let replicas = match keyspace.simple_replicas() {
-            Some(value) => value,
-            None => {
-                return Err(Error::ServerError(
-                    "No se usa una estrategia de replicacion simple".to_string(),
-                ))
-            }
-        };
-        Ok(replicas)
+        Ok(keyspace.total_replicas())
     }
 
-    /// Obtiene la cantidad de replicas de un keyspace, dado su nombre.
-    /// Devuelve error si no se usa una estrategia de replicación simple.
+    /// Obtiene la cantidad total de replicas de un keyspace, dado su nombre.
     pub fn get_quantity_of_replicas_from_keyspace_name(&self, keyspace_name: &str) -> Result<Uint> {
         match self.keyspaces.get(keyspace_name) {
             Some(keyspace) => self.get_quantity_of_replicas_from_keyspace(keyspace),
@@ -1375,11 +2640,8 @@ impl Node {
 
         let nodes_ids = self.get_nodes_ids();
         for table in self.tables.values() {
-            let replicas_to_create =
-                self.get_quantity_of_replicas_from_keyspace_name(&table.keyspace)? as usize;
-            for position in 0..replicas_to_create {
-                let id_of_replica = n_th_node_in_the_cluster(self.id, &nodes_ids, position, true);
-                DiskHandler::create_table_csv_file(
+            for id_of_replica in self.get_owners_replicated_here(&table.keyspace, &nodes_ids)? {
+                DiskHandler::create_table_replica(
                     &self.storage_addr,
                     &table.keyspace,
                     &table.name,
@@ -1405,115 +2667,51 @@ impl Node {
         Ok(())
     }
 
-    /// Segun el node_id recibido, revisa si este deberia tener alguna de las replicas de las tablas que
-    /// le pertenecen a este nodo. Si no le pertenece ninguna replica devuelve un vector vacio.
-    pub fn copy_tables(&self, node_id: NodeId, only_farthest_replica: bool) -> Result<Vec<Byte>> {
-        let mut nodes_ids = self.get_nodes_ids();
-        // Se necesita que el nodo pertenezca aunque todavía no se haya presentado, solo en
-        // este caso excepcional.
-        if !nodes_ids.contains(&node_id) {
-            nodes_ids.push(node_id);
-            nodes_ids.sort();
-        }
-        // Tiene que ser un valor suficientemente grande.
-        let mut distance = usize::MAX;
-        for i in 1..nodes_ids.len() {
-            if n_th_node_in_the_cluster(self.id, &nodes_ids, i, false) == node_id {
-                distance = i;
-            }
-        }
-        let mut final_rows: Vec<String> = Vec::new();
-        for table in self.tables.values() {
-            if (only_farthest_replica
-                && (distance as Uint)
-                    != self.get_quantity_of_replicas_from_keyspace_name(&table.keyspace)?)
-                || (distance as Uint)
-                    >= self.get_quantity_of_replicas_from_keyspace_name(&table.keyspace)?
-            {
-                continue;
-            }
-
-            let rows = DiskHandler::get_all_rows(
-                table.get_name(),
-                &self.storage_addr,
-                table.get_keyspace(),
-                table.get_keyspace(),
-                self.id,
-            )?;
-            if rows.is_empty() {
-                continue;
-            }
-            final_rows.insert(0, table.get_name().to_string());
-            final_rows.insert(0, table.get_keyspace().to_string());
-            for row in rows {
-                final_rows.push(row.join(","));
-            }
-            final_rows.push("\n".to_string());
-        }
-        let rows_as_string = final_rows.join("\n").as_bytes().to_vec();
-        Ok(rows_as_string)
-    }
-
     /// Actualiza las replicas para adaptarse a la creacion o borrado de un nodo.
     ///
-    /// Se encarga de crear los archivos CSV necesarios para este nodo, y eliminar los que
-    /// ya no le corresponden.
+    /// Se encarga de crear los directorios de las réplicas que ahora le corresponden a este
+    /// nodo, y eliminar los que ya no le corresponden, según la estrategia de replicación de
+    /// cada keyspace.
     pub fn update_node_replicas(
         &mut self,
         node_id_changed: NodeId,
         is_deletion: bool,
     ) -> Result<Vec<Byte>> {
-        // let mut im_new_node = false;
-        // if self.endpoint_state.get_appstate_status() == &AppStatus::NewNode {
-        //     im_new_node = true;
-        // }
         self.endpoint_state
             .set_appstate_status(AppStatus::UpdatingReplicas);
-        let tables = self.tables.values();
-        for table in tables {
-            let quantity_of_replicas =
-                self.get_quantity_of_replicas_from_keyspace_name(&table.keyspace)? as Byte;
-            let mut update_table_replica = false;
-            for pos in 1..quantity_of_replicas {
-                let replica_node_id = n_th_node_in_the_cluster(
-                    self.id,
-                    &Node::get_all_nodes_ids(),
-                    pos as usize,
-                    true,
-                );
-                if replica_node_id == node_id_changed {
-                    update_table_replica = true
-                }
-            }
-            if !update_table_replica {
-                continue;
-            }
-            self.delete_and_create_replicas(
-                quantity_of_replicas,
-                is_deletion,
-                node_id_changed,
-                table,
-            )?;
+        let mut nodes_ids = self.get_nodes_ids();
+        if is_deletion {
+            nodes_ids.retain(|node_id| *node_id != node_id_changed);
+        } else if !nodes_ids.contains(&node_id_changed) {
+            // Se necesita que el nodo pertenezca aunque todavía no se haya presentado.
+            nodes_ids.push(node_id_changed);
+            nodes_ids.sort();
         }
         for table in self.tables.values() {
-            let keyspace_name = table.get_keyspace();
-            let replicas_quantity =
-                self.get_quantity_of_replicas_from_keyspace_name(keyspace_name)?;
-            for replica in 1..replicas_quantity {
-                let node_number = n_th_node_in_the_cluster(
-                    self.id,
-                    &self.get_nodes_ids(),
-                    replica as usize,
-                    true,
-                );
-                DiskHandler::truncate_rows(
+            let stored = DiskHandler::get_table_replicas_numbers(
+                &self.storage_addr,
+                table.get_keyspace(),
+                table.get_name(),
+            )?;
+            let (to_create, to_delete) =
+                self.get_replicas_changes(table.get_keyspace(), &stored, &nodes_ids)?;
+            for owner in to_create {
+                DiskHandler::create_table_replica(
                     &self.storage_addr,
+                    &table.keyspace,
+                    &table.name,
+                    &table.get_columns_names(),
+                    owner,
+                )?;
+            }
+            for owner in to_delete {
+                DiskHandler::delete_table_replica(
+                    &self.storage_addr,
+                    &table.keyspace,
                     &table.name,
-                    keyspace_name,
-                    keyspace_name,
-                    node_number,
-                    "",
+                    owner,
                 )?;
+                DiskHandler::delete_index_replicas(&self.storage_addr, table, owner)?;
             }
         }
         self.endpoint_state
@@ -1522,36 +2720,29 @@ impl Node {
         Ok(vec![1])
     }
 
-    fn delete_and_create_replicas(
+    /// Compara las réplicas que este nodo guarda de un keyspace con las que le corresponden
+    /// según los nodos dados.
+    ///
+    /// Devuelve los dueños de cuyas particiones hay que crear una réplica, y los de las que
+    /// hay que borrar.
+    fn get_replicas_changes(
         &self,
-        quantity_of_replicas: Byte,
-        is_deletion: bool,
-        mut node_id: NodeId,
-        table: &Table,
-    ) -> Result<()> {
-        let replica_pos = quantity_of_replicas as usize;
-        let mut id_of_replica =
-            n_th_node_in_the_cluster(self.id, &Node::get_all_nodes_ids(), replica_pos, true);
-        if is_deletion {
-            let aux1 = node_id;
-            let aux2 = id_of_replica;
-            id_of_replica = aux1;
-            node_id = aux2;
-        }
-        DiskHandler::create_table_csv_file(
-            &self.storage_addr,
-            &table.keyspace,
-            &table.name,
-            &table.get_columns_names(),
-            node_id,
-        )?;
-        DiskHandler::delete_table_csv_file(
-            &self.storage_addr,
-            &table.keyspace,
-            &table.name,
-            id_of_replica,
-        )?;
-        Ok(())
+        keyspace_name: &str,
+        stored: &[NodeId],
+        nodes_ids: &[NodeId],
+    ) -> Result<(Vec<NodeId>, Vec<NodeId>)> {
+        let owners = self.get_owners_replicated_here(keyspace_name, nodes_ids)?;
+        let to_create = owners
+            .iter()
+            .filter(|owner| !stored.contains(owner))
+            .copied()
+            .collect();
+        let to_delete = stored
+            .iter()
+            .filter(|owner| !owners.contains(owner))
+            .copied()
+            .collect();
+        Ok((to_create, to_delete))
     }
 
     /// Filtra las tablas que contiene para asegurarse de tener las filas que le
@@ -1562,10 +2753,54 @@ impl Node {
     /// Además notifica a sus réplicas que deben filtrarse también y al nodo siguiente
     /// en el anillo del clúster.
     pub fn relocate_rows(&mut self) -> Result<()> {
+        let nodes_ids = self.get_nodes_ids();
         for table in self.tables.values() {
             let mut nodes_rows: HashMap<NodeId, Vec<String>> = HashMap::new();
-            let nodes_ids = self.get_nodes_ids();
             self.filter_and_repair_rows(&mut nodes_rows, &nodes_ids, table)?;
+            self.filter_replicas_of_other_nodes(&nodes_ids, table)?;
+        }
+        self.last_ring_nodes = nodes_ids;
+        Ok(())
+    }
+
+    /// Indica si cambiaron las réplicas de las particiones de este nodo desde la última
+    /// relocalización.
+    fn own_replicas_changed(&self, keyspace: &Keyspace, nodes_ids: &[NodeId]) -> bool {
+        self.last_ring_nodes.is_empty()
+            || self.get_replicas_nodes(self.id, keyspace, &self.last_ring_nodes)
+                != self.get_replicas_nodes(self.id, keyspace, nodes_ids)
+    }
+
+    /// Descarta de las réplicas que guarda de otros nodos las filas que ya no les pertenecen.
+    ///
+    /// Esas filas las reenvía el dueño anterior a las réplicas del dueño nuevo.
+    fn filter_replicas_of_other_nodes(&self, nodes_ids: &[NodeId], table: &Table) -> Result<()> {
+        let position_of_partition_key = table.get_position_of_partition_key()?;
+        for owner in self.get_owners_replicated_here(table.get_keyspace(), nodes_ids)? {
+            if owner == self.id {
+                continue;
+            }
+            let rows = DiskHandler::get_all_rows(
+                table.get_name(),
+                &self.storage_addr,
+                &self.get_default_keyspace_name()?,
+                table.get_keyspace(),
+                owner,
+            )?;
+            let kept_rows: Vec<String> = rows
+                .iter()
+                .filter(|row| self.select_node(&row[position_of_partition_key]) == owner)
+                .map(|row| row.join(","))
+                .collect();
+            if kept_rows.len() < rows.len() {
+                DiskHandler::truncate_rows(
+                    &self.storage_addr,
+                    table,
+                    &self.get_default_keyspace_name()?,
+                    owner,
+                    &kept_rows.join("\n"),
+                )?;
+            }
         }
         Ok(())
     }
@@ -1573,7 +2808,8 @@ impl Node {
     /// Filtra sus propias filas para quedarse con las que le correspondan según el valor de _hashing_
     /// de la _partition key_ de la tabla.
     ///
-    /// Además, reenvía las que le correspondan a otros nodos.
+    /// Además, reenvía las que le correspondan a otros nodos. Las filas que siguen siendo suyas
+    /// sólo se reenvían a sus réplicas si estas cambiaron.
     fn filter_and_repair_rows(
         &self,
         nodes_rows: &mut HashMap<NodeId, Vec<String>>,
@@ -1604,27 +2840,26 @@ impl Node {
 
         for (node_id, rows) in nodes_rows.iter() {
             if rows.len() > 2 {
-                let replicas_quantity = self
-                    .get_quantity_of_replicas_from_keyspace_name(table.get_keyspace())?
-                    as usize;
-                for position in 0..replicas_quantity {
-                    let next_node_id =
-                        n_th_node_in_the_cluster(*node_id, nodes_ids, position, false);
+                let keyspace = self.get_keyspace_from_name(table.get_keyspace())?;
+                let skip_replicas =
+                    *node_id == self.id && !self.own_replicas_changed(keyspace, nodes_ids);
+                for next_node_id in self.get_replicas_nodes(*node_id, keyspace, nodes_ids) {
+                    if skip_replicas && next_node_id != self.id {
+                        continue;
+                    }
                     if next_node_id == self.id && *node_id == self.id {
                         DiskHandler::truncate_rows(
                             &self.storage_addr,
-                            table.get_name(),
-                            table.get_keyspace(),
+                            table,
                             &self.get_default_keyspace_name()?,
                             *node_id,
                             &rows[2..].join("\n"),
                         )?;
                     } else if next_node_id == self.id {
                         DiskHandler::append_new_rows(
-                            rows[2..].join("\n"),
+                            &rows[2..].join("\n"),
                             &self.storage_addr,
-                            table.get_keyspace(),
-                            table.get_name(),
+                            table,
                             *node_id,
                         )?;
                     } else {
@@ -1638,8 +2873,7 @@ impl Node {
             } else if *node_id == self.id {
                 DiskHandler::truncate_rows(
                     &self.storage_addr,
-                    table.get_name(),
-                    table.get_keyspace(),
+                    table,
                     &self.get_default_keyspace_name()?,
                     *node_id,
                     &rows[2..].join("\n"),
@@ -1654,11 +2888,11 @@ impl Node {
     /// _node_id_ se usa para diferenciar entre las réplicas de los nodos vecinos.
     pub fn add_relocated_rows(&self, node_id: NodeId, rows: String) -> Result<()> {
         let rows_splitted: Vec<&str> = rows.split("\n").collect();
+        let table = self.get_table(rows_splitted[1])?;
         DiskHandler::append_new_rows(
-            rows_splitted[2..].join("\n").to_string(),
+            &rows_splitted[2..].join("\n"),
             &self.storage_addr,
-            rows_splitted[0],
-            rows_splitted[1],
+            table,
             node_id,
         )
     }
@@ -1667,17 +2901,13 @@ impl Node {
     pub fn finish_relocation(&mut self) -> Result<()> {
         let nodes_ids = self.get_nodes_ids();
         for table in self.tables.values() {
-            let replicas_quantity =
-                self.get_quantity_of_replicas_from_keyspace_name(&table.keyspace)? as usize;
-            for position in 0..replicas_quantity {
-                let id_of_replica = n_th_node_in_the_cluster(self.id, &nodes_ids, position, true);
+            for id_of_replica in self.get_owners_replicated_here(&table.keyspace, &nodes_ids)? {
                 DiskHandler::remove_repeated_rows(
                     &self.storage_addr,
                     table,
                     &table.keyspace,
                     id_of_replica,
                 )?;
-                // DiskHandler::sort_rows(table, &self.storage_addr, id_of_replica)?;
             }
         }
         // Una vez todo finalizado, el estado del nodo vuelve a ser normal.
@@ -1695,6 +2925,7 @@ impl Node {
                 let actual_n_nodes = self.get_actual_n_nodes();
                 self.inicialize_nodes_weights(actual_n_nodes);
             }
+            self.refresh_token_ring();
         }
         Ok(())
     }
@@ -1758,3 +2989,131 @@ impl PartialEq for Node {
         self.endpoint_state.eq(&other.endpoint_state)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::nodes::table_metadata::{
+            column_config::ColumnConfig, column_data_type::ColumnDataType,
+        },
+        parser::{main_parser::make_parse, statements::statement::Statement},
+        std::fs::create_dir_all,
+        tokenizer::tok::tokenize_query,
+    };
+
+    /// Arma un nodo que conoce a los nodos dados, cada uno en su _datacenter_ y en un _rack_
+    /// propio, con un keyspace `vuelos` replicado con [NetworkTopologyStrategy](ReplicationStrategy::NetworkTopologyStrategy).
+    fn node_with_topology(id: NodeId, datacenters: &[(NodeId, &str)]) -> Result<Node> {
+        let mut node = Node::new(id, ConnectionMode::Parsing, false, Vec::new())?;
+        for (node_id, datacenter) in datacenters {
+            let rack = format!("rack_{node_id}");
+            let state = EndpointState::with_id_and_mode(*node_id, ConnectionMode::Parsing)
+                .with_location(datacenter, &rack);
+            if *node_id == id {
+                node.endpoint_state = state.clone();
+            }
+            node.neighbours_states.insert(*node_id, state);
+        }
+        node.keyspaces.insert(
+            "vuelos".to_string(),
+            Keyspace::new(
+                "vuelos".to_string(),
+                ReplicationStrategy::NetworkTopologyStrategy(vec![
+                    ("dc1".to_string(), 2),
+                    ("dc2".to_string(), 1),
+                ]),
+            ),
+        );
+        Ok(node)
+    }
+
+    /// Arma un nodo con una tabla `vuelos` y una _query_ preparada sobre ella, devolviendo su ID.
+    fn node_with_prepared_query(id: NodeId) -> Result<(Node, PreparedId)> {
+        let mut node = Node::new(id, ConnectionMode::Parsing, false, Vec::new())?;
+        let columns = [
+            ("orig", ColumnDataType::String),
+            ("id", ColumnDataType::Int),
+        ];
+        node.tables.insert(
+            "vuelos".to_string(),
+            Table::new(
+                "vuelos".to_string(),
+                "aerolinea".to_string(),
+                columns
+                    .into_iter()
+                    .map(|(name, data_type)| ColumnConfig::new(name.to_string(), data_type))
+                    .collect(),
+                vec!["orig".to_string()],
+                None,
+            ),
+        );
+        let query = "SELECT * FROM vuelos WHERE orig = ?";
+        let Statement::DmlStatement(statement) = make_parse(&mut tokenize_query(query))? else {
+            return Err(Error::Invalid(format!("No es una query DML: {query}")));
+        };
+        let id = node.prepare_statement(query, statement, "aerolinea")?;
+        Ok((node, id))
+    }
+
+    fn execute_ddl(node: &mut Node, query: &str) -> Result<Vec<Byte>> {
+        let Statement::DdlStatement(statement) = make_parse(&mut tokenize_query(query))? else {
+            return Err(Error::Invalid(format!("No es una query DDL: {query}")));
+        };
+        node.handle_internal_ddl_statement(statement, (None, None))
+    }
+
+    const DATACENTERS: [(NodeId, &str); 5] =
+        [(1, "dc1"), (2, "dc2"), (3, "dc1"), (4, "dc2"), (5, "dc1")];
+
+    #[test]
+    fn test_1_al_unirse_un_nodo_se_mueven_las_replicas_segun_los_datacenters() -> Result<()> {
+        let node = node_with_topology(1, &DATACENTERS)?;
+
+        // Antes de unirse el nodo 5, el nodo 1 guarda réplicas de todos
+        let (to_create, to_delete) =
+            node.get_replicas_changes("vuelos", &[1, 2, 3, 4], &[1, 2, 3, 4, 5])?;
+
+        assert_eq!(to_create, vec![5]);
+        assert_eq!(to_delete, vec![2, 3]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_al_irse_un_nodo_se_borra_su_replica() -> Result<()> {
+        let node = node_with_topology(3, &DATACENTERS)?;
+
+        let (to_create, to_delete) =
+            node.get_replicas_changes("vuelos", &[1, 2, 3], &[1, 3, 4, 5])?;
+
+        assert!(to_create.is_empty());
+        assert_eq!(to_delete, vec![2]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_3_un_alter_table_descarta_las_queries_preparadas_sobre_la_tabla() -> Result<()> {
+        let (mut node, id) = node_with_prepared_query(21)?;
+        assert!(node.get_prepared_statement(&id).is_some());
+
+        execute_ddl(
+            &mut node,
+            "ALTER TABLE vuelos WITH default_time_to_live = 60",
+        )?;
+
+        assert!(node.get_prepared_statement(&id).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_4_un_drop_table_descarta_las_queries_preparadas_sobre_la_tabla() -> Result<()> {
+        let (mut node, id) = node_with_prepared_query(22)?;
+        create_dir_all(format!("{}/aerolinea", node.storage_addr))
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+
+        execute_ddl(&mut node, "DROP TABLE vuelos")?;
+
+        assert!(node.get_prepared_statement(&id).is_none());
+        Ok(())
+    }
+}
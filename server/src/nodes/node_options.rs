@@ -0,0 +1,35 @@
+//! Módulo para las opciones de inicio de un nodo.
+
+use {crate::nodes::disk_operations::commit_log::CommitLogSync, protocol::aliases::types::Long};
+
+/// Opciones opcionales con las que se puede iniciar un nodo.
+///
+/// Las opciones que no se especifican toman el valor guardado en los metadatos del nodo,
+/// o su valor por defecto si el nodo se inicia por primera vez.
+#[derive(Clone, Debug, Default)]
+pub struct NodeOptions {
+    /// La cantidad de nodos virtuales que ocupa el nodo en el anillo de _tokens_.
+    ///
+    /// Sólo se tiene en cuenta la primera vez que se inicia el nodo, ya que luego sus _tokens_
+    /// se cargan de su archivo de metadatos.
+    pub vnodes: Option<usize>,
+
+    /// El modo de sincronización con el disco del _commit log_.
+    pub commit_log_sync: Option<CommitLogSync>,
+
+    /// Cada cuántos segundos el nodo repara sus réplicas por su cuenta. Con 0 no lo hace.
+    pub repair_interval_secs: Option<Long>,
+
+    /// El umbral de sospecha del detector de fallas a partir del cual se considera caído a otro nodo.
+    pub phi_convict_threshold: Option<f64>,
+
+    /// El _datacenter_ en el que se ubica un nodo nuevo.
+    ///
+    /// Sólo se tiene en cuenta al crear el nodo, ya que luego su ubicación se lee del archivo
+    /// de IPs.
+    pub datacenter: Option<String>,
+
+    /// El _rack_ en el que se ubica un nodo nuevo. Al igual que el _datacenter_, sólo se tiene
+    /// en cuenta al crear el nodo.
+    pub rack: Option<String>,
+}
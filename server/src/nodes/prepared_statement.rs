@@ -0,0 +1,354 @@
+//! Módulo para las _queries_ preparadas de un nodo.
+
+use {
+    crate::nodes::{
+        table_metadata::{column_data_type::ColumnDataType, table::Table},
+        utils::hash_value,
+    },
+    parser::{
+        data_types::{bind_marker::BindMarker, constant::Constant},
+        statements::dml_statement::dml_statement_parser::DmlStatement,
+    },
+    protocol::{
+        aliases::{
+            results::Result,
+            types::{Byte, Double, Int, Long, Short, Uint},
+        },
+        errors::error::Error,
+        messages::responses::{
+            result::{col_type::ColType, rows_flags::RowsFlag},
+            result_kinds::ResultKind,
+        },
+        traits::{Byteable, Maskable},
+        utils::encode_string_to_bytes,
+    },
+    std::collections::HashMap,
+};
+
+/// El ID de una _query_ preparada. Son 16 bytes, al estilo de un hash MD5.
+pub type PreparedId = Vec<Byte>;
+/// Mapea los IDs de las _queries_ preparadas con las mismas.
+pub type PreparedCache = HashMap<PreparedId, PreparedStatement>;
+
+/// Las cláusulas que reciben un número en vez del valor de una columna, con el tipo de ese número.
+const CLAUSES: [(&str, ColumnDataType); 3] = [
+    ("LIMIT", ColumnDataType::Int),
+    ("TTL", ColumnDataType::Int),
+    ("TIMESTAMP", ColumnDataType::Timestamp),
+];
+
+/// Una _query_ preparada, lista para ser ejecutada vinculando sus marcadores con valores.
+pub struct PreparedStatement {
+    /// La _query_ tal cual fue preparada.
+    query: String,
+
+    /// La _query_ ya parseada, con sus marcadores sin vincular.
+    statement: DmlStatement,
+
+    /// El _keyspace_ en el que fue preparada.
+    keyspace: String,
+
+    /// El nombre de la tabla sobre la que opera la _query_.
+    table_name: String,
+
+    /// El nombre y tipo de la columna asociada a cada marcador, en orden.
+    bind_columns: Vec<(String, ColumnDataType)>,
+
+    /// Los marcadores de la _query_, en orden.
+    markers: Vec<BindMarker>,
+
+    /// Las posiciones de los marcadores que corresponden a la _partition key_.
+    pk_indexes: Vec<usize>,
+}
+
+impl PreparedStatement {
+    /// Prepara una _query_ ya parseada, tomando de sus marcadores a qué columna de la tabla
+    /// corresponde cada uno.
+    pub fn new(
+        query: &str,
+        statement: DmlStatement,
+        keyspace: &str,
+        table: &Table,
+    ) -> Result<Self> {
+        let columns_types = table.get_columns_name_and_data_type();
+        let mut bind_columns = Vec::new();
+        let mut markers = Vec::new();
+        let mut pk_indexes = Vec::new();
+
+        for (marker, column_name) in statement.bind_marker_slots()? {
+            let clause_type = CLAUSES
+                .iter()
+                .find(|(clause, _)| *clause == column_name)
+                .map(|(_, data_type)| data_type.clone());
+            let data_type = match clause_type {
+                Some(data_type) => data_type,
+                None => match columns_types.iter().find(|(name, _)| name == &column_name) {
+                    Some((_, data_type)) => data_type.clone(),
+                    None => {
+                        return Err(Error::Invalid(format!(
+                            "La columna {column_name} no existe en la tabla {}",
+                            table.get_name()
+                        )))
+                    }
+                },
+            };
+            if table.get_partition_key().contains(&column_name) {
+                pk_indexes.push(bind_columns.len());
+            }
+            bind_columns.push((column_name, data_type));
+            markers.push(marker);
+        }
+
+        Ok(Self {
+            query: query.to_string(),
+            statement,
+            keyspace: keyspace.to_string(),
+            table_name: table.get_name().to_string(),
+            bind_columns,
+            markers,
+            pk_indexes,
+        })
+    }
+
+    /// Genera el ID de una _query_ preparada a partir de la _query_ y el _keyspace_.
+    ///
+    /// El mismo par siempre genera el mismo ID, así que preparar dos veces una _query_ no la duplica.
+    pub fn generate_id(query: &str, keyspace: &str) -> PreparedId {
+        let mut id = hash_value((keyspace, query)).to_be_bytes().to_vec();
+        id.extend(hash_value((query, keyspace)).to_be_bytes());
+        id
+    }
+
+    /// Devuelve la _query_ original.
+    pub fn get_query(&self) -> &str {
+        &self.query
+    }
+
+    /// Devuelve el nombre de la tabla sobre la que opera la _query_.
+    pub fn get_table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    /// Devuelve una copia de la _query_ parseada con cada marcador reemplazado por una constante
+    /// con su valor, según el tipo de su columna.
+    ///
+    /// Si se reciben los nombres de los valores, cada marcador `:nombre` toma el valor con ese nombre.
+    /// Si no, los valores se toman en el orden en el que aparecen los marcadores.
+    pub fn bind(&self, values: &[Vec<Byte>], names: Option<&[String]>) -> Result<DmlStatement> {
+        let ordered_values = match names {
+            Some(names) => self.order_named_values(values, names)?,
+            None if values.len() == self.markers.len() => values.iter().collect(),
+            None => {
+                return Err(Error::Invalid(format!(
+                    "Se esperaban {} valores para la query pero se recibieron {}",
+                    self.markers.len(),
+                    values.len()
+                )))
+            }
+        };
+
+        // El parser recorre los marcadores en el mismo orden en que se armaron las columnas
+        let mut bound = ordered_values.into_iter().zip(&self.bind_columns);
+        let mut statement = self.statement.clone();
+        statement.bind_markers(&mut |marker, _| match bound.next() {
+            Some((value, (column, data_type))) => value_as_constant(value, column, data_type),
+            None => Err(Error::Invalid(format!(
+                "El marcador {} no tiene un valor vinculado",
+                marker.get_value_as_string()
+            ))),
+        })?;
+        Ok(statement)
+    }
+
+    /// Ordena los valores recibidos con nombre según los marcadores `:nombre` de la _query_.
+    fn order_named_values<'a>(
+        &self,
+        values: &'a [Vec<Byte>],
+        names: &[String],
+    ) -> Result<Vec<&'a Vec<Byte>>> {
+        self.markers
+            .iter()
+            .enumerate()
+            .map(|(i, marker)| {
+                let Some(marker_name) = marker.get_name() else {
+                    return Err(Error::Invalid(format!(
+                        "El marcador número {} no tiene nombre, y los valores se recibieron con nombres",
+                        i + 1
+                    )));
+                };
+                match names
+                    .iter()
+                    .position(|name| name.eq_ignore_ascii_case(marker_name))
+                {
+                    Some(position) => values.get(position).ok_or_else(|| {
+                        Error::Invalid(format!("Falta el valor para el marcador :{marker_name}"))
+                    }),
+                    None => Err(Error::Invalid(format!(
+                        "Falta el valor para el marcador :{marker_name}"
+                    ))),
+                }
+            })
+            .collect()
+    }
+
+    /// Serializa el cuerpo de una _response_ RESULT de tipo [Prepared](ResultKind::Prepared).
+    ///
+    /// Tiene la forma `<id><result_metadata_id><metadata><result_metadata>`. Como las columnas
+    /// del resultado no dependen de los valores vinculados, se usa el mismo ID como `<result_metadata_id>`.
+    pub fn as_prepared_result(&self, id: &[Byte]) -> Vec<Byte> {
+        let mut bytes = ResultKind::Prepared.as_bytes();
+        bytes.extend((id.len() as Short).to_be_bytes());
+        bytes.extend_from_slice(id);
+        bytes.extend((id.len() as Short).to_be_bytes());
+        bytes.extend_from_slice(id);
+
+        // <metadata>
+        bytes.extend(RowsFlag::GlobalTablesSpec.as_bytes());
+        bytes.extend((self.bind_columns.len() as Int).to_be_bytes());
+        bytes.extend((self.pk_indexes.len() as Int).to_be_bytes());
+        for pk_index in &self.pk_indexes {
+            bytes.extend((*pk_index as Short).to_be_bytes());
+        }
+        bytes.extend(encode_string_to_bytes(&self.keyspace));
+        bytes.extend(encode_string_to_bytes(&self.table_name));
+        for ((column, data_type), marker) in self.bind_columns.iter().zip(&self.markers) {
+            // Los marcadores con nombre se describen con su nombre, y el resto con el de su columna
+            bytes.extend(encode_string_to_bytes(marker.get_name().unwrap_or(column)));
+            bytes.extend(ColType::from(data_type).as_bytes());
+        }
+
+        // <result_metadata>
+        let no_metadata: Uint = RowsFlag::accumulate(&[&RowsFlag::NoMetadata]) as Uint;
+        bytes.extend(no_metadata.to_be_bytes());
+        bytes.extend((0 as Int).to_be_bytes());
+        bytes
+    }
+}
+
+/// Transforma un valor serializado a la constante que leería el parser en su lugar.
+fn value_as_constant(value: &[Byte], column: &str, data_type: &ColumnDataType) -> Result<Constant> {
+    let wrong_len = |expected: usize| {
+        Error::Invalid(format!(
+            "El valor para la columna {column} debería tener {expected} bytes y tiene {}",
+            value.len()
+        ))
+    };
+    let constant = match data_type {
+        ColumnDataType::String => {
+            let string = String::from_utf8(value.to_vec()).map_err(|_| {
+                Error::Invalid(format!(
+                    "El valor para la columna {column} no es un texto UTF-8 válido"
+                ))
+            })?;
+            Constant::String(string)
+        }
+        ColumnDataType::Int => {
+            let array: [Byte; 4] = value.try_into().map_err(|_| wrong_len(4))?;
+            Constant::Integer(Int::from_be_bytes(array))
+        }
+        ColumnDataType::Double => {
+            let array: [Byte; 8] = value.try_into().map_err(|_| wrong_len(8))?;
+            Constant::from_number(Double::from_be_bytes(array))
+        }
+        ColumnDataType::Timestamp | ColumnDataType::Counter => {
+            let array: [Byte; 8] = value.try_into().map_err(|_| wrong_len(8))?;
+            Constant::from_number(Long::from_be_bytes(array) as Double)
+        }
+    };
+    Ok(constant)
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::nodes::table_metadata::column_config::ColumnConfig,
+        parser::{main_parser::make_parse, statements::statement::Statement},
+        tokenizer::tok::tokenize_query,
+    };
+
+    fn vuelos() -> Table {
+        let columns = [
+            ("orig", ColumnDataType::String),
+            ("id", ColumnDataType::Int),
+            ("salida", ColumnDataType::Timestamp),
+            ("estado", ColumnDataType::String),
+        ];
+        Table::new(
+            "vuelos".to_string(),
+            "aerolinea".to_string(),
+            columns
+                .into_iter()
+                .map(|(name, data_type)| ColumnConfig::new(name.to_string(), data_type))
+                .collect(),
+            vec!["orig".to_string()],
+            None,
+        )
+    }
+
+    fn prepare(query: &str) -> Result<PreparedStatement> {
+        match make_parse(&mut tokenize_query(query))? {
+            Statement::DmlStatement(statement) => {
+                PreparedStatement::new(query, statement, "aerolinea", &vuelos())
+            }
+            _ => Err(Error::Invalid(format!("No es una query DML: {query}"))),
+        }
+    }
+
+    fn bound_columns(prepared: &PreparedStatement) -> Vec<&str> {
+        prepared
+            .bind_columns
+            .iter()
+            .map(|(column, _)| column.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_1_una_columna_repetida_tiene_un_marcador_por_comparacion() -> Result<()> {
+        let prepared =
+            prepare("SELECT * FROM vuelos WHERE orig = ? AND salida > ? AND salida < ?")?;
+
+        assert_eq!(bound_columns(&prepared), vec!["orig", "salida", "salida"]);
+        assert!(matches!(
+            prepared.bind_columns[2].1,
+            ColumnDataType::Timestamp
+        ));
+        assert_eq!(prepared.pk_indexes, vec![0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_el_marcador_de_un_in_es_de_su_columna() -> Result<()> {
+        let prepared = prepare("SELECT * FROM vuelos WHERE orig IN ? AND id = ?")?;
+
+        assert_eq!(bound_columns(&prepared), vec!["orig", "id"]);
+        assert_eq!(prepared.pk_indexes, vec![0]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_3_los_valores_de_using_se_vinculan_en_el_orden_de_la_query() -> Result<()> {
+        let prepared = prepare(
+            "UPDATE vuelos USING TIMESTAMP ? AND TTL ? SET estado = ? WHERE orig = ? AND id = ?",
+        )?;
+        assert_eq!(
+            bound_columns(&prepared),
+            vec!["TIMESTAMP", "TTL", "estado", "orig", "id"]
+        );
+
+        let values = vec![
+            (1_000 as Long).to_be_bytes().to_vec(),
+            (60 as Int).to_be_bytes().to_vec(),
+            b"demorado".to_vec(),
+            b"EZE".to_vec(),
+            (7 as Int).to_be_bytes().to_vec(),
+        ];
+        let statement = prepared.bind(&values, None)?;
+        let Some(using) = statement.update_parameters() else {
+            return Err(Error::Invalid("El UPDATE no tiene USING".to_string()));
+        };
+        assert_eq!(using.get_timestamp()?, Some(1_000));
+        assert_eq!(using.get_ttl()?, Some(60));
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+//! Módulo para consultar a varias réplicas en paralelo y recibir sus respuestas.
+
+use {
+    protocol::{
+        aliases::{results::Result, types::Byte},
+        errors::error::Error,
+    },
+    std::{
+        sync::mpsc::{channel, Receiver},
+        thread::Builder,
+        time::{Duration, Instant},
+    },
+};
+
+/// Las respuestas de pedidos enviados en paralelo a varias réplicas.
+///
+/// Cada pedido se ejecuta en su propio hilo, y las respuestas se devuelven en el orden en que
+/// llegan, hasta recibirlas todas o hasta que se cumpla el tiempo máximo de espera. Los pedidos
+/// que no terminen a tiempo siguen en segundo plano, pero sus respuestas se descartan.
+///
+/// Cada pedido se identifica con una clave `K`, que se devuelve junto a su respuesta.
+pub struct ReplicaResponses<K> {
+    /// El canal por el que llegan las respuestas.
+    receiver: Receiver<(K, Vec<Byte>)>,
+
+    /// La cantidad de respuestas que todavía no llegaron.
+    pending: usize,
+
+    /// El momento a partir del cual se dejan de esperar respuestas.
+    deadline: Instant,
+}
+
+impl<K: Send + 'static> ReplicaResponses<K> {
+    /// Ejecuta `request` para cada clave en un hilo propio.
+    ///
+    /// Si un pedido falla, se toma como una respuesta vacía.
+    pub fn spawn<F>(keys: Vec<K>, timeout: Duration, request: F) -> Result<Self>
+    where
+        F: Fn(&K) -> Result<Vec<Byte>> + Clone + Send + 'static,
+    {
+        let (sender, receiver) = channel();
+        let pending = keys.len();
+        for (i, key) in keys.into_iter().enumerate() {
+            let sender = sender.clone();
+            let request = request.clone();
+            Builder::new()
+                .name(format!("replica_request_{i}"))
+                .spawn(move || {
+                    let response = request(&key).unwrap_or_default();
+                    // Si ya no se esperan respuestas, el receptor no existe más.
+                    let _ = sender.send((key, response));
+                })
+                .map_err(|e| {
+                    Error::ServerError(format!("No se pudo enviar el pedido a una réplica: {e}"))
+                })?;
+        }
+        Ok(Self {
+            receiver,
+            pending,
+            deadline: Instant::now() + timeout,
+        })
+    }
+}
+
+impl<K> Iterator for ReplicaResponses<K> {
+    type Item = (K, Vec<Byte>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pending == 0 {
+            return None;
+        }
+        let remaining = self.deadline.saturating_duration_since(Instant::now());
+        match self.receiver.recv_timeout(remaining) {
+            Ok(response) => {
+                self.pending -= 1;
+                Some(response)
+            }
+            Err(_) => {
+                self.pending = 0;
+                None
+            }
+        }
+    }
+}
@@ -0,0 +1,493 @@
+//! Módulo para las funciones que se pueden usar en los selectores de un SELECT.
+
+use {
+    crate::nodes::{disk_operations::storage_entry::StorageEntry, table_metadata::table::Table},
+    chrono::Utc,
+    parser::statements::dml_statement::main_statements::select::{
+        function_call::FunctionCall, kind_of_columns::KindOfColumns, selector::Selector,
+    },
+    protocol::{
+        aliases::{
+            results::Result,
+            types::{Double, Long, Uuid},
+        },
+        errors::error::Error,
+        messages::responses::result::col_type::ColType,
+        utils::encode_uuid_to_string,
+    },
+    rand::random,
+    std::cmp::Ordering,
+};
+
+/// La cantidad de intervalos de 100 nanosegundos entre el inicio del calendario gregoriano
+/// (15 de octubre de 1582) y la época UNIX, que es desde donde cuentan los _timeuuids_.
+const GREGORIAN_TO_UNIX_INTERVALS: Long = 0x01B2_1DD2_1381_4000;
+/// La cantidad de intervalos de 100 nanosegundos en un milisegundo.
+const INTERVALS_PER_MILLI: Long = 10_000;
+
+/// Las funciones disponibles, según el nombre con el que se las llama.
+const FUNCTIONS: [(&str, SelectFunction); 9] = [
+    ("count", SelectFunction::Count),
+    ("min", SelectFunction::Min),
+    ("max", SelectFunction::Max),
+    ("sum", SelectFunction::Sum),
+    ("avg", SelectFunction::Avg),
+    ("now", SelectFunction::Now),
+    ("totimestamp", SelectFunction::ToTimestamp),
+    ("writetime", SelectFunction::WriteTime),
+    ("ttl", SelectFunction::Ttl),
+];
+
+/// Una función que se puede usar en un selector.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SelectFunction {
+    /// Cuenta las filas, o los valores no nulos de su argumento.
+    Count,
+    /// El menor valor no nulo de su argumento.
+    Min,
+    /// El mayor valor no nulo de su argumento.
+    Max,
+    /// La suma de los valores de su argumento.
+    Sum,
+    /// El promedio de los valores de su argumento.
+    Avg,
+    /// Un _timeuuid_ nuevo, del momento en que se evalúa.
+    Now,
+    /// El momento _(en milisegundos)_ de un _timeuuid_.
+    ToTimestamp,
+    /// El timestamp _(en microsegundos)_ de la escritura de una celda.
+    WriteTime,
+    /// Los segundos que le quedan a una celda antes de vencer.
+    Ttl,
+}
+
+impl SelectFunction {
+    /// Busca una función por su nombre, sin importar mayúsculas y minúsculas.
+    pub fn from_name(name: &str) -> Result<Self> {
+        match FUNCTIONS
+            .iter()
+            .find(|(function_name, _)| function_name.eq_ignore_ascii_case(name))
+        {
+            Some((_, function)) => Ok(*function),
+            None => Err(Error::Invalid(format!("No existe la función {name}"))),
+        }
+    }
+
+    /// Verifica si la función agrega los valores de varias filas en uno solo.
+    pub fn is_aggregate(&self) -> bool {
+        matches!(
+            self,
+            Self::Count | Self::Min | Self::Max | Self::Sum | Self::Avg
+        )
+    }
+
+    /// Valida los argumentos de una llamada a la función.
+    fn check_args(&self, function_call: &FunctionCall, table: &Table) -> Result<()> {
+        let args = &function_call.args;
+        let expected_args = match self {
+            Self::Count => args.len().min(1),
+            Self::Now => 0,
+            _ => 1,
+        };
+        if args.len() != expected_args {
+            return Err(Error::Invalid(format!(
+                "La función {} recibe {expected_args} argumento(s), no {}",
+                function_call.name,
+                args.len()
+            )));
+        }
+        if self.is_aggregate() && args.iter().any(has_aggregates) {
+            return Err(Error::Invalid(format!(
+                "No se pueden anidar funciones de agregación en {}",
+                function_call.get_name()
+            )));
+        }
+        if matches!(self, Self::WriteTime | Self::Ttl) {
+            match args.first() {
+                Some(Selector::ColumnName(column))
+                    if table.is_primary_key_column(column.get_name()) =>
+                {
+                    return Err(Error::Invalid(format!(
+                        "No se puede usar {} sobre la columna {} de la primary key",
+                        function_call.name,
+                        column.get_name()
+                    )))
+                }
+                Some(Selector::ColumnName(_)) => (),
+                _ => {
+                    return Err(Error::Invalid(format!(
+                        "La función {} sólo recibe el nombre de una columna",
+                        function_call.name
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Obtiene el tipo del resultado de la función, según los tipos de sus argumentos.
+    fn return_type(&self, function_call: &FunctionCall, arg_types: &[ColType]) -> Result<ColType> {
+        match (self, arg_types.first()) {
+            (Self::Count | Self::WriteTime, _) => Ok(ColType::Bigint),
+            (Self::Ttl, _) => Ok(ColType::Int),
+            (Self::Now, _) => Ok(ColType::Timeuuid),
+            (Self::ToTimestamp, Some(ColType::Timeuuid)) => Ok(ColType::Timestamp),
+            (Self::Min | Self::Max, Some(arg_type)) => Ok(arg_type.clone()),
+            (Self::Sum | Self::Avg, Some(arg_type)) if is_numeric(arg_type) => Ok(arg_type.clone()),
+            _ => Err(Error::Invalid(format!(
+                "Tipo de argumento inválido para la función {}",
+                function_call.get_name()
+            ))),
+        }
+    }
+
+    /// Evalúa la función escalar con los argumentos de una fila.
+    fn evaluate(&self, function_call: &FunctionCall, row: &RowContext) -> Result<String> {
+        match self {
+            Self::Now => Ok(new_timeuuid(Utc::now().timestamp_millis())),
+            Self::ToTimestamp => {
+                let timeuuid = evaluate(&function_call.args[0], row)?;
+                match millis_of_timeuuid(&timeuuid) {
+                    Some(millis) => Ok(millis.to_string()),
+                    None if timeuuid.is_empty() => Ok(String::new()),
+                    None => Err(Error::Invalid(format!("{timeuuid} no es un timeuuid"))),
+                }
+            }
+            Self::WriteTime | Self::Ttl => {
+                let column = function_call.args[0].get_name();
+                let has_value = !row.get_value(&column)?.is_empty();
+                let Some(entry) = row.entry.filter(|_| has_value) else {
+                    return Ok(String::new());
+                };
+                if *self == Self::WriteTime {
+                    return Ok(entry.timestamp().to_string());
+                }
+                let now = Utc::now().timestamp();
+                Ok(entry
+                    .expiring_cells
+                    .get(&column)
+                    .copied()
+                    .or(entry.expires_at)
+                    .map(|expires_at| (expires_at - now).max(0).to_string())
+                    .unwrap_or_default())
+            }
+            _ => Err(Error::Invalid(format!(
+                "La función de agregación {} no puede usarse dentro de otra función",
+                function_call.get_name()
+            ))),
+        }
+    }
+
+    /// Agrega los valores de las filas de un grupo.
+    ///
+    /// Los valores nulos se ignoran, salvo en `COUNT(*)`, que cuenta todas las filas.
+    fn aggregate(
+        &self,
+        function_call: &FunctionCall,
+        arg_type: Option<&ColType>,
+        rows: &[RowContext],
+    ) -> Result<String> {
+        let Some(arg) = function_call.args.first() else {
+            return Ok(rows.len().to_string());
+        };
+        let mut values = Vec::new();
+        for row in rows {
+            let value = evaluate(arg, row)?;
+            if !value.is_empty() {
+                values.push(value);
+            }
+        }
+        let is_double = matches!(arg_type, Some(ColType::Double | ColType::Float));
+        match self {
+            Self::Count => Ok(values.len().to_string()),
+            Self::Min | Self::Max => {
+                let numeric = arg_type.is_some_and(is_numeric);
+                let compare = |a: &&String, b: &&String| compare_values(a, b, numeric);
+                let value = match self {
+                    Self::Min => values.iter().min_by(compare),
+                    _ => values.iter().max_by(compare),
+                };
+                Ok(value.cloned().unwrap_or_default())
+            }
+            Self::Sum | Self::Avg if is_double => {
+                let numbers = parse_all::<Double>(&values)?;
+                let sum: Double = numbers.iter().sum();
+                match self {
+                    Self::Avg if !numbers.is_empty() => {
+                        Ok((sum / numbers.len() as Double).to_string())
+                    }
+                    _ => Ok(sum.to_string()),
+                }
+            }
+            Self::Sum | Self::Avg => {
+                let numbers = parse_all::<Long>(&values)?;
+                let sum: Long = numbers.iter().sum();
+                match self {
+                    Self::Avg if !numbers.is_empty() => {
+                        Ok((sum / numbers.len() as Long).to_string())
+                    }
+                    _ => Ok(sum.to_string()),
+                }
+            }
+            _ => Err(Error::Invalid(format!(
+                "La función {} no es de agregación",
+                function_call.get_name()
+            ))),
+        }
+    }
+}
+
+/// Los valores de una fila con los que se evalúan los selectores.
+pub struct RowContext<'a> {
+    /// Los nombres de las columnas de la fila.
+    columns: &'a [String],
+    /// Los valores de la fila.
+    row: &'a [String],
+    /// La entrada de la que salió la fila, si se leyó del disco.
+    entry: Option<&'a StorageEntry>,
+}
+
+impl<'a> RowContext<'a> {
+    /// Crea el contexto para evaluar selectores sobre una fila.
+    pub fn new(columns: &'a [String], row: &'a [String], entry: Option<&'a StorageEntry>) -> Self {
+        Self {
+            columns,
+            row,
+            entry,
+        }
+    }
+
+    /// Obtiene el valor de una columna de la fila, si es que la tiene.
+    fn find_value(&self, column: &str) -> Option<&String> {
+        self.columns
+            .iter()
+            .position(|col| col == column)
+            .and_then(|position| self.row.get(position))
+    }
+
+    /// Obtiene el valor de una columna de la fila.
+    fn get_value(&self, column: &str) -> Result<&String> {
+        self.find_value(column)
+            .ok_or_else(|| Error::Invalid(format!("La tabla no contiene la columna {column}")))
+    }
+}
+
+/// Verifica si alguno de los selectores del SELECT usa una función de agregación.
+pub fn select_has_aggregates(columns: &KindOfColumns) -> bool {
+    match columns {
+        KindOfColumns::SelectClause(selectors) => selectors.iter().any(has_aggregates),
+        KindOfColumns::All => false,
+    }
+}
+
+/// Verifica si el selector usa una función de agregación.
+fn has_aggregates(selector: &Selector) -> bool {
+    match selector.unaliased() {
+        Selector::FunctionCall(function_call) => {
+            SelectFunction::from_name(&function_call.name).is_ok_and(|f| f.is_aggregate())
+                || function_call.args.iter().any(has_aggregates)
+        }
+        _ => false,
+    }
+}
+
+/// Obtiene los selectores del SELECT que se calculan fila por fila: los términos y las
+/// funciones escalares, incluyendo a los que son argumentos de funciones de agregación.
+///
+/// Sus resultados se agregan al final de cada fila como columnas más, con el nombre del
+/// selector, para poder filtrarlas, ordenarlas y proyectarlas igual que al resto.
+pub fn get_computed_selectors(columns: &KindOfColumns) -> Vec<&Selector> {
+    let mut computed: Vec<&Selector> = Vec::new();
+    if let KindOfColumns::SelectClause(selectors) = columns {
+        for selector in selectors {
+            add_computed_selectors(selector.unaliased(), &mut computed);
+        }
+    }
+    computed
+}
+
+fn add_computed_selectors<'a>(selector: &'a Selector, computed: &mut Vec<&'a Selector>) {
+    match selector {
+        Selector::ColumnName(_) | Selector::Alias(..) => (),
+        Selector::FunctionCall(function_call) if has_aggregates(selector) => {
+            for arg in &function_call.args {
+                add_computed_selectors(arg, computed);
+            }
+        }
+        _ => {
+            if computed
+                .iter()
+                .all(|other| other.get_name() != selector.get_name())
+            {
+                computed.push(selector);
+            }
+        }
+    }
+}
+
+/// Obtiene el tipo del valor de un selector.
+pub fn selector_type(selector: &Selector, table: &Table) -> Result<ColType> {
+    match selector {
+        Selector::ColumnName(column) => table
+            .get_columns_name_and_data_type()
+            .iter()
+            .find(|(name, _)| name == column.get_name())
+            .map(|(_, data_type)| data_type.into())
+            .ok_or_else(|| {
+                Error::Invalid(format!(
+                    "La tabla {} no contiene la columna {}",
+                    table.get_name(),
+                    column.get_name()
+                ))
+            }),
+        Selector::Term(_) => Ok(ColType::Varchar),
+        Selector::FunctionCall(function_call) => {
+            let function = SelectFunction::from_name(&function_call.name)?;
+            function.check_args(function_call, table)?;
+            let arg_types = function_call
+                .args
+                .iter()
+                .map(|arg| selector_type(arg, table))
+                .collect::<Result<Vec<ColType>>>()?;
+            function.return_type(function_call, &arg_types)
+        }
+        Selector::Alias(selector, _) => selector_type(selector, table),
+    }
+}
+
+/// Evalúa un selector sin funciones de agregación sobre una fila.
+///
+/// Si la fila ya tiene una columna con el nombre del selector, se usa su valor.
+pub fn evaluate(selector: &Selector, row: &RowContext) -> Result<String> {
+    match selector {
+        Selector::ColumnName(column) => row.get_value(column.get_name()).cloned(),
+        Selector::Term(term) => Ok(term.get_value_as_string()),
+        Selector::FunctionCall(function_call) => {
+            if let Some(value) = row.find_value(&selector.get_name()) {
+                return Ok(value.to_string());
+            }
+            SelectFunction::from_name(&function_call.name)?.evaluate(function_call, row)
+        }
+        Selector::Alias(selector, _) => evaluate(selector, row),
+    }
+}
+
+/// Agrega las filas de un SELECT con funciones de agregación, devolviendo una fila por grupo.
+///
+/// Las filas se agrupan según los valores en `group_positions`, en el orden en que aparecen.
+/// Sin GROUP BY todas las filas forman un único grupo, aunque no haya ninguna. Los selectores
+/// que no son de agregación toman el valor de la primera fila de su grupo.
+pub fn aggregate_rows(
+    selectors: &[Selector],
+    columns: &[String],
+    rows: &[Vec<String>],
+    group_positions: Option<&[usize]>,
+    table: &Table,
+) -> Result<Vec<Vec<String>>> {
+    let mut groups: Vec<(Vec<String>, Vec<RowContext>)> = Vec::new();
+    if group_positions.is_none() {
+        groups.push((Vec::new(), Vec::new()));
+    }
+    for row in rows {
+        let group_key: Vec<String> = group_positions
+            .unwrap_or_default()
+            .iter()
+            .map(|position| row.get(*position).cloned().unwrap_or_default())
+            .collect();
+        let context = RowContext::new(columns, row, None);
+        match groups.iter_mut().find(|(key, _)| *key == group_key) {
+            Some((_, group_rows)) => group_rows.push(context),
+            None => groups.push((group_key, vec![context])),
+        }
+    }
+
+    let mut aggregated = Vec::new();
+    for (_, group_rows) in &groups {
+        let mut aggregated_row = Vec::new();
+        for selector in selectors {
+            let value = match selector.unaliased() {
+                Selector::FunctionCall(function_call) if has_aggregates(selector) => {
+                    let function = SelectFunction::from_name(&function_call.name)?;
+                    let arg_type = match function_call.args.first() {
+                        Some(arg) => Some(selector_type(arg, table)?),
+                        None => None,
+                    };
+                    function.aggregate(function_call, arg_type.as_ref(), group_rows)?
+                }
+                selector => match group_rows.first() {
+                    Some(first_row) => evaluate(selector, first_row)?,
+                    None => String::new(),
+                },
+            };
+            aggregated_row.push(value);
+        }
+        aggregated.push(aggregated_row);
+    }
+    Ok(aggregated)
+}
+
+/// Verifica si el tipo es numérico.
+fn is_numeric(col_type: &ColType) -> bool {
+    matches!(
+        col_type,
+        ColType::Int
+            | ColType::Bigint
+            | ColType::Smallint
+            | ColType::Tinyint
+            | ColType::Varint
+            | ColType::Counter
+            | ColType::Double
+            | ColType::Float
+            | ColType::Decimal
+            | ColType::Timestamp
+    )
+}
+
+/// Compara dos valores, como números si el tipo es numérico y si no como strings.
+fn compare_values(a: &str, b: &str, numeric: bool) -> Ordering {
+    match (numeric, a.parse::<Double>(), b.parse::<Double>()) {
+        (true, Ok(a), Ok(b)) => a.total_cmp(&b),
+        _ => a.cmp(b),
+    }
+}
+
+/// Parsea todos los valores como números.
+fn parse_all<T: std::str::FromStr>(values: &[String]) -> Result<Vec<T>> {
+    values
+        .iter()
+        .map(|value| {
+            value
+                .parse::<T>()
+                .map_err(|_| Error::Invalid(format!("El valor {value} no es numérico")))
+        })
+        .collect()
+}
+
+/// Crea un _timeuuid_ (UUID versión 1) del momento dado _(en milisegundos)_, con el resto de
+/// sus bits al azar.
+fn new_timeuuid(millis: Long) -> String {
+    let intervals = (millis * INTERVALS_PER_MILLI + GREGORIAN_TO_UNIX_INTERVALS) as Uuid;
+    let time_low = intervals & 0xFFFF_FFFF;
+    let time_mid = (intervals >> 32) & 0xFFFF;
+    let time_high_and_version = ((intervals >> 48) & 0x0FFF) | 0x1000;
+    let clock_seq_and_variant = (random::<u16>() as Uuid & 0x3FFF) | 0x8000;
+    let node = random::<u64>() as Uuid & 0xFFFF_FFFF_FFFF;
+    encode_uuid_to_string(
+        (time_low << 96)
+            | (time_mid << 80)
+            | (time_high_and_version << 64)
+            | (clock_seq_and_variant << 48)
+            | node,
+    )
+}
+
+/// Obtiene el momento _(en milisegundos)_ de un _timeuuid_, o `None` si no es uno.
+fn millis_of_timeuuid(timeuuid: &str) -> Option<Long> {
+    let uuid = Uuid::from_str_radix(&timeuuid.replace('-', ""), 16).ok()?;
+    if timeuuid.len() != 36 || (uuid >> 76) & 0xF != 1 {
+        return None;
+    }
+    let intervals =
+        (((uuid >> 64) & 0x0FFF) << 48) | (((uuid >> 80) & 0xFFFF) << 32) | (uuid >> 96);
+    Some((intervals as Long - GREGORIAN_TO_UNIX_INTERVALS) / INTERVALS_PER_MILLI)
+}
@@ -2,15 +2,35 @@
 
 use {
     crate::{
-        cql_frame::query_body::QueryBody,
+        cql_frame::{
+            batch_body::{BatchBody, BatchQuery},
+            frame::Frame,
+            paging_state::{Page, PagingState},
+            query_body::QueryBody,
+        },
         modes::ConnectionMode,
         nodes::{
             actions::opcode::{GossipInfo, SvAction},
-            disk_operations::disk_handler::DiskHandler,
+            consistency_counter::ConsistencyCounter,
+            disk_operations::{
+                disk_handler::DiskHandler,
+                storage_entry::{SortedEntries, StorageEntry},
+            },
+            materialized_view::{
+                get_inserted_row, get_key_condition, get_view_mutations, get_view_ttl, BaseRows,
+                NamedRow, PartitionLocks,
+            },
+            merkle_tree::MerkleTree,
             node::{Node, NodeId, NodesMap /*N_NODES*/},
             port_type::PortType,
+            replica_responses::ReplicaResponses,
+            select_functions::select_has_aggregates,
             states::{appstatus::AppStatus, endpoints::EndpointState, heartbeat::HeartbeatState},
             table_metadata::table::Table,
+            trace_session::{
+                get_traces_schema_queries, TraceSession, EVENTS_TABLE, SESSIONS_TABLE,
+                TRACES_KEYSPACE,
+            },
             utils::{
                 hash_value, next_node_in_the_cluster, send_to_node,
                 send_to_node_and_wait_response_with_timeout,
@@ -21,13 +41,17 @@ use {
     chrono::Utc,
     logger::log::{LogLevel, Logger},
     parser::{
+        assignment::Assignment,
         data_types::keyspace_name::KeyspaceName,
         main_parser::make_parse,
         statements::{
             ddl_statement::{
-                alter_keyspace::AlterKeyspace, create_keyspace::CreateKeyspace,
+                alter_keyspace::AlterKeyspace, alter_table::AlterTable, create_index::CreateIndex,
+                create_keyspace::CreateKeyspace, create_materialized_view::CreateMaterializedView,
                 create_table::CreateTable, ddl_statement_parser::DdlStatement,
-                drop_keyspace::DropKeyspace,
+                drop_index::DropIndex, drop_keyspace::DropKeyspace,
+                drop_materialized_view::DropMaterializedView, drop_table::DropTable,
+                truncate::Truncate,
             },
             dml_statement::{
                 dml_statement_parser::DmlStatement,
@@ -42,22 +66,38 @@ use {
     protocol::{
         aliases::{
             results::Result,
-            types::{Byte, Long, Uint, Ulong},
+            types::{Byte, Int, Long, Short, ShortInt, SupportedMultiMap, Uint, Ulong, Uuid},
         },
-        errors::error::Error,
+        errors::{error::Error, write_type::WriteType},
         headers::{
-            flags::Flag, length::Length, msg_headers::Headers, opcode::Opcode, stream::Stream,
+            compression::{is_compressed, Compression},
+            flags::Flag,
+            length::Length,
+            msg_headers::Headers,
+            opcode::Opcode,
+            stream::Stream,
             version::Version,
         },
+        messages::requests::{batch_types::BatchType, prepare_flags::PrepareFlag},
         notations::consistency::Consistency,
-        traits::Byteable,
-        utils::{parse_bytes_to_string, parse_bytes_to_string_map},
+        traits::{Byteable, Maskable},
+        utils::{
+            encode_string_multimap_to_bytes, parse_bytes_to_long_string, parse_bytes_to_string,
+            parse_bytes_to_string_map,
+        },
     },
     std::{
         collections::{HashMap, HashSet},
         io::{Read, Write},
+        net::SocketAddr,
         path::Path,
-        sync::{Arc, RwLock},
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            mpsc::Sender,
+            Arc, RwLock,
+        },
+        thread::Builder,
+        time::Duration,
     },
     tokenizer::tok::tokenize_query,
     utils::get_root_path::get_root_path,
@@ -65,8 +105,28 @@ use {
 
 /// El tiempo de espera _(en segundos)_ por una respuesta.
 pub const TIMEOUT_SECS: Ulong = 1;
+/// El tiempo máximo _(en milisegundos)_ que el coordinador espera las confirmaciones de una escritura.
+const WRITE_REQUEST_TIMEOUT_MILLIS: Ulong = 2000;
+/// El tiempo máximo _(en milisegundos)_ que el coordinador espera las respuestas de una lectura.
+const READ_REQUEST_TIMEOUT_MILLIS: Ulong = 5000;
+/// El tiempo de espera _(en segundos)_ por las respuestas de otra réplica durante una reparación.
+const REPAIR_TIMEOUT_SECS: Ulong = 10;
+/// El tamaño máximo _(en bytes)_ de las entradas que se envían en un mismo mensaje al reparar.
+const REPAIR_BATCH_BYTES: usize = 4096;
 /// El nombre del directorio para el almacenamiento de los logs de mensajes de los nodos.
 const LOGS_DIR_NAME: &str = "logs";
+/// La versión de CQL soportada.
+const CQL_VERSION: &str = "5.0.0";
+/// Los tipos de eventos a los que un cliente se puede registrar.
+const EVENT_TYPES: [&str; 3] = ["TOPOLOGY_CHANGE", "STATUS_CHANGE", "SCHEMA_CHANGE"];
+
+/// Agrupa las _queries_ de un BATCH según la réplica (y opcionalmente la partición) a la que van.
+///
+/// Cada _query_ se guarda como su índice en el BATCH junto al nodo dueño de su partición.
+type BatchGroups = HashMap<(NodeId, Option<NodeId>), Vec<(usize, NodeId)>>;
+/// Un grupo de _queries_ de un BATCH para otra réplica, con las posiciones y dueños de sus
+/// _queries_ y la request interna que se le envía.
+type RemoteBatchGroup = (NodeId, Vec<(usize, NodeId)>, Vec<Byte>);
 
 /// Se encarga de procesar todo lo relacionado a una sesión de un cliente.
 ///
@@ -79,6 +139,14 @@ pub struct SessionHandler {
     pub logger: Arc<RwLock<Logger>>,
     /// Referencia compartida del lock conteniendo al nodo.
     pub lock: Arc<RwLock<Node>>,
+    /// La dirección del cliente de la sesión y el canal por el cual enviarle eventos, si tiene.
+    events_sender: Option<(SocketAddr, Sender<Vec<Byte>>)>,
+    /// La sesión de _tracing_ de la request que se está procesando, si el cliente la pidió.
+    trace: Option<TraceSession>,
+    /// Las particiones de tablas con vistas materializadas que se están escribiendo.
+    view_locks: Arc<PartitionLocks>,
+    /// Si se están reenviando los _hints_ y BATCH pendientes en segundo plano.
+    replaying: Arc<AtomicBool>,
 }
 
 impl SessionHandler {
@@ -101,9 +169,47 @@ impl SessionHandler {
             id,
             logger: Arc::new(RwLock::new(logger)),
             lock: Arc::new(RwLock::new(node)),
+            events_sender: None,
+            trace: None,
+            view_locks: Arc::new(PartitionLocks::default()),
+            replaying: Arc::new(AtomicBool::new(false)),
         })
     }
 
+    /// Crea una copia de este `SessionHandler` asociada a un cliente, que podrá registrarse
+    /// para recibir eventos por el canal dado.
+    pub fn with_events_sender(&self, client_addr: SocketAddr, sender: Sender<Vec<Byte>>) -> Self {
+        let mut session_handler = self.clone();
+        session_handler.events_sender = Some((client_addr, sender));
+        session_handler
+    }
+
+    /// Crea una copia de este `SessionHandler` que registra sus eventos en la sesión de
+    /// _tracing_ dada.
+    fn with_trace(&self, trace: TraceSession) -> Self {
+        let mut session_handler = self.clone();
+        session_handler.trace = Some(trace);
+        session_handler
+    }
+
+    /// Registra un evento en la sesión de _tracing_ de la request actual, si la hay.
+    fn trace(&self, activity: &str) -> Result<()> {
+        match &self.trace {
+            Some(trace) => trace.record(self.id, activity),
+            None => Ok(()),
+        }
+    }
+
+    /// Envuelve los bytes de una acción para otro nodo en una acción
+    /// [Traced](SvAction::Traced), si la request actual tiene una sesión de _tracing_, para que
+    /// la réplica registre en ella sus propios eventos.
+    fn traced_action(&self, action: Vec<Byte>) -> Vec<Byte> {
+        match &self.trace {
+            Some(trace) => SvAction::Traced(trace.get_id(), action).as_bytes(),
+            None => action,
+        }
+    }
+
     /// Accede al nodo para escritura mutable, es lockeado para otros.
     fn write(&self) -> Result<std::sync::RwLockWriteGuard<'_, Node>> {
         match self.lock.write() {
@@ -180,11 +286,14 @@ impl SessionHandler {
 
     /// Procesa una _request_ en forma de [Byte]s.
     /// También devuelve un [bool] indicando si se debe parar el hilo.
+    ///
+    /// `compression` es el algoritmo de compresión negociado con el cliente en el STARTUP, si hay.
     pub fn process_stream<S>(
         &self,
         stream: &mut S,
         bytes: Vec<Byte>,
         is_logged: bool,
+        compression: Option<Compression>,
     ) -> Result<Vec<Byte>>
     where
         S: Read + Write,
@@ -203,7 +312,7 @@ impl SessionHandler {
                 }
                 Ok(vec![])
             }
-            None => self.match_kind_of_conection_mode(bytes, stream, is_logged),
+            None => self.match_kind_of_conection_mode(bytes, stream, is_logged, compression),
         }
     }
 
@@ -213,6 +322,10 @@ impl SessionHandler {
         S: Read + Write,
     {
         let mut stop = false;
+        let action = action.decompressed()?;
+        if let SvAction::Traced(session_id, action_bytes) = action {
+            return self.sv_action_traced(session_id, &action_bytes, tcp_stream);
+        }
         let logger = self
             .logger
             .read()
@@ -239,8 +352,8 @@ impl SessionHandler {
             SvAction::NewNeighbour(id, state) => {
                 self.sv_action_new_neighbour(&logger, id, state)?;
             }
-            SvAction::SendEndpointState(id, ip) => {
-                self.sv_action_send_endpoint_state(&logger, id, ip)?;
+            SvAction::SendEndpointState(id, ip, location) => {
+                self.sv_action_send_endpoint_state(&logger, id, ip, location)?;
             }
             SvAction::InternalQuery(bytes) => {
                 self.sv_action_internal_query(&mut tcp_stream, &logger, bytes)?;
@@ -294,11 +407,150 @@ impl SessionHandler {
             SvAction::UpdateIpsTable(ips_table) => {
                 self.sv_action_update_ips_table(&logger, ips_table)?;
             }
+            SvAction::Repair(keyspace_name, table_name) => {
+                self.sv_action_repair(&logger, keyspace_name, table_name)?;
+            }
+            SvAction::MerkleTreeRequest(table_name, node_id) => {
+                self.sv_action_merkle_tree_request(&mut tcp_stream, &logger, table_name, node_id)?;
+            }
+            SvAction::RepairRangesRequest(table_name, node_id, leaves) => {
+                self.sv_action_repair_ranges_request(
+                    &mut tcp_stream,
+                    &logger,
+                    table_name,
+                    node_id,
+                    leaves,
+                )?;
+            }
+            SvAction::Compressed(_) => {
+                return Err(Error::ServerError(
+                    "No se pudo descomprimir la acción recibida".to_string(),
+                ));
+            }
+            SvAction::Traced(..) => {
+                return Err(Error::ServerError(
+                    "La acción con tracing debería haberse procesado antes".to_string(),
+                ));
+            }
         };
 
         Ok(stop)
     }
 
+    /// Procesa una acción que el coordinador mandó dentro de una sesión de _tracing_,
+    /// registrando los eventos de esta réplica en ella.
+    ///
+    /// Los eventos se guardan en otro hilo una vez respondida la acción, para no demorar al
+    /// coordinador.
+    fn sv_action_traced<S>(
+        &self,
+        session_id: Uuid,
+        action_bytes: &[Byte],
+        tcp_stream: S,
+    ) -> Result<bool>
+    where
+        S: Read + Write,
+    {
+        let action = SvAction::try_from(action_bytes)?.decompressed()?;
+        let trace = TraceSession::resume(session_id, self.id);
+        let traced_handler = self.with_trace(trace.clone());
+        traced_handler.trace(format!("Request recibida por la réplica {}", self.id).as_str())?;
+        let stop = traced_handler.handle_sv_action(action, tcp_stream);
+        match &stop {
+            Ok(_) => traced_handler.trace("Respuesta enviada al coordinador")?,
+            Err(err) => traced_handler.trace(format!("La request falló: {err}").as_str())?,
+        }
+
+        let mut session_handler = self.clone();
+        session_handler.trace = None;
+        Builder::new()
+            .name(format!("{}_trace", self.id))
+            .spawn(move || {
+                if let Err(err) = session_handler.store_trace(&trace) {
+                    session_handler.log_trace_error(err);
+                }
+            })
+            .map_err(|e| {
+                Error::ServerError(format!("No se pudo guardar la sesión de tracing: {e}"))
+            })?;
+        stop
+    }
+
+    fn sv_action_repair(
+        &self,
+        logger: &std::sync::RwLockReadGuard<'_, Logger>,
+        keyspace_name: Option<String>,
+        table_name: Option<String>,
+    ) -> Result<()> {
+        logger
+            .info(
+                format!(
+                    "Iniciando reparación de las réplicas del nodo {} (keyspace: {}, tabla: {})",
+                    self.id,
+                    keyspace_name.as_deref().unwrap_or("todos"),
+                    table_name.as_deref().unwrap_or("todas"),
+                )
+                .as_str(),
+            )
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+        self.start_repair(keyspace_name, table_name)
+    }
+
+    fn sv_action_merkle_tree_request<S>(
+        &self,
+        tcp_stream: &mut S,
+        logger: &std::sync::RwLockReadGuard<'_, Logger>,
+        table_name: String,
+        node_id: NodeId,
+    ) -> Result<()>
+    where
+        S: Read + Write,
+    {
+        logger
+            .debug("Procesando solicitud de árbol de Merkle")
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+        let res = self.exec_merkle_tree_request(&table_name, node_id)?;
+        let _ = tcp_stream.write_all(&res);
+        if let Err(err) = tcp_stream.flush() {
+            logger
+                .error(format!("Error al enviar el árbol de Merkle: {err}").as_str())
+                .map_err(|e| Error::ServerError(e.to_string()))?;
+            return Err(Error::ServerError(err.to_string()));
+        };
+        logger
+            .info("Árbol de Merkle enviado exitosamente")
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+        Ok(())
+    }
+
+    fn sv_action_repair_ranges_request<S>(
+        &self,
+        tcp_stream: &mut S,
+        logger: &std::sync::RwLockReadGuard<'_, Logger>,
+        table_name: String,
+        node_id: NodeId,
+        leaves: Vec<usize>,
+    ) -> Result<()>
+    where
+        S: Read + Write,
+    {
+        logger
+            .debug("Procesando solicitud de rangos a reparar")
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+        let res = self.exec_repair_ranges_request(&table_name, node_id, &leaves)?;
+        let _ = tcp_stream.write_all(res.as_bytes());
+        if let Err(err) = tcp_stream.flush() {
+            logger
+                .error(format!("Error al enviar los rangos a reparar: {err}").as_str())
+                .map_err(|e| Error::ServerError(e.to_string()))?;
+            return Err(Error::ServerError(err.to_string()));
+        };
+        logger
+            .info("Rangos a reparar enviados exitosamente")
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+        Ok(())
+    }
+
     fn sv_action_update_ips_table(
         &self,
         logger: &std::sync::RwLockReadGuard<'_, Logger>,
@@ -649,11 +901,12 @@ impl SessionHandler {
         logger: &std::sync::RwLockReadGuard<'_, Logger>,
         id: u8,
         ip: String,
+        location: (String, String),
     ) -> Result<()> {
         logger
             .debug(format!("Enviando estado del endpoint al nodo {id}").as_str())
             .map_err(|e| Error::ServerError(e.to_string()))?;
-        self.read()?.send_endpoint_state(id, ip);
+        self.read()?.send_endpoint_state(id, ip, location);
         logger
             .info(format!("Estado del endpoint enviado al nodo {id} exitosamente").as_str())
             .map_err(|e| Error::ServerError(e.to_string()))?;
@@ -744,6 +997,19 @@ impl SessionHandler {
             .debug("Procesando heartbeat")
             .map_err(|e| Error::ServerError(e.to_string()))?;
         self.write()?.beat();
+        for node_id in self.write()?.convict_dead_neighbours() {
+            logger
+                .warning(
+                    format!("El detector de fallas considera caído al nodo {node_id}").as_str(),
+                )
+                .map_err(|e| Error::ServerError(e.to_string()))?;
+        }
+        if self.write()?.repair_is_due(Utc::now().timestamp()) {
+            logger
+                .info("Iniciando la reparación programada de las réplicas")
+                .map_err(|e| Error::ServerError(e.to_string()))?;
+            self.start_repair(None, None)?;
+        }
         Ok(())
     }
 
@@ -752,6 +1018,7 @@ impl SessionHandler {
         bytes: Vec<Byte>,
         mut stream: S,
         is_logged: bool,
+        compression: Option<Compression>,
     ) -> Result<Vec<Byte>>
     where
         S: Read + Write,
@@ -781,7 +1048,17 @@ impl SessionHandler {
                 }
             }
             ConnectionMode::Parsing => {
-                let res = self.handle_request(&bytes[..], false, is_logged);
+                let res = match decompress_request(&bytes, compression) {
+                    // La respuesta al STARTUP no se comprime, porque es el que negocia la compresión
+                    Ok(request) if is_startup(&request) => {
+                        self.handle_request(&request[..], false, is_logged)
+                    }
+                    Ok(request) => compress_response(
+                        self.handle_request(&request[..], false, is_logged),
+                        compression,
+                    ),
+                    Err(err) => make_error_response_for(&bytes, err),
+                };
                 let _ = stream.write_all(&res[..]);
                 if let Err(err) = stream.flush() {
                     logger
@@ -812,24 +1089,141 @@ impl SessionHandler {
             Ok(header) => header,
             Err(err) => return make_error_response(err),
         };
-        let left_response = match header.opcode {
+        let left_response = if !is_internal_request && is_traceable(&header) {
+            self.handle_traced_request(request, &header, is_logged)
+        } else {
+            self.dispatch_request(request, &header, is_internal_request, is_logged)
+        };
+        match left_response {
+            Ok(value) => wrap_header(value, is_internal_request, header),
+            Err(err) => wrap_header(make_error_response(err), is_internal_request, header),
+        }
+    }
+
+    /// Procesa una request dentro de una nueva sesión de _tracing_, que se guarda al terminar.
+    ///
+    /// A la respuesta se le agrega el ID de la sesión, para que el cliente pueda consultar
+    /// sus eventos en el keyspace [TRACES_KEYSPACE].
+    fn handle_traced_request(
+        &self,
+        request: &[Byte],
+        header: &Headers,
+        is_logged: bool,
+    ) -> Result<Vec<Byte>> {
+        if let Err(err) = self.ensure_traces_schema() {
+            self.log_trace_error(err);
+        }
+        let trace = TraceSession::new(self.id, describe_request(request, header));
+        let traced_handler = self.with_trace(trace.clone());
+        traced_handler
+            .trace(format!("Request recibida por el coordinador {}", self.id).as_str())?;
+        let response = traced_handler.dispatch_request(request, header, false, is_logged);
+        let trace_id = trace.get_id();
+        match &response {
+            Ok(_) => traced_handler.trace("Request completada")?,
+            Err(err) => traced_handler.trace(format!("La request falló: {err}").as_str())?,
+        }
+        if let Err(err) = self.store_trace(&trace.finish()) {
+            self.log_trace_error(err);
+        }
+        Ok(add_tracing_id(
+            response.unwrap_or_else(make_error_response),
+            trace_id,
+        ))
+    }
+
+    /// Crea el keyspace [TRACES_KEYSPACE] y sus tablas, si todavía no existen.
+    fn ensure_traces_schema(&self) -> Result<()> {
+        let node_reader = self.read()?;
+        let is_traces_table = |table_name: &str| {
+            node_reader
+                .get_table(table_name)
+                .is_ok_and(|table| table.get_keyspace() == TRACES_KEYSPACE)
+        };
+        // En el mismo orden que las queries: el keyspace y después cada tabla
+        let existing = [
+            node_reader.keyspace_exists(TRACES_KEYSPACE),
+            is_traces_table(SESSIONS_TABLE),
+            is_traces_table(EVENTS_TABLE),
+        ];
+        drop(node_reader);
+        for (query, exists) in get_traces_schema_queries().into_iter().zip(existing) {
+            if exists {
+                continue;
+            }
+            let query_body = QueryBody::new(query, Consistency::All);
+            let request = Frame::new_query(0, &query_body).as_bytes();
+            match make_parse(&mut tokenize_query(query_body.get_query()))? {
+                Statement::DdlStatement(ddl_statement) => {
+                    self.handle_ddl_statement(ddl_statement, &request)?;
+                }
+                _ => {
+                    return Err(Error::ServerError(format!(
+                        "Se esperaba un CREATE y se armó '{}'",
+                        query_body.get_query()
+                    )))
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Guarda en las tablas de [TRACES_KEYSPACE] los eventos de una sesión de _tracing_ y, si
+    /// ya terminó, la fila de la sesión.
+    fn store_trace(&self, trace: &TraceSession) -> Result<()> {
+        let timestamp = Utc::now().timestamp_micros();
+        for query in trace.get_insert_queries()? {
+            let query_body = QueryBody::new(query, Consistency::Quorum);
+            let request = Frame::new_query(0, &query_body).as_bytes();
+            let Statement::DmlStatement(DmlStatement::InsertStatement(insert)) =
+                make_parse(&mut tokenize_query(query_body.get_query()))?
+            else {
+                return Err(Error::ServerError(format!(
+                    "Se esperaba un INSERT y se armó '{}'",
+                    query_body.get_query()
+                )));
+            };
+            let response =
+                self.insert_with_other_nodes(insert, &request, &Consistency::Quorum, timestamp)?;
+            if !verify_succesful_response(&response) {
+                return Err(Error::ServerError(describe_failed_response(&response)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Registra en el log un error al guardar una sesión de _tracing_, que no se le devuelve
+    /// al cliente porque su request no depende de eso.
+    fn log_trace_error(&self, err: Error) {
+        if let Ok(logger) = self.logger.read() {
+            let _ = logger.error(format!("Error al guardar la sesión de tracing: {err}").as_str());
+        }
+    }
+
+    /// Procesa una request según su _opcode_.
+    fn dispatch_request(
+        &self,
+        request: &[Byte],
+        header: &Headers,
+        is_internal_request: bool,
+        is_logged: bool,
+    ) -> Result<Vec<Byte>> {
+        match header.opcode {
             Opcode::Startup => self.handle_startup(&request[9..]),
             Opcode::Options => self.handle_options(),
             Opcode::Query => {
                 self.handle_query(request, &header.length, is_internal_request, is_logged)
             }
-            Opcode::Prepare => self.handle_prepare(),
-            Opcode::Execute => self.handle_execute(),
-            Opcode::Register => self.handle_register(),
-            Opcode::Batch => self.handle_batch(),
+            Opcode::Prepare => self.handle_prepare(request, &header.length, is_logged),
+            Opcode::Execute => self.handle_execute(request, header, is_logged),
+            Opcode::Register => self.handle_register(request, &header.length, is_logged),
+            Opcode::Batch => {
+                self.handle_batch(request, &header.length, is_internal_request, is_logged)
+            }
             Opcode::AuthResponse => self.handle_auth_response(request, &header.length),
             _ => Err(Error::ProtocolError(
                 "El opcode recibido no es una request".to_string(),
             )),
-        };
-        match left_response {
-            Ok(value) => wrap_header(value, is_internal_request, header),
-            Err(err) => wrap_header(make_error_response(err), is_internal_request, header),
         }
     }
 
@@ -841,26 +1235,46 @@ impl SessionHandler {
                 "En el startup se debia mandar al menos la version CQL".to_string(),
             )));
         }
-        if string_map[0].1 != "5.0.0" {
+        if string_map[0].1 != CQL_VERSION {
             return Ok(make_error_response(Error::ConfigError(format!(
                 "{} es una version CQL no soportada",
                 string_map[0].1
             ))));
         }
+        if let Some((_, algorithm)) = string_map.iter().find(|(key, _)| key == "COMPRESSION") {
+            if let Err(err) = Compression::try_from(algorithm.as_str()) {
+                return Ok(make_error_response(err));
+            }
+        }
         let mut response: Vec<Byte> = Vec::new();
         response.append(&mut Version::ResponseV5.as_bytes());
         response.append(&mut Flag::Default.as_bytes());
         response.append(&mut Stream::new(0).as_bytes());
-        response.append(&mut Opcode::AuthChallenge.as_bytes());
+        response.append(&mut Opcode::Authenticate.as_bytes());
         response.append(&mut Length::new(0).as_bytes()); // REVISAR ESTO
         Ok(response)
     }
 
     fn handle_options(&self) -> Result<Vec<Byte>> {
-        // No tiene body
-        // Responder con supported
-        Opcode::Supported.as_bytes();
-        Ok(vec![0])
+        let mut supported = SupportedMultiMap::new();
+        supported.insert("CQL_VERSION".to_string(), vec![CQL_VERSION.to_string()]);
+        supported.insert(
+            "COMPRESSION".to_string(),
+            Compression::SUPPORTED
+                .iter()
+                .map(|compression| compression.name().to_string())
+                .collect(),
+        );
+        let mut body = encode_string_multimap_to_bytes(&supported);
+
+        let mut response: Vec<Byte> = Vec::new();
+        response.append(&mut Version::ResponseV5.as_bytes());
+        response.append(&mut Flag::Default.as_bytes());
+        response.append(&mut Stream::new(0).as_bytes());
+        response.append(&mut Opcode::Supported.as_bytes());
+        response.append(&mut Length::new(body.len() as Uint).as_bytes());
+        response.append(&mut body);
+        Ok(response)
     }
 
     fn handle_query(
@@ -876,7 +1290,8 @@ impl SessionHandler {
             ));
         }
         if let Ok(query_body) = QueryBody::try_from(&request[9..(lenght.len as usize) + 9]) {
-            let res = match make_parse(&mut tokenize_query(query_body.get_query())) {
+            // Las réplicas reciben la query con los mismos valores, y los vinculan ellas
+            let res = match self.parse_query_body(&query_body) {
                 Ok(statement) => {
                     if internal_request {
                         let mut internal_metadata: Vec<Byte> = Vec::new();
@@ -886,13 +1301,11 @@ impl SessionHandler {
                         }
                         let internal_metadata =
                             read_metadata_from_internal_request(internal_metadata);
-                        self.handle_internal_statement(statement, internal_metadata)
+                        let page = Page::from_query_body(&query_body)?;
+                        self.handle_internal_statement(statement, internal_metadata, page.as_ref())
                     } else {
-                        self.handle_statement(
-                            statement,
-                            request,
-                            query_body.get_consistency_level(),
-                        )
+                        self.trace(format!("Query parseada: {}", query_body.get_query()).as_str())?;
+                        self.handle_statement(statement, request, &query_body)
                     }
                 }
                 Err(err) => {
@@ -906,92 +1319,292 @@ impl SessionHandler {
         ))
     }
 
-    fn handle_prepare(&self) -> Result<Vec<Byte>> {
-        // El body es <query><flags>[<keyspace>]
-        Ok(vec![0])
-    }
-
-    fn handle_execute(&self) -> Result<Vec<Byte>> {
-        // El body es <id><result_metadata_id><query_parameters>
-        Ok(vec![0])
-    }
-
-    fn handle_register(&self) -> Result<Vec<Byte>> {
-        Ok(vec![0])
-    }
-
-    fn handle_batch(&self) -> Result<Vec<Byte>> {
-        Ok(vec![0])
-    }
-
-    fn handle_auth_response(&self, request: &[Byte], lenght: &Length) -> Result<Vec<Byte>> {
-        let req: &[Byte] = &request[9..(lenght.len as usize) + 9];
-        let node_reader = self.read()?;
-        let users = DiskHandler::read_admitted_users(&node_reader.storage_addr)?;
-        drop(node_reader);
-
-        let mut response: Vec<Byte> = Vec::new();
-        let mut i = 0;
-        let user_from_req = parse_bytes_to_string(req, &mut i)?;
-        let password_from_req = parse_bytes_to_string(&req[i..], &mut i)?;
-        let mut node_writer = self.write()?;
-        for user in users {
-            if user.0 == user_from_req && user.1 == password_from_req {
-                response.append(&mut Version::ResponseV5.as_bytes());
-                response.append(&mut Flag::Default.as_bytes());
-                response.append(&mut Stream::new(0).as_bytes());
-                response.append(&mut Opcode::AuthSuccess.as_bytes());
-                response.append(&mut Length::new(0).as_bytes());
-                // REVISAR AL TESTEAR
-                if !node_writer
-                    .users_default_keyspace_name
-                    .contains_key(&user.0)
-                {
-                    node_writer
-                        .users_default_keyspace_name
-                        .insert(user.0.to_string(), "".to_string());
-                }
-                return Ok(response);
+    /// Parsea la query del body, vinculando sus marcadores con los valores del body si los tiene.
+    fn parse_query_body(&self, query_body: &QueryBody) -> Result<Statement> {
+        let query = query_body.get_query();
+        let values = query_body
+            .get_values()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        match make_parse(&mut tokenize_query(query))? {
+            Statement::DmlStatement(dml_statement) => {
+                Ok(Statement::DmlStatement(self.read()?.bind_statement(
+                    query,
+                    dml_statement,
+                    values,
+                    query_body.get_value_names(),
+                )?))
             }
+            _ if !values.is_empty() => Err(Error::Invalid(format!(
+                "La query no tiene marcadores, pero se recibieron {} valores",
+                values.len()
+            ))),
+            statement => Ok(statement),
         }
-
-        response = make_error_response(Error::AuthenticationError(
-            "Las credenciales pasadas no son validas".to_string(),
-        ));
-        Ok(response)
     }
 
-    // ###########################################################################################
-    // ############################### PROCESAMIENTO DE STATEMENTS ###############################
-    // ###########################################################################################
-
-    fn handle_statement(
+    fn handle_prepare(
         &self,
-        statement: Statement,
         request: &[Byte],
-        consistency_level: &Consistency,
+        lenght: &Length,
+        is_logged: bool,
     ) -> Result<Vec<Byte>> {
-        match statement {
-            Statement::DdlStatement(ddl_statement) => {
-                self.handle_ddl_statement(ddl_statement, request)
+        if !is_logged {
+            return Err(Error::AuthenticationError(
+                "No se pueden preparar queries antes de autenticar el usuario".to_string(),
+            ));
+        }
+        // El body es <query><flags>[<keyspace>]
+        let Some(body) = request.get(9..(lenght.len as usize) + 9) else {
+            return Err(Error::ProtocolError(
+                "El largo del mensaje PREPARE no coincide con su cuerpo".to_string(),
+            ));
+        };
+        let mut i = 0;
+        let query = parse_bytes_to_long_string(body, &mut i)?;
+        if body.len() < i + 4 {
+            return Err(Error::ProtocolError(
+                "Faltan las flags del mensaje PREPARE".to_string(),
+            ));
+        }
+        let flags = Int::from_be_bytes([body[i], body[i + 1], body[i + 2], body[i + 3]]);
+        i += 4;
+
+        let mut node_writer = self.write()?;
+        let keyspace = if PrepareFlag::has_mask(&flags, &PrepareFlag::WithKeyspace) {
+            parse_bytes_to_string(&body[i..], &mut i)?
+        } else {
+            node_writer.get_default_keyspace_name()?
+        };
+        let statement = match make_parse(&mut tokenize_query(&query))? {
+            Statement::DmlStatement(dml_statement) => dml_statement,
+            _ => {
+                return Err(Error::Invalid(format!(
+                    "Sólo se pueden preparar queries DML, y se recibió '{query}'"
+                )))
             }
-            Statement::DmlStatement(dml_statement) => {
-                self.handle_dml_statement(dml_statement, request, consistency_level)
+        };
+        let id = node_writer.prepare_statement(&query, statement, &keyspace)?;
+        let prepared = match node_writer.get_prepared_statement(&id) {
+            Some(prepared) => prepared,
+            None => {
+                return Err(Error::ServerError(
+                    "La query preparada no quedó guardada en la caché".to_string(),
+                ))
             }
-            Statement::Startup => Err(Error::Invalid(
-                "No se deberia haber mandado el startup por este canal".to_string(),
-            )),
-            Statement::LoginUser(_) => Err(Error::Invalid(
-                "No se deberia haber mandado el login por este canal".to_string(),
-            )),
-        }
-    }
+        };
+        let mut result = prepared.as_prepared_result(&id);
+        drop(node_writer);
 
-    // ###########################################################################################
-    // ##################################### DDL STATEMENTS ######################################
-    // ###########################################################################################
+        self.logger
+            .read()
+            .map_err(|e| Error::ServerError(e.to_string()))?
+            .info(format!("Query preparada: {query}").as_str())
+            .map_err(|e| Error::ServerError(e.to_string()))?;
 
-    fn handle_ddl_statement(
+        Ok(Node::create_result_select(&mut result))
+    }
+
+    fn handle_execute(
+        &self,
+        request: &[Byte],
+        header: &Headers,
+        is_logged: bool,
+    ) -> Result<Vec<Byte>> {
+        if !is_logged {
+            return Err(Error::AuthenticationError(
+                "No se pueden mandar queries antes de autenticar el usuario".to_string(),
+            ));
+        }
+        // El body es <id><result_metadata_id><query_parameters>
+        let Some(body) = request.get(9..(header.length.len as usize) + 9) else {
+            return Err(Error::ProtocolError(
+                "El largo del mensaje EXECUTE no coincide con su cuerpo".to_string(),
+            ));
+        };
+        let mut i = 0;
+        let id = parse_short_bytes(body, &mut i)?;
+        let _result_metadata_id = parse_short_bytes(body, &mut i)?;
+        let mut query_body = QueryBody::parse_query_parameters(String::new(), &body[i..])?;
+
+        let node_reader = self.read()?;
+        let values = query_body
+            .get_values()
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let (query, statement) = match node_reader.get_prepared_statement(&id) {
+            Some(prepared) => (
+                prepared.get_query().to_string(),
+                prepared.bind(values, query_body.get_value_names())?,
+            ),
+            None => {
+                return Err(Error::Unprepared(
+                    "La query no fue preparada en este nodo".to_string(),
+                    id,
+                ))
+            }
+        };
+        drop(node_reader);
+
+        // Las réplicas reciben un QUERY con el texto de la query preparada y los mismos valores
+        let stream_id = ShortInt::from_be_bytes([request[2], request[3]]);
+        query_body.set_query(query);
+        let query_request = Frame::new_query(stream_id, &query_body).as_bytes();
+        self.trace(format!("Query preparada vinculada: {}", query_body.get_query()).as_str())?;
+        self.handle_statement(
+            Statement::DmlStatement(statement),
+            &query_request,
+            &query_body,
+        )
+    }
+
+    fn handle_register(
+        &self,
+        request: &[Byte],
+        lenght: &Length,
+        is_logged: bool,
+    ) -> Result<Vec<Byte>> {
+        if !is_logged {
+            return Err(Error::AuthenticationError(
+                "No se puede registrar a eventos antes de autenticar el usuario".to_string(),
+            ));
+        }
+        // El body es una lista de strings: <n><string_1>...<string_n>
+        let Some(body) = request.get(9..(lenght.len as usize) + 9) else {
+            return Err(Error::ProtocolError(
+                "El largo del mensaje REGISTER no coincide con su cuerpo".to_string(),
+            ));
+        };
+        if body.len() < 2 {
+            return Err(Error::ProtocolError(
+                "Falta la lista de eventos del mensaje REGISTER".to_string(),
+            ));
+        }
+        let n = ShortInt::from_be_bytes([body[0], body[1]]);
+        let mut i = 2;
+        let mut event_types = Vec::new();
+        for _ in 0..n {
+            let event_type = parse_bytes_to_string(&body[i..], &mut i)?;
+            if !EVENT_TYPES.contains(&event_type.as_str()) {
+                return Err(Error::ProtocolError(format!(
+                    "{event_type} no es un tipo de evento válido"
+                )));
+            }
+            event_types.push(event_type);
+        }
+
+        let (client_addr, sender) = match &self.events_sender {
+            Some((client_addr, sender)) => (*client_addr, sender.clone()),
+            None => {
+                return Err(Error::ServerError(
+                    "Esta conexión no admite el envío de eventos".to_string(),
+                ))
+            }
+        };
+        self.write()?
+            .register_event_listener(client_addr, event_types, sender);
+
+        let mut response: Vec<Byte> = Vec::new();
+        response.append(&mut Version::ResponseV5.as_bytes());
+        response.append(&mut Flag::Default.as_bytes());
+        response.append(&mut Stream::new(0).as_bytes());
+        response.append(&mut Opcode::Ready.as_bytes());
+        response.append(&mut Length::new(0).as_bytes());
+        Ok(response)
+    }
+
+    fn handle_batch(
+        &self,
+        request: &[Byte],
+        lenght: &Length,
+        internal_request: bool,
+        is_logged: bool,
+    ) -> Result<Vec<Byte>> {
+        if !is_logged {
+            return Err(Error::AuthenticationError(
+                "No se pueden mandar batches antes de autenticar el usuario".to_string(),
+            ));
+        }
+        if internal_request {
+            return self.write()?.process_internal_batch(request);
+        }
+        let Some(body) = request.get(9..(lenght.len as usize) + 9) else {
+            return Err(Error::ProtocolError(
+                "El largo del mensaje BATCH no coincide con su cuerpo".to_string(),
+            ));
+        };
+        let batch = BatchBody::try_from(body)?;
+        self.batch_with_other_nodes(&batch)
+    }
+
+    fn handle_auth_response(&self, request: &[Byte], lenght: &Length) -> Result<Vec<Byte>> {
+        let req: &[Byte] = &request[9..(lenght.len as usize) + 9];
+        let node_reader = self.read()?;
+        let users = DiskHandler::read_admitted_users(&node_reader.storage_addr)?;
+        drop(node_reader);
+
+        let mut response: Vec<Byte> = Vec::new();
+        let mut i = 0;
+        let user_from_req = parse_bytes_to_string(req, &mut i)?;
+        let password_from_req = parse_bytes_to_string(&req[i..], &mut i)?;
+        let mut node_writer = self.write()?;
+        for user in users {
+            if user.0 == user_from_req && user.1 == password_from_req {
+                response.append(&mut Version::ResponseV5.as_bytes());
+                response.append(&mut Flag::Default.as_bytes());
+                response.append(&mut Stream::new(0).as_bytes());
+                response.append(&mut Opcode::AuthSuccess.as_bytes());
+                response.append(&mut Length::new(0).as_bytes());
+                // REVISAR AL TESTEAR
+                if !node_writer
+                    .users_default_keyspace_name
+                    .contains_key(&user.0)
+                {
+                    node_writer
+                        .users_default_keyspace_name
+                        .insert(user.0.to_string(), "".to_string());
+                }
+                return Ok(response);
+            }
+        }
+
+        response = make_error_response(Error::AuthenticationError(
+            "Las credenciales pasadas no son validas".to_string(),
+        ));
+        Ok(response)
+    }
+
+    // ###########################################################################################
+    // ############################### PROCESAMIENTO DE STATEMENTS ###############################
+    // ###########################################################################################
+
+    fn handle_statement(
+        &self,
+        statement: Statement,
+        request: &[Byte],
+        query_body: &QueryBody,
+    ) -> Result<Vec<Byte>> {
+        match statement {
+            Statement::DdlStatement(ddl_statement) => {
+                self.handle_ddl_statement(ddl_statement, request)
+            }
+            Statement::DmlStatement(dml_statement) => {
+                self.handle_dml_statement(dml_statement, request, query_body)
+            }
+            Statement::Startup => Err(Error::Invalid(
+                "No se deberia haber mandado el startup por este canal".to_string(),
+            )),
+            Statement::LoginUser(_) => Err(Error::Invalid(
+                "No se deberia haber mandado el login por este canal".to_string(),
+            )),
+        }
+    }
+
+    // ###########################################################################################
+    // ##################################### DDL STATEMENTS ######################################
+    // ###########################################################################################
+
+    fn handle_ddl_statement(
         &self,
         ddl_statement: DdlStatement,
         request: &[Byte],
@@ -1012,15 +1625,27 @@ impl SessionHandler {
             DdlStatement::CreateTableStatement(create_table) => {
                 self.process_create_table_statement(create_table, request)
             }
-            DdlStatement::AlterTableStatement(_alter_table) => Err(Error::Invalid(
-                "Alter Table Statement no está soportado.".to_string(),
-            )),
-            DdlStatement::DropTableStatement(_drop_table) => Err(Error::Invalid(
-                "Drop Table Statement no está soportado.".to_string(),
-            )),
-            DdlStatement::TruncateStatement(_truncate) => Err(Error::Invalid(
-                "Truncate Statement no está soportado.".to_string(),
-            )),
+            DdlStatement::AlterTableStatement(alter_table) => {
+                self.process_alter_table_statement(alter_table, request)
+            }
+            DdlStatement::DropTableStatement(drop_table) => {
+                self.process_drop_table_statement(drop_table, request)
+            }
+            DdlStatement::TruncateStatement(truncate) => {
+                self.process_truncate_statement(truncate, request)
+            }
+            DdlStatement::CreateIndexStatement(create_index) => {
+                self.process_create_index_statement(create_index, request)
+            }
+            DdlStatement::DropIndexStatement(drop_index) => {
+                self.process_drop_index_statement(drop_index, request)
+            }
+            DdlStatement::CreateMaterializedViewStatement(create_view) => {
+                self.process_create_view_statement(create_view, request)
+            }
+            DdlStatement::DropMaterializedViewStatement(drop_view) => {
+                self.process_drop_view_statement(drop_view, request)
+            }
         }
     }
 
@@ -1159,22 +1784,47 @@ impl SessionHandler {
         create_table: CreateTable,
         request: &[Byte],
     ) -> Result<Vec<Byte>> {
+        self.process_table_creation_in_replicas(
+            create_table.name.get_keyspace(),
+            request,
+            |node, node_number| {
+                node.process_internal_create_table_statement(&create_table, node_number)
+            },
+        )
+    }
+
+    /// Crea las réplicas de una tabla nueva en los nodos que corresponden según el keyspace,
+    /// mandándole a cada uno el número de la réplica que guarda, y procesando las propias
+    /// localmente con la función dada.
+    fn process_table_creation_in_replicas<F>(
+        &self,
+        keyspace_name: Option<String>,
+        request: &[Byte],
+        process_locally: F,
+    ) -> Result<Vec<Byte>>
+    where
+        F: Fn(&mut Node, NodeId) -> Result<Vec<Byte>>,
+    {
         let node_reader = self.read()?;
-        let keyspace_name =
-            node_reader.choose_available_keyspace_name(create_table.name.get_keyspace())?;
+        let keyspace_name = node_reader.choose_available_keyspace_name(keyspace_name)?;
         let keyspace = node_reader.get_keyspace_from_name(&keyspace_name)?;
-        let quantity_replicas: Uint =
-            node_reader.get_quantity_of_replicas_from_keyspace(keyspace)?;
         let nodes_ids = node_reader.get_nodes_ids();
+        let replicas_of_nodes: Vec<(NodeId, Vec<NodeId>)> = nodes_ids
+            .iter()
+            .map(|node_id| {
+                (
+                    *node_id,
+                    node_reader.get_replicas_nodes(*node_id, keyspace, &nodes_ids),
+                )
+            })
+            .collect();
         drop(node_reader);
         let mut response: Vec<Byte> = Vec::new();
-        for actual_node_id in &nodes_ids {
-            let mut next_node_id = *actual_node_id;
-            for _ in 0..quantity_replicas {
-                response = if next_node_id == self.id {
+        for (actual_node_id, replicas) in &replicas_of_nodes {
+            for next_node_id in replicas {
+                response = if *next_node_id == self.id {
                     let mut node_writer = self.write()?;
-                    node_writer
-                        .process_internal_create_table_statement(&create_table, *actual_node_id)?
+                    process_locally(&mut node_writer, *actual_node_id)?
                 } else {
                     let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
                         SvAction::InternalQuery(request.to_vec()).as_bytes(),
@@ -1182,19 +1832,233 @@ impl SessionHandler {
                         Some(*actual_node_id),
                     );
                     send_to_node_and_wait_response_with_timeout(
-                        next_node_id,
+                        *next_node_id,
                         request_with_metadata,
                         PortType::Priv,
                         true,
                         Some(TIMEOUT_SECS),
                     )?
                 };
-                next_node_id = next_node_in_the_cluster(next_node_id, &nodes_ids);
             }
         }
         Ok(response)
     }
 
+    fn process_alter_table_statement(
+        &self,
+        alter_table: AlterTable,
+        request: &[Byte],
+    ) -> Result<Vec<Byte>> {
+        self.check_table_exists(&alter_table.name.get_name(), alter_table.name.if_exists)?;
+        self.read()?.check_alter_table_with_views(&alter_table)?;
+        self.process_table_ddl_in_all_nodes(request, |node| {
+            node.process_internal_alter_table_statement(&alter_table)
+        })
+    }
+
+    fn process_drop_table_statement(
+        &self,
+        drop_table: DropTable,
+        request: &[Byte],
+    ) -> Result<Vec<Byte>> {
+        let table_name = &drop_table.table_name;
+        self.check_table_exists(&table_name.get_name(), table_name.if_exists)?;
+        let node_reader = self.read()?;
+        if node_reader
+            .get_table(&table_name.get_name())
+            .is_ok_and(|table| table.is_view())
+        {
+            return Err(Error::Invalid(format!(
+                "{} es una vista materializada, se debe usar DROP MATERIALIZED VIEW",
+                table_name.get_name()
+            )));
+        }
+        let views = node_reader.get_views_names(&table_name.get_name());
+        if !views.is_empty() {
+            return Err(Error::Invalid(format!(
+                "No se puede borrar la tabla {} porque tiene las vistas materializadas {}",
+                table_name.get_name(),
+                views.join(", ")
+            )));
+        }
+        drop(node_reader);
+        self.process_table_ddl_in_all_nodes(request, |node| {
+            node.process_internal_drop_table_statement(&drop_table)
+        })
+    }
+
+    fn process_truncate_statement(
+        &self,
+        truncate: Truncate,
+        request: &[Byte],
+    ) -> Result<Vec<Byte>> {
+        let table_name = &truncate.table_name;
+        self.check_table_exists(&table_name.get_name(), table_name.if_exists)?;
+        if self
+            .read()?
+            .get_table(&table_name.get_name())
+            .is_ok_and(|table| table.is_view())
+        {
+            return Err(Error::Invalid(format!(
+                "No se puede truncar la vista materializada {}, sino su tabla base",
+                table_name.get_name()
+            )));
+        }
+        self.process_table_ddl_in_all_nodes(request, |node| {
+            node.process_internal_truncate_statement(&truncate)
+        })
+    }
+
+    fn process_create_index_statement(
+        &self,
+        create_index: CreateIndex,
+        request: &[Byte],
+    ) -> Result<Vec<Byte>> {
+        self.check_table_exists(&create_index.table_name.get_name(), false)?;
+        self.process_table_ddl_in_all_nodes(request, |node| {
+            node.process_internal_create_index_statement(&create_index)
+        })
+    }
+
+    fn process_drop_index_statement(
+        &self,
+        drop_index: DropIndex,
+        request: &[Byte],
+    ) -> Result<Vec<Byte>> {
+        let index_name = drop_index.name.get_name();
+        let index_table = self
+            .read()?
+            .get_index_table_name(drop_index.keyspace.as_deref(), index_name);
+        if index_table.is_none() {
+            if drop_index.if_exists {
+                return Ok(Node::create_result_void());
+            }
+            return Err(Error::Invalid(format!("El índice {index_name} no existe")));
+        }
+        self.process_table_ddl_in_all_nodes(request, |node| {
+            node.process_internal_drop_index_statement(&drop_index)
+        })
+    }
+
+    /// Crea las réplicas de una vista materializada y las llena con las filas que ya tiene su
+    /// tabla base.
+    fn process_create_view_statement(
+        &self,
+        create_view: CreateMaterializedView,
+        request: &[Byte],
+    ) -> Result<Vec<Byte>> {
+        let base_table_name = create_view.base_table.get_name();
+        let view_name = create_view.get_name();
+        self.check_table_exists(&base_table_name, false)?;
+        let node_reader = self.read()?;
+        if node_reader.table_exists(&view_name) {
+            if create_view.if_not_exists {
+                return Ok(Node::create_result_void());
+            }
+            return Err(Error::Invalid(format!("La tabla {view_name} ya existe")));
+        }
+        let base_table = node_reader.get_table(&base_table_name)?;
+        if base_table.is_view() {
+            return Err(Error::Invalid(format!(
+                "No se puede crear una vista materializada de la vista {base_table_name}"
+            )));
+        }
+        let keyspace_name = base_table.get_keyspace().to_string();
+        drop(node_reader);
+
+        let response =
+            self.process_table_creation_in_replicas(Some(keyspace_name), request, |node, n| {
+                node.process_internal_create_view_statement(&create_view, n)
+            })?;
+        self.build_view(&view_name)?;
+        Ok(response)
+    }
+
+    fn process_drop_view_statement(
+        &self,
+        drop_view: DropMaterializedView,
+        request: &[Byte],
+    ) -> Result<Vec<Byte>> {
+        let view_name = &drop_view.view_name;
+        self.check_table_exists(&view_name.get_name(), view_name.if_exists)?;
+        if self
+            .read()?
+            .get_table(&view_name.get_name())
+            .is_ok_and(|table| !table.is_view())
+        {
+            return Err(Error::Invalid(format!(
+                "{} no es una vista materializada",
+                view_name.get_name()
+            )));
+        }
+        self.process_table_ddl_in_all_nodes(request, |node| {
+            node.process_internal_drop_view_statement(&drop_view)
+        })
+    }
+
+    /// Verifica que una tabla exista, salvo que la _query_ admita que no lo haga.
+    fn check_table_exists(&self, table_name: &str, if_exists: bool) -> Result<()> {
+        if !self.read()?.table_exists(table_name) && !if_exists {
+            return Err(Error::ServerError(format!(
+                "La tabla {table_name} no existe"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Manda una _query_ DDL sobre una tabla a todos los nodos del clúster, procesándola
+    /// localmente con la función dada.
+    ///
+    /// Devuelve la respuesta del nodo actual. Si algún otro nodo no pudo aplicar la _query_,
+    /// ya sea porque respondió un error o porque no respondió, se sigue con el resto y al final
+    /// se devuelve un error que indica cuáles fallaron.
+    fn process_table_ddl_in_all_nodes<F>(
+        &self,
+        request: &[Byte],
+        process_locally: F,
+    ) -> Result<Vec<Byte>>
+    where
+        F: Fn(&mut Node) -> Result<Vec<Byte>>,
+    {
+        let node_reader = self.read()?;
+        let nodes_quantity = node_reader.get_actual_n_nodes();
+        let nodes_ids = node_reader.get_nodes_ids();
+        drop(node_reader);
+        let mut response = Node::create_result_void();
+        let mut failures: Vec<String> = Vec::new();
+        let mut actual_node_id = self.id;
+        for _ in 0..nodes_quantity {
+            if actual_node_id != self.id {
+                let node_response = send_to_node_and_wait_response_with_timeout(
+                    actual_node_id,
+                    SvAction::InternalQuery(request.to_vec()).as_bytes(),
+                    PortType::Priv,
+                    true,
+                    Some(TIMEOUT_SECS),
+                );
+                match node_response {
+                    Ok(node_response) if verify_succesful_response(&node_response) => {}
+                    Ok(node_response) => failures.push(format!(
+                        "nodo {actual_node_id}: {}",
+                        describe_failed_response(&node_response)
+                    )),
+                    Err(err) => failures.push(format!("nodo {actual_node_id}: {err}")),
+                }
+            } else {
+                let mut node_writer = self.write()?;
+                response = process_locally(&mut node_writer)?;
+            }
+            actual_node_id = next_node_in_the_cluster(actual_node_id, &nodes_ids);
+        }
+        if !failures.is_empty() {
+            return Err(Error::ServerError(format!(
+                "La query se aplicó en este nodo, pero no en todos los demás ({})",
+                failures.join("; ")
+            )));
+        }
+        Ok(response)
+    }
+
     // ##########################################################################################
     // ##################################### DML STATEMENTS #####################################
     // ##########################################################################################
@@ -1203,118 +2067,282 @@ impl SessionHandler {
         &self,
         dml_statement: DmlStatement,
         request: &[Byte],
-        consistency_level: &Consistency,
+        query_body: &QueryBody,
     ) -> Result<Vec<Byte>> {
-        match dml_statement {
+        let consistency_level = query_body.get_consistency_level();
+        let timestamp = dml_statement
+            .update_parameters()
+            .map(|using| using.get_timestamp())
+            .transpose()?
+            .flatten()
+            .or(query_body.get_timestamp())
+            .unwrap_or_else(|| Utc::now().timestamp_micros());
+        let base_partition = self.get_base_partition(&dml_statement)?;
+        let _partition_guard = self
+            .view_locks
+            .lock(base_partition.iter().cloned().collect())?;
+        let base_rows = match base_partition {
+            Some(_) => Some(self.read_rows_for_views(&dml_statement, consistency_level)?),
+            None => None,
+        };
+        let response = match dml_statement {
             DmlStatement::SelectStatement(select) => {
-                self.select_with_other_nodes(select, request, consistency_level)
+                self.select_with_other_nodes(select, request, query_body)
             }
             DmlStatement::InsertStatement(insert) => {
-                self.insert_with_other_nodes(insert, request, consistency_level)
+                self.insert_with_other_nodes(insert, request, consistency_level, timestamp)
             }
             DmlStatement::UpdateStatement(update) => {
-                self.update_with_other_nodes(update, request, consistency_level)
+                self.update_with_other_nodes(update, request, consistency_level, timestamp)
             }
             DmlStatement::DeleteStatement(delete) => {
-                self.delete_with_other_nodes(delete, request, consistency_level)
+                self.delete_with_other_nodes(delete, request, consistency_level, timestamp)
             }
+        }?;
+        if let Some(base_rows) = base_rows {
+            self.update_views(base_rows, consistency_level, timestamp)?;
         }
+        Ok(response)
     }
 
     // ###########################################################################################
     // ######################################### SELECT ##########################################
     // ###########################################################################################
+    /// Consulta a los nodos que tienen particiones de la tabla, en orden según su ID, y junta
+    /// sus resultados.
+    ///
+    /// Si el cliente pidió paginar, se consulta a cada nodo sólo por las filas que faltan para
+    /// completar la página, retomando desde el _paging_state_ recibido, y se devuelve el
+    /// estado desde el cual seguir si quedan filas.
+    ///
+    /// Cada réplica ya aplica el LIMIT a sus filas, y acá se vuelve a aplicar sobre las filas
+    /// juntadas de todos los nodos, dejando de consultar cuando se alcanza. Al paginar, el
+    /// _paging_state_ recuerda cuántas filas faltan para el LIMIT, así las páginas siguientes
+    /// no lo vuelven a contar desde el principio.
+    ///
+    /// Si el SELECT usa funciones de agregación, las réplicas devuelven sus filas sin agregar y
+    /// se agregan acá, todas en una única página.
     fn select_with_other_nodes(
         &self,
         select: Select,
         request: &[Byte],
-        consistency_level: &Consistency,
+        query_body: &QueryBody,
     ) -> Result<Vec<Byte>> {
+        let consistency_level = query_body.get_consistency_level();
+        let has_aggregates = select_has_aggregates(&select.columns);
+        let limit = select
+            .options
+            .limit
+            .as_ref()
+            .filter(|_| !has_aggregates)
+            .map(|limit| limit.get_limit())
+            .transpose()?
+            .map(|limit| limit as usize);
+        // Si todas las filas del LIMIT entran en una página, no hace falta paginar
+        let page = Page::from_query_body(query_body)?
+            .filter(|page| !has_aggregates && limit.is_none_or(|limit| limit > page.size));
         let table_name = select.from.get_name();
         let mut results_from_another_nodes: Vec<Byte> = Vec::new();
-        let mut consulted_nodes: Vec<Byte> = Vec::new();
         let node_reader = self.read()?;
-        let replication_factor_quantity = node_reader.get_replicas_from_table_name(&table_name)?;
-        let consistency_number =
-            consistency_level.as_usize(replication_factor_quantity as usize)?;
-        let partitions_keys_to_nodes = node_reader.get_partition_keys_values(&table_name)?.clone(); // Tuve que agregar un clone para que no me tire error de referencia mutable e inmutable al mismo tiempo
+        let mut nodes_ids: Vec<NodeId> = node_reader
+            .get_partition_keys_values(&table_name)?
+            .iter()
+            .map(|partition_key_value| node_reader.select_node(partition_key_value))
+            .collect();
+        let index_name = DiskHandler::get_index_for_where(
+            select.options.the_where.as_ref(),
+            node_reader.get_table(&table_name)?,
+        )
+        .map(|(index_name, _)| index_name.to_string());
         drop(node_reader);
+        if let Some(index_name) = index_name {
+            self.trace(
+                format!("Las réplicas van a buscar las filas con el índice {index_name}").as_str(),
+            )?;
+        }
+        // Las páginas se recorren siempre en el mismo orden de nodos
+        nodes_ids.sort();
+        nodes_ids.dedup();
+        if let Some(state) = page.as_ref().and_then(|page| page.state.as_ref()) {
+            nodes_ids.retain(|node_id| *node_id >= state.node_id);
+        }
 
-        for partition_key_value in partitions_keys_to_nodes {
-            let node_reader = self.read()?;
-            let node_id = node_reader.select_node(&partition_key_value);
-            drop(node_reader);
-
-            if !consulted_nodes.contains(&node_id) {
-                let wait_response = true;
-                let mut read_repair_executed = false;
-                let mut consistency_counter = 0;
-                let mut responsive_replica = node_id;
-                let mut replicas_asked = 0;
-                let mut actual_result = self.decide_how_to_request_internal_query_select(
-                    node_id,
-                    (&select, request),
-                    wait_response,
-                    &mut responsive_replica,
-                    &mut replicas_asked,
-                    replication_factor_quantity,
-                )?;
-                consistency_counter += 1;
-                match self.consult_replica_nodes_consistency(
-                    (node_id, replicas_asked),
-                    (request, &table_name),
-                    &mut consistency_counter,
-                    consistency_number,
-                    (responsive_replica, &actual_result),
-                    replication_factor_quantity,
-                ) {
-                    Ok(rr_executed) => {
-                        // Este chequeo es porque si ya es true, no queremos que vuelva a ser false
-                        // Nos importa si se ejecutó al menos una vez
-                        if !read_repair_executed {
-                            read_repair_executed = rr_executed;
-                        }
-                    }
-                    Err(err) => return Err(Error::ServerError(format!(
-                        "No se pudo cumplir con el nivel de consistencia {consistency_level}, solo se logró con {consistency_counter} de {consistency_number}: {err}",
-                    ))),
+        let mut rows_left = page.as_ref().map(|page| page.size);
+        let limit = match page.as_ref().and_then(|page| page.state.as_ref()) {
+            Some(state) => state
+                .limit_left
+                .map(|limit_left| limit_left as usize)
+                .or(limit),
+            None => limit,
+        };
+        let mut limit_left = limit;
+        let mut next_paging_state = None;
+        for (i, node_id) in nodes_ids.iter().enumerate() {
+            let node_page = page.as_ref().map(|page| {
+                let state = page
+                    .state
+                    .as_ref()
+                    .filter(|state| state.node_id == *node_id)
+                    .cloned();
+                let size = rows_left.unwrap_or(page.size);
+                Page::new(
+                    limit_left.map_or(size, |limit_left| size.min(limit_left)),
+                    state,
+                )
+            });
+            let node_request = match &node_page {
+                Some(node_page) => {
+                    let mut node_query_body = query_body.clone();
+                    node_query_body.set_paging(
+                        node_page.size as Int,
+                        node_page.state.as_ref().map(|state| state.as_bytes()),
+                    );
+                    let stream_id = ShortInt::from_be_bytes([request[2], request[3]]);
+                    Frame::new_query(stream_id, &node_query_body).as_bytes()
+                }
+                None => request.to_vec(),
+            };
+            let mut actual_result = self.select_from_node(
+                *node_id,
+                (&select, &node_request, node_page.as_ref()),
+                consistency_level,
+            )?;
+            let node_paging_state = Node::take_paging_state(&mut actual_result);
+            let rows_count = self.read()?.get_rows_count(&actual_result);
+            self.handle_result_from_node(&mut results_from_another_nodes, &actual_result, &select)?;
+
+            if let Some(limit_left) = limit_left.as_mut() {
+                *limit_left = limit_left.saturating_sub(rows_count.max(0) as usize);
+                if *limit_left == 0 {
+                    next_paging_state = None;
+                    break;
+                }
+            }
+            if let Some(rows_left) = rows_left.as_mut() {
+                if let Some(node_paging_state) = node_paging_state {
+                    next_paging_state = Some(PagingState::try_from(&node_paging_state[..])?);
+                    break;
+                }
+                *rows_left = rows_left.saturating_sub(rows_count.max(0) as usize);
+                if *rows_left == 0 {
+                    next_paging_state = nodes_ids
+                        .get(i + 1)
+                        .map(|next_node| PagingState::start_of(*next_node));
+                    break;
                 }
-                // Una vez que todo fue reparado, queremos reenviar la query para obtener el resultado
-                // pero ahora con las tablas reparadas.
-                if read_repair_executed {
-                    actual_result = self.decide_how_to_request_internal_query_select(
-                        node_id,
-                        (&select, request),
-                        wait_response,
-                        &mut responsive_replica,
-                        &mut replicas_asked,
-                        replication_factor_quantity,
-                    )?;
-                };
-                self.handle_result_from_node(
-                    &mut results_from_another_nodes,
-                    &actual_result,
-                    &select,
-                )?;
-                consulted_nodes.push(node_id);
             }
         }
+        if let Some(limit) = limit {
+            self.read()?
+                .truncate_rows(&mut results_from_another_nodes, limit);
+        }
+        if let Some(paging_state) = next_paging_state {
+            let limit_left = limit_left.map(|limit_left| limit_left as Uint);
+            Node::set_paging_state(
+                &mut results_from_another_nodes,
+                &paging_state.with_limit_left(limit_left).as_bytes(),
+            );
+        }
+        if has_aggregates {
+            return self.aggregate_results(&select, &results_from_another_nodes);
+        }
         Ok(results_from_another_nodes)
     }
 
+    /// Agrega las filas sin agregar que devolvieron las réplicas para un SELECT con funciones
+    /// de agregación.
+    fn aggregate_results(&self, select: &Select, results: &[Byte]) -> Result<Vec<Byte>> {
+        let node_reader = self.read()?;
+        let table = node_reader.get_table(&select.from.get_name())?;
+        let (mut columns, rows) = node_reader.get_columns_and_rows(results)?;
+        if columns.is_empty() {
+            columns = table.get_columns_names();
+        }
+        let mut result = DiskHandler::aggregate_select(select, &columns, rows, table)?;
+        Ok(Node::create_result_select(&mut result))
+    }
+
+    /// Consulta las filas de las particiones de un nodo a sus réplicas, según el nivel de
+    /// consistencia, ejecutando _read-repair_ si hace falta.
+    fn select_from_node(
+        &self,
+        node_id: NodeId,
+        select_request: (&Select, &[Byte], Option<&Page>),
+        consistency_level: &Consistency,
+    ) -> Result<Vec<Byte>> {
+        let (_, request, _) = select_request;
+        let table_name = select_request.0.from.get_name();
+        let node_reader = self.read()?;
+        let replicas = node_reader.get_replicas_nodes_from_table_name(node_id, &table_name)?;
+        let mut consistency_counter =
+            node_reader.new_consistency_counter(consistency_level, &replicas)?;
+        drop(node_reader);
+        self.trace(
+            format!("Réplicas elegidas para las particiones del nodo {node_id}: {replicas:?}")
+                .as_str(),
+        )?;
+        let wait_response = true;
+        let mut responsive_replica = node_id;
+        let mut replicas_asked = 0;
+        let actual_result = self.decide_how_to_request_internal_query_select(
+            node_id,
+            select_request,
+            wait_response,
+            &mut responsive_replica,
+            &mut replicas_asked,
+            &replicas,
+        )?;
+        consistency_counter.ack(responsive_replica);
+        let read_repair_executed = match self.consult_replica_nodes_consistency(
+            (node_id, replicas_asked),
+            (request, &table_name),
+            &mut consistency_counter,
+            &actual_result,
+            &replicas,
+        ) {
+            Ok(rr_executed) => rr_executed,
+            Err(err @ Error::ReadTimeout(..)) => return Err(err),
+            Err(err) => return Err(Error::ServerError(format!(
+                "No se pudo cumplir con el nivel de consistencia {consistency_level}, solo se logró con {consistency_counter}: {err}",
+            ))),
+        };
+        // Una vez que todo fue reparado, queremos reenviar la query para obtener el resultado
+        // pero ahora con las tablas reparadas.
+        if read_repair_executed {
+            return self.decide_how_to_request_internal_query_select(
+                node_id,
+                select_request,
+                wait_response,
+                &mut responsive_replica,
+                &mut replicas_asked,
+                &replicas,
+            );
+        }
+        Ok(actual_result)
+    }
+
     fn decide_how_to_request_internal_query_select(
         &self,
         node_id: NodeId,
-        select_and_request: (&Select, &[Byte]),
+        select_request: (&Select, &[Byte], Option<&Page>),
         wait_response: bool,
         responsive_replica: &mut NodeId,
         replicas_asked: &mut usize,
-        replication_factor_quantity: Uint,
+        replicas: &[NodeId],
     ) -> Result<Vec<Byte>> {
-        let (select, request) = select_and_request;
-        let actual_result = if node_id == self.id {
-            let node_writer = self.write()?;
-            node_writer.process_select(select, node_id)?
+        let (select, request, page) = select_request;
+        let first_replica = match replicas.first() {
+            Some(replica) => *replica,
+            None => {
+                return Err(Error::ServerError(format!(
+                    "No hay réplicas para las particiones del nodo {node_id}"
+                )))
+            }
+        };
+        *responsive_replica = first_replica;
+        *replicas_asked = 1;
+        let actual_result = if first_replica == self.id {
+            self.select_locally(select, node_id, page)?
         } else {
             let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
                 SvAction::InternalQuery(request.to_vec()).as_bytes(),
@@ -1322,12 +2350,11 @@ impl SessionHandler {
                 Some(node_id),
             );
             let mut result: Vec<Byte> = Vec::new();
-            *responsive_replica = node_id;
-            *replicas_asked = 0;
-            if self.neighbour_is_responsive(node_id)? {
+            if self.neighbour_is_responsive(first_replica)? {
+                self.trace(format!("Enviando la query a la réplica {first_replica}").as_str())?;
                 result = match send_to_node_and_wait_response_with_timeout(
-                    node_id,
-                    request_with_metadata,
+                    first_replica,
+                    self.traced_action(request_with_metadata),
                     PortType::Priv,
                     wait_response,
                     Some(TIMEOUT_SECS),
@@ -1335,27 +2362,32 @@ impl SessionHandler {
                     Ok(res) => res,
                     Err(err) => {
                         return Err(Error::ServerError(format!(
-                            "Error al enviar la query al nodo {node_id}: {err}"
+                            "Error al enviar la query al nodo {first_replica}: {err}"
                         )));
                     }
                 }
             }
-            *replicas_asked += 1;
 
             // Si hubo error al enviar el mensaje y habia que esperar la respuesta, se asume que
             // el vecino está apagado, entonces se intenta con las replicas
             if result.is_empty() && wait_response {
+                self.trace(format!("La réplica {first_replica} no respondió").as_str())?;
                 let mut node_writer = self.write()?;
-                node_writer.acknowledge_offline_neighbour(node_id);
+                node_writer.acknowledge_offline_neighbour(first_replica);
                 drop(node_writer);
 
                 result = self.forward_select_request_to_replicas(
                     node_id,
-                    (select, request),
+                    (select, request, page),
                     wait_response,
                     responsive_replica,
                     replicas_asked,
-                    replication_factor_quantity,
+                    replicas,
+                )?;
+            }
+            if !result.is_empty() {
+                self.trace(
+                    format!("Respuesta recibida de la réplica {responsive_replica}").as_str(),
                 )?;
             }
             result
@@ -1363,36 +2395,48 @@ impl SessionHandler {
         Ok(actual_result)
     }
 
+    /// Lee del disco local las filas de las particiones de un nodo.
+    fn select_locally(
+        &self,
+        select: &Select,
+        node_id: NodeId,
+        page: Option<&Page>,
+    ) -> Result<Vec<Byte>> {
+        self.trace(format!("Leyendo del disco las particiones del nodo {node_id}").as_str())?;
+        let node_writer = self.write()?;
+        let result = node_writer.process_select(select, node_id, page)?;
+        let rows_count = node_writer.get_rows_count(&result);
+        drop(node_writer);
+        self.trace(format!("Se leyeron {rows_count} filas del disco").as_str())?;
+        Ok(result)
+    }
+
     fn forward_select_request_to_replicas(
         &self,
         node_id: NodeId,
-        select_and_request: (&Select, &[Byte]),
+        select_request: (&Select, &[Byte], Option<&Page>),
         wait_response: bool,
         responsive_replica: &mut NodeId,
         replicas_asked: &mut usize,
-        replication_factor_quantity: Uint,
+        replicas: &[NodeId],
     ) -> Result<Vec<Byte>> {
-        let (select, request) = select_and_request;
+        let (select, request, page) = select_request;
         let mut result: Vec<Byte> = Vec::new();
-        let node_reader = self.read()?;
-        let nodes_ids = node_reader.get_nodes_ids();
-        drop(node_reader);
-        let mut node_replica = next_node_in_the_cluster(node_id, &nodes_ids);
 
-        for _ in 1..replication_factor_quantity {
-            if self.neighbour_is_responsive(node_replica)? {
+        for node_replica in replicas.iter().skip(1) {
+            if self.neighbour_is_responsive(*node_replica)? {
                 let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
                     SvAction::InternalQuery(request.to_vec()).as_bytes(),
                     None,
                     Some(node_id),
                 );
-                let replica_response = if node_replica == self.id {
-                    let node_writer = self.write()?;
-                    node_writer.process_select(select, node_id)?
+                let replica_response = if *node_replica == self.id {
+                    self.select_locally(select, node_id, page)?
                 } else {
+                    self.trace(format!("Enviando la query a la réplica {node_replica}").as_str())?;
                     send_to_node_and_wait_response_with_timeout(
-                        node_replica,
-                        request_with_metadata,
+                        *node_replica,
+                        self.traced_action(request_with_metadata),
                         PortType::Priv,
                         wait_response,
                         Some(TIMEOUT_SECS),
@@ -1401,17 +2445,17 @@ impl SessionHandler {
                 *replicas_asked += 1;
 
                 if replica_response.is_empty() && wait_response {
+                    self.trace(format!("La réplica {node_replica} no respondió").as_str())?;
                     let mut node_writer = self.write()?;
-                    node_writer.acknowledge_offline_neighbour(node_replica);
+                    node_writer.acknowledge_offline_neighbour(*node_replica);
                 } else {
                     result = replica_response;
-                    *responsive_replica = node_replica;
+                    *responsive_replica = *node_replica;
                     break;
                 }
             } else {
                 *replicas_asked += 1;
             }
-            node_replica = next_node_in_the_cluster(node_replica, &nodes_ids);
         }
 
         Ok(result)
@@ -1437,6 +2481,7 @@ impl SessionHandler {
             }
             res_with_opcode
         } else {
+            self.trace(format!("Pidiendo el digest a la réplica {node_to_consult}").as_str())?;
             let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
                 SvAction::DigestReadRequest(request.to_vec()).as_bytes(),
                 None,
@@ -1444,7 +2489,7 @@ impl SessionHandler {
             );
             send_to_node_and_wait_response_with_timeout(
                 node_to_consult,
-                request_with_metadata,
+                self.traced_action(request_with_metadata),
                 PortType::Priv,
                 true,
                 Some(TIMEOUT_SECS),
@@ -1478,78 +2523,74 @@ impl SessionHandler {
 
     /// Revisa si se cumple el _Consistency Level_ y además si es necesario ejecutar _read-repair_, si es el caso, lo ejecuta.
     ///
+    /// Los _digests_ se piden a las réplicas restantes en paralelo. Si no responden las suficientes
+    /// antes de que se agote el tiempo de espera de lecturas, se devuelve un error de _timeout_.
+    ///
     /// Devuelve un booleano indicando si _read-repair_ fue ejecutado o no.
     fn consult_replica_nodes_consistency(
         &self,
         id_and_replicas_asked: (NodeId, usize),
         request_and_table_name: (&[Byte], &str),
-        consistency_counter: &mut usize,
-        consistency_number: usize,
-        first_responsive_id_and_response: (NodeId, &[Byte]),
-        replication_factor_quantity: Uint,
+        consistency_counter: &mut ConsistencyCounter,
+        response_from_first_responsive_replica: &[Byte],
+        replicas: &[NodeId],
     ) -> Result<bool> {
-        if consistency_number == 1 {
+        if consistency_counter.is_reached() {
             return Ok(false);
         }
         let mut exec_read_repair = false;
         let (node_id, replicas_asked) = id_and_replicas_asked;
         let (request, table_name) = request_and_table_name;
-        let (responsive_replica, response_from_first_responsive_replica) =
-            first_responsive_id_and_response;
 
         let first_hashed_value = hash_value(response_from_first_responsive_replica);
         let mut responses: Vec<Vec<Byte>> = Vec::new();
-        let node_reader = self.read()?;
-        let nodes_ids = node_reader.get_nodes_ids();
-        drop(node_reader);
-        let mut node_to_consult = next_node_in_the_cluster(responsive_replica, &nodes_ids);
         let mut inconsistent_digest_request = false;
-        for _ in (replicas_asked as Uint)..replication_factor_quantity {
-            let opcode_with_hashed_value = match self.decide_how_to_request_the_digest_read_request(
-                node_to_consult,
-                request,
-                node_id,
-            ) {
-                Ok(res) => res,
-                Err(err) => {
-                    return Err(Error::ServerError(format!(
-                        "Error al enviar la query al nodo {node_to_consult}: {err}"
-                    )));
-                }
+        // Cuenta las réplicas que respondieron, coincidan o no con la primera respuesta.
+        let mut responses_counter = consistency_counter.clone();
+        let session_handler = self.clone();
+        let digest_request = request.to_vec();
+        let mut digests = ReplicaResponses::spawn(
+            replicas.iter().skip(replicas_asked).copied().collect(),
+            Duration::from_millis(READ_REQUEST_TIMEOUT_MILLIS),
+            move |node_to_consult| {
+                session_handler.decide_how_to_request_the_digest_read_request(
+                    *node_to_consult,
+                    &digest_request,
+                    node_id,
+                )
+            },
+        )?;
+        while !responses_counter.is_reached() {
+            let Some((node_to_consult, opcode_with_hashed_value)) = digests.next() else {
+                break;
             };
             if opcode_with_hashed_value.is_empty() {
-                node_to_consult = next_node_in_the_cluster(node_to_consult, &nodes_ids);
                 continue;
             }
+            self.trace(format!("Digest recibido de la réplica {node_to_consult}").as_str())?;
+            responses_counter.ack(node_to_consult);
             let res_hashed_value = self.get_digest_read_request_value(&opcode_with_hashed_value)?;
             self.check_consistency_of_the_responses(
                 opcode_with_hashed_value,
                 first_hashed_value,
                 res_hashed_value,
-                consistency_counter,
+                (node_to_consult, consistency_counter),
                 &mut responses,
                 &mut inconsistent_digest_request,
             )?;
-            if *consistency_counter >= consistency_number {
-                break;
-            }
-            node_to_consult = next_node_in_the_cluster(node_to_consult, &nodes_ids);
+        }
+        if !responses_counter.is_reached() {
+            return Err(responses_counter.read_timeout(true));
         }
         check_if_read_repair_is_neccesary(
-            consistency_counter,
-            consistency_number,
+            consistency_counter.is_reached(),
             &mut exec_read_repair,
             responses,
             first_hashed_value,
             inconsistent_digest_request,
         );
         if exec_read_repair && self.neighbour_is_responsive(node_id)? {
-            return self.start_read_repair(
-                node_id,
-                request,
-                table_name,
-                replication_factor_quantity,
-            );
+            return self.start_read_repair(node_id, request, table_name, replicas);
         }
         Ok(false)
     }
@@ -1559,16 +2600,21 @@ impl SessionHandler {
         opcode_with_hashed_value: Vec<Byte>,
         first_hashed_value: Ulong,
         res_hashed_value: Ulong,
-        consistency_counter: &mut usize,
+        replica_and_consistency_counter: (NodeId, &mut ConsistencyCounter),
         responses: &mut Vec<Vec<Byte>>,
         inconsistent_digest_request: &mut bool,
     ) -> Result<()> {
+        let (replica, consistency_counter) = replica_and_consistency_counter;
         if Opcode::try_from(opcode_with_hashed_value[0])? == Opcode::Result
             && first_hashed_value == res_hashed_value
         {
-            *consistency_counter += 1;
+            consistency_counter.ack(replica);
             responses.push(opcode_with_hashed_value[1..].to_vec());
         } else {
+            self.trace(
+                format!("El digest de la réplica {replica} no coincide con la primera respuesta")
+                    .as_str(),
+            )?;
             *inconsistent_digest_request = true
         };
         Ok(())
@@ -1579,54 +2625,71 @@ impl SessionHandler {
         node_id: Byte,
         request: &[Byte],
         table_name: &str,
-        replication_factor_quantity: Uint,
+        replicas: &[NodeId],
     ) -> Result<bool> {
-        let mut rows_of_nodes: Vec<Vec<Vec<String>>> = vec![];
+        self.trace(
+            format!("Iniciando read-repair de las particiones del nodo {node_id}").as_str(),
+        )?;
         let mut req_with_node_replica = request[9..].to_vec();
         req_with_node_replica.push(node_id);
-        let node_reader = self.read()?;
-        let nodes_ids = node_reader.get_nodes_ids();
-        drop(node_reader);
-        let mut node_to_consult = node_id;
-        for _ in 0..replication_factor_quantity {
-            let res = if node_to_consult == self.id {
-                self.exec_direct_read_request(req_with_node_replica.clone())?
-            } else {
-                let extern_response = send_to_node_and_wait_response_with_timeout(
-                    node_to_consult,
-                    SvAction::DirectReadRequest(req_with_node_replica.clone()).as_bytes(),
-                    PortType::Priv,
-                    true,
-                    Some(TIMEOUT_SECS),
-                )?;
-                create_utf8_string_from_bytes(extern_response)?
-            };
-            add_rows(res, &mut rows_of_nodes);
-            node_to_consult = next_node_in_the_cluster(node_to_consult, &nodes_ids);
+        let (entries, reachable) = gather_replica_entries(replicas, |node_to_consult| {
+            let res = self.direct_read_for_repair(node_to_consult, &req_with_node_replica);
+            if let Err(err) = &res {
+                self.skip_replica_in_repair(node_to_consult, err)?;
+            }
+            res
+        })?;
+        if reachable.is_empty() {
+            return Ok(false);
         }
-        self.execute_read_repair(
-            node_id,
-            &nodes_ids,
-            table_name,
-            rows_of_nodes,
-            replication_factor_quantity,
-        )?;
+        self.execute_read_repair(node_id, &reachable, table_name, entries)?;
 
         Ok(true)
     }
 
+    /// Pide a una réplica todas sus entradas de la partición a reparar.
+    fn direct_read_for_repair(&self, node_to_consult: NodeId, request: &[Byte]) -> Result<String> {
+        if node_to_consult == self.id {
+            return self.exec_direct_read_request(request.to_vec());
+        }
+        let extern_response = send_to_node_and_wait_response_with_timeout(
+            node_to_consult,
+            self.traced_action(SvAction::DirectReadRequest(request.to_vec()).as_bytes()),
+            PortType::Priv,
+            true,
+            Some(TIMEOUT_SECS),
+        )?;
+        create_utf8_string_from_bytes(extern_response)
+    }
+
+    /// Registra que una réplica queda fuera del _read-repair_ por no haber respondido.
+    ///
+    /// La réplica se pondrá al día con la reparación por árboles de Merkle, o con un
+    /// próximo _read-repair_.
+    fn skip_replica_in_repair(&self, replica: NodeId, err: &Error) -> Result<()> {
+        let msg = format!("La réplica {replica} no participa del read-repair: {err}");
+        if let Ok(logger) = self.logger.read() {
+            let _ = logger.warning(msg.as_str());
+        }
+        self.trace(msg.as_str())
+    }
+
     fn execute_read_repair(
         &self,
         replica_to_repair: NodeId,
-        nodes_ids: &[NodeId],
+        replicas: &[NodeId],
         table_name: &str,
-        rows_of_nodes: Vec<Vec<Vec<String>>>,
-        replication_factor_quantity: Uint,
+        entries: SortedEntries,
     ) -> Result<()> {
-        let rows_as_string = self.get_most_recent_rows_as_string(rows_of_nodes, table_name)?;
-        let mut node_to_repair = replica_to_repair;
-        for _ in 0..replication_factor_quantity {
-            if node_to_repair == self.id {
+        // Las entradas ya quedaron combinadas, con la versión más reciente de cada fila y
+        // todos los borrados que conoce alguna réplica
+        let rows_as_string = entries
+            .values()
+            .map(|entry| entry.to_line())
+            .collect::<Vec<String>>()
+            .join("\n");
+        for node_to_repair in replicas {
+            if *node_to_repair == self.id {
                 let node_writer = self.write()?;
                 let table = node_writer.get_table(table_name)?;
                 DiskHandler::repair_rows(
@@ -1643,16 +2706,18 @@ impl SessionHandler {
                     rows_as_string.as_bytes().to_vec(),
                 )
                 .as_bytes();
-                send_to_node_and_wait_response_with_timeout(
-                    node_to_repair,
+                if let Err(err) = send_to_node_and_wait_response_with_timeout(
+                    *node_to_repair,
                     sv_action,
                     PortType::Priv,
                     false,
                     Some(TIMEOUT_SECS),
-                )?;
+                ) {
+                    self.skip_replica_in_repair(*node_to_repair, &err)?;
+                }
             };
-            node_to_repair = next_node_in_the_cluster(node_to_repair, nodes_ids);
         }
+        self.trace(format!("Read-repair aplicado en las réplicas {replicas:?}").as_str())?;
         Ok(())
     }
 
@@ -1665,102 +2730,47 @@ impl SessionHandler {
         insert: Insert,
         request: &[Byte],
         consistency_level: &Consistency,
+        timestamp: Long,
     ) -> Result<Vec<Byte>> {
-        let timestamp = Utc::now().timestamp();
         let table_name: String = insert.table.get_name();
         // let partitions_keys_to_nodes = self.get_partition_keys_values(&table_name)?.clone();
-        let mut response: Vec<Byte> = Vec::new();
         let node_reader = self.read()?;
         let partition_key_value = get_partition_key_value_from_insert_statement(
             &insert,
             node_reader.get_table(&table_name)?,
         )?;
         let node_id = node_reader.select_node(&partition_key_value);
-        let replication_factor_quantity = node_reader.get_replicas_from_table_name(&table_name)?;
-        let consistency_number =
-            consistency_level.as_usize(replication_factor_quantity as usize)?;
-        let mut consistency_counter = 0;
-        let mut wait_response = true;
+        let replicas = node_reader.get_replicas_nodes_from_table_name(node_id, &table_name)?;
+        let mut consistency_counter =
+            node_reader.new_consistency_counter(consistency_level, &replicas)?;
         let nodes_ids = node_reader.get_nodes_ids();
-        let mut node_to_replicate = node_id;
-        let nodes_quantity = node_reader.get_actual_n_nodes();
         drop(node_reader);
 
-        for i in 0..nodes_quantity {
-            if (i as Uint) < replication_factor_quantity {
-                response = if node_to_replicate == self.id {
-                    let mut node_writer = self.write()?;
-                    node_writer.process_insert(&insert, timestamp, node_id)?
-                } else {
-                    self.forward_insert_to_replica(
-                        node_id,
-                        node_to_replicate,
-                        request,
-                        timestamp,
-                        wait_response,
-                    )?
-                }
-            } else if node_to_replicate == self.id {
+        self.replicate_mutation(
+            (&replicas, node_id),
+            (request, timestamp),
+            || self.write()?.process_insert(&insert, timestamp, node_id),
+            &mut consistency_counter,
+        )?;
+
+        let wait_response = !consistency_counter.is_reached();
+        for node_id in nodes_ids.iter().filter(|id| !replicas.contains(id)) {
+            if *node_id == self.id {
                 self.add_partition_value_if_new(&table_name, &insert)?;
             } else {
                 self.forward_insert_request_to_other_nodes_table(
-                    node_to_replicate,
+                    *node_id,
                     &table_name,
                     &insert,
                     wait_response,
                 )?;
-            };
-            node_to_replicate = next_node_in_the_cluster(node_to_replicate, &nodes_ids);
-
-            if consistency_counter >= consistency_number {
-                wait_response = false;
-            } else if verify_succesful_response(&response) {
-                consistency_counter += 1;
             }
         }
 
-        if consistency_counter < consistency_number {
-            Err(Error::ServerError(format!(
-                "No se pudo cumplir con el nivel de consistencia {consistency_level}, solo se logró con {consistency_counter} de {consistency_number}",
-            )))
-        } else {
-            Ok(response)
-        }
-    }
-
-    fn forward_insert_to_replica(
-        &self,
-        node_id: NodeId,
-        node_to_replicate: NodeId,
-        request: &[Byte],
-        timestamp: Long,
-        wait_response: bool,
-    ) -> Result<Vec<Byte>> {
-        let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
-            SvAction::InternalQuery(request.to_vec()).as_bytes(),
-            Some(timestamp),
-            Some(node_id),
-        );
-        let mut res: Vec<Byte> = Vec::new();
-        if self.neighbour_is_responsive(node_to_replicate)? {
-            res = match send_to_node_and_wait_response_with_timeout(
-                node_to_replicate,
-                request_with_metadata,
-                PortType::Priv,
-                wait_response,
-                Some(TIMEOUT_SECS),
-            ) {
-                Ok(res) => res,
-                Err(err) => {
-                    return Err(err);
-                }
-            };
-        }
-        if res.is_empty() && wait_response {
-            self.write()?
-                .acknowledge_offline_neighbour(node_to_replicate);
+        if !consistency_counter.is_reached() {
+            return Err(consistency_counter.write_timeout(WriteType::Simple));
         }
-        Ok(res)
+        Ok(Node::create_result_void())
     }
 
     fn add_partition_value_if_new(&self, table_name: &str, insert: &Insert) -> Result<()> {
@@ -1827,52 +2837,37 @@ impl SessionHandler {
         update: Update,
         request: &[Byte],
         consistency_level: &Consistency,
+        timestamp: Long,
     ) -> Result<Vec<Byte>> {
-        let timestamp = Utc::now().timestamp();
         let table_name = update.table_name.get_name();
         let node_reader = self.read()?;
         let partitions_keys_to_nodes = node_reader.get_partition_keys_values(&table_name)?.clone();
         let mut consulted_nodes: Vec<String> = Vec::new();
-        let replication_factor_quantity = node_reader.get_replicas_from_table_name(&table_name)?;
-        let consistency_number =
-            consistency_level.as_usize(replication_factor_quantity as usize)?;
         drop(node_reader);
 
         for partition_key_value in partitions_keys_to_nodes {
-            let mut consistency_counter = 0;
             let node_reader = self.read()?;
             let node_id = node_reader.select_node(&partition_key_value);
             drop(node_reader);
 
             if !consulted_nodes.contains(&partition_key_value) {
-                let current_response = if node_id == self.id {
-                    let mut node_writer = self.write()?;
-                    node_writer.process_update(&update, timestamp, self.id)?
-                } else {
-                    self.forward_update(node_id, request, timestamp)?
-                };
-                if verify_succesful_response(&current_response) {
-                    consistency_counter += 1;
-                }
-
                 consulted_nodes.push(partition_key_value.clone());
                 let node_reader = self.read()?;
-                let replication_factor = node_reader.get_replicas_from_table_name(&table_name)?;
+                let replicas =
+                    node_reader.get_replicas_nodes_from_table_name(node_id, &table_name)?;
+                let mut consistency_counter =
+                    node_reader.new_consistency_counter(consistency_level, &replicas)?;
                 drop(node_reader);
 
-                self.replicate_update_in_other_nodes(
-                    replication_factor,
-                    node_id,
-                    request,
-                    &update,
-                    timestamp,
+                self.replicate_mutation(
+                    (&replicas, node_id),
+                    (request, timestamp),
+                    || self.write()?.process_update(&update, timestamp, node_id),
                     &mut consistency_counter,
                 )?;
 
-                if consistency_counter < consistency_number {
-                    return Err(Error::ServerError(format!(
-                        "No se pudo cumplir con el nivel de consistencia {consistency_level}, solo se logró con {consistency_counter} de {consistency_number}",
-                    )));
+                if !consistency_counter.is_reached() {
+                    return Err(consistency_counter.write_timeout(WriteType::Simple));
                 }
             }
         }
@@ -1880,204 +2875,624 @@ impl SessionHandler {
         Ok(Node::create_result_void())
     }
 
-    fn forward_update(
+    // ###########################################################################################
+    // ######################################### DELETE ##########################################
+    // ###########################################################################################
+
+    fn delete_with_other_nodes(
         &self,
-        node_id: NodeId,
+        delete: Delete,
         request: &[Byte],
+        consistency_level: &Consistency,
         timestamp: Long,
     ) -> Result<Vec<Byte>> {
-        let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
-            SvAction::InternalQuery(request.to_vec()).as_bytes(),
-            Some(timestamp),
-            Some(node_id),
-        );
-        let mut res: Vec<Byte> = Vec::new();
-        if self.neighbour_is_responsive(node_id)? {
-            res = match send_to_node_and_wait_response_with_timeout(
-                node_id,
-                request_with_metadata,
-                PortType::Priv,
-                true,
-                Some(TIMEOUT_SECS),
-            ) {
-                Ok(res) => res,
-                Err(err) => {
-                    return Err(err);
+        let table_name = delete.from.get_name();
+        let mut consulted_nodes: Vec<String> = Vec::new();
+        let node_reader = self.read()?;
+        let partitions_keys_to_nodes = node_reader.get_partition_keys_values(&table_name)?.clone();
+        drop(node_reader);
+
+        for partition_key_value in partitions_keys_to_nodes {
+            let node_reader = self.read()?;
+            let node_id = node_reader.select_node(&partition_key_value);
+            drop(node_reader);
+
+            if !consulted_nodes.contains(&partition_key_value) {
+                consulted_nodes.push(partition_key_value.clone());
+                let node_reader = self.read()?;
+                let replicas =
+                    node_reader.get_replicas_nodes_from_table_name(node_id, &table_name)?;
+                let mut consistency_counter =
+                    node_reader.new_consistency_counter(consistency_level, &replicas)?;
+                drop(node_reader);
+
+                self.replicate_mutation(
+                    (&replicas, node_id),
+                    (request, timestamp),
+                    || self.write()?.process_delete(&delete, timestamp, node_id),
+                    &mut consistency_counter,
+                )?;
+
+                if !consistency_counter.is_reached() {
+                    return Err(consistency_counter.write_timeout(WriteType::Simple));
                 }
-            };
+            }
         }
-        if res.is_empty() {
-            self.write()?.acknowledge_offline_neighbour(node_id);
+        Ok(Node::create_result_void())
+    }
+
+    // ###########################################################################################
+    // ################################## VISTAS MATERIALIZADAS ##################################
+    // ###########################################################################################
+
+    /// Obtiene la partición de la tabla base que toca una escritura, si la tabla tiene vistas
+    /// materializadas, para bloquearla con [PartitionLocks] mientras se actualizan sus vistas.
+    ///
+    /// Las vistas no se pueden escribir directamente, sino sólo a través de su tabla base, y un
+    /// UPDATE o DELETE sobre una tabla con vistas debe comparar por igualdad su _partition key_.
+    fn get_base_partition(&self, dml_statement: &DmlStatement) -> Result<Option<(String, String)>> {
+        let table_name = get_dml_statement_table_name(dml_statement);
+        let the_where = match dml_statement {
+            DmlStatement::SelectStatement(_) => return Ok(None),
+            DmlStatement::InsertStatement(_) => None,
+            DmlStatement::UpdateStatement(update) => update.the_where.as_ref(),
+            DmlStatement::DeleteStatement(delete) => delete.the_where.as_ref(),
+        };
+        let node_reader = self.read()?;
+        let table = node_reader.get_table(&table_name)?;
+        if table.is_view() {
+            return Err(Error::Invalid(format!(
+                "No se puede escribir en la vista materializada {table_name}, sino en su tabla base"
+            )));
+        }
+        if node_reader.get_views_names(&table_name).is_empty() {
+            return Ok(None);
+        }
+        let partition = match dml_statement {
+            DmlStatement::InsertStatement(insert) => Some(
+                get_partition_key_value_from_insert_statement(insert, table)?,
+            ),
+            _ => DiskHandler::get_partition_from_where(the_where, table),
+        };
+        match partition {
+            Some(partition) => Ok(Some((
+                format!("{}.{table_name}", table.get_keyspace()),
+                partition,
+            ))),
+            None => Err(Error::Invalid(format!(
+                "La tabla {table_name} tiene vistas materializadas, así que el WHERE debe comparar por igualdad su partition key"
+            ))),
         }
-        Ok(res)
     }
 
-    fn replicate_update_in_other_nodes(
+    /// Lee las filas de la tabla base que puede modificar una escritura sobre una tabla con
+    /// vistas materializadas, para después actualizarlas con [update_views](Self::update_views).
+    ///
+    /// La partición de la escritura tiene que estar bloqueada hasta actualizar las vistas.
+    fn read_rows_for_views(
         &self,
-        replication_factor: Uint,
-        node_id: Byte,
-        request: &[Byte],
-        update: &Update,
+        dml_statement: &DmlStatement,
+        consistency_level: &Consistency,
+    ) -> Result<BaseRows> {
+        let table_name = get_dml_statement_table_name(dml_statement);
+        let node_reader = self.read()?;
+        let table = node_reader.get_table(&table_name)?;
+        let (condition, new_rows, inserted_key) = match dml_statement {
+            DmlStatement::InsertStatement(insert) => {
+                let inserted_row = get_inserted_row(insert, table);
+                let inserted_key = get_key_condition(&inserted_row, table);
+                // Con IF NOT EXISTS puede que no se escriba, así que la fila se relee después
+                let new_rows = (!insert.if_not_exists).then(|| vec![inserted_row]);
+                (inserted_key.clone(), new_rows, inserted_key)
+            }
+            DmlStatement::UpdateStatement(Update { the_where, .. })
+            | DmlStatement::DeleteStatement(Delete { the_where, .. }) => (
+                the_where.as_ref().and_then(|the_where| the_where.as_cql()),
+                None,
+                None,
+            ),
+            DmlStatement::SelectStatement(_) => (None, None, None),
+        };
+        let ttl = get_view_ttl(dml_statement.update_parameters(), table)?;
+        // Sin una condición, habría que leer la tabla entera
+        let Some(condition) = condition else {
+            return Err(Error::Invalid(format!(
+                "No se pudieron obtener las filas de {table_name} que modifica la escritura, para actualizar sus vistas materializadas"
+            )));
+        };
+        let select = format!(
+            "SELECT * FROM {}.{table_name} WHERE {condition}",
+            table.get_keyspace()
+        );
+        drop(node_reader);
+
+        Ok(BaseRows {
+            table_name,
+            old_rows: self.read_named_rows(select, consistency_level)?,
+            new_rows,
+            inserted_key,
+            ttl,
+        })
+    }
+
+    /// Lleva las vistas materializadas de una tabla base desde las filas leídas antes de una
+    /// escritura a sus nuevas versiones, escribiéndolas con el timestamp de la escritura.
+    fn update_views(
+        &self,
+        base_rows: BaseRows,
+        consistency_level: &Consistency,
         timestamp: Long,
-        consistency_counter: &mut usize,
     ) -> Result<()> {
+        let table_name = &base_rows.table_name;
+        let new_rows = match base_rows.new_rows {
+            Some(new_rows) => new_rows,
+            None => {
+                let node_reader = self.read()?;
+                let table = node_reader.get_table(table_name)?;
+                let mut conditions: Vec<String> = base_rows
+                    .old_rows
+                    .iter()
+                    .filter_map(|row| get_key_condition(row, table))
+                    .chain(base_rows.inserted_key.clone())
+                    .collect();
+                conditions.sort();
+                conditions.dedup();
+                let keyspace_name = table.get_keyspace().to_string();
+                drop(node_reader);
+
+                let mut new_rows = Vec::new();
+                for condition in conditions {
+                    new_rows.extend(self.read_named_rows(
+                        format!("SELECT * FROM {keyspace_name}.{table_name} WHERE {condition}"),
+                        consistency_level,
+                    )?);
+                }
+                new_rows
+            }
+        };
+
         let node_reader = self.read()?;
-        let nodes_ids = node_reader.get_nodes_ids();
+        let table = node_reader.get_table(table_name)?;
+        let mut mutations = Vec::new();
+        for view_name in node_reader.get_views_names(table_name) {
+            mutations.extend(get_view_mutations(
+                node_reader.get_table(&view_name)?,
+                table,
+                &base_rows.old_rows,
+                &new_rows,
+                base_rows.ttl,
+            ));
+        }
         drop(node_reader);
-        let mut node_to_replicate = next_node_in_the_cluster(node_id, &nodes_ids);
-        for _ in 1..replication_factor {
-            let current_response = if node_to_replicate == self.id {
-                let mut node_writer = self.write()?;
-                node_writer.process_update(update, timestamp, node_id)?
-            } else {
-                let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
-                    SvAction::InternalQuery(request.to_vec()).as_bytes(),
-                    Some(timestamp),
-                    Some(node_id),
-                );
-                let mut replica_response: Vec<Byte> = Vec::new();
-                if self.neighbour_is_responsive(node_to_replicate)? {
-                    replica_response = match send_to_node_and_wait_response_with_timeout(
-                        node_to_replicate,
-                        request_with_metadata,
-                        PortType::Priv,
-                        true,
-                        Some(TIMEOUT_SECS),
-                    ) {
-                        Ok(res) => res,
-                        Err(err) => {
-                            return Err(err);
-                        }
-                    };
-                }
 
-                if replica_response.is_empty() {
-                    let mut node_writer = self.write()?;
-                    node_writer.acknowledge_offline_neighbour(node_to_replicate);
-                }
-                replica_response
-            };
-            node_to_replicate = next_node_in_the_cluster(node_to_replicate, &nodes_ids);
+        if !mutations.is_empty() {
+            self.trace(
+                format!("Se actualizan las vistas materializadas de la tabla {table_name}")
+                    .as_str(),
+            )?;
+        }
+        for mutation in mutations {
+            self.write_to_view(mutation, consistency_level, timestamp)?;
+        }
+        Ok(())
+    }
 
-            if verify_succesful_response(&current_response) {
-                *consistency_counter += 1;
+    /// Llena una vista materializada recién creada con las filas que ya tiene su tabla base.
+    fn build_view(&self, view_name: &str) -> Result<()> {
+        let node_reader = self.read()?;
+        let view = node_reader.get_table(view_name)?;
+        let base_table_name = match &view.base_table {
+            Some(base_table_name) => base_table_name.to_string(),
+            None => {
+                return Err(Error::ServerError(format!(
+                    "{view_name} no es una vista materializada"
+                )))
             }
+        };
+        let select = format!("SELECT * FROM {}.{base_table_name}", view.get_keyspace());
+        drop(node_reader);
+
+        let consistency_level = Consistency::Quorum;
+        let rows = self.read_named_rows(select, &consistency_level)?;
+        let node_reader = self.read()?;
+        let base_table = node_reader.get_table(&base_table_name)?;
+        let mutations = get_view_mutations(
+            node_reader.get_table(view_name)?,
+            base_table,
+            &[],
+            &rows,
+            get_view_ttl(None, base_table)?,
+        );
+        drop(node_reader);
+
+        let timestamp = Utc::now().timestamp_micros();
+        for mutation in mutations {
+            self.write_to_view(mutation, &consistency_level, timestamp)?;
         }
         Ok(())
     }
 
+    /// Hace un SELECT interno como coordinador y devuelve las filas con sus columnas.
+    fn read_named_rows(
+        &self,
+        query: String,
+        consistency_level: &Consistency,
+    ) -> Result<Vec<NamedRow>> {
+        let query_body = QueryBody::new(query, *consistency_level);
+        let select = match make_parse(&mut tokenize_query(query_body.get_query()))? {
+            Statement::DmlStatement(DmlStatement::SelectStatement(select)) => select,
+            _ => {
+                return Err(Error::ServerError(format!(
+                    "Se esperaba un SELECT y se armó '{}'",
+                    query_body.get_query()
+                )))
+            }
+        };
+        let request = Frame::new_query(0, &query_body).as_bytes();
+        let result = self.select_with_other_nodes(select, &request, &query_body)?;
+        let (columns, rows) = self.read()?.get_columns_and_rows(&result)?;
+        Ok(rows
+            .into_iter()
+            .map(|row| columns.iter().cloned().zip(row).collect())
+            .collect())
+    }
+
+    /// Manda a las réplicas de una vista un INSERT o DELETE sobre ella.
+    fn write_to_view(
+        &self,
+        query: String,
+        consistency_level: &Consistency,
+        timestamp: Long,
+    ) -> Result<()> {
+        let query_body = QueryBody::new(query, *consistency_level);
+        let request = Frame::new_query(0, &query_body).as_bytes();
+        match make_parse(&mut tokenize_query(query_body.get_query()))? {
+            Statement::DmlStatement(DmlStatement::InsertStatement(insert)) => {
+                self.insert_with_other_nodes(insert, &request, consistency_level, timestamp)?
+            }
+            Statement::DmlStatement(DmlStatement::DeleteStatement(delete)) => {
+                self.delete_with_other_nodes(delete, &request, consistency_level, timestamp)?
+            }
+            _ => {
+                return Err(Error::ServerError(format!(
+                    "Se esperaba un INSERT o DELETE sobre la vista y se armó '{}'",
+                    query_body.get_query()
+                )))
+            }
+        };
+        Ok(())
+    }
+
     // ###########################################################################################
-    // ######################################### DELETE ##########################################
+    // ######################################### BATCH ###########################################
     // ###########################################################################################
 
-    fn delete_with_other_nodes(
-        &self,
-        delete: Delete,
-        request: &[Byte],
-        consistency_level: &Consistency,
-    ) -> Result<Vec<Byte>> {
-        let table_name = delete.from.get_name();
-        let mut consulted_nodes: Vec<String> = Vec::new();
-        let node_reader = self.write()?;
-        let partitions_keys_to_nodes = node_reader.get_partition_keys_values(&table_name)?.clone();
-        let replication_factor_quantity = node_reader.get_replicas_from_table_name(&table_name)?;
-        let consistency_number =
-            consistency_level.as_usize(replication_factor_quantity as usize)?;
-        drop(node_reader);
-
-        for partition_key_value in partitions_keys_to_nodes {
-            let node_reader = self.read()?;
-            let node_id = node_reader.select_node(&partition_key_value);
-            drop(node_reader);
-
-            if !consulted_nodes.contains(&partition_key_value) {
-                consulted_nodes.push(partition_key_value.clone());
-                let node_reader = self.read()?;
-                let replication_factor = node_reader.get_replicas_from_table_name(&table_name)?;
-                drop(node_reader);
-
-                self.replicate_delete_in_other_nodes(
-                    replication_factor,
-                    node_id,
-                    request,
-                    &delete,
-                    consistency_number,
-                )?;
+    /// Reparte las _queries_ de un BATCH entre las réplicas de cada partición.
+    ///
+    /// Si el BATCH es [Logged](BatchType::Logged), cada réplica recibe todas sus _queries_ juntas
+    /// y las aplica de forma atómica. Si no, se manda un BATCH por cada partición de cada réplica.
+    fn batch_with_other_nodes(&self, batch: &BatchBody) -> Result<Vec<Byte>> {
+        let timestamp = batch
+            .get_timestamp()
+            .unwrap_or_else(|| Utc::now().timestamp_micros());
+        let batch_type = batch.get_batch_type();
+        let consistency_level = batch.get_consistency_level();
+        let queries = self.bind_batch_queries(batch)?;
+
+        let mut views_statements = Vec::new();
+        let mut base_partitions = Vec::new();
+        for (statement, _) in &queries {
+            if let Some(base_partition) = self.get_base_partition(statement)? {
+                base_partitions.push(base_partition);
+                views_statements.push(statement);
             }
         }
-        Ok(Node::create_result_void())
-    }
+        base_partitions.sort();
+        base_partitions.dedup();
+        let _partitions_guard = self.view_locks.lock(base_partitions)?;
+        let mut views_base_rows = Vec::new();
+        for statement in views_statements {
+            views_base_rows.push(self.read_rows_for_views(statement, consistency_level)?);
+        }
 
-    // Función auxiliar para replicar el delete en otros nodos
-    fn replicate_delete_in_other_nodes(
-        &self,
-        replication_factor: Uint,
-        node_id: Byte,
-        request: &[Byte],
-        delete: &Delete,
-        consistency_number: usize,
-    ) -> Result<()> {
-        let mut consistency_counter = 0;
-        let mut wait_response = true;
+        let mut groups = BatchGroups::new();
+        let mut consistency_counters: HashMap<(usize, NodeId), ConsistencyCounter> = HashMap::new();
+        let mut inserts: Vec<(&Insert, Vec<NodeId>)> = Vec::new();
         let node_reader = self.read()?;
         let nodes_ids = node_reader.get_nodes_ids();
+        for (i, (statement, _)) in queries.iter().enumerate() {
+            let table_name = get_dml_statement_table_name(statement);
+            let table = node_reader.get_table(&table_name)?;
+
+            let mut owners: Vec<NodeId> =
+                match statement {
+                    DmlStatement::InsertStatement(insert) => vec![node_reader.select_node(
+                        &get_partition_key_value_from_insert_statement(insert, table)?,
+                    )],
+                    _ => node_reader
+                        .get_partition_keys_values(&table_name)?
+                        .iter()
+                        .map(|partition_key_value| node_reader.select_node(partition_key_value))
+                        .collect(),
+                };
+            owners.sort();
+            owners.dedup();
+
+            let mut replicas = Vec::new();
+            for owner in owners {
+                let owner_replicas = node_reader.get_replicas_nodes(
+                    owner,
+                    node_reader.get_keyspace_from_name(table.get_keyspace())?,
+                    &nodes_ids,
+                );
+                consistency_counters.insert(
+                    (i, owner),
+                    node_reader.new_consistency_counter(consistency_level, &owner_replicas)?,
+                );
+                for replica in owner_replicas {
+                    let group_key = match batch_type {
+                        BatchType::Logged => (replica, None),
+                        _ => (replica, Some(owner)),
+                    };
+                    groups.entry(group_key).or_default().push((i, owner));
+                    replicas.push(replica);
+                }
+            }
+            if let DmlStatement::InsertStatement(insert) = statement {
+                inserts.push((insert, replicas));
+            }
+        }
         drop(node_reader);
-        let mut node_to_replicate = node_id;
-        for _ in 0..replication_factor {
-            let current_response = if node_to_replicate == self.id {
-                let mut node_writer = self.write()?;
-                node_writer.process_delete(delete, node_id)?
+
+        let mut local_groups = Vec::new();
+        let mut remote_groups = Vec::new();
+        for ((replica, _), group) in groups {
+            let group_queries = group.iter().map(|(i, _)| queries[*i].1.clone()).collect();
+            let mut request =
+                Frame::new_batch(0, batch_type, group_queries, *consistency_level).as_bytes();
+            request.extend(timestamp.to_be_bytes());
+            request.extend(group.iter().map(|(_, owner)| *owner));
+
+            if replica == self.id {
+                local_groups.push((group, request));
             } else {
-                self.forward_delete(node_id, node_to_replicate, request, wait_response)?
+                remote_groups.push((replica, group, request));
+            }
+        }
+
+        // Un BATCH Logged queda en el batch log del coordinador hasta que lo confirmen todas sus
+        // réplicas, para volver a enviárselo a las que no lo hayan hecho
+        let is_logged = batch_type == BatchType::Logged;
+        let queries_bytes: Vec<Vec<Byte>> =
+            queries.iter().map(|(_, query)| query.as_bytes()).collect();
+        let batch_id = format!("{:016x}", hash_value((timestamp, queries_bytes)));
+        let groups_count = local_groups.len() + remote_groups.len();
+        if is_logged {
+            let logged_groups: Vec<(NodeId, Vec<Byte>)> = local_groups
+                .iter()
+                .map(|(_, request)| (self.id, request.clone()))
+                .chain(
+                    remote_groups
+                        .iter()
+                        .map(|(replica, _, request)| (*replica, request.clone())),
+                )
+                .collect();
+            let storage_addr = self.read()?.storage_addr.clone();
+            DiskHandler::store_coordinator_batch_log(&storage_addr, &batch_id, &logged_groups)?;
+        }
+
+        let session_handler = self.clone();
+        let mut responses = ReplicaResponses::spawn(
+            remote_groups,
+            Duration::from_millis(WRITE_REQUEST_TIMEOUT_MILLIS),
+            move |(replica, _, request)| match is_logged {
+                true => Ok(session_handler.send_logged_batch_group(*replica, request)),
+                false => session_handler.forward_batch_to_replica(*replica, request.clone()),
+            },
+        )?;
+        let mut acked_groups = 0;
+        for (group, request) in local_groups {
+            let response = match self.write()?.process_internal_batch(&request) {
+                Ok(response) => response,
+                Err(err) if is_logged => make_error_response(err),
+                Err(err) => return Err(err),
+            };
+            acked_groups += verify_succesful_response(&response) as usize;
+            ack_batch_group(&mut consistency_counters, self.id, &group, &response);
+        }
+        while !consistency_counters
+            .values()
+            .all(ConsistencyCounter::is_reached)
+        {
+            let Some(((replica, group, _), response)) = responses.next() else {
+                break;
             };
-            if consistency_counter >= consistency_number {
-                wait_response = false;
-            } else if verify_succesful_response(&current_response) {
-                consistency_counter += 1;
+            acked_groups += verify_succesful_response(&response) as usize;
+            ack_batch_group(&mut consistency_counters, replica, &group, &response);
+        }
+        if is_logged {
+            self.finish_logged_batch(batch_id, responses, groups_count - acked_groups)?;
+        }
+
+        for (insert, replicas) in inserts {
+            let table_name = insert.table.get_name();
+            for node_id in nodes_ids.iter().filter(|id| !replicas.contains(id)) {
+                if *node_id == self.id {
+                    self.add_partition_value_if_new(&table_name, insert)?;
+                } else {
+                    self.forward_insert_request_to_other_nodes_table(
+                        *node_id,
+                        &table_name,
+                        insert,
+                        true,
+                    )?;
+                }
             }
-            node_to_replicate = next_node_in_the_cluster(node_to_replicate, &nodes_ids);
         }
-        if consistency_counter < consistency_number {
-            return Err(Error::ServerError(format!(
-                "No se pudo cumplir con el nivel de consistencia, solo se logró con {consistency_counter} de {consistency_number}",
-            )));
+
+        let write_type = match batch_type {
+            BatchType::Logged => WriteType::Batch,
+            _ => WriteType::UnloggedBatch,
         };
-        Ok(())
+        if let Some(consistency_counter) = consistency_counters
+            .values()
+            .find(|consistency_counter| !consistency_counter.is_reached())
+        {
+            return Err(consistency_counter.write_timeout(write_type));
+        }
+        for base_rows in views_base_rows {
+            self.update_views(base_rows, consistency_level, timestamp)?;
+        }
+        Ok(Node::create_result_void())
+    }
+
+    /// Parsea las _queries_ de un BATCH, vinculando los valores de sus marcadores.
+    ///
+    /// Junto a cada una devuelve la _query_ en texto con sus valores, que es lo que se manda a
+    /// las réplicas para que la vinculen ellas.
+    fn bind_batch_queries(&self, batch: &BatchBody) -> Result<Vec<(DmlStatement, BatchQuery)>> {
+        let batch_type = batch.get_batch_type();
+        let node_reader = self.read()?;
+        batch
+            .get_queries()
+            .iter()
+            .map(|batch_query| {
+                let (query, statement) = match batch_query {
+                    BatchQuery::Query { query, values } => {
+                        let statement = parse_batch_statement(query, batch_type)?;
+                        let statement =
+                            node_reader.bind_statement(query, statement, values, None)?;
+                        (query.clone(), statement)
+                    }
+                    BatchQuery::Prepared { id, values } => {
+                        match node_reader.get_prepared_statement(id) {
+                            Some(prepared) => {
+                                let query = prepared.get_query();
+                                let statement = check_batch_statement(
+                                    prepared.bind(values, None)?,
+                                    query,
+                                    batch_type,
+                                )?;
+                                (query.to_string(), statement)
+                            }
+                            None => {
+                                return Err(Error::Unprepared(
+                                    "La query no fue preparada en este nodo".to_string(),
+                                    id.clone(),
+                                ))
+                            }
+                        }
+                    }
+                };
+                // Se rechaza antes de mandarla a las réplicas, para no aplicar el BATCH a medias
+                if let DmlStatement::UpdateStatement(update) = &statement {
+                    let table = node_reader.get_table(&update.table_name.get_name())?;
+                    DiskHandler::validate_counter_assignments(&update.set_parameter, table)?;
+                }
+                let values = batch_query.get_values().to_vec();
+                Ok((statement, BatchQuery::Query { query, values }))
+            })
+            .collect()
     }
 
-    fn forward_delete(
+    fn forward_batch_to_replica(
         &self,
-        node_id: NodeId,
         node_to_replicate: NodeId,
-        request: &[Byte],
-        wait_response: bool,
+        request: Vec<Byte>,
     ) -> Result<Vec<Byte>> {
-        let request_with_metadata = add_metadata_to_internal_request_of_any_kind(
-            SvAction::InternalQuery(request.to_vec()).as_bytes(),
-            None,
-            Some(node_id),
-        );
-        let mut res: Vec<Byte> = Vec::new();
-        if self.neighbour_is_responsive(node_to_replicate)? {
-            res = match send_to_node_and_wait_response_with_timeout(
-                node_to_replicate,
-                request_with_metadata,
-                PortType::Priv,
-                wait_response,
-                Some(TIMEOUT_SECS),
-            ) {
-                Ok(res) => res,
-                Err(err) => {
-                    return Err(err);
+        self.send_mutation_to_replica(
+            node_to_replicate,
+            SvAction::InternalQuery(request).as_bytes(),
+            true,
+        )
+    }
+
+    /// Envía a una réplica el grupo de _queries_ que le corresponde de un BATCH
+    /// [Logged](BatchType::Logged), y devuelve su respuesta, o una vacía si no respondió.
+    ///
+    /// No se le guarda un _hint_ a la réplica si no responde, porque el grupo queda en el
+    /// _batch log_ del coordinador hasta que la réplica lo confirme.
+    fn send_logged_batch_group(&self, replica: NodeId, request: &[Byte]) -> Vec<Byte> {
+        if replica == self.id {
+            return match self.write() {
+                Ok(mut node_writer) => node_writer
+                    .process_internal_batch(request)
+                    .unwrap_or_else(make_error_response),
+                Err(err) => make_error_response(err),
+            };
+        }
+        if !self.neighbour_is_responsive(replica).unwrap_or(false) {
+            return Vec::new();
+        }
+        send_to_node_and_wait_response_with_timeout(
+            replica,
+            self.traced_action(SvAction::InternalQuery(request.to_vec()).as_bytes()),
+            PortType::Priv,
+            true,
+            Some(TIMEOUT_SECS),
+        )
+        .unwrap_or_default()
+    }
+
+    /// Espera en otro hilo las respuestas que faltan de un BATCH [Logged](BatchType::Logged) y lo
+    /// borra del _batch log_ del coordinador si todas sus réplicas lo confirmaron. Si no, queda
+    /// para que [replay_batch_logs](Self::replay_batch_logs) se lo vuelva a enviar a las que faltan.
+    fn finish_logged_batch(
+        &self,
+        batch_id: String,
+        responses: ReplicaResponses<RemoteBatchGroup>,
+        pending_groups: usize,
+    ) -> Result<()> {
+        let storage_addr = self.read()?.storage_addr.clone();
+        let logger = self.logger.clone();
+        Builder::new()
+            .name(format!("batch_{batch_id}"))
+            .spawn(move || {
+                let acked_groups = responses
+                    .filter(|(_, response)| verify_succesful_response(response))
+                    .count();
+                if acked_groups < pending_groups {
+                    return;
                 }
-            }
-        };
+                if let (Err(err), Ok(logger)) = (
+                    DiskHandler::delete_coordinator_batch_log(&storage_addr, &batch_id),
+                    logger.read(),
+                ) {
+                    let _ = logger.error(format!("Error al borrar el batch log: {err}").as_str());
+                }
+            })
+            .map(|_| ())
+            .map_err(|e| Error::ServerError(format!("No se pudo esperar a las réplicas: {e}")))
+    }
 
-        if res.is_empty() && wait_response {
-            let mut node_writer = self.write()?;
-            node_writer.acknowledge_offline_neighbour(node_to_replicate);
+    /// Vuelve a enviar los BATCH [Logged](BatchType::Logged) coordinados por este nodo a las
+    /// réplicas que todavía no los confirmaron, y vuelve a aplicar los que quedaron a medio
+    /// aplicar en este nodo.
+    ///
+    /// Un grupo se da por entregado si la réplica respondió, aunque sea con un error, porque
+    /// ella misma lo guarda en su _batch log_ antes de aplicarlo.
+    fn replay_batch_logs(&self) -> Result<()> {
+        self.write()?.replay_batch_logs()?;
+
+        let storage_addr = self.read()?.storage_addr.clone();
+        for (batch_id, groups) in DiskHandler::get_pending_coordinator_batch_logs(&storage_addr)? {
+            let pending_groups: Vec<(NodeId, Vec<Byte>)> = groups
+                .into_iter()
+                .filter(|(replica, request)| {
+                    self.send_logged_batch_group(*replica, request).is_empty()
+                })
+                .collect();
+            if pending_groups.is_empty() {
+                DiskHandler::delete_coordinator_batch_log(&storage_addr, &batch_id)?;
+            } else {
+                DiskHandler::store_coordinator_batch_log(
+                    &storage_addr,
+                    &batch_id,
+                    &pending_groups,
+                )?;
+            }
         }
-        Ok(res)
+        Ok(())
     }
 
     // ###########################################################################################
@@ -2089,6 +3504,7 @@ impl SessionHandler {
         &self,
         statement: Statement,
         internal_metadata: (Option<Long>, Option<Byte>),
+        page: Option<&Page>,
     ) -> Result<Vec<Byte>> {
         let mut node_writer = self.write()?;
         match statement {
@@ -2096,7 +3512,7 @@ impl SessionHandler {
                 node_writer.handle_internal_ddl_statement(ddl_statement, internal_metadata)
             }
             Statement::DmlStatement(dml_statement) => {
-                node_writer.handle_internal_dml_statement(dml_statement, internal_metadata)
+                node_writer.handle_internal_dml_statement(dml_statement, internal_metadata, page)
             }
             Statement::Startup => Err(Error::Invalid(
                 "No se deberia haber mandado el startup por este canal".to_string(),
@@ -2276,6 +3692,7 @@ impl SessionHandler {
         self.leaving_node_has_to_relocate()?;
         self.is_relocation_needed()?;
         self.is_relocation_done()?;
+        self.start_replay()?;
 
         for neighbour_id in neighbours {
             if send_to_node(
@@ -2302,6 +3719,7 @@ impl SessionHandler {
 
     /// Se recibe un mensaje [SYN](crate::actions::opcode::SvAction::Syn).
     pub fn syn(&self, emissor_id: NodeId, emissor_gossip_info: GossipInfo) -> Result<()> {
+        self.write()?.report_heartbeats(&emissor_gossip_info);
         let mut own_gossip_info = GossipInfo::new(); // quiero info de estos nodos
         let mut response_nodes = NodesMap::new(); // doy info de estos nodos
 
@@ -2374,71 +3792,421 @@ impl SessionHandler {
         Ok(())
     }
 
-    /// Se recibe un mensaje [ACK](crate::actions::opcode::SvAction::Ack).
-    pub fn ack(
+    /// Se recibe un mensaje [ACK](crate::actions::opcode::SvAction::Ack).
+    pub fn ack(
+        &self,
+        receptor_id: NodeId,
+        receptor_gossip_info: GossipInfo,
+        mut response_nodes: NodesMap,
+    ) -> Result<()> {
+        self.write()?.report_heartbeats(
+            response_nodes
+                .iter()
+                .map(|(node_id, state)| (node_id, state.get_heartbeat())),
+        );
+        // Poblamos un mapa con los estados que pide el receptor
+        let mut nodes_for_receptor = NodesMap::new();
+        let node_reader = self.read()?;
+        let neighbours_states = &node_reader.neighbours_states;
+        for (node_id, receptor_heartbeat) in &receptor_gossip_info {
+            let own_endpoint_state = &neighbours_states[node_id];
+            if own_endpoint_state.get_heartbeat() > receptor_heartbeat {
+                // Hacemos doble chequeo que efectivamente tenemos información más nueva
+                nodes_for_receptor.insert(*node_id, own_endpoint_state.clone());
+            }
+        }
+
+        for (node_id, response_node_state) in &mut response_nodes {
+            if let Some(own_endpoint_state) = neighbours_states.get(node_id) {
+                let own_heartbeat = own_endpoint_state.get_heartbeat();
+                let mut offline_heartbeat = own_endpoint_state.get_heartbeat().clone();
+                offline_heartbeat.beat();
+                if own_heartbeat > response_node_state.get_heartbeat()
+                    || (*own_endpoint_state.get_appstate_status() == AppStatus::Offline
+                        && &offline_heartbeat > response_node_state.get_heartbeat())
+                {
+                    // El nodo propio tiene un heartbeat más nuevo que el que se recibió
+                    // o
+                    // El nodo propio no está listo para recibir queries
+                    *response_node_state = own_endpoint_state.clone();
+                }
+            }
+        }
+
+        drop(node_reader);
+        // Reemplazamos la información de nuestros vecinos por la más nueva que viene del nodo receptor
+        // Asumimos que es más nueva ya que fue previamente verificada
+        self.write()?.update_neighbours(response_nodes)?;
+
+        if let Err(err) = send_to_node(
+            receptor_id,
+            SvAction::Ack2(nodes_for_receptor).as_bytes(),
+            PortType::Priv,
+        ) {
+            let logger = self
+                .logger
+                .read()
+                .map_err(|e| Error::ServerError(e.to_string()))?;
+            if let Err(log_err) = logger.error(
+                format!(
+                    "Ocurrió un error al mandar un mensaje ACK2 al nodo [{receptor_id}]:\n\n{err}"
+                )
+                .as_str(),
+            ) {
+                println!("Error logging message: {log_err}");
+            }
+        }
+        Ok(())
+    }
+
+    /// Se recibe un mensaje [ACK2](crate::actions::opcode::SvAction::Ack2).
+    pub fn ack2(&self, nodes_map: NodesMap) -> Result<()> {
+        let mut node_writer = self.write()?;
+        node_writer.report_heartbeats(
+            nodes_map
+                .iter()
+                .map(|(node_id, state)| (node_id, state.get_heartbeat())),
+        );
+        node_writer.update_neighbours(nodes_map)
+    }
+
+    // ###########################################################################################
+    // ######################################### REPAIR ##########################################
+    // ###########################################################################################
+
+    /// Inicia en segundo plano una reparación _anti-entropy_ de las réplicas de este nodo,
+    /// para no bloquear la atención de otros mensajes mientras dura.
+    fn start_repair(
+        &self,
+        keyspace_name: Option<String>,
+        table_name: Option<String>,
+    ) -> Result<()> {
+        let session_handler = self.clone();
+        Builder::new()
+            .name(format!("repair_node_{}", self.id))
+            .spawn(move || {
+                let res = session_handler.repair(keyspace_name.as_deref(), table_name.as_deref());
+                if let (Err(err), Ok(logger)) = (res, session_handler.logger.read()) {
+                    let _ = logger.error(format!("Error al reparar las réplicas: {err}").as_str());
+                }
+            })
+            .map(|_| ())
+            .map_err(|e| Error::ServerError(format!("No se pudo iniciar la reparación: {e}")))
+    }
+
+    /// Repara las réplicas de este nodo de las tablas del keyspace y la tabla dados.
+    ///
+    /// Cada réplica se compara con la de cada uno de los otros nodos que la guardan mediante
+    /// [árboles de Merkle](MerkleTree), y sólo se intercambian las entradas de los rangos de
+    /// _tokens_ que difieren, que se combinan en ambos nodos quedándose con la versión más reciente.
+    fn repair(&self, keyspace_name: Option<&str>, table_name: Option<&str>) -> Result<()> {
+        if !self.read()?.is_responsive() {
+            return Err(Error::ServerError(format!(
+                "El nodo {} no está listo para reparar sus réplicas",
+                self.id
+            )));
+        }
+        let to_repair = self
+            .read()?
+            .get_replicas_to_repair(keyspace_name, table_name)?;
+        let mut repaired_ranges = 0;
+        for (table_name, owner, peers) in to_repair {
+            for peer in peers {
+                let res = if self.neighbour_is_responsive(peer)? {
+                    self.repair_replica_with(&table_name, owner, peer)
+                } else {
+                    Err(Error::ServerError(format!(
+                        "El nodo {peer} no está disponible"
+                    )))
+                };
+                let logger = self
+                    .logger
+                    .read()
+                    .map_err(|e| Error::ServerError(e.to_string()))?;
+                match res {
+                    Ok(ranges) => repaired_ranges += ranges,
+                    Err(err) => logger
+                        .warning(
+                            format!(
+                                "No se pudo reparar la réplica {owner} de la tabla {table_name} con el nodo {peer}: {err}"
+                            )
+                            .as_str(),
+                        )
+                        .map_err(|e| Error::ServerError(e.to_string()))?,
+                }
+            }
+        }
+        self.logger
+            .read()
+            .map_err(|e| Error::ServerError(e.to_string()))?
+            .info(
+                format!("Reparación terminada: se repararon {repaired_ranges} rangos de tokens")
+                    .as_str(),
+            )
+            .map_err(|e| Error::ServerError(e.to_string()))
+    }
+
+    /// Sincroniza la réplica `owner` de una tabla con la que guarda el nodo `peer`.
+    ///
+    /// Devuelve la cantidad de rangos de _tokens_ que diferían.
+    fn repair_replica_with(&self, table_name: &str, owner: NodeId, peer: NodeId) -> Result<usize> {
+        let local_entries = self.get_replica_entries(table_name, owner)?;
+        let peer_tree = send_to_node_and_wait_response_with_timeout(
+            peer,
+            SvAction::MerkleTreeRequest(table_name.to_string(), owner).as_bytes(),
+            PortType::Priv,
+            true,
+            Some(REPAIR_TIMEOUT_SECS),
+        )?;
+        let leaves = MerkleTree::build(&local_entries)
+            .differing_leaves(&MerkleTree::try_from(&peer_tree[..])?);
+        if leaves.is_empty() {
+            return Ok(0);
+        }
+
+        let peer_entries =
+            create_utf8_string_from_bytes(send_to_node_and_wait_response_with_timeout(
+                peer,
+                SvAction::RepairRangesRequest(table_name.to_string(), owner, leaves.clone())
+                    .as_bytes(),
+                PortType::Priv,
+                true,
+                Some(REPAIR_TIMEOUT_SECS),
+            )?)?;
+        {
+            let node_writer = self.write()?;
+            DiskHandler::repair_rows(
+                &node_writer.storage_addr,
+                node_writer.get_table(table_name)?,
+                &node_writer.default_keyspace_name,
+                owner,
+                &peer_entries,
+            )?;
+        }
+
+        let leaves: HashSet<usize> = leaves.into_iter().collect();
+        let local_lines = local_entries
+            .iter()
+            .filter(|entry| leaves.contains(&MerkleTree::leaf_of(entry.partition())))
+            .map(|entry| entry.to_line());
+        for batch in batch_lines(local_lines) {
+            send_to_node_and_wait_response_with_timeout(
+                peer,
+                SvAction::RepairRows(table_name.to_string(), owner, batch.into_bytes()).as_bytes(),
+                PortType::Priv,
+                false,
+                None,
+            )?;
+        }
+        Ok(leaves.len())
+    }
+
+    /// Obtiene las entradas de la réplica `owner` de una tabla guardada en este nodo.
+    fn get_replica_entries(&self, table_name: &str, owner: NodeId) -> Result<Vec<StorageEntry>> {
+        let node_reader = self.read()?;
+        DiskHandler::get_replica_entries(
+            &node_reader.storage_addr,
+            node_reader.get_table(table_name)?,
+            &node_reader.default_keyspace_name,
+            owner,
+        )
+    }
+
+    // ###########################################################################################
+    // ##################################### HINTED HANDOFF ######################################
+    // ###########################################################################################
+
+    /// Replica una mutación en todas las réplicas de una partición a la vez.
+    ///
+    /// La mutación se envía en paralelo a las réplicas remotas, mientras que en la local se aplica
+    /// con `apply_locally`. Se esperan las confirmaciones hasta cumplir con el _Consistency Level_
+    /// o hasta que se agote el tiempo de espera de escrituras. Las réplicas que respondan más tarde
+    /// lo hacen en segundo plano, y si no llegan a confirmar la mutación se les guarda un _hint_.
+    fn replicate_mutation<F>(
+        &self,
+        replicas_and_owner: (&[NodeId], NodeId),
+        request_and_timestamp: (&[Byte], Long),
+        apply_locally: F,
+        consistency_counter: &mut ConsistencyCounter,
+    ) -> Result<()>
+    where
+        F: FnOnce() -> Result<Vec<Byte>>,
+    {
+        let (replicas, node_id) = replicas_and_owner;
+        let (request, timestamp) = request_and_timestamp;
+        self.trace(
+            format!("Réplicas elegidas para las particiones del nodo {node_id}: {replicas:?}")
+                .as_str(),
+        )?;
+        let mutation = add_metadata_to_internal_request_of_any_kind(
+            SvAction::InternalQuery(request.to_vec()).as_bytes(),
+            Some(timestamp),
+            Some(node_id),
+        );
+        let session_handler = self.clone();
+        let mut responses = ReplicaResponses::spawn(
+            replicas
+                .iter()
+                .filter(|id| **id != self.id)
+                .copied()
+                .collect(),
+            Duration::from_millis(WRITE_REQUEST_TIMEOUT_MILLIS),
+            move |replica| {
+                session_handler.send_mutation_to_replica(*replica, mutation.clone(), true)
+            },
+        )?;
+
+        if replicas.contains(&self.id) {
+            self.trace("Aplicando la mutación en el disco local")?;
+            if verify_succesful_response(&apply_locally()?) {
+                consistency_counter.ack(self.id);
+            }
+        }
+        while !consistency_counter.is_reached() {
+            let Some((replica, response)) = responses.next() else {
+                break;
+            };
+            if verify_succesful_response(&response) {
+                consistency_counter.ack(replica);
+            }
+        }
+        if consistency_counter.is_reached() {
+            self.trace(
+                format!("Se alcanzó el nivel de consistencia con {consistency_counter}").as_str(),
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Envía una mutación a una réplica.
+    ///
+    /// Si la réplica no está disponible o no responde, se marca como caída y se guarda un
+    /// _hint_ con la mutación, para reenviársela cuando vuelva a estar disponible.
+    fn send_mutation_to_replica(
         &self,
-        receptor_id: NodeId,
-        receptor_gossip_info: GossipInfo,
-        mut response_nodes: NodesMap,
-    ) -> Result<()> {
-        // Poblamos un mapa con los estados que pide el receptor
-        let mut nodes_for_receptor = NodesMap::new();
-        let node_reader = self.read()?;
-        let neighbours_states = &node_reader.neighbours_states;
-        for (node_id, receptor_heartbeat) in &receptor_gossip_info {
-            let own_endpoint_state = &neighbours_states[node_id];
-            if own_endpoint_state.get_heartbeat() > receptor_heartbeat {
-                // Hacemos doble chequeo que efectivamente tenemos información más nueva
-                nodes_for_receptor.insert(*node_id, own_endpoint_state.clone());
-            }
-        }
-
-        for (node_id, response_node_state) in &mut response_nodes {
-            if let Some(own_endpoint_state) = neighbours_states.get(node_id) {
-                let own_heartbeat = own_endpoint_state.get_heartbeat();
-                let mut offline_heartbeat = own_endpoint_state.get_heartbeat().clone();
-                offline_heartbeat.beat();
-                if own_heartbeat > response_node_state.get_heartbeat()
-                    || (*own_endpoint_state.get_appstate_status() == AppStatus::Offline
-                        && &offline_heartbeat > response_node_state.get_heartbeat())
-                {
-                    // El nodo propio tiene un heartbeat más nuevo que el que se recibió
-                    // o
-                    // El nodo propio no está listo para recibir queries
-                    *response_node_state = own_endpoint_state.clone();
+        node_to_replicate: NodeId,
+        mutation: Vec<Byte>,
+        wait_response: bool,
+    ) -> Result<Vec<Byte>> {
+        if self.neighbour_is_responsive(node_to_replicate)? {
+            self.trace(format!("Enviando la mutación a la réplica {node_to_replicate}").as_str())?;
+            // Sólo se envuelve lo que se envía, para que el hint no quede asociado a la sesión
+            if let Ok(res) = send_to_node_and_wait_response_with_timeout(
+                node_to_replicate,
+                self.traced_action(mutation.clone()),
+                PortType::Priv,
+                wait_response,
+                Some(TIMEOUT_SECS),
+            ) {
+                if !res.is_empty() || !wait_response {
+                    self.trace(
+                        format!("Confirmación recibida de la réplica {node_to_replicate}").as_str(),
+                    )?;
+                    return Ok(res);
                 }
             }
         }
+        self.trace(
+            format!("La réplica {node_to_replicate} no respondió, se le guarda un hint").as_str(),
+        )?;
+        self.write()?
+            .acknowledge_offline_neighbour(node_to_replicate);
+        self.store_hint(node_to_replicate, &mutation)?;
+        Ok(Vec::new())
+    }
 
-        drop(node_reader);
-        // Reemplazamos la información de nuestros vecinos por la más nueva que viene del nodo receptor
-        // Asumimos que es más nueva ya que fue previamente verificada
-        self.write()?.update_neighbours(response_nodes)?;
+    /// Guarda un _hint_ con una mutación que no le llegó al nodo `target`.
+    fn store_hint(&self, target: NodeId, mutation: &[Byte]) -> Result<()> {
+        let storage_addr = self.read()?.storage_addr.clone();
+        let stored =
+            DiskHandler::store_hint(&storage_addr, target, Utc::now().timestamp(), mutation)?;
+        let logger = self
+            .logger
+            .read()
+            .map_err(|e| Error::ServerError(e.to_string()))?;
+        let message = if stored {
+            format!("Se guardó un hint para el nodo {target}")
+        } else {
+            format!("El nodo {target} superó la ventana máxima de hints, se descarta la mutación")
+        };
+        logger
+            .warning(message.as_str())
+            .map_err(|e| Error::ServerError(e.to_string()))
+    }
 
-        if let Err(err) = send_to_node(
-            receptor_id,
-            SvAction::Ack2(nodes_for_receptor).as_bytes(),
-            PortType::Priv,
-        ) {
-            let logger = self
-                .logger
-                .read()
-                .map_err(|e| Error::ServerError(e.to_string()))?;
-            if let Err(log_err) = logger.error(
-                format!(
-                    "Ocurrió un error al mandar un mensaje ACK2 al nodo [{receptor_id}]:\n\n{err}"
-                )
-                .as_str(),
-            ) {
-                println!("Error logging message: {log_err}");
-            }
+    /// Inicia en segundo plano el reenvío de los _hints_ y los BATCH pendientes, para no
+    /// demorar la ronda de _gossip_ mientras se espera a las réplicas.
+    ///
+    /// Si todavía sigue el reenvío de una ronda anterior, no se inicia otro.
+    fn start_replay(&self) -> Result<()> {
+        if self.replaying.swap(true, Ordering::AcqRel) {
+            return Ok(());
+        }
+        let session_handler = self.clone();
+        let spawned = Builder::new()
+            .name(format!("replay_node_{}", self.id))
+            .spawn(move || {
+                let hints_res = session_handler.replay_hints();
+                let batches_res = session_handler.replay_batch_logs();
+                session_handler.replaying.store(false, Ordering::Release);
+                if let Ok(logger) = session_handler.logger.read() {
+                    if let Err(err) = hints_res {
+                        let _ = logger.error(format!("Error al reenviar hints: {err}").as_str());
+                    }
+                    if let Err(err) = batches_res {
+                        let _ = logger.error(
+                            format!("Error al reenviar los BATCH pendientes: {err}").as_str(),
+                        );
+                    }
+                }
+            });
+        if let Err(e) = spawned {
+            self.replaying.store(false, Ordering::Release);
+            return Err(Error::ServerError(format!(
+                "No se pudo iniciar el reenvío de hints: {e}"
+            )));
         }
         Ok(())
     }
 
-    /// Se recibe un mensaje [ACK2](crate::actions::opcode::SvAction::Ack2).
-    pub fn ack2(&self, nodes_map: NodesMap) -> Result<()> {
-        self.write()?.update_neighbours(nodes_map)
+    /// Reenvía los _hints_ pendientes a los nodos que volvieron a estar disponibles.
+    ///
+    /// Si un nodo deja de responder a mitad del reenvío, sus _hints_ restantes quedan para
+    /// la próxima ronda de _gossip_.
+    fn replay_hints(&self) -> Result<()> {
+        let storage_addr = self.read()?.storage_addr.clone();
+        for target in DiskHandler::get_hinted_nodes(&storage_addr)? {
+            if !self.neighbour_is_responsive(target)? {
+                continue;
+            }
+            let hints =
+                DiskHandler::get_pending_hints(&storage_addr, target, Utc::now().timestamp())?;
+            let mut replayed = 0;
+            for (hint_id, mutation) in hints {
+                match send_to_node_and_wait_response_with_timeout(
+                    target,
+                    mutation,
+                    PortType::Priv,
+                    true,
+                    Some(TIMEOUT_SECS),
+                ) {
+                    // Si la réplica respondió, aunque sea con un error, el hint ya no sirve
+                    Ok(res) if !res.is_empty() => {
+                        DiskHandler::delete_hint(&storage_addr, target, &hint_id)?;
+                        replayed += 1;
+                    }
+                    _ => break,
+                }
+            }
+            if replayed > 0 {
+                self.logger
+                    .read()
+                    .map_err(|e| Error::ServerError(e.to_string()))?
+                    .info(format!("Se reenviaron {replayed} hints al nodo {target}").as_str())
+                    .map_err(|e| Error::ServerError(e.to_string()))?;
+            }
+        }
+        Ok(())
     }
 
     // ###########################################################################################
@@ -2454,18 +4222,67 @@ impl SessionHandler {
                 ))
             }
         };
-        let select = parse_select_from_query_body_as_bytes(&bytes)?;
+        let select = self.parse_select_from_query_body_as_bytes(&bytes)?;
+        self.trace(
+            format!("Leyendo las filas del nodo {node_number} para el read-repair").as_str(),
+        )?;
 
         // Queremos lockear cuando entra a una operacion de DiskHandler ya que no queremos inconsistencias
         let node_writer = self.write()?;
-        DiskHandler::get_rows_with_timestamp_as_string(
+        DiskHandler::get_entries_as_string(
             &node_writer.storage_addr,
+            node_writer.get_table(&select.from.get_name())?,
             &node_writer.get_default_keyspace_name()?,
             &select,
             node_number,
         )
     }
 
+    /// Parsea el SELECT de una lectura directa, vinculando sus marcadores con los valores del body.
+    fn parse_select_from_query_body_as_bytes(&self, bytes: &[Byte]) -> Result<Select> {
+        let statement = match QueryBody::try_from(bytes) {
+            Ok(query_body) => match self.parse_query_body(&query_body) {
+                Ok(statement) => statement,
+                Err(_err) => {
+                    return Err(Error::ServerError(
+                        "No se pudo parsear el statement, durante read-repair".to_string(),
+                    ))
+                }
+            },
+            Err(_err) => {
+                return Err(Error::ServerError(
+                    "No se pudo parsear el body de la query, durante read-repair".to_string(),
+                ))
+            }
+        };
+        match statement {
+            Statement::DmlStatement(DmlStatement::SelectStatement(select)) => Ok(select),
+            _ => Err(Error::ServerError(
+                "La declaración no es un SELECT, durante read-repair".to_string(),
+            )),
+        }
+    }
+
+    fn exec_merkle_tree_request(&self, table_name: &str, node_id: NodeId) -> Result<Vec<Byte>> {
+        let entries = self.get_replica_entries(table_name, node_id)?;
+        Ok(MerkleTree::build(&entries).as_bytes())
+    }
+
+    fn exec_repair_ranges_request(
+        &self,
+        table_name: &str,
+        node_id: NodeId,
+        leaves: &[usize],
+    ) -> Result<String> {
+        let entries = self.get_replica_entries(table_name, node_id)?;
+        Ok(entries
+            .iter()
+            .filter(|entry| leaves.contains(&MerkleTree::leaf_of(entry.partition())))
+            .map(|entry| entry.to_line())
+            .collect::<Vec<String>>()
+            .join("\n"))
+    }
+
     fn exec_digest_read_request(&self, bytes: Vec<Byte>) -> Vec<Byte> {
         let response = self.handle_request(&bytes, true, true);
         // Devolvemos además un opcode para poder saber si el resultado fue un error o no.
@@ -2514,12 +4331,16 @@ impl SessionHandler {
     // ###########################################################################################
 
     /// Consulta si un nodo vecino está listo para recibir _queries_.
+    ///
+    /// Además de su estado, se tiene en cuenta si el detector de fallas lo considera vivo, para
+    /// no esperar en vano la respuesta de un nodo caído que todavía no se marcó como tal.
     fn neighbour_is_responsive(&self, node_id: NodeId) -> Result<bool> {
         let mut is_ready = false;
         let node_reader = self.read()?;
         if let Some(endpoint_state) = node_reader.neighbours_states.get(&node_id) {
-            is_ready = *endpoint_state.get_appstate_status() == AppStatus::Normal
-                || *endpoint_state.get_appstate_status() == AppStatus::Ready;
+            is_ready = (*endpoint_state.get_appstate_status() == AppStatus::Normal
+                || *endpoint_state.get_appstate_status() == AppStatus::Ready)
+                && node_reader.is_alive(node_id);
         }
         Ok(is_ready)
     }
@@ -2574,56 +4395,6 @@ impl SessionHandler {
         results_from_another_nodes[5..9].copy_from_slice(&final_length.to_be_bytes());
         Ok(())
     }
-
-    fn get_most_recent_rows_as_string(
-        &self,
-        rows_of_nodes: Vec<Vec<Vec<String>>>,
-        table_name: &str,
-    ) -> Result<String> {
-        let node_reader = self.read()?;
-        let table = node_reader.get_table(table_name)?;
-        let primary_key_columns = table.get_position_of_primary_key()?;
-        drop(node_reader);
-        let mut merged_map: HashMap<Vec<String>, Vec<String>> = HashMap::new();
-        for rows in rows_of_nodes {
-            for row in rows {
-                // Asegúrate de que la fila tenga suficientes columnas
-                if primary_key_columns.iter().any(|&idx| idx >= row.len()) || row.is_empty() {
-                    continue;
-                }
-                // Crear la clave dinámica
-                let key: Vec<String> = primary_key_columns
-                    .iter()
-                    .map(|&idx| row[idx].clone())
-                    .collect();
-                let timestamp = &row[row.len() - 1]; // Última columna como timestamp
-
-                // Revisar si ya existe una entrada en el mapa
-                match merged_map.get(&key) {
-                    Some(existing_row) => {
-                        let existing_timestamp = &existing_row[existing_row.len() - 1];
-                        // Actualizar si el timestamp actual es más reciente
-                        if timestamp > existing_timestamp {
-                            merged_map.insert(key, row.clone());
-                        }
-                    }
-                    None => {
-                        // Insertar nueva entrada
-                        merged_map.insert(key, row.clone());
-                    }
-                }
-            }
-        }
-
-        // Convertir el mapa a un Vec<Vec<String>>
-        let newer_rows: Vec<Vec<String>> = merged_map.into_values().collect();
-        let rows_as_string = newer_rows
-            .iter()
-            .map(|row| row.join(","))
-            .collect::<Vec<String>>()
-            .join("\n");
-        Ok(rows_as_string)
-    }
 }
 
 fn sv_action_exit(stop: &mut bool, logger: &std::sync::RwLockReadGuard<'_, Logger>) -> Result<()> {
@@ -2640,6 +4411,10 @@ impl Clone for SessionHandler {
             id: self.id,
             logger: Arc::clone(&self.logger),
             lock: Arc::clone(&self.lock),
+            events_sender: self.events_sender.clone(),
+            trace: self.trace.clone(),
+            view_locks: Arc::clone(&self.view_locks),
+            replaying: Arc::clone(&self.replaying),
         }
     }
 }
@@ -2660,6 +4435,105 @@ pub fn make_error_response(err: Error) -> Vec<Byte> {
     response
 }
 
+/// Arma una respuesta de error con el mismo ID de _stream_ que la request que la causó, para
+/// que el cliente sepa a cuál de sus requests corresponde.
+pub fn make_error_response_for(request: &[Byte], err: Error) -> Vec<Byte> {
+    let mut response = make_error_response(err);
+    if let Some(stream) = request.get(2..4) {
+        response.splice(2..4, stream.to_vec());
+    }
+    response
+}
+
+/// Si la request es un STARTUP aceptado, actualiza el algoritmo de compresión de la conexión
+/// al que se pidió en éste, o a ninguno si no pidió compresión.
+pub fn negotiate_compression(
+    request: &[Byte],
+    response: &[Byte],
+    compression: &mut Option<Compression>,
+) {
+    if !is_startup(request) || response.get(4) == Opcode::RequestError.as_bytes().first() {
+        return;
+    }
+    let Some(body) = request.get(9..).filter(|body| body.len() >= 2) else {
+        return;
+    };
+    if let Ok(string_map) = parse_bytes_to_string_map(body) {
+        *compression = string_map
+            .iter()
+            .find(|(key, _)| key == "COMPRESSION")
+            .and_then(|(_, algorithm)| Compression::try_from(algorithm.as_str()).ok());
+    }
+}
+
+/// Verifica si una request es un STARTUP.
+fn is_startup(request: &[Byte]) -> bool {
+    request.get(4) == Opcode::Startup.as_bytes().first()
+}
+
+/// Comprime el body de una respuesta al cliente, si se negoció un algoritmo de compresión.
+pub fn compress_response(response: Vec<Byte>, compression: Option<Compression>) -> Vec<Byte> {
+    match compression {
+        Some(algorithm) => algorithm.compress_frame(&response).unwrap_or(response),
+        None => response,
+    }
+}
+
+/// Descomprime el body de una request del cliente, si llegó comprimido.
+fn decompress_request(request: &[Byte], compression: Option<Compression>) -> Result<Vec<Byte>> {
+    if !is_compressed(request) {
+        return Ok(request.to_vec());
+    }
+    match compression {
+        Some(algorithm) => algorithm.decompress_frame(request),
+        None => Err(Error::ProtocolError(
+            "Se recibió un mensaje comprimido sin haber negociado la compresión en el STARTUP"
+                .to_string(),
+        )),
+    }
+}
+
+/// Verifica si el cliente pidió _tracing_ para una request que lo admite.
+fn is_traceable(header: &Headers) -> bool {
+    header
+        .flags
+        .iter()
+        .any(|flag| matches!(flag, Flag::Tracing))
+        && matches!(
+            header.opcode,
+            Opcode::Query | Opcode::Execute | Opcode::Batch
+        )
+}
+
+/// Describe una request para su sesión de _tracing_, con la _query_ si es un QUERY.
+fn describe_request(request: &[Byte], header: &Headers) -> String {
+    let body_end = (header.length.len as usize + 9).min(request.len());
+    match header.opcode {
+        Opcode::Query => match QueryBody::try_from(&request[9..body_end]) {
+            Ok(query_body) => query_body.get_query().to_string(),
+            Err(_) => "QUERY".to_string(),
+        },
+        Opcode::Execute => "EXECUTE de una query preparada".to_string(),
+        _ => "BATCH".to_string(),
+    }
+}
+
+/// Agrega el ID de una sesión de _tracing_ al principio del body de una respuesta, marcándola
+/// con la flag [Tracing](Flag::Tracing).
+fn add_tracing_id(mut response: Vec<Byte>, trace_id: Uuid) -> Vec<Byte> {
+    if response.is_empty() {
+        response.append(&mut Node::create_result_void())
+    }
+    if response.len() < 9 {
+        return response;
+    }
+    response[1] |= Flag::Tracing.collapse();
+    response.splice(9..9, trace_id.to_be_bytes());
+    let len = Uint::from_be_bytes([response[5], response[6], response[7], response[8]]) + 16;
+    response.splice(5..9, len.to_be_bytes());
+    response
+}
+
 fn wrap_header(mut response: Vec<Byte>, is_internal_request: bool, header: Headers) -> Vec<Byte> {
     if response.is_empty() {
         response.append(&mut Node::create_result_void())
@@ -2673,30 +4547,93 @@ fn wrap_header(mut response: Vec<Byte>, is_internal_request: bool, header: Heade
     response
 }
 
-fn parse_select_from_query_body_as_bytes(bytes: &[Byte]) -> Result<Select> {
-    let statement = match QueryBody::try_from(bytes) {
-        Ok(query_body) => match make_parse(&mut tokenize_query(query_body.get_query())) {
-            Ok(statement) => statement,
-            Err(_err) => {
-                return Err(Error::ServerError(
-                    "No se pudo parsear el statement, durante read-repair".to_string(),
-                ))
-            }
-        },
-        Err(_err) => {
-            return Err(Error::ServerError(
-                "No se pudo parsear el body de la query, durante read-repair".to_string(),
-            ))
+/// Parsea una de las _queries_ de un BATCH, verificando que se pueda usar en un BATCH de ese tipo.
+pub fn parse_batch_statement(query: &str, batch_type: BatchType) -> Result<DmlStatement> {
+    match make_parse(&mut tokenize_query(query))? {
+        Statement::DmlStatement(dml_statement) => {
+            check_batch_statement(dml_statement, query, batch_type)
         }
-    };
-    match statement {
-        Statement::DmlStatement(DmlStatement::SelectStatement(select)) => Ok(select),
-        _ => Err(Error::ServerError(
-            "La declaración no es un SELECT, durante read-repair".to_string(),
+        Statement::DdlStatement(_) => Err(Error::Invalid(format!(
+            "Un BATCH sólo puede tener INSERT, UPDATE o DELETE, y se recibió '{query}'"
+        ))),
+        _ => Err(Error::Invalid(
+            "No se deberia haber mandado el login o startup por este canal".to_string(),
         )),
     }
 }
 
+/// Verifica que una de las _queries_ de un BATCH, ya parseada, se pueda usar en un BATCH de ese
+/// tipo.
+fn check_batch_statement(
+    dml_statement: DmlStatement,
+    query: &str,
+    batch_type: BatchType,
+) -> Result<DmlStatement> {
+    if let DmlStatement::SelectStatement(_) = dml_statement {
+        return Err(Error::Invalid(format!(
+            "Un BATCH sólo puede tener INSERT, UPDATE o DELETE, y se recibió '{query}'"
+        )));
+    }
+    if batch_type == BatchType::Counter {
+        let is_counter_update = match &dml_statement {
+            DmlStatement::UpdateStatement(update) => update
+                .set_parameter
+                .iter()
+                .all(|assignment| matches!(assignment, Assignment::ColumnNameColTerm(..))),
+            _ => false,
+        };
+        if !is_counter_update {
+            return Err(Error::Invalid(format!(
+                "Un BATCH de tipo COUNTER sólo puede tener UPDATE de contadores, y se recibió '{query}'"
+            )));
+        }
+    }
+    Ok(dml_statement)
+}
+
+/// Obtiene el nombre de la tabla sobre la que opera una declaración DML.
+pub fn get_dml_statement_table_name(dml_statement: &DmlStatement) -> String {
+    match dml_statement {
+        DmlStatement::SelectStatement(select) => select.from.get_name(),
+        DmlStatement::InsertStatement(insert) => insert.table.get_name(),
+        DmlStatement::UpdateStatement(update) => update.table_name.get_name(),
+        DmlStatement::DeleteStatement(delete) => delete.from.get_name(),
+    }
+}
+
+/// Parsea una secuencia de [Byte]s precedida por su longitud como un [Short](protocol::aliases::types::Short).
+fn parse_short_bytes(bytes: &[Byte], i: &mut usize) -> Result<Vec<Byte>> {
+    if bytes.len() < *i + 2 {
+        return Err(Error::ProtocolError(
+            "Se esperaban 2 bytes con la longitud de la secuencia".to_string(),
+        ));
+    }
+    let len = Short::from_be_bytes([bytes[*i], bytes[*i + 1]]) as usize;
+    *i += 2;
+    match bytes.get(*i..*i + len) {
+        Some(parsed) => {
+            *i += len;
+            Ok(parsed.to_vec())
+        }
+        None => Err(Error::ProtocolError(format!(
+            "Se esperaba una secuencia de {len} bytes"
+        ))),
+    }
+}
+
+/// Describe por qué falló una respuesta de otro nodo que no fue exitosa.
+fn describe_failed_response(response: &[Byte]) -> String {
+    match response.get(9..).filter(|body| body.len() >= 4) {
+        Some(body) if response.get(4) == Opcode::RequestError.as_bytes().first() => {
+            match Error::try_from(body.to_vec()) {
+                Ok(err) => err.to_string(),
+                Err(_) => "respondió un error que no se pudo leer".to_string(),
+            }
+        }
+        _ => "no respondió".to_string(),
+    }
+}
+
 fn verify_succesful_response(response: &[Byte]) -> bool {
     if response.len() < 9 {
         return false;
@@ -2750,15 +4687,32 @@ pub fn add_metadata_to_internal_request_of_any_kind(
     sv_action_with_request
 }
 
+/// Registra la confirmación de una réplica para todas las _queries_ de un grupo de un BATCH,
+/// si su respuesta fue exitosa.
+fn ack_batch_group(
+    consistency_counters: &mut HashMap<(usize, NodeId), ConsistencyCounter>,
+    replica: NodeId,
+    group: &[(usize, NodeId)],
+    response: &[Byte],
+) {
+    if !verify_succesful_response(response) {
+        return;
+    }
+    for key in group {
+        if let Some(counter) = consistency_counters.get_mut(key) {
+            counter.ack(replica);
+        }
+    }
+}
+
 fn check_if_read_repair_is_neccesary(
-    consistency_counter: &mut usize,
-    consistency_number: usize,
+    consistency_reached: bool,
     exec_read_repair: &mut bool,
     responses: Vec<Vec<Byte>>,
     first_hashed_value: Ulong,
     inconsistent_digest_request: bool,
 ) {
-    if *consistency_counter < consistency_number || inconsistent_digest_request {
+    if !consistency_reached || inconsistent_digest_request {
         *exec_read_repair = true
     };
     for hashed_value_vec in responses {
@@ -2785,12 +4739,50 @@ fn create_utf8_string_from_bytes(extern_response: Vec<Byte>) -> Result<String> {
     })
 }
 
-fn add_rows(res: String, rows_of_nodes: &mut Vec<Vec<Vec<String>>>) {
-    let rows: Vec<Vec<String>> = res
-        .split("\n")
-        .map(|row| row.split(",").map(|col| col.to_string()).collect())
-        .collect();
-    rows_of_nodes.push(rows);
+/// Agrupa líneas de entradas en tandas de a lo sumo [REPAIR_BATCH_BYTES] bytes, salvo que una
+/// sola línea ya los supere, para que cada tanda entre en un único mensaje entre nodos.
+fn batch_lines(lines: impl Iterator<Item = String>) -> Vec<String> {
+    let mut batches: Vec<String> = Vec::new();
+    for line in lines {
+        match batches.last_mut() {
+            Some(batch) if batch.len() + line.len() < REPAIR_BATCH_BYTES => {
+                batch.push('\n');
+                batch.push_str(&line);
+            }
+            _ => batches.push(line),
+        }
+    }
+    batches
+}
+
+/// Combina con las entradas ya recibidas las que devolvió una réplica para el _read repair_.
+fn add_entries(res: String, entries: &mut SortedEntries) -> Result<()> {
+    for line in res.split("\n").filter(|line| !line.trim().is_empty()) {
+        StorageEntry::from_line(line)?.merge_into(entries);
+    }
+    Ok(())
+}
+
+/// Junta las entradas de las réplicas que responden para el _read repair_.
+///
+/// Las réplicas cuya lectura falla se saltean, así una réplica caída no impide reparar al resto.
+/// Devuelve las entradas combinadas junto con las réplicas que respondieron.
+fn gather_replica_entries<F>(
+    replicas: &[NodeId],
+    mut direct_read: F,
+) -> Result<(SortedEntries, Vec<NodeId>)>
+where
+    F: FnMut(NodeId) -> Result<String>,
+{
+    let mut entries = SortedEntries::new();
+    let mut reachable = Vec::with_capacity(replicas.len());
+    for replica in replicas {
+        if let Ok(res) = direct_read(*replica) {
+            add_entries(res, &mut entries)?;
+            reachable.push(*replica);
+        }
+    }
+    Ok((entries, reachable))
 }
 
 fn get_partition_key_value_from_insert_statement(insert: &Insert, table: &Table) -> Result<String> {
@@ -2831,3 +4823,36 @@ pub fn get_partition_value_from_insert(insert: &Insert, table: &Table) -> Result
     };
     Ok(insert_column_values[position].to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_1_una_replica_caida_no_impide_reparar_al_resto() -> Result<()> {
+        let (entries, reachable) = gather_replica_entries(&[1, 2, 3], |replica| match replica {
+            1 => Ok("U,1,a,a,viejo,10".to_string()),
+            2 => Err(Error::ServerError("La réplica 2 no responde".to_string())),
+            _ => Ok("U,1,a,a,nuevo,20".to_string()),
+        })?;
+
+        assert_eq!(reachable, vec![1, 3]);
+        assert_eq!(entries.len(), 1);
+        let entry = &entries[&vec!["a".to_string()]];
+        assert_eq!(entry.row, vec!["a", "nuevo", "20"]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_sin_replicas_que_respondan_no_hay_nada_que_reparar() -> Result<()> {
+        let (entries, reachable) = gather_replica_entries(&[1, 2], |replica| {
+            Err(Error::ServerError(format!(
+                "La réplica {replica} no responde"
+            )))
+        })?;
+
+        assert!(entries.is_empty());
+        assert!(reachable.is_empty());
+        Ok(())
+    }
+}
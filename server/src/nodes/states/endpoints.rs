@@ -4,20 +4,27 @@ use {
     crate::{
         modes::ConnectionMode,
         nodes::{
-            addr::loader::AddrLoader,
+            addr::loader::{AddrLoader, DEFAULT_DATACENTER, DEFAULT_RACK},
             node::NodeId,
             port_type::PortType,
             states::{
                 application::AppState, appstatus::AppStatus, heartbeat::HeartbeatState,
                 heartbeat::VerType,
             },
+            token_ring::Token,
         },
     },
     protocol::{
-        aliases::{results::Result, types::Byte},
+        aliases::{
+            results::Result,
+            types::{Byte, Short},
+        },
         errors::error::Error,
         traits::Byteable,
-        utils::{encode_ipaddr_to_bytes, parse_bytes_to_ipaddr},
+        utils::{
+            encode_ipaddr_to_bytes, encode_string_to_bytes, parse_bytes_to_ipaddr,
+            parse_bytes_to_string,
+        },
     },
     std::{
         cmp::PartialEq,
@@ -36,6 +43,15 @@ pub struct EndpointState {
 
     /// Otra información relacionada al nodo.
     application: AppState,
+
+    /// El _datacenter_ al que pertenece el nodo.
+    datacenter: String,
+
+    /// El _rack_ del _datacenter_ en el que está el nodo.
+    rack: String,
+
+    /// Los _tokens_ que ocupa el nodo en el anillo.
+    tokens: Vec<Token>,
 }
 
 impl EndpointState {
@@ -48,21 +64,30 @@ impl EndpointState {
     }
 
     /// Instancia las propiedades del nodo.
+    ///
+    /// El nodo queda ubicado en [DEFAULT_DATACENTER] y [DEFAULT_RACK].
     pub fn new(ipaddr: IpAddr, heartbeat: HeartbeatState, application: AppState) -> Self {
         Self {
             ipaddr,
             heartbeat,
             application,
+            datacenter: DEFAULT_DATACENTER.to_string(),
+            rack: DEFAULT_RACK.to_string(),
+            tokens: Vec::new(),
         }
     }
 
     /// Crea una instancia dado un ID.
+    ///
+    /// La ubicación del nodo se toma del archivo de IPs.
     pub fn with_id(id: NodeId) -> Self {
+        let (datacenter, rack) = AddrLoader::default_runtime().get_location(id);
         Self::new(
             Self::generate_ipaddr(id),
             HeartbeatState::default(),
             AppState::default(),
         )
+        .with_location(&datacenter, &rack)
     }
 
     /// Crea una instancia dado un ID y modo de conexión.
@@ -73,6 +98,15 @@ impl EndpointState {
         }
     }
 
+    /// Devuelve una copia del estado, ubicada en el _datacenter_ y _rack_ dados.
+    pub fn with_location(self, datacenter: &str, rack: &str) -> Self {
+        Self {
+            datacenter: datacenter.to_string(),
+            rack: rack.to_string(),
+            ..self
+        }
+    }
+
     /// Compara si el _heartbeat_ de este estado es más nuevo que otro.
     pub fn is_newer(&self, other: &Self) -> bool {
         self.heartbeat > other.heartbeat
@@ -103,6 +137,26 @@ impl EndpointState {
         self.application.get_status()
     }
 
+    /// Consulta el _datacenter_ del nodo.
+    pub fn get_datacenter(&self) -> &str {
+        &self.datacenter
+    }
+
+    /// Consulta el _rack_ del nodo.
+    pub fn get_rack(&self) -> &str {
+        &self.rack
+    }
+
+    /// Consulta los _tokens_ que ocupa el nodo en el anillo.
+    pub fn get_tokens(&self) -> &[Token] {
+        &self.tokens
+    }
+
+    /// Establece los _tokens_ que ocupa el nodo en el anillo.
+    pub fn set_tokens(&mut self, tokens: Vec<Token>) {
+        self.tokens = tokens;
+    }
+
     /// Establece el estado de aplicación del _endpoint_.
     pub fn set_appstate_status(&mut self, appstatus: AppStatus) {
         self.application.set_status(appstatus);
@@ -135,6 +189,12 @@ impl Byteable for EndpointState {
 
         bytes.extend(self.heartbeat.as_bytes());
         bytes.extend(self.application.as_bytes());
+        bytes.extend(encode_string_to_bytes(&self.datacenter));
+        bytes.extend(encode_string_to_bytes(&self.rack));
+        bytes.extend((self.tokens.len() as Short).to_be_bytes());
+        for token in &self.tokens {
+            bytes.extend(token.to_be_bytes());
+        }
         bytes
     }
 }
@@ -151,7 +211,36 @@ impl TryFrom<&[Byte]> for EndpointState {
         i += heartbeat.as_bytes().len();
 
         let application = AppState::try_from(&bytes[i..])?;
-        Ok(Self::new(ipaddr, heartbeat, application))
+        i += application.as_bytes().len();
+
+        let datacenter = parse_bytes_to_string(&bytes[i..], &mut i)?;
+        let rack = parse_bytes_to_string(&bytes[i..], &mut i)?;
+
+        if bytes.len() < i + 2 {
+            return Err(Error::ServerError(
+                "Conjunto de bytes demasiado chico para los tokens del nodo.".to_string(),
+            ));
+        }
+        let tokens_len = Short::from_be_bytes([bytes[i], bytes[i + 1]]) as usize;
+        i += 2;
+        if bytes.len() < i + tokens_len * 8 {
+            return Err(Error::ServerError(
+                "Conjunto de bytes demasiado chico para los tokens del nodo.".to_string(),
+            ));
+        }
+        let tokens = bytes[i..i + tokens_len * 8]
+            .chunks_exact(8)
+            .map(|chunk| {
+                Token::from_be_bytes([
+                    chunk[0], chunk[1], chunk[2], chunk[3], chunk[4], chunk[5], chunk[6], chunk[7],
+                ])
+            })
+            .collect();
+
+        let mut endpoint_state =
+            Self::new(ipaddr, heartbeat, application).with_location(&datacenter, &rack);
+        endpoint_state.set_tokens(tokens);
+        Ok(endpoint_state)
     }
 }
 
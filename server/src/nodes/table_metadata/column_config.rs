@@ -5,7 +5,7 @@ use {
 };
 
 /// Representa la configuración de una columna.
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct ColumnConfig {
     /// Nombre de la columna.
     pub name: String,
@@ -16,6 +16,9 @@ pub enum ColumnDataType {
     Double,
     /// Tipo de dato `Int`.
     Int,
+    /// Tipo de dato `Counter`, un [Long](protocol::aliases::types::Long) que sólo se puede
+    /// incrementar o decrementar.
+    Counter,
 }
 
 impl From<ColType> for ColumnDataType {
@@ -25,6 +28,7 @@ impl From<ColType> for ColumnDataType {
             ColType::Timestamp => ColumnDataType::Timestamp,
             ColType::Double => ColumnDataType::Double,
             ColType::Int => ColumnDataType::Int,
+            ColType::Counter => ColumnDataType::Counter,
             _ => ColumnDataType::String,
         }
     }
@@ -43,6 +47,7 @@ impl From<&ColumnDataType> for ColType {
             ColumnDataType::Timestamp => Self::Timestamp,
             ColumnDataType::Double => Self::Double,
             ColumnDataType::Int => Self::Int,
+            ColumnDataType::Counter => Self::Counter,
         }
     }
 }
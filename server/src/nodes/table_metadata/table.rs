@@ -3,10 +3,28 @@
 use {
     crate::nodes::table_metadata::{column_config::ColumnConfig, column_data_type::ColumnDataType},
     parser::statements::dml_statement::main_statements::select::ordering::ProtocolOrdering,
-    protocol::{aliases::results::Result, errors::error::Error},
+    protocol::{
+        aliases::{results::Result, types::Long},
+        errors::error::Error,
+    },
     serde::{Deserialize, Serialize},
+    std::collections::HashMap,
 };
 
+/// Las opciones que se le pueden configurar a una tabla.
+const TABLE_OPTIONS: [&str; 6] = [
+    "comment",
+    "default_time_to_live",
+    "gc_grace_seconds",
+    "compaction",
+    "compression",
+    "caching",
+];
+/// Las opciones de una tabla cuyo valor debe ser una cantidad de segundos.
+const NUMERIC_TABLE_OPTIONS: [&str; 2] = ["default_time_to_live", "gc_grace_seconds"];
+/// Los segundos que se conservan los _tombstones_ si la tabla no configura `gc_grace_seconds` (10 días).
+pub const DEFAULT_GC_GRACE_SECONDS: Long = 864000;
+
 /// Representa una tabla en CQL.
 #[derive(Serialize, Deserialize)]
 pub struct Table {
@@ -20,6 +38,15 @@ pub struct Table {
     pub partition_key: Vec<String>,
     /// Clave de clustering de la tabla y orden de agrupamiento de las columnas.
     pub clustering_key_and_order: Option<Vec<(String, ProtocolOrdering)>>,
+    /// Opciones de la tabla, como `comment` o `default_time_to_live`.
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+    /// Índices secundarios de la tabla, con la columna que indexa cada uno según su nombre.
+    #[serde(default)]
+    pub indexes: HashMap<String, String>,
+    /// Si la tabla es una vista materializada, el nombre de la tabla base de la que se deriva.
+    #[serde(default)]
+    pub base_table: Option<String>,
 }
 
 impl Table {
@@ -37,6 +64,9 @@ impl Table {
             columns,
             partition_key,
             clustering_key_and_order,
+            options: HashMap::new(),
+            indexes: HashMap::new(),
+            base_table: None,
         }
     }
 
@@ -97,6 +127,16 @@ impl Table {
         Ok(res)
     }
 
+    /// Obtiene los nombres de las columnas de la _primary key_, empezando por la _partition key_
+    /// y en el mismo orden que [get_position_of_primary_key](Self::get_position_of_primary_key).
+    pub fn get_primary_key_columns(&self) -> Vec<String> {
+        let mut primary_key = vec![self.partition_key[0].clone()];
+        if let Some(clustering_columns) = &self.clustering_key_and_order {
+            primary_key.extend(clustering_columns.iter().map(|(column, _)| column.clone()));
+        }
+        primary_key
+    }
+
     /// Obtiene los tipos de datos de las columnas de la tabla.
     pub fn get_columns_data_type(&self) -> Vec<ColumnDataType> {
         self.columns
@@ -105,6 +145,52 @@ impl Table {
             .collect()
     }
 
+    /// Verifica si la columna dada forma parte de la _primary key_ de la tabla.
+    pub fn is_primary_key_column(&self, column_name: &str) -> bool {
+        let is_clustering_column = match &self.clustering_key_and_order {
+            Some(clustering_columns) => clustering_columns.iter().any(|(c, _)| c == column_name),
+            None => false,
+        };
+        self.partition_key.iter().any(|c| c == column_name) || is_clustering_column
+    }
+
+    /// Obtiene el valor de una opción de la tabla, si fue configurada.
+    pub fn get_option(&self, option_name: &str) -> Option<&String> {
+        self.options.get(option_name)
+    }
+
+    /// Obtiene los segundos que se conservan los _tombstones_ de la tabla antes de poder descartarlos.
+    pub fn get_gc_grace_seconds(&self) -> Long {
+        self.get_option("gc_grace_seconds")
+            .and_then(|value| value.parse::<Long>().ok())
+            .unwrap_or(DEFAULT_GC_GRACE_SECONDS)
+    }
+
+    /// Obtiene el TTL _(en segundos)_ con el que se escriben las filas que no indican uno.
+    ///
+    /// Si es 0, las filas no vencen.
+    pub fn get_default_time_to_live(&self) -> Long {
+        self.get_option("default_time_to_live")
+            .and_then(|value| value.parse::<Long>().ok())
+            .unwrap_or(0)
+    }
+
+    /// Configura una opción de la tabla, validando que exista y que su valor tenga sentido.
+    pub fn set_option(&mut self, option_name: &str, value: String) -> Result<()> {
+        if !TABLE_OPTIONS.contains(&option_name) {
+            return Err(Error::Invalid(format!(
+                "{option_name} no es una opción válida para una tabla"
+            )));
+        }
+        if NUMERIC_TABLE_OPTIONS.contains(&option_name) && value.parse::<u32>().is_err() {
+            return Err(Error::Invalid(format!(
+                "La opción {option_name} debe ser una cantidad de segundos no negativa"
+            )));
+        }
+        self.options.insert(option_name.to_string(), value);
+        Ok(())
+    }
+
     /// Obtiene los nombres y tipos de datos de las columnas de la tabla.
     pub fn get_columns_name_and_data_type(&self) -> Vec<(String, ColumnDataType)> {
         self.columns
@@ -112,4 +198,27 @@ impl Table {
             .map(|column| (column.get_name(), column.get_data_type()))
             .collect()
     }
+
+    /// Verifica si la tabla es una vista materializada.
+    pub fn is_view(&self) -> bool {
+        self.base_table.is_some()
+    }
+
+    /// Verifica si la tabla es una vista materializada de la tabla base dada.
+    pub fn is_view_of(&self, base_table: &str) -> bool {
+        self.base_table.as_deref() == Some(base_table)
+    }
+
+    /// Obtiene la columna que indexa el índice secundario dado, si existe en la tabla.
+    pub fn get_index_column(&self, index_name: &str) -> Option<&str> {
+        self.indexes.get(index_name).map(|column| column.as_str())
+    }
+
+    /// Obtiene el nombre del índice secundario sobre la columna dada, si hay uno.
+    pub fn get_index_on(&self, column_name: &str) -> Option<&str> {
+        self.indexes
+            .iter()
+            .find(|(_, column)| *column == column_name)
+            .map(|(index_name, _)| index_name.as_str())
+    }
 }
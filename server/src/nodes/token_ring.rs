@@ -0,0 +1,64 @@
+//! Módulo para el anillo de _tokens_ del clúster.
+
+use {
+    crate::nodes::{node::NodeId, utils::hash_value},
+    protocol::aliases::types::Ulong,
+    std::collections::BTreeMap,
+};
+
+/// Un _token_ del anillo. Es el resultado de hashear un valor.
+pub type Token = Ulong;
+
+/// La cantidad de nodos virtuales _(vnodes)_ que tiene un nodo si no se indica otra.
+pub const DEFAULT_VNODES: usize = 16;
+
+/// El anillo de _tokens_ del clúster, usado para decidir qué nodo es dueño de cada partición.
+///
+/// Cada nodo ocupa varias posiciones del anillo, una por cada uno de sus nodos virtuales.
+/// El dueño de un valor es el nodo del primer _token_ mayor o igual a su hash, dando la vuelta
+/// al anillo si hace falta. Así, cuando un nodo entra o sale del clúster, sólo cambian de dueño
+/// los rangos vecinos a sus _tokens_.
+#[derive(Default)]
+pub struct TokenRing {
+    /// Los _tokens_ del anillo, ordenados, con el nodo al que pertenecen.
+    tokens: BTreeMap<Token, NodeId>,
+}
+
+impl TokenRing {
+    /// Crea un anillo vacío.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Genera los _tokens_ de un nodo con una cantidad dada de nodos virtuales.
+    ///
+    /// Es determinístico, así que cualquier nodo puede calcular los _tokens_ de otro.
+    pub fn generate_tokens(node_id: NodeId, vnodes: usize) -> Vec<Token> {
+        (0..vnodes)
+            .map(|vnode| hash_value((node_id, vnode)))
+            .collect()
+    }
+
+    /// Agrega al anillo los _tokens_ de un nodo.
+    ///
+    /// Si un _token_ ya pertenece a otro nodo, lo conserva el que lo tenía.
+    pub fn add_node(&mut self, node_id: NodeId, tokens: &[Token]) {
+        for token in tokens {
+            self.tokens.entry(*token).or_insert(node_id);
+        }
+    }
+
+    /// Verifica si el anillo no tiene ningún _token_.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Devuelve el nodo dueño de un _token_, o `None` si el anillo está vacío.
+    pub fn owner_of(&self, token: Token) -> Option<NodeId> {
+        self.tokens
+            .range(token..)
+            .next()
+            .or_else(|| self.tokens.iter().next())
+            .map(|(_, node_id)| *node_id)
+    }
+}
@@ -0,0 +1,219 @@
+//! Módulo para el _tracing_ de las requests de los clientes.
+
+use {
+    crate::nodes::node::NodeId,
+    chrono::Utc,
+    protocol::{
+        aliases::{
+            results::Result,
+            types::{Long, Uuid},
+        },
+        errors::error::Error,
+        utils::encode_uuid_to_string,
+    },
+    rand::random,
+    std::{
+        sync::{Arc, Mutex},
+        thread,
+    },
+};
+
+/// El keyspace en el que se guardan las sesiones de _tracing_.
+pub const TRACES_KEYSPACE: &str = "system_traces";
+/// La tabla con una fila por cada sesión de _tracing_.
+pub const SESSIONS_TABLE: &str = "sessions";
+/// La tabla con una fila por cada evento de una sesión de _tracing_.
+pub const EVENTS_TABLE: &str = "events";
+/// El tiempo, en segundos, que se guardan las sesiones de _tracing_ antes de vencer (1 día).
+const TRACES_TTL_SECS: Long = 24 * 60 * 60;
+
+/// Las _queries_ que crean, si no existen, el keyspace [TRACES_KEYSPACE] y sus tablas.
+pub fn get_traces_schema_queries() -> Vec<String> {
+    vec![
+        format!(
+            "CREATE KEYSPACE IF NOT EXISTS {TRACES_KEYSPACE} WITH replication = {{'class': 'SimpleStrategy', 'replication_factor' : 2}}"
+        ),
+        format!(
+            "CREATE TABLE IF NOT EXISTS {TRACES_KEYSPACE}.{SESSIONS_TABLE} (session_id text, coordinator int, request text, started_at timestamp, duration int, PRIMARY KEY ((session_id)))"
+        ),
+        format!(
+            "CREATE TABLE IF NOT EXISTS {TRACES_KEYSPACE}.{EVENTS_TABLE} (session_id text, event_id timestamp, activity text, source int, source_elapsed int, thread text, PRIMARY KEY ((session_id), event_id, source))"
+        ),
+    ]
+}
+
+/// Transforma un texto a un literal de CQL, duplicando sus comillas.
+fn as_text_literal(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "''"))
+}
+
+/// Un evento registrado durante una sesión de _tracing_.
+#[derive(Clone, Debug)]
+pub struct TraceEvent {
+    /// El momento del evento _(en microsegundos)_. Identifica al evento dentro de su sesión.
+    pub event_id: Long,
+
+    /// Una descripción de lo que se hizo.
+    pub activity: String,
+
+    /// El nodo en el que ocurrió el evento.
+    pub source: NodeId,
+
+    /// El tiempo _(en microsegundos)_ transcurrido desde el inicio de la sesión.
+    pub source_elapsed: Long,
+
+    /// El nombre del hilo que registró el evento.
+    pub thread: String,
+}
+
+/// Una sesión de _tracing_, con los eventos registrados durante una request de un cliente.
+///
+/// Las copias de una sesión comparten los eventos, por lo que se pueden registrar eventos
+/// desde los hilos que se comunican con las réplicas.
+#[derive(Clone, Debug)]
+pub struct TraceSession {
+    /// El identificador de la sesión, que se le devuelve al cliente.
+    id: Uuid,
+
+    /// El nodo coordinador de la request.
+    coordinator: NodeId,
+
+    /// Una descripción de la request.
+    request: String,
+
+    /// El momento _(en microsegundos)_ en que empezó la sesión.
+    started_at: Long,
+
+    /// La duración _(en microsegundos)_ de la sesión, una vez terminada.
+    duration: Option<Long>,
+
+    /// Los eventos registrados hasta ahora.
+    events: Arc<Mutex<Vec<TraceEvent>>>,
+}
+
+impl TraceSession {
+    /// Crea una nueva sesión, con un identificador aleatorio (UUID versión 4).
+    pub fn new(coordinator: NodeId, request: String) -> Self {
+        let id = (random::<Uuid>() & !(0xF << 76) & !(0x3 << 62)) | (0x4 << 76) | (0x2 << 62);
+        Self {
+            id,
+            coordinator,
+            request,
+            started_at: Utc::now().timestamp_micros(),
+            duration: None,
+            events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Retoma en una réplica la sesión de _tracing_ dada, para registrar sus propios eventos.
+    ///
+    /// Los tiempos de los eventos se cuentan desde que la réplica recibió la request.
+    pub fn resume(id: Uuid, source: NodeId) -> Self {
+        Self {
+            id,
+            coordinator: source,
+            request: String::new(),
+            started_at: Utc::now().timestamp_micros(),
+            duration: None,
+            events: Arc::new(Mutex::new(Vec::new())),
+        }
+    }
+
+    /// Obtiene el identificador de la sesión.
+    pub fn get_id(&self) -> Uuid {
+        self.id
+    }
+
+    /// Registra un evento ocurrido en el nodo `source`.
+    pub fn record(&self, source: NodeId, activity: &str) -> Result<()> {
+        let now = Utc::now().timestamp_micros();
+        let mut events = self.events.lock().map_err(|e| {
+            Error::ServerError(format!("No se pudo registrar el evento de tracing: {e}"))
+        })?;
+        // Dos eventos no pueden compartir el ID dentro de una sesión
+        let event_id = match events.last() {
+            Some(last) if last.event_id >= now => last.event_id + 1,
+            _ => now,
+        };
+        events.push(TraceEvent {
+            event_id,
+            activity: activity.to_string(),
+            source,
+            source_elapsed: event_id - self.started_at,
+            thread: thread::current()
+                .name()
+                .unwrap_or("desconocido")
+                .to_string(),
+        });
+        Ok(())
+    }
+
+    /// Termina la sesión, registrando su duración.
+    pub fn finish(mut self) -> Self {
+        self.duration = Some(Utc::now().timestamp_micros() - self.started_at);
+        self
+    }
+
+    /// Obtiene los eventos registrados hasta ahora.
+    pub fn get_events(&self) -> Result<Vec<TraceEvent>> {
+        let events = self.events.lock().map_err(|e| {
+            Error::ServerError(format!("No se pudieron leer los eventos de tracing: {e}"))
+        })?;
+        Ok(events.clone())
+    }
+
+    /// Arma los INSERT que guardan los eventos registrados en la tabla [EVENTS_TABLE] y, si la
+    /// sesión ya terminó, su fila en la tabla [SESSIONS_TABLE]. Todas las filas vencen después
+    /// de un día.
+    pub fn get_insert_queries(&self) -> Result<Vec<String>> {
+        let session_id = as_text_literal(&encode_uuid_to_string(self.id));
+        let mut queries = Vec::new();
+        if let Some(duration) = self.duration {
+            queries.push(format!(
+                "INSERT INTO {TRACES_KEYSPACE}.{SESSIONS_TABLE} (session_id, coordinator, request, started_at, duration) VALUES ({session_id}, {}, {}, {}, {duration}) USING TTL {TRACES_TTL_SECS}",
+                self.coordinator,
+                as_text_literal(&self.request),
+                self.started_at,
+            ));
+        }
+        for event in self.get_events()? {
+            queries.push(format!(
+                "INSERT INTO {TRACES_KEYSPACE}.{EVENTS_TABLE} (session_id, event_id, activity, source, source_elapsed, thread) VALUES ({session_id}, {}, {}, {}, {}, {}) USING TTL {TRACES_TTL_SECS}",
+                event.event_id,
+                as_text_literal(&event.activity),
+                event.source,
+                event.source_elapsed,
+                as_text_literal(&event.thread),
+            ));
+        }
+        Ok(queries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use {parser::main_parser::make_parse, tokenizer::tok::tokenize_query};
+
+    #[test]
+    fn test_1_las_queries_del_esquema_se_pueden_parsear() -> Result<()> {
+        for query in get_traces_schema_queries() {
+            make_parse(&mut tokenize_query(&query))?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_2_los_insert_de_una_sesion_terminada_se_pueden_parsear() -> Result<()> {
+        let session = TraceSession::new(1, "SELECT * FROM t WHERE a = 'x'".to_string());
+        session.record(1, "Parseando la query")?;
+        session.record(2, "Leyendo filas")?;
+        let queries = session.finish().get_insert_queries()?;
+
+        assert_eq!(queries.len(), 3);
+        for query in queries {
+            make_parse(&mut tokenize_query(&query))?;
+        }
+        Ok(())
+    }
+}
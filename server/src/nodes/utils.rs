@@ -1,7 +1,9 @@
 //! Módulo para funciones auxiliares relacionadas a nodos.
 
 use {
-    crate::nodes::{addr::loader::AddrLoader, node::NodeId, port_type::PortType},
+    crate::nodes::{
+        actions::opcode::SvAction, addr::loader::AddrLoader, node::NodeId, port_type::PortType,
+    },
     protocol::{
         aliases::{
             results::Result,
@@ -26,21 +28,6 @@ pub fn hash_value<T: Hash>(value: T) -> Ulong {
     hasher.finish()
 }
 
-/// Divide un rango en `n` partes iguales.
-pub fn divide_range(start: Ulong, end: Ulong, n: usize) -> Vec<(Ulong, Ulong)> {
-    let range_length = end - start;
-    let part_length = range_length / n as Ulong;
-    let remainder = range_length % n as Ulong;
-
-    (0..n)
-        .map(|i| {
-            let part_start = start + i as Ulong * part_length + remainder.min(i as Ulong);
-            let part_end = part_start + part_length + if i < remainder as usize { 1 } else { 0 };
-            (part_start, part_end)
-        })
-        .collect()
-}
-
 /// Devuelve el ID del siguiente nodo del cluster.
 ///
 /// Se asume que el vector de IDs de los nodos está ordenado de menor a mayor.
@@ -56,37 +43,6 @@ pub fn next_node_in_the_cluster(current_id: Byte, nodes_ids: &[Byte]) -> Byte {
     }
 }
 
-/// Devuelve el ID del nodo `n`-ésimo en el cluster, tomando como punto de partida `current_id`.
-/// Si `reverse` es `true`, se devuelve el `n`-ésimo nodo en sentido contrario.
-///
-/// Se asume que el vector de IDs de los nodos está ordenado de menor a mayor.
-pub fn n_th_node_in_the_cluster(
-    current_id: Byte,
-    nodes_ids: &[Byte],
-    n: usize,
-    reverse: bool,
-) -> Byte {
-    let current_index = match nodes_ids.binary_search(&current_id) {
-        Ok(index) => index as i8,
-        Err(_) => return nodes_ids[0], // si no se encuentra, se asume que es el primer nodo
-    };
-
-    let mut new_index: i8 = if reverse {
-        current_index - n as i8
-    } else {
-        current_index + n as i8
-    };
-
-    let nodes_ids_len = nodes_ids.len() as i8;
-    if new_index >= nodes_ids_len {
-        new_index -= nodes_ids_len;
-    }
-    if new_index < 0 {
-        new_index += nodes_ids_len;
-    }
-    nodes_ids[new_index as usize]
-}
-
 /// Manda un mensaje a un nodo específico.
 pub fn send_to_node(id: NodeId, bytes: Vec<Byte>, port_type: PortType) -> Result<()> {
     let loader = match &port_type {
@@ -94,6 +50,7 @@ pub fn send_to_node(id: NodeId, bytes: Vec<Byte>, port_type: PortType) -> Result
         PortType::Priv => AddrLoader::default_runtime(),
     };
     let addr = loader.get_socket(&id, &port_type)?;
+    let bytes = compress_for_port(bytes, &port_type);
     let mut stream = match TcpStream::connect(addr) {
         Ok(tcpstream) => tcpstream,
         Err(err) => {
@@ -122,6 +79,7 @@ pub fn send_to_node_and_wait_response_with_timeout(
     timeout: Option<Ulong>,
 ) -> Result<Vec<Byte>> {
     let addr = AddrLoader::default_runtime().get_socket(&id, &port_type)?;
+    let bytes = compress_for_port(bytes, &port_type);
     let mut stream = match TcpStream::connect(addr) {
         Ok(tcpstream) => tcpstream,
         Err(_) => {
@@ -161,3 +119,13 @@ pub fn send_to_node_and_wait_response_with_timeout(
 
     Ok(buf)
 }
+
+/// Comprime las acciones grandes que se mandan al puerto privado de otro nodo.
+///
+/// Los mensajes al puerto de clientes se mandan tal cual.
+fn compress_for_port(bytes: Vec<Byte>, port_type: &PortType) -> Vec<Byte> {
+    match port_type {
+        PortType::Priv => SvAction::compress_if_large(bytes),
+        PortType::Cli => bytes,
+    }
+}
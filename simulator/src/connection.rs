@@ -1,6 +1,13 @@
 use client::cli::{get_client_connection, Client, TlsStream};
 use data::login_info::LoginInfo;
 use protocol::{aliases::results::Result, errors::error::Error};
+use std::sync::{Arc, Mutex};
+
+/// Una conexión con el servidor compartida entre todos los vuelos simulados.
+///
+/// Cada vuelo manda sus requests sin esperar la respuesta, así que el lock sólo se toma para
+/// escribir una request o para revisar si ya llegó su respuesta.
+pub type SharedConnection = Arc<Mutex<(Client, TlsStream)>>;
 
 /// Establece la conexión con el servidor y el cliente.
 pub fn set_client_and_connection(has_to_connect: bool) -> Result<(Client, Option<TlsStream>)> {
@@ -11,6 +18,52 @@ pub fn set_client_and_connection(has_to_connect: bool) -> Result<(Client, Option
     Ok((client, tls_stream))
 }
 
+/// Establece una conexión con el servidor para compartir entre los hilos del simulador.
+pub fn set_shared_connection(has_to_connect: bool) -> Result<Option<SharedConnection>> {
+    let (client, tls_stream) = set_client_and_connection(has_to_connect)?;
+    Ok(tls_stream.map(|tls_stream| Arc::new(Mutex::new((client, tls_stream)))))
+}
+
+/// Reemplaza la conexión compartida por una nueva.
+///
+/// Las requests que estaban en curso en la conexión anterior se pierden.
+pub fn reconnect(connection: &SharedConnection) -> Result<()> {
+    let (new_client, new_tls_stream) = set_client_and_connection(true)?;
+    let new_tls_stream = match new_tls_stream {
+        Some(tls_stream) => tls_stream,
+        None => {
+            return Err(Error::ServerError(
+                "No se pudo crear el stream TLS".to_string(),
+            ))
+        }
+    };
+    with_connection(connection, |client, tls_stream| {
+        *client = new_client;
+        *tls_stream = new_tls_stream;
+        Ok(())
+    })
+}
+
+/// Ejecuta una acción con el cliente y el _stream_ de la conexión compartida, tomando el lock
+/// sólo mientras dure la misma.
+pub fn with_connection<T, F>(connection: &SharedConnection, action: F) -> Result<T>
+where
+    F: FnOnce(&mut Client, &mut TlsStream) -> Result<T>,
+{
+    match connection.lock() {
+        Ok(mut locked_in) => {
+            let (client, tls_stream) = &mut *locked_in;
+            action(client, tls_stream)
+        }
+        Err(poison_err) => {
+            connection.clear_poison();
+            Err(Error::ServerError(format!(
+                "Error de lock envenenado:\n\n{poison_err}"
+            )))
+        }
+    }
+}
+
 fn create_connection(client: &mut Client, has_to_connect: bool) -> Result<Option<TlsStream>> {
     if has_to_connect {
         let client_connection = get_client_connection()?;
@@ -1,4 +1,8 @@
-use crate::{initializer::initialize_flight, updater::simulate_flight};
+use crate::{
+    connection::{set_shared_connection, SharedConnection},
+    initializer::initialize_flight,
+    updater::simulate_flight,
+};
 use data::{
     airports::airp::{Airport, AirportsMap},
     flights::states::FlightState,
@@ -27,12 +31,14 @@ pub struct FlightSimulator {
     pub airports: Arc<AirportsMap>,
     flights: Arc<RwLock<HashMap<Int, LiveFlightData>>>,
     thread_pool: ThreadPool,
-    has_to_connect: bool,
+    connection: Option<SharedConnection>,
 }
 
 impl FlightSimulator {
     /// Crea un nuevo simulador de vuelos con un número máximo de hilos y un cliente.
     ///
+    /// Si se tiene que conectar, todos los vuelos comparten una misma conexión con el servidor.
+    ///
     /// # Ejemplo
     ///
     /// ```
@@ -47,7 +53,7 @@ impl FlightSimulator {
             flights: Arc::new(RwLock::new(HashMap::new())),
             thread_pool: ThreadPool::build(max_threads)?,
             airports: Arc::new(airports),
-            has_to_connect,
+            connection: set_shared_connection(has_to_connect)?,
         })
     }
 
@@ -152,17 +158,11 @@ impl FlightSimulator {
         let (flight, dest_coords, dest_elevation) =
             initialize_flight(self, flight_id, &origin, &destination, avg_spd)?;
 
-        let has_to_connect = self.has_to_connect;
+        let connection = self.connection.clone();
         let flight_map_ref = Arc::downgrade(&self.flights);
         self.thread_pool.execute(move || {
             if let Some(flights) = flight_map_ref.upgrade() {
-                simulate_flight(
-                    &flights,
-                    flight,
-                    dest_coords,
-                    dest_elevation,
-                    has_to_connect,
-                );
+                simulate_flight(&flights, flight, dest_coords, dest_elevation, connection);
             }
             Ok(())
         })
@@ -1,10 +1,10 @@
 use crate::{
+    connection::SharedConnection,
     flight_simulator::{FlightSimulator, FLIGHT_LIMIT_SECS},
     sender::send_flight_update,
     updater::update_flight_in_list,
     utils::{get_current_timestamp, FlightCalculations},
 };
-use client::cli::{Client, TlsStream};
 use data::{
     airports::airp::Airport,
     flights::{states::FlightState, types::FlightType},
@@ -65,14 +65,13 @@ pub fn initialize_flight(
 pub fn prepare_flight(
     flights: &Arc<RwLock<HashMap<Int, LiveFlightData>>>,
     flight: &mut LiveFlightData,
-    client: &mut Client,
-    tls_stream: &mut Option<TlsStream>,
+    connection: &Option<SharedConnection>,
 ) -> Result<()> {
     flight.state = FlightState::Preparing;
 
     update_flight_in_list(flights, flight);
-    if tls_stream.is_some() {
-        let _ = send_flight_update(flight, client, flight.fuel, 0.0, tls_stream);
+    if let Some(connection) = connection {
+        let _ = send_flight_update(flight, flight.fuel, 0.0, connection);
     }
     Ok(())
 }
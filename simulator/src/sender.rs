@@ -1,63 +1,74 @@
-use crate::{connection::set_client_and_connection, utils::get_current_timestamp};
+use crate::{
+    connection::{reconnect, with_connection, SharedConnection},
+    utils::get_current_timestamp,
+};
 use client::cli::{Client, TlsStream};
-use data::tracking::live_flight_data::LiveFlightData;
+use data::{protocol_result::ProtocolResult, tracking::live_flight_data::LiveFlightData};
 use protocol::{
-    aliases::{results::Result, types::Double},
+    aliases::{
+        results::Result,
+        types::{Double, ShortInt, Ulong},
+    },
     errors::error::Error,
 };
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Cuánto tiempo _(en milisegundos)_ se espera la respuesta a una actualización de vuelo.
+const RESPONSE_TIMEOUT_MILLIS: Ulong = 5000;
+/// Cada cuánto tiempo _(en milisegundos)_ se revisa si llegó la respuesta a una actualización.
+const RESPONSE_POLL_MILLIS: Ulong = 20;
 
 /// Arma la query y envía la actualización de vuelo a la base de datos.
 pub fn send_flight_update(
     flight: &LiveFlightData,
-    client: &mut Client,
     fuel: Double,
     elapsed: Double,
-    tls_stream: &mut Option<TlsStream>,
+    connection: &SharedConnection,
 ) -> Result<()> {
     let timestamp = get_current_timestamp()?;
 
-    let incoming_query = format!(
-        "INSERT INTO vuelos_entrantes_en_vivo (id, orig, dest, llegada, pos_lat, pos_lon, estado, velocidad, altitud, nivel_combustible, duracion) VALUES ({}, '{}', '{}', {}, {}, {}, '{}', {}, {}, {:.2}, {:.2});",
-        flight.flight_id, flight.orig, flight.dest, timestamp, flight.lat(), flight.lon(), flight.state, flight.get_spd(), flight.altitude_ft, fuel, elapsed);
-
-    let departing_query = format!(
+    let query = format!(
         "INSERT INTO vuelos_salientes_en_vivo (id, orig, dest, salida, pos_lat, pos_lon, estado, velocidad, altitud, nivel_combustible, duracion) VALUES ({}, '{}', '{}', {}, {}, {}, '{}', {}, {}, {:.2}, {:.2});",
         flight.flight_id, flight.orig, flight.dest, timestamp, flight.lat(), flight.lon(), flight.state, flight.get_spd(), flight.altitude_ft, fuel, elapsed);
 
-    send_insert_query(&incoming_query, client, tls_stream)?;
-    send_insert_query(&departing_query, client, tls_stream)?;
-
-    Ok(())
+    // La vista de vuelos entrantes se actualiza sola a partir de la tabla de salientes
+    send_insert(&query, connection)
 }
 
-fn send_insert_query(
-    query: &str,
-    client: &mut Client,
-    tls_stream: &mut Option<TlsStream>,
-) -> Result<()> {
-    if let Some(tls_stream) = tls_stream {
-        match client.send_query(query, tls_stream) {
-            Ok(_) => (),
-            Err(_) => {
-                let (new_client, new_tls_stream) = match set_client_and_connection(true) {
-                    Ok((new_client, new_tls_stream)) => (new_client, new_tls_stream),
-                    Err(reconnect_err) => {
-                        eprintln!("Error en la reconexión del cliente: {reconnect_err}");
-                        return Err(reconnect_err);
-                    }
-                };
-                *client = new_client;
-                *tls_stream = match new_tls_stream {
-                    Some(tls_stream) => tls_stream,
-                    None => {
-                        return Err(Error::ServerError(
-                            "No se pudo crear el stream TLS".to_string(),
-                        ))
-                    }
-                };
-                client.send_query(query, tls_stream)?;
+fn send_insert(query: &str, connection: &SharedConnection) -> Result<()> {
+    let submit =
+        |client: &mut Client, tls_stream: &mut TlsStream| client.submit_query(query, tls_stream);
+    let stream_id = match with_connection(connection, submit) {
+        Ok(stream_id) => stream_id,
+        Err(_) => {
+            if let Err(reconnect_err) = reconnect(connection) {
+                eprintln!("Error en la reconexión del cliente: {reconnect_err}");
+                return Err(reconnect_err);
             }
+            with_connection(connection, submit)?
+        }
+    };
+    wait_for_response(stream_id, connection)
+}
+
+/// Espera la respuesta a la request con el ID de stream dado, soltando la conexión entre cada
+/// revisión para que los otros vuelos puedan usarla.
+fn wait_for_response(stream_id: ShortInt, connection: &SharedConnection) -> Result<()> {
+    let deadline = Instant::now() + Duration::from_millis(RESPONSE_TIMEOUT_MILLIS);
+    while Instant::now() < deadline {
+        let response = with_connection(connection, |client, tls_stream| {
+            client.try_take_response(stream_id, tls_stream)
+        })?;
+        match response {
+            Some(ProtocolResult::QueryError(err)) => return Err(err),
+            Some(_) => return Ok(()),
+            None => thread::sleep(Duration::from_millis(RESPONSE_POLL_MILLIS)),
         }
     }
-    Ok(())
+    Err(Error::ServerError(
+        "Timeout al esperar la respuesta de la actualización de vuelo".to_string(),
+    ))
 }
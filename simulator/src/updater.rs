@@ -1,11 +1,10 @@
 use crate::{
-    connection::set_client_and_connection,
+    connection::SharedConnection,
     flight_simulator::FLIGHT_LIMIT_SECS,
     initializer::{initialize_flight_parameters, prepare_flight},
     sender::send_flight_update,
     utils::FlightCalculations,
 };
-use client::cli::{Client, TlsStream};
 use data::{flights::states::FlightState, tracking::live_flight_data::LiveFlightData};
 use protocol::aliases::{
     results::Result,
@@ -34,18 +33,10 @@ pub fn simulate_flight(
     mut flight: LiveFlightData,
     dest_coords: (Double, Double),
     dest_elevation: Double,
-    has_to_connect: bool,
+    connection: Option<SharedConnection>,
 ) {
-    let (mut client, mut tls_stream) = match set_client_and_connection(has_to_connect) {
-        Ok((client, tls_stream)) => (client, tls_stream),
-        Err(err) => {
-            eprintln!("Error en la conexión del cliente: {err}");
-            return;
-        }
-    };
-
     let mut rng = thread_rng();
-    let _ = prepare_flight(flights, &mut flight, &mut client, &mut tls_stream);
+    let _ = prepare_flight(flights, &mut flight, &connection);
 
     let (total_distance, fuel_consumption_rate) =
         initialize_flight_parameters(&flight, dest_coords);
@@ -56,7 +47,7 @@ pub fn simulate_flight(
     update_flight_in_list(flights, &mut flight);
 
     let simulation_start = Instant::now();
-    let simulation_limit = if tls_stream.is_some() {
+    let simulation_limit = if connection.is_some() {
         Duration::from_secs(
             ((total_distance * (FLIGHT_LIMIT_SECS as Double)) / flight.get_spd()) as Ulong,
         )
@@ -73,21 +64,13 @@ pub fn simulate_flight(
         fuel_consumption_rate,
     };
 
-    let _ = run_flight_simulation(
-        flights,
-        &mut flight,
-        &mut client,
-        &params,
-        &mut rng,
-        &mut tls_stream,
-    );
+    let _ = run_flight_simulation(flights, &mut flight, &params, &mut rng, &connection);
     let _ = finish_flight(
         flights,
         &mut flight,
         &params,
-        &mut client,
         params.simulation_start.elapsed().as_secs_f64(),
-        &mut tls_stream,
+        &connection,
     );
 }
 
@@ -114,10 +97,9 @@ pub fn update_flight_in_list(
 fn run_flight_simulation(
     flights: &Arc<RwLock<HashMap<Int, LiveFlightData>>>,
     flight: &mut LiveFlightData,
-    client: &mut Client,
     params: &FlightSimulationParams,
     rng: &mut ThreadRng,
-    tls_stream: &mut Option<TlsStream>,
+    connection: &Option<SharedConnection>,
 ) -> Result<()> {
     while params.simulation_start.elapsed().as_secs_f64() < params.simulation_limit.as_secs_f64() {
         let progress =
@@ -126,13 +108,12 @@ fn run_flight_simulation(
 
         flight.fuel = (flight.fuel - params.fuel_consumption_rate).max(0.0);
         update_flight_in_list(flights, flight);
-        if tls_stream.is_some() {
+        if let Some(connection) = connection {
             let _ = send_flight_update(
                 flight,
-                client,
                 flight.fuel,
                 params.simulation_start.elapsed().as_secs_f64(),
-                tls_stream,
+                connection,
             );
         }
 
@@ -173,9 +154,8 @@ fn finish_flight(
     flights: &Arc<RwLock<HashMap<Int, LiveFlightData>>>,
     flight: &mut LiveFlightData,
     params: &FlightSimulationParams,
-    client: &mut Client,
     elapsed: Double,
-    tls_stream: &mut Option<TlsStream>,
+    connection: &Option<SharedConnection>,
 ) -> Result<()> {
     flight.state = FlightState::Finished;
     flight.pos = params.dest_coords;
@@ -184,8 +164,8 @@ fn finish_flight(
 
     update_flight_in_list(flights, flight);
 
-    if tls_stream.is_some() {
-        let _ = send_flight_update(flight, client, flight.fuel, elapsed, tls_stream);
+    if let Some(connection) = connection {
+        let _ = send_flight_update(flight, flight.fuel, elapsed, connection);
     }
     Ok(())
 }
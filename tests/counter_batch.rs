@@ -0,0 +1,78 @@
+//! Módulo para BATCH de tipo COUNTER.
+
+mod common;
+
+use {
+    client::{cli::Client, conn_holder::ConnectionHolder},
+    common::{clean_nodes, create_parsing_nodes},
+    data::{col_data::ColData, login_info::LoginInfo, protocol_result::ProtocolResult},
+    protocol::messages::requests::batch_types::BatchType,
+    std::{thread::sleep, time::Duration},
+};
+
+#[test]
+fn test_counter_batches_increment() {
+    assert!(clean_nodes().is_ok());
+    let _ = create_parsing_nodes(5, Duration::from_secs(1));
+
+    sleep(Duration::from_secs(1));
+    let conn_res = ConnectionHolder::with_cli(Client::default(), "QUORUM");
+    sleep(Duration::from_secs(1));
+
+    assert!(conn_res.is_ok());
+
+    // le damos tiempo para procesar
+    sleep(Duration::from_secs(2));
+
+    if let Ok(mut conn) = conn_res {
+        let client_lock = conn.get_cli();
+        let login_res = conn.login(&LoginInfo::new_str("juan", "1234"));
+        sleep(Duration::from_secs(1));
+
+        assert!(login_res.is_ok());
+
+        if let Ok(mut client) = client_lock.lock() {
+            for query in [
+                "CREATE KEYSPACE IF NOT EXISTS aerolinea_rustica WITH replication = {'class': 'SimpleStrategy', 'replication_factor' : 3};",
+                "USE aerolinea_rustica;",
+                "CREATE TABLE IF NOT EXISTS pasajeros (id int, total counter, nombre text, PRIMARY KEY ((id)));",
+                "INSERT INTO pasajeros (id) VALUES (1);",
+            ] {
+                let res = client.send_query(query, &mut conn.tls_stream);
+                sleep(Duration::from_secs(1));
+                assert!(res.is_ok());
+            }
+
+            let increment = "UPDATE pasajeros SET total = total + 1 WHERE id = 1;";
+            for _ in 0..2 {
+                let batch_res =
+                    client.send_batch(BatchType::Counter, &[increment], &mut conn.tls_stream);
+                sleep(Duration::from_secs(1));
+                assert!(batch_res.is_ok());
+            }
+
+            // Una columna que no es contador no se puede incrementar
+            let not_counter = "UPDATE pasajeros SET nombre = nombre + 1 WHERE id = 1;";
+            let batch_res =
+                client.send_batch(BatchType::Counter, &[not_counter], &mut conn.tls_stream);
+            sleep(Duration::from_secs(1));
+            assert!(batch_res.is_err());
+
+            let select_query = "SELECT total FROM pasajeros WHERE id = 1;";
+            let select_res = client.send_query(select_query, &mut conn.tls_stream);
+            sleep(Duration::from_secs(1));
+            assert!(select_res.is_ok());
+
+            if let Ok((protocol_res, _)) = select_res {
+                assert!(matches!(&protocol_res, ProtocolResult::Rows(_)));
+                if let ProtocolResult::Rows(rows) = protocol_res {
+                    assert_eq!(rows.len(), 1);
+                    assert!(matches!(rows[0].first(), Some(ColData::Bigint(2))));
+                }
+            }
+        };
+    }
+
+    assert!(Client::default().send_shutdown().is_ok());
+    assert!(clean_nodes().is_ok());
+}
@@ -0,0 +1,74 @@
+//! Módulo para _tests_ de vistas materializadas.
+
+mod common;
+
+use {
+    client::{cli::Client, conn_holder::ConnectionHolder},
+    common::{clean_nodes, create_parsing_nodes},
+    data::{
+        flights::{states::FlightState, types::FlightType},
+        login_info::LoginInfo,
+        protocol_result::ProtocolResult,
+        tracking::live_flight_data::LiveFlightData,
+    },
+    std::{thread::sleep, time::Duration},
+};
+
+#[test]
+fn test_select_all_from_live_view() {
+    assert!(clean_nodes().is_ok());
+    let _ = create_parsing_nodes(5, Duration::from_secs(1));
+
+    sleep(Duration::from_secs(1));
+    let conn_res = ConnectionHolder::with_cli(Client::default(), "QUORUM");
+    sleep(Duration::from_secs(1));
+
+    assert!(conn_res.is_ok());
+
+    // le damos tiempo para procesar
+    sleep(Duration::from_secs(2));
+
+    if let Ok(mut conn) = conn_res {
+        let client_lock = conn.get_cli();
+        let login_res = conn.login(&LoginInfo::new_str("juan", "1234"));
+        sleep(Duration::from_secs(1));
+
+        assert!(login_res.is_ok());
+
+        if let Ok(mut client) = client_lock.lock() {
+            for query in [
+                "CREATE KEYSPACE IF NOT EXISTS aerolinea_rustica WITH replication = {'class': 'SimpleStrategy', 'replication_factor' : 3};",
+                "USE aerolinea_rustica;",
+                "CREATE TABLE IF NOT EXISTS vuelos_salientes_en_vivo (id int, orig text, dest text, salida timestamp, pos_lat double, pos_lon double, estado text, velocidad double, altitud double, nivel_combustible double, duracion double, PRIMARY KEY ((orig), id));",
+                "CREATE MATERIALIZED VIEW IF NOT EXISTS vuelos_entrantes_en_vivo AS SELECT id, orig, dest, salida, pos_lat, pos_lon, estado, velocidad, altitud, nivel_combustible, duracion FROM vuelos_salientes_en_vivo WHERE dest IS NOT NULL AND id IS NOT NULL PRIMARY KEY ((dest), id);",
+                "INSERT INTO vuelos_salientes_en_vivo (id, orig, dest, salida, pos_lat, pos_lon, estado, velocidad, altitud, nivel_combustible, duracion) VALUES (123456, 'AEP', 'BFS', 1700000000, 34.5, 58.4, 'in_course', 800.0, 30000.0, 90.0, 1.5);",
+            ] {
+                let res = client.send_query(query, &mut conn.tls_stream);
+                sleep(Duration::from_secs(1));
+                assert!(res.is_ok());
+            }
+
+            // La vista devuelve sus columnas en el orden en que se seleccionaron al crearla
+            let select_query = "SELECT * FROM vuelos_entrantes_en_vivo WHERE dest = 'BFS';";
+            let select_res = client.send_query(select_query, &mut conn.tls_stream);
+            sleep(Duration::from_secs(1));
+            assert!(select_res.is_ok());
+
+            if let Ok((protocol_res, _)) = select_res {
+                assert!(matches!(&protocol_res, ProtocolResult::Rows(_)));
+                let live_data_res =
+                    LiveFlightData::try_from_protocol_result(protocol_res, &FlightType::Incoming);
+                assert!(live_data_res.is_ok());
+
+                if let Ok(live_data) = live_data_res {
+                    assert_eq!(live_data.len(), 1);
+                    assert_eq!(live_data[0].flight_id, 123456);
+                    assert!(matches!(live_data[0].state, FlightState::InCourse));
+                }
+            }
+        };
+    }
+
+    assert!(Client::default().send_shutdown().is_ok());
+    assert!(clean_nodes().is_ok());
+}
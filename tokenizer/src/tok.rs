@@ -1,15 +1,23 @@
 //! Módulo para tokenizar queries.
 
 /// Tokeniza una query dada, devolviendo un vector de strings listos para ser parseados.
+///
+/// Dentro de un string, una comilla simple duplicada (`''`) representa a una comilla literal.
+/// Fuera de los strings, los marcadores `?` y `:nombre` quedan cada uno como un único token.
 pub fn tokenize_query(query: &str) -> Vec<String> {
     let mut tokens = Vec::new();
     let mut current_token = String::new();
     let mut in_single_quotes = false;
     let mut in_double_quotes = false;
 
-    for c in query.chars() {
+    let mut chars = query.chars().peekable();
+    while let Some(c) = chars.next() {
         match c {
             '\n' => continue,
+            '\'' if in_single_quotes && chars.peek() == Some(&'\'') => {
+                chars.next();
+                current_token.push(c);
+            }
             '\'' => handle_single_quote(
                 &mut tokens,
                 &mut current_token,
@@ -17,7 +25,14 @@ pub fn tokenize_query(query: &str) -> Vec<String> {
                 &mut in_double_quotes,
             ),
             '"' => handle_double_quote(&mut tokens, &mut current_token, &mut in_double_quotes),
-            ' ' | ',' | ':' | ';' | '(' | ')' | '[' | ']' | '{' | '}' => handle_separator(
+            ':' if !in_single_quotes
+                && !in_double_quotes
+                && chars.peek().is_some_and(|next| next.is_ascii_alphabetic()) =>
+            {
+                push_token_if_not_empty(&mut tokens, &mut current_token);
+                current_token.push(c);
+            }
+            ' ' | ',' | ':' | ';' | '(' | ')' | '[' | ']' | '{' | '}' | '?' => handle_separator(
                 c,
                 &mut tokens,
                 &mut current_token,
@@ -41,7 +56,13 @@ fn handle_single_quote(
     in_double_quotes: &mut bool,
 ) {
     if !*in_double_quotes {
-        push_token_if_not_empty(tokens, current_token);
+        if *in_single_quotes {
+            // Al cerrar el string se agrega su contenido aunque esté vacío, para que `''` sea
+            // una constante válida
+            tokens.push(current_token.split_off(0));
+        } else {
+            push_token_if_not_empty(tokens, current_token);
+        }
         tokens.push("'".to_string());
         *in_single_quotes = !*in_single_quotes;
     } else {